@@ -0,0 +1,683 @@
+//! Corpus analysis over PHON payloads: varṇamālā collation, pada
+//! frequencies, accent coverage, recitation timing, and quotations.
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use slbc_core::akshara;
+use slbc_core::codepage::{self, ExtKind};
+use slbc_core::decoder::{self, Script};
+use slbc_core::error::SlbcError;
+use slbc_core::frame;
+use slbc_core::json;
+use slbc_core::lang;
+use slbc_core::numeral;
+use slbc_core::types::*;
+
+// ═══════════════════════════════════════════════
+//  Collation (varṇamālā order)
+// ═══════════════════════════════════════════════
+
+/// Where anusvāra sorts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Anusvara {
+    /// After the vowels, before ka (the varṇamālā position of aṃ).
+    #[default]
+    AfterVowels,
+    /// As the nasal homorganic with the following stop (ṃk as ṅk), or m
+    /// when no stop follows — the order of most printed dictionaries.
+    Homorganic,
+    /// Not at all.
+    Ignore,
+}
+
+/// Where visarga (and jihvāmūlīya/upadhmānīya) sorts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Visarga {
+    /// After anusvāra, before ka (the varṇamālā position of aḥ).
+    #[default]
+    AfterVowels,
+    Ignore,
+}
+
+/// How accents affect order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Accents {
+    Ignore,
+    /// Only to order words that are otherwise equal.
+    #[default]
+    Secondary,
+    /// At each vowel: unaccented, udātta, anudātta, svarita.
+    Primary,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CollationOptions {
+    pub anusvara: Anusvara,
+    pub visarga: Visarga,
+    pub accents: Accents,
+}
+
+/// Native phonemes (accent and pluta stripped) in varṇamālā order.
+const VARNAMALA: [u8; 49] = [
+    // Svaras: a ā i ī u ū ṛ ṝ ḷ ḹ e ai o au
+    0x40, 0x80, 0x44, 0x84, 0x48, 0x88, 0x4C, 0x8C, 0x4F, 0x8F, 0x85, 0x86, 0x89, 0x8A,
+    // Ayogavāha: ṃ ḥ
+    0x3A, 0x39, // Vargas
+    0x00, 0x01, 0x02, 0x03, 0x04, 0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x10, 0x11, 0x12, 0x13, 0x14, 0x18,
+    0x19, 0x1A, 0x1B, 0x1C, 0x20, 0x21, 0x22, 0x23, 0x24, // y r l v ś ṣ s h
+    0x31, 0x33, 0x34, 0x32, 0x29, 0x2A, 0x2B, 0x38,
+];
+
+/// Key weights: word separator, digits, then phonemes. Accent weights (1–4)
+/// only ever follow a vowel weight, so they never compare against these.
+const W_SEPARATOR: u8 = 0x01;
+const W_DIGIT: u8 = 0x02;
+const W_PHONEME: u8 = 0x10;
+/// Extension phonemes sort after h, by code.
+const W_EXT: u8 = 0x80;
+
+fn weight(b: u8) -> Option<u8> {
+    // Strip accent; pluta sorts as dīrgha
+    let b = match b {
+        b if is_svara(b) && svara_q(b) == 0b11 => (b & 0x0F) | 0x80,
+        b if is_svara(b) => b & !0x30,
+        0x3B | 0x3C => 0x39,
+        b => b,
+    };
+    VARNAMALA
+        .iter()
+        .position(|&v| v == b)
+        .map(|p| W_PHONEME + p as u8)
+}
+
+/// Collation key for a PHON payload or a single pada's contents, in
+/// varṇamālā order with the default options. Keys compare with plain byte
+/// order.
+pub fn sort_key(data: &[u8]) -> Vec<u8> {
+    sort_key_with(data, &CollationOptions::default())
+}
+
+/// Collation key with explicit options.
+///
+/// Padas are separated by a weight below every letter, so "ka ca" sorts
+/// before "kaca". META blocks and avagraha are ignored; numerals sort before
+/// letters by their digits.
+pub fn sort_key_with(data: &[u8], opts: &CollationOptions) -> Vec<u8> {
+    let mut key = Vec::with_capacity(data.len());
+    let mut accents = Vec::new();
+    let mut i = 0;
+
+    let separate = |key: &mut Vec<u8>| {
+        if key.last().is_some_and(|&w| w != W_SEPARATOR) {
+            key.push(W_SEPARATOR);
+        }
+    };
+
+    while i < data.len() {
+        let b = data[i];
+        match b {
+            SANKHYA_START => {
+                separate(&mut key);
+                match numeral::decode_sankhya(data, i) {
+                    Ok((digits, consumed)) => {
+                        key.extend(digits.iter().map(|d| W_DIGIT + d));
+                        i += consumed;
+                        // The glyph span repeats the same number
+                        if let Ok((_, n)) = numeral::decode_num(data, i) {
+                            i += n;
+                        }
+                    }
+                    Err(_) => i += 1,
+                }
+                continue;
+            }
+            NUM => {
+                separate(&mut key);
+                let (glyphs, consumed) = numeral::decode_num(data, i).unwrap_or_default();
+                key.extend(glyphs.iter().map(|g| W_DIGIT + g.min(&9)));
+                i += consumed.max(1);
+                continue;
+            }
+            META_START => {
+                while i < data.len() && data[i] != META_END {
+                    i += 1;
+                }
+            }
+            META_EXT
+                if data
+                    .get(i + 1)
+                    .and_then(|&c| codepage::punctuation(c))
+                    .is_some() =>
+            {
+                separate(&mut key);
+                i += 1;
+            }
+            META_EXT => {
+                if let Some(&code) = data.get(i + 1) {
+                    key.push(W_EXT.saturating_add(code));
+                }
+                i += 1;
+            }
+            PADA_END | SPACE | LINE_BREAK | DANDA | DOUBLE_DANDA => separate(&mut key),
+            0x3A if opts.anusvara == Anusvara::Ignore => {}
+            0x3A if opts.anusvara == Anusvara::Homorganic => {
+                let nasal = match data.get(i + 1) {
+                    Some(&next) if is_varga(next) => (next & 0b11_111_000) | 0b100,
+                    _ => 0x24,
+                };
+                key.extend(weight(nasal));
+            }
+            0x39 | 0x3B | 0x3C if opts.visarga == Visarga::Ignore => {}
+            b if is_svara(b) => {
+                key.extend(weight(b));
+                match opts.accents {
+                    Accents::Ignore => {}
+                    Accents::Secondary => accents.push(svara_a(b) + 1),
+                    Accents::Primary => key.push(svara_a(b) + 1),
+                }
+            }
+            b if is_vyanjana(b) => key.extend(weight(b)),
+            _ => {}
+        }
+        i += 1;
+    }
+
+    if key.last() == Some(&W_SEPARATOR) {
+        key.pop();
+    }
+    if !accents.is_empty() {
+        key.push(0x00);
+        key.extend(accents);
+    }
+    key
+}
+
+// ═══════════════════════════════════════════════
+//  Word frequencies
+// ═══════════════════════════════════════════════
+
+/// How often a pada occurs across a corpus.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WordFrequency {
+    /// Pada contents, without framing or language tag.
+    pub pada: Vec<u8>,
+    /// Total occurrences.
+    pub count: usize,
+    /// Number of documents it occurs in.
+    pub documents: usize,
+}
+
+/// Pada frequencies over a corpus, one payload per document.
+///
+/// Ranked by count, then document frequency, then varṇamālā order. Numeral
+/// digit-words are not counted.
+pub fn word_frequencies<'a, I>(documents: I) -> Result<Vec<WordFrequency>, SlbcError>
+where
+    I: IntoIterator<Item = &'a [u8]>,
+{
+    let mut slot: HashMap<&[u8], usize> = HashMap::new();
+    let mut words: Vec<WordFrequency> = Vec::new();
+    // Last document each word was seen in
+    let mut seen_in: Vec<usize> = Vec::new();
+    for (doc, data) in documents.into_iter().enumerate() {
+        for (_, pada) in lang::padas(data)? {
+            let body = &pada[lang::split_tag(pada)?.1..];
+            if body.is_empty() {
+                continue;
+            }
+            let n = *slot.entry(body).or_insert_with(|| {
+                words.push(WordFrequency {
+                    pada: body.to_vec(),
+                    count: 0,
+                    documents: 0,
+                });
+                seen_in.push(usize::MAX);
+                words.len() - 1
+            });
+            words[n].count += 1;
+            if seen_in[n] != doc {
+                seen_in[n] = doc;
+                words[n].documents += 1;
+            }
+        }
+    }
+    words.sort_by_cached_key(|w| {
+        (
+            std::cmp::Reverse(w.count),
+            std::cmp::Reverse(w.documents),
+            sort_key(&w.pada),
+        )
+    });
+    Ok(words)
+}
+
+// ═══════════════════════════════════════════════
+//  Accent coverage
+// ═══════════════════════════════════════════════
+
+/// A pada with both accented and unaccented svaras.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MixedAccentPada {
+    /// Offset of PADA_START.
+    pub offset: usize,
+    /// Pada contents, without framing or language tag.
+    pub pada: Vec<u8>,
+    pub accented: usize,
+    pub unaccented: usize,
+}
+
+/// Accent coverage of a payload.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AccentReport {
+    /// Svaras counted, by A field: unaccented, udātta, anudātta, svarita.
+    pub by_accent: [usize; 4],
+    pub mixed: Vec<MixedAccentPada>,
+}
+
+impl AccentReport {
+    pub fn svaras(&self) -> usize {
+        self.by_accent.iter().sum()
+    }
+
+    pub fn accented(&self) -> usize {
+        self.svaras() - self.by_accent[0]
+    }
+
+    /// Fraction of svaras carrying accent bits (0 when there are none).
+    pub fn coverage(&self) -> f64 {
+        match self.svaras() {
+            0 => 0.0,
+            n => self.accented() as f64 / n as f64,
+        }
+    }
+}
+
+/// Count accented svaras and flag padas that mix accented and unaccented
+/// svaras — usually an accent dropped in editing. Numerals are skipped.
+pub fn accent_report(data: &[u8]) -> Result<AccentReport, SlbcError> {
+    let mut report = AccentReport::default();
+    for (offset, pada) in lang::padas(data)? {
+        let body = &pada[lang::split_tag(pada)?.1..];
+        let mut counts = [0usize; 4];
+        let mut ext_code = false;
+        for &b in body {
+            // The code byte after META_EXT is not a svara
+            if std::mem::take(&mut ext_code) {
+                continue;
+            }
+            ext_code = b == META_EXT;
+            if is_svara(b) {
+                counts[svara_a(b) as usize] += 1;
+            }
+        }
+        let accented = counts[1] + counts[2] + counts[3];
+        if accented > 0 && counts[0] > 0 {
+            report.mixed.push(MixedAccentPada {
+                offset,
+                pada: body.to_vec(),
+                accented,
+                unaccented: counts[0],
+            });
+        }
+        for (total, n) in report.by_accent.iter_mut().zip(counts) {
+            *total += n;
+        }
+    }
+    Ok(report)
+}
+
+// ═══════════════════════════════════════════════
+//  Quotations
+// ═══════════════════════════════════════════════
+
+/// Quoted speech: a PHON frame followed by the word iti.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Quotation {
+    /// The bytes inside the frame.
+    pub body: Range<usize>,
+    /// Number of quotations or other PHON frames around this one.
+    pub depth: usize,
+    /// Offset of the iti pada's PADA_START.
+    pub iti: usize,
+}
+
+/// Every quotation in a payload, outer ones before the ones they hold: each
+/// PHON frame (§6.1) whose next pada, past nothing but SPACE or line breaks, is iti (or
+/// ity), accented or not. Other frames are recitation units and are left
+/// out. Fails on unbalanced frames, like `frame::phon_spans`.
+pub fn quotations(data: &[u8]) -> Result<Vec<Quotation>, SlbcError> {
+    let padas = lang::padas(data)?;
+    let mut out = Vec::new();
+    for span in frame::phon_spans(data)? {
+        // The PHON_END, then only SPACEs up to the next pada
+        let after = span.body.end + 1;
+        let Some(&(offset, pada)) = padas.iter().find(|(o, _)| *o >= after) else {
+            continue;
+        };
+        if data[after..offset]
+            .iter()
+            .any(|&b| b != SPACE && b != LINE_BREAK)
+        {
+            continue;
+        }
+        let body = &pada[lang::split_tag(pada)?.1..];
+        let plain: Vec<u8> = body
+            .iter()
+            .map(|&b| if is_svara(b) { b & !0x30 } else { b })
+            .collect();
+        if matches!(plain[..], [0x44, 0x18, 0x44 | 0x31]) {
+            out.push(Quotation {
+                body: span.body,
+                depth: span.depth,
+                iti: offset,
+            });
+        }
+    }
+    Ok(out)
+}
+
+// ═══════════════════════════════════════════════
+//  Recitation timing
+// ═══════════════════════════════════════════════
+
+/// One akṣara's place in a recitation, in half-mātrās so that times stay
+/// exact.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AksharaTiming {
+    /// Payload offset of the akṣara's first byte.
+    pub offset: usize,
+    /// Length in bytes.
+    pub len: usize,
+    /// Half-mātrās from the start of the payload.
+    pub start: u32,
+    /// Duration in half-mātrās.
+    pub duration: u32,
+    /// Vowel quantity in mātrās: 1 hrasva, 2 dīrgha, 3 pluta; 0 for an
+    /// akṣara without a vowel.
+    pub vowel: u8,
+    /// Heavy for metre: a long vowel, a closing consonant, anusvāra, or
+    /// visarga, or a cluster opening the next akṣara of the verse.
+    pub guru: bool,
+}
+
+/// Vowel quantity, consonant count, and whether a consonant follows the
+/// vowel, for one akṣara.
+fn akshara_units(bytes: &[u8]) -> Result<(u8, u32, bool), SlbcError> {
+    let (mut vowel, mut consonants, mut closed) = (0, 0, false);
+    let mut j = 0;
+    while j < bytes.len() {
+        let b = bytes[j];
+        let is_vowel = match b {
+            META_EXT if codepage::is_samasa(bytes, j) => {
+                j += 2;
+                continue;
+            }
+            META_EXT => {
+                let p = codepage::read_ext(bytes, j)?;
+                j += 1;
+                p.kind == ExtKind::Vowel
+            }
+            b if is_svara(b) => true,
+            b if is_vyanjana(b) => false,
+            // Morpheme seams and candrabindu take no time, nor do compound
+            // boundaries
+            _ => {
+                j += 1;
+                continue;
+            }
+        };
+        if is_vowel {
+            // Extension vowels (short e/o) are hrasva
+            vowel = if is_svara(b) { svara_q(b) } else { 1 };
+        } else {
+            consonants += 1;
+            closed |= vowel > 0;
+        }
+        j += 1;
+    }
+    Ok((vowel, consonants, closed))
+}
+
+/// Consonants before the vowel of an akṣara.
+fn onset(bytes: &[u8]) -> Result<u32, SlbcError> {
+    let mut n = 0;
+    let mut j = 0;
+    while j < bytes.len() {
+        match bytes[j] {
+            META_EXT if codepage::is_samasa(bytes, j) => j += 1,
+            META_EXT if codepage::read_ext(bytes, j)?.kind == ExtKind::Consonant => {
+                n += 1;
+                j += 1;
+            }
+            b if is_vyanjana(b) => n += 1,
+            b if is_svara(b) || b == META_EXT => break,
+            _ => {}
+        }
+        j += 1;
+    }
+    Ok(n)
+}
+
+/// Canonical durations of a payload's akṣaras (see `akshara::aksharas`).
+///
+/// A vowel lasts its quantity in mātrās (hrasva 1, dīrgha 2, pluta 3) and
+/// every consonant, anusvāra, or visarga half a mātrā, so "rma" is 1½ and
+/// "kṣe" 3. Akṣaras follow one another without pauses; numerals are not
+/// timed.
+pub fn timing(data: &[u8]) -> Result<Vec<AksharaTiming>, SlbcError> {
+    let aksharas = akshara::aksharas(data)?;
+    let mut out = Vec::with_capacity(aksharas.len());
+    let mut start = 0;
+    for (n, a) in aksharas.iter().enumerate() {
+        let bytes = &data[a.offset..a.offset + a.len];
+        let (vowel, consonants, closed) = akshara_units(bytes)?;
+        // A cluster after a daṇḍa belongs to the next verse
+        let cluster_follows = match aksharas.get(n + 1) {
+            Some(next) => {
+                let between = &data[a.offset + a.len..next.offset];
+                !between.iter().any(|&b| matches!(b, DANDA | DOUBLE_DANDA))
+                    && onset(&data[next.offset..next.offset + next.len])? > 1
+            }
+            None => false,
+        };
+        let duration = 2 * vowel as u32 + consonants;
+        out.push(AksharaTiming {
+            offset: a.offset,
+            len: a.len,
+            start,
+            duration,
+            vowel,
+            guru: vowel > 1 || closed || cluster_follows,
+        });
+        start += duration;
+    }
+    Ok(out)
+}
+
+/// Half-mātrās as a JSON number of mātrās.
+fn matras_json(halves: u32) -> String {
+    match halves % 2 {
+        0 => (halves / 2).to_string(),
+        _ => format!("{}.5", halves / 2),
+    }
+}
+
+/// The timing track as JSON: each akṣara's IAST text, payload span, start
+/// and duration in mātrās, vowel quantity, and weight.
+pub fn timing_json(data: &[u8], track: &[AksharaTiming]) -> Result<String, SlbcError> {
+    let mut entries = Vec::with_capacity(track.len());
+    for t in track {
+        let mut pada = vec![PADA_START];
+        pada.extend_from_slice(&data[t.offset..t.offset + t.len]);
+        pada.push(PADA_END);
+        entries.push(format!(
+            "{{\"text\":{},\"offset\":{},\"len\":{},\"start\":{},\"duration\":{},\"vowel\":{},\"weight\":{}}}",
+            json::quote(&decoder::decode_phon(&pada, Script::Iast)?),
+            t.offset,
+            t.len,
+            matras_json(t.start),
+            matras_json(t.duration),
+            t.vowel,
+            json::quote(if t.guru { "guru" } else { "laghu" })
+        ));
+    }
+    let total = track.last().map_or(0, |t| t.start + t.duration);
+    Ok(format!(
+        "{{\"unit\":\"mātrā\",\"total\":{},\"aksharas\":[{}]}}",
+        matras_json(total),
+        entries.join(",")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use slbc_core::encoder;
+
+    fn sorted(words: &[&str], opts: CollationOptions) -> Vec<String> {
+        let mut words: Vec<(Vec<u8>, &str)> = words
+            .iter()
+            .map(|w| (sort_key_with(&encoder::encode_iast(w).unwrap(), &opts), *w))
+            .collect();
+        words.sort();
+        words.into_iter().map(|(_, w)| w.to_string()).collect()
+    }
+
+    #[test]
+    fn test_varnamala_order() {
+        let words = [
+            "ha", "vana", "lava", "ka", "aṃśa", "āpa", "rava", "au", "kha", "ya",
+        ];
+        assert_eq!(
+            sorted(&words, CollationOptions::default()),
+            ["aṃśa", "āpa", "au", "ka", "kha", "ya", "rava", "lava", "vana", "ha"]
+        );
+        // Word boundaries sort before letters
+        assert!(
+            sort_key(&encoder::encode_iast("ka ca").unwrap())
+                < sort_key(&encoder::encode_iast("kaca").unwrap())
+        );
+    }
+
+    #[test]
+    fn test_anusvara_treatment() {
+        let words = ["saṃgha", "sakha", "saṅga"];
+        assert_eq!(
+            sorted(&words, CollationOptions::default()),
+            ["saṃgha", "sakha", "saṅga"]
+        );
+        let homorganic = CollationOptions {
+            anusvara: Anusvara::Homorganic,
+            ..Default::default()
+        };
+        // saṃgha sorts as saṅgha
+        assert_eq!(sorted(&words, homorganic), ["sakha", "saṅga", "saṃgha"]);
+    }
+
+    #[test]
+    fn test_word_frequencies() {
+        let docs = [
+            encoder::encode_iast("rāmaḥ vanaṃ gacchati rāmaḥ 12").unwrap(),
+            encoder::encode_iast("sītā vanaṃ rāmaḥ").unwrap(),
+        ];
+        let words = word_frequencies(docs.iter().map(|d| d.as_slice())).unwrap();
+        let pada = |w: &str| slbc_core::search::query_pada(w).unwrap();
+        assert_eq!(words[0].pada, pada("rāmaḥ"));
+        assert_eq!((words[0].count, words[0].documents), (3, 2));
+        assert_eq!((words[1].count, words[1].documents), (2, 2));
+        // Ties fall back to varṇamālā order: gacchati before sītā
+        assert_eq!(words[2].pada, pada("gacchati"));
+        assert_eq!(words.len(), 4); // digit-words not counted
+    }
+
+    #[test]
+    fn test_accent_report() {
+        let mut data = encoder::encode_iast("agni mitra").unwrap();
+        // Accent every vowel of agni, and only the first of mitra
+        data[1] |= 0x10; // a: udātta
+        data[4] |= 0x20; // i: anudātta
+        data[9] |= 0x30; // i of mitra: svarita
+        let report = accent_report(&data).unwrap();
+        assert_eq!(report.by_accent, [1, 1, 1, 1]);
+        assert_eq!(report.coverage(), 0.75);
+        assert_eq!(report.mixed.len(), 1);
+        assert_eq!(report.mixed[0].offset, 7);
+        assert_eq!(
+            (report.mixed[0].accented, report.mixed[0].unaccented),
+            (1, 1)
+        );
+
+        let plain = encoder::encode_iast("agni 108").unwrap();
+        let report = accent_report(&plain).unwrap();
+        assert_eq!((report.svaras(), report.accented()), (2, 0));
+        assert!(report.mixed.is_empty());
+    }
+
+    #[test]
+    fn test_accents() {
+        let plain = encoder::encode_iast("agni").unwrap();
+        let mut accented = plain.clone();
+        accented[1] |= 0x10; // udātta on the first vowel
+        assert!(sort_key(&plain) < sort_key(&accented));
+        let ignore = CollationOptions {
+            accents: Accents::Ignore,
+            ..Default::default()
+        };
+        assert_eq!(
+            sort_key_with(&plain, &ignore),
+            sort_key_with(&accented, &ignore)
+        );
+        // Secondary accents never outweigh letters
+        let aha = encoder::encode_iast("aha").unwrap();
+        assert!(sort_key(&accented) < sort_key(&aha));
+    }
+
+    #[test]
+    fn test_quotations() {
+        let text = "rāmo vanaṃ gacchati iti śrutvā | ⟨ahaṃ ⟨na⟩ iti jāne⟩ ity uvāca ⟨mantraḥ⟩ ca";
+        let opts = encoder::EncodeOptions {
+            iti: true,
+            ..Default::default()
+        };
+        let data = encoder::encode_script_with(text, Script::Iast, &opts).unwrap();
+        let quotes = quotations(&data).unwrap();
+        let said: Vec<String> = quotes
+            .iter()
+            .map(|q| decoder::decode_phon(&data[q.body.clone()], Script::Iast).unwrap())
+            .collect();
+        assert_eq!(said, ["rāmo vanaṃ gacchati", "ahaṃ na iti jāne", "na"]);
+        assert_eq!(
+            quotes.iter().map(|q| q.depth).collect::<Vec<_>>(),
+            [0, 0, 1]
+        );
+        // The framed mantra is not followed by iti
+        assert_eq!(frame::phon_spans(&data).unwrap().len(), 4);
+        let iti = &data[quotes[0].iti..];
+        assert_eq!(&iti[..5], &[PADA_START, 0x44, 0x18, 0x44, PADA_END]);
+    }
+
+    #[test]
+    fn test_timing() {
+        let data = encoder::encode_iast("dharma kṣetra | prāṇa agnā3i").unwrap();
+        let track = timing(&data).unwrap();
+        let durations: Vec<u32> = track.iter().map(|t| t.duration).collect();
+        // dha rma kṣe tra | prā ṇa a gnā3 i
+        assert_eq!(durations, [3, 4, 6, 4, 6, 3, 2, 8, 2]);
+        let guru: Vec<bool> = track.iter().map(|t| t.guru).collect();
+        // A following cluster makes dha, rma, and a heavy, but not tra
+        // across the daṇḍa
+        assert_eq!(
+            guru,
+            [true, true, true, false, true, false, true, true, false]
+        );
+        assert_eq!(track[1].start, 3);
+        assert_eq!(track[7].vowel, 3);
+
+        let json = timing_json(&data, &track).unwrap();
+        assert!(json.starts_with("{\"unit\":\"mātrā\",\"total\":19,"));
+        assert!(json.contains(
+            "{\"text\":\"dha\",\"offset\":1,\"len\":2,\"start\":0,\"duration\":1.5,\"vowel\":1,\"weight\":\"guru\"}"
+        ));
+    }
+}
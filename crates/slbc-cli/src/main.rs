@@ -1,16 +1,48 @@
 //! SLBC CLI — encode, decode, inspect, transform, roundtrip.
 
+use std::fmt;
 use std::fs;
-use std::path::PathBuf;
+use std::io::{self, Write as _};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 use anyhow::{bail, Context, Result};
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
 
-use slbc::container;
-use slbc::decoder::{self, Script};
-use slbc::encoder;
+use slbc::akshara::AksharaIndex;
+use slbc::analysis;
+use slbc::anvaya::Anvaya;
+use slbc::audit;
+use slbc::checksum::{self, VerseSums};
+use slbc::container::{self, Chunk, MetaChunk};
+use slbc::decoder::{
+    self, AsciiFallback, Conjuncts, DecodeOptions, IsoStyle, NumeralStyle, Script, TamilStyle,
+};
+use slbc::dict::{self, DictDirs, DictReference, PadaDict};
+use slbc::emend::{self, Reading};
+use slbc::encoder::{
+    self, CaseFolding, EncodeOptions, Newlines, PhonFrames, Punctuation, SamasaMarker, ToneMarks,
+};
+use slbc::error::SlbcError;
+use slbc::extension::{self, ExtChunk};
+use slbc::fidelity;
+use slbc::frame;
+use slbc::golden;
+use slbc::index::PadaIndex;
 use slbc::inspect;
+use slbc::json;
+use slbc::lang;
+use slbc::layers;
+use slbc::numeral;
+use slbc::pack;
+use slbc::pipeline::{self, IngestOptions};
+use slbc::proof;
+use slbc::provenance::{self, Operation};
+use slbc::scheme;
+use slbc::search::{self, LemmaTable, Lemmatizer, NoLemmas};
 use slbc::transform;
+use slbc::translation;
 use slbc::types::*;
 
 #[derive(Parser)]
@@ -18,39 +50,330 @@ use slbc::types::*;
 struct Cli {
     #[command(subcommand)]
     command: Command,
+
+    /// How to report a failure: text, or json (one object on stderr with
+    /// kind, exit code, message, position, snippet, and offset)
+    #[arg(long, global = true, default_value = "text", value_parser = ["text", "json"])]
+    error_format: String,
 }
 
-#[derive(Subcommand)]
-enum Command {
-    /// Encode IAST text to .slbc binary
-    Encode {
-        /// IAST text to encode (if no -i file)
-        text: Option<String>,
+#[derive(Args)]
+struct EncodeArgs {
+    /// Text to encode (if no -i file)
+    text: Option<String>,
 
-        /// Input file (IAST text)
-        #[arg(short, long)]
-        i: Option<PathBuf>,
+    /// Input file (text)
+    #[arg(short, long)]
+    i: Option<PathBuf>,
 
-        /// Output file (.slbc binary)
-        #[arg(short, long)]
-        o: Option<PathBuf>,
+    /// Input script: iast, slp1, hk, itrans, velthuis, wx, iso15919,
+    /// devanagari, or auto (detected per run, for mixed-script documents)
+    #[arg(long, default_value = "iast")]
+    from: String,
 
-        /// Print hex dump instead of writing binary
-        #[arg(long)]
-        hex: bool,
-    },
+    /// Output file (.slbc binary)
+    #[arg(short, long)]
+    o: Option<PathBuf>,
+
+    /// Print hex dump instead of writing binary
+    #[arg(long)]
+    hex: bool,
+
+    /// Compress padas against a shared dictionary file
+    #[arg(long)]
+    dict: Option<PathBuf>,
+
+    /// Corpus id recorded with the dictionary reference
+    #[arg(long, default_value_t = 0)]
+    dict_id: u32,
+
+    /// Accept inline language markup, e.g. "<pra>ajja</pra>"
+    #[arg(long)]
+    markup: bool,
+
+    /// Accept inline emendation markup, manuscript reading then the
+    /// editor's, e.g. "{dha[?]ma=dharma}"
+    #[arg(long)]
+    emend: bool,
+
+    /// Add an akṣara index chunk
+    #[arg(long)]
+    akshara_index: bool,
+
+    /// Add per-verse checksums (for verify --fast)
+    #[arg(long)]
+    verse_sums: bool,
+
+    /// Add an index of pada and verse offsets (for decode --verse)
+    #[arg(long)]
+    pada_index: bool,
+
+    /// Store the bhāṣā stream and the lipi layer as separate BHA and LIPI
+    /// chunks instead of one interleaved PHON chunk
+    #[arg(long)]
+    separated: bool,
+
+    /// Pack frequent clusters and akṣara runs into two-byte codes, with the
+    /// pack table stored in the container
+    #[arg(long)]
+    pack: bool,
+
+    /// Vedic text: read IAST ḻ, ḻh as the Ṛgvedic laterals ळ, ळ्ह (not
+    /// Tamil ழ) and set the VEDIC header flag
+    #[arg(long)]
+    vedic: bool,
+
+    /// Keep a standalone oṃ (oṃ, om̐, ॐ) as the sacred symbol, written ॐ
+    /// in Devanāgarī output
+    #[arg(long)]
+    pranava: bool,
+
+    /// Record the yamas and nāsikyas of Prātiśākhya recitation (paliknī,
+    /// brahma); decoded text is unchanged
+    #[arg(long)]
+    yama: bool,
+
+    /// How Devanāgarī tone marks are read: direct (॑ udātta, ॒ anudātta)
+    /// or samhita (॑ svarita, ॒ anudātta, udātta unmarked)
+    #[arg(long, default_value = "direct")]
+    tone_marks: String,
+
+    /// Frame the clause before each standalone iti as a quotation, from the
+    /// previous daṇḍa (mark quotations with ⟨ … ⟩ to be exact)
+    #[arg(long)]
+    iti: bool,
+
+    /// Capitals in IAST or ISO 15919 input (Dharma, ŚRĪ): off (rejected),
+    /// fold (read as lower case), or preserve (restored on decoding)
+    #[arg(long, default_value = "off")]
+    fold_case: String,
+
+    /// What a line break is read as: keep (a LINE_BREAK, restored on
+    /// decoding), space, or danda (a space, after a daṇḍa if the line does
+    /// not end in one)
+    #[arg(long, default_value = "keep")]
+    newlines: String,
+
+    /// Punctuation the input script does not spell: keep (commas, dashes,
+    /// parentheses and quotation marks, restored on decoding), reject,
+    /// drop, or danda (. ! ? ; read as a daṇḍa, the rest dropped)
+    #[arg(long, default_value = "keep")]
+    punctuation: String,
+
+    /// Skip or substitute characters that cannot be read instead of failing
+    #[arg(long)]
+    lenient: bool,
+
+    /// Frame stretches of the text as PHON frames: none, verses, sentences
+    /// (ending at each daṇḍa), or text
+    #[arg(long, default_value = "none")]
+    phon_frames: String,
+
+    /// Read this mark as a compound boundary inside a pada, for
+    /// pre-segmented corpora: off, hyphen (-), or plus (+)
+    #[arg(long, default_value = "off")]
+    samasa: String,
+
+    /// Read . and .. in IAST and ISO 15919 as daṇḍa and double daṇḍa
+    #[arg(long)]
+    dot_dandas: bool,
+
+    /// Title recorded in a metadata (META) chunk
+    #[arg(long)]
+    title: Option<String>,
+
+    /// Author recorded in the metadata chunk
+    #[arg(long)]
+    author: Option<String>,
+
+    /// Work the text belongs to, recorded in the metadata chunk
+    #[arg(long)]
+    work: Option<String>,
+
+    /// Section of the work, recorded in the metadata chunk
+    #[arg(long)]
+    section: Option<String>,
+
+    /// Edition or manuscript transcribed, recorded in the metadata chunk
+    #[arg(long)]
+    source: Option<String>,
+
+    /// Language tag of the text (e.g. sa), recorded in the metadata chunk
+    #[arg(long)]
+    language: Option<String>,
+
+    /// Any other metadata field, as key=value (repeatable)
+    #[arg(long, value_name = "KEY=VALUE")]
+    meta: Vec<String>,
+}
+
+#[derive(Args)]
+struct DecodeArgs {
+    /// Input .slbc file
+    #[arg(short, long)]
+    i: PathBuf,
+
+    /// Output script: iast, slp1, hk, itrans, velthuis, wx, iso15919,
+    /// devanagari, telugu, kannada, grantha, sharada, tamil, gujarati,
+    /// gurmukhi, siddham, or odia
+    #[arg(long, default_value = "iast")]
+    to: String,
+
+    /// Output file (text)
+    #[arg(short, long)]
+    o: Option<PathBuf>,
+
+    /// Numeral rendering: digits, words, both, or composed (one word,
+    /// e.g. aṣṭottaraśatam)
+    #[arg(long, default_value = "digits")]
+    numerals: String,
+
+    /// ASCII-only romanized output: velthuis, itrans or hk (Harvard-Kyoto)
+    /// spellings replace diacritics
+    #[arg(long)]
+    ascii: Option<String>,
+
+    /// ISO 15919 style: strict (ṁ, ē/ō) or library (ṃ, e/o)
+    #[arg(long, default_value = "strict")]
+    iso_style: String,
+
+    /// Tamil style: superscript (க² for kha) or plain (lossy)
+    #[arg(long, default_value = "superscript")]
+    tamil_style: String,
+
+    /// Abugida conjuncts: natural, explicit (a visible virāma on every
+    /// member, with ZWNJ), or half (half forms, with ZWJ)
+    #[arg(long, default_value = "natural")]
+    conjuncts: String,
+
+    /// IAST/ISO 15919: capitalize the first word of each verse
+    #[arg(long)]
+    capitalize: bool,
+
+    /// Reading of emended spans: diplomatic (the manuscript) or edited
+    #[arg(long, default_value = "diplomatic")]
+    reading: String,
+
+    /// Show morpheme seams inside padas as "+"
+    #[arg(long)]
+    morph: bool,
+
+    /// Show compound boundaries inside padas as "-"
+    #[arg(long)]
+    compounds: bool,
+
+    /// Write an avagraha for an elided a as the a ("te api" for "te 'pi")
+    #[arg(long)]
+    restore_elision: bool,
+
+    /// Write visarga before k/kh and p/ph as jihvāmūlīya and upadhmānīya
+    #[arg(long)]
+    visarga_allophones: bool,
+
+    /// Show recitation units and quotations (PHON frames) as ⟨ … ⟩
+    #[arg(long)]
+    phon: bool,
+
+    /// Write each sentence (outermost PHON frame) on a line of its own
+    #[arg(long)]
+    sentences: bool,
+
+    /// Decode only verse N (from 1), seeking to it through the pada index
+    /// when the file has one
+    #[arg(long, value_name = "N")]
+    verse: Option<usize>,
+
+    /// Write inline annotations (verse numbers, speakers, notes) as
+    /// {key: value}
+    #[arg(long)]
+    annotations: bool,
+
+    /// Audit the decoded text for confusable or non-canonical sequences:
+    /// report (list findings) or fix (also normalize the output)
+    #[arg(long)]
+    audit: Option<String>,
+
+    /// List the META blocks and long extensions the decoded text leaves
+    /// out (otherwise only their count is reported)
+    #[arg(long)]
+    show_meta: bool,
+
+    /// Shared dictionary file referenced by the container
+    #[arg(long)]
+    dict: Option<PathBuf>,
+
+    /// Directory to search for referenced dictionaries (repeatable;
+    /// the input file's directory is always searched)
+    #[arg(long)]
+    dict_dir: Vec<PathBuf>,
+}
+
+#[derive(Args)]
+struct IngestArgs {
+    /// Input text file, or a directory of them
+    #[arg(short, long)]
+    i: PathBuf,
+
+    /// Output .slbc file, or directory for directory input (default: next
+    /// to each input)
+    #[arg(short, long)]
+    o: Option<PathBuf>,
+
+    /// Input script (default: detect per file)
+    #[arg(long)]
+    from: Option<String>,
+
+    /// Repair mismatched numerals from this layer: words or glyphs
+    #[arg(long)]
+    repair: Option<String>,
+
+    /// Also check pada phonotactics
+    #[arg(long)]
+    phonotactics: bool,
+
+    /// Keep degenerate framing as encoded
+    #[arg(long)]
+    no_normalize: bool,
+
+    /// Omit the akṣara index chunk
+    #[arg(long)]
+    no_akshara_index: bool,
+
+    /// Omit per-verse checksums
+    #[arg(long)]
+    no_verse_sums: bool,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Encode IAST or Devanāgarī text to .slbc binary
+    Encode(EncodeArgs),
 
     /// Decode .slbc binary to text
-    Decode {
-        /// Input .slbc file
+    Decode(DecodeArgs),
+
+    /// Detect, encode, validate, canonicalize, and index text files in one
+    /// pass, with corpus stats
+    Ingest(IngestArgs),
+
+    /// Pair the verses of .slbc files with translation text files and store
+    /// them as translation (ANUV) chunks, reporting what did not match
+    Pair {
+        /// Input .slbc file, or a directory of them
         #[arg(short, long)]
         i: PathBuf,
 
-        /// Output script: iast or devanagari
-        #[arg(long, default_value = "iast")]
-        to: String,
+        /// Translation .txt file, or a directory mirroring the input's
+        /// layout (foo.slbc ↔ foo.txt)
+        #[arg(long)]
+        translations: PathBuf,
+
+        /// Language tag of the translations (e.g. en)
+        #[arg(long)]
+        lang: String,
 
-        /// Output file (text)
+        /// Output .slbc file, or directory for directory input (default:
+        /// rewrite in place)
         #[arg(short, long)]
         o: Option<PathBuf>,
     },
@@ -68,12 +391,16 @@ enum Command {
         /// .slbc file to inspect
         #[arg(short, long)]
         i: Option<PathBuf>,
+
+        /// Emit JSON instead of text
+        #[arg(long)]
+        json: bool,
     },
 
     /// Apply algebraic transformation to a byte
     Transform {
-        /// Operation: guna, vrddhi, dirgha, hrasva, jastva,
-        /// toggle-voice, toggle-aspiration, nasal,
+        /// Operation: guna, vrddhi, dirgha, hrasva, pluta, jastva,
+        /// toggle-voice, toggle-aspiration, nasal, assimilate-nasal,
         /// samprasarana-svara, samprasarana-sonorant
         #[arg(long)]
         op: String,
@@ -81,218 +408,2086 @@ enum Command {
         /// Input byte (hex, e.g. 0x44)
         byte: String,
 
-        /// Second byte (for savarṇa-dīrgha)
+        /// Second byte (for savarṇa-dīrgha and assimilate-nasal)
         byte2: Option<String>,
+
+        /// Accent policy for savarṇa-dīrgha: first, second, udatta, equal
+        #[arg(long, default_value = "first")]
+        accent: String,
+
+        /// Enforce the Pāṇinian operand domain for savarṇa-dīrgha
+        #[arg(long)]
+        strict: bool,
+    },
+
+    /// Shared pada dictionaries
+    Dict {
+        #[command(subcommand)]
+        action: DictCommand,
+    },
+
+    /// Validate a .slbc file (numeral layer agreement, framing)
+    Validate {
+        /// Input .slbc file
+        #[arg(short, long)]
+        i: PathBuf,
+
+        /// Repair mismatched numerals from this layer: words or glyphs
+        #[arg(long)]
+        repair: Option<String>,
+
+        /// Output file for the repaired container (default: overwrite input)
+        #[arg(short, long)]
+        o: Option<PathBuf>,
+
+        /// Also check pada phonotactics (relaxed for language-tagged padas)
+        #[arg(long)]
+        phonotactics: bool,
+
+        /// Rewrite degenerate framing (empty padas, doubled SPACEs, nested PHON)
+        #[arg(long)]
+        normalize: bool,
+    },
+
+    /// Check per-verse checksums written by encode --verse-sums, or sweep
+    /// a whole archive with --dir
+    Verify {
+        /// Input .slbc file
+        #[arg(short, long)]
+        i: Option<PathBuf>,
+
+        /// Check every .slbc file under this directory: parse, verse
+        /// checksums, numerals, framing, and an IAST roundtrip
+        #[arg(long)]
+        dir: Option<PathBuf>,
+
+        /// Files checked in parallel with --dir (default: available cores)
+        #[arg(long)]
+        jobs: Option<usize>,
+
+        /// Seek to the requested verses instead of reading the whole file
+        #[arg(long)]
+        fast: bool,
+
+        /// Verses to check, 1-based (e.g. "1,5-9"); all when omitted
+        #[arg(long)]
+        verses: Option<String>,
+    },
+
+    /// Emit the full transform coverage table (TSV)
+    Matrix {
+        /// Output file (default: stdout)
+        #[arg(short, long)]
+        o: Option<PathBuf>,
+    },
+
+    /// Query a container's akṣara index
+    Akshara {
+        /// Input .slbc file (encoded with --akshara-index)
+        #[arg(short, long)]
+        i: PathBuf,
+
+        /// Akṣara to look up, in IAST (default: list the most frequent)
+        query: Option<String>,
+
+        /// Number of akṣaras to list
+        #[arg(long, default_value_t = 20)]
+        top: usize,
+    },
+
+    /// Corpus statistics for a file or directory
+    Stats {
+        /// Input .slbc or IAST file, or a directory of them
+        #[arg(short, long)]
+        i: PathBuf,
+
+        /// Emit a ranked pada-frequency list (TSV: pada, count, documents)
+        #[arg(long)]
+        words: bool,
+
+        /// Limit the frequency list to the most frequent N padas
+        #[arg(long)]
+        top: Option<usize>,
+
+        /// Report accent coverage and padas mixing accented and unaccented svaras
+        #[arg(long)]
+        accents: bool,
+
+        /// List quoted speech: PHON frames followed by iti
+        #[arg(long)]
+        quotations: bool,
+    },
+
+    /// Search for a pada, optionally matching inflected forms via a lemma table
+    Grep {
+        /// Input .slbc file
+        #[arg(short, long)]
+        i: PathBuf,
+
+        /// Pada (or stem, with --lemmas) to find, in IAST
+        query: String,
+
+        /// Padas of context on either side
+        #[arg(long, default_value_t = 3)]
+        context: usize,
+
+        /// Lemma table (IAST TSV: form, lemma) for matching inflected forms
+        #[arg(long)]
+        lemmas: Option<PathBuf>,
+    },
+
+    /// Print each verse in several scripts, aligned verse by verse
+    Preview {
+        /// Input .slbc file
+        #[arg(short, long)]
+        i: PathBuf,
+
+        /// Comma-separated output scripts
+        #[arg(long, value_delimiter = ',', default_value = "iast,devanagari")]
+        scripts: Vec<String>,
+    },
+
+    /// Report which output scripts can carry every sound in a file
+    Fidelity {
+        /// Input .slbc file
+        #[arg(short, long)]
+        i: PathBuf,
+
+        /// Comma-separated output scripts (default: all)
+        #[arg(long, value_delimiter = ',')]
+        scripts: Vec<String>,
+    },
+
+    /// Export the akṣaras' recitation durations (in mātrās) as JSON
+    Timing {
+        /// Input .slbc file
+        #[arg(short, long)]
+        i: PathBuf,
+
+        /// Output .json file (default: stdout)
+        #[arg(short, long)]
+        o: Option<PathBuf>,
+    },
+
+    /// Decode and align against the original source text, listing divergences
+    Proof {
+        /// Input .slbc file
+        #[arg(short, long)]
+        i: PathBuf,
+
+        /// Original text the archive was encoded from
+        #[arg(long)]
+        against: PathBuf,
+
+        /// Script of the original text
+        #[arg(long, default_value = "iast")]
+        scheme: String,
+    },
+
+    /// List transliteration schemes, or export one scheme's table
+    Scheme {
+        /// Scheme to export (e.g. iast); lists schemes when omitted
+        name: Option<String>,
+
+        /// Export as JSON instead of TSV
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Cross-check IAST ↔ Devanāgarī conversion against verified pairs
+    Golden {
+        /// Corpus TSV (source, IAST, Devanāgarī); the bundled corpus when omitted
+        #[arg(long)]
+        corpus: Option<PathBuf>,
+    },
+
+    /// Round-trip test: encode IAST → .slbc → decode IAST and compare
+    Roundtrip {
+        /// IAST text to test
+        text: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum DictCommand {
+    /// Build a dictionary of the most frequent padas in a corpus
+    Train {
+        /// Corpus directory (.slbc files, or IAST text files)
+        #[arg(long)]
+        dir: PathBuf,
+
+        /// Output dictionary file
+        #[arg(short, long)]
+        o: PathBuf,
+
+        /// Maximum number of entries
+        #[arg(long, default_value_t = 4096)]
+        size: usize,
+    },
+
+    /// Show a dictionary's hash and entries
+    Show {
+        /// Dictionary file
+        #[arg(short, long)]
+        i: PathBuf,
     },
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    match run(cli.command) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => report(&e, &cli.error_format),
+    }
+}
+
+fn run(command: Command) -> Result<()> {
+    match command {
+        Command::Encode(args) => cmd_encode(args),
+        Command::Decode(args) => cmd_decode(args),
+        Command::Ingest(args) => cmd_ingest(args),
+        Command::Pair {
+            i,
+            translations,
+            lang,
+            o,
+        } => cmd_pair(i, translations, lang, o),
+        Command::Dict { action } => cmd_dict(action),
+        Command::Inspect {
+            byte,
+            from_hex,
+            i,
+            json,
+        } => cmd_inspect(byte, from_hex, i, json),
+        Command::Transform {
+            op,
+            byte,
+            byte2,
+            accent,
+            strict,
+        } => cmd_transform(op, byte, byte2, accent, strict),
+        Command::Validate {
+            i,
+            repair,
+            o,
+            phonotactics,
+            normalize,
+        } => cmd_validate(i, repair, o, phonotactics, normalize),
+        Command::Verify {
+            i,
+            dir,
+            jobs,
+            fast,
+            verses,
+        } => match (i, dir) {
+            (Some(i), None) if jobs.is_none() => cmd_verify(i, fast, verses),
+            (None, Some(dir)) if !fast && verses.is_none() => cmd_verify_dir(dir, jobs),
+            (None, None) => bail!("provide -i <file> or --dir <archive>"),
+            (Some(_), Some(_)) => bail!("-i and --dir cannot be combined"),
+            (Some(_), None) => bail!("--jobs requires --dir"),
+            (None, Some(_)) => bail!("--fast and --verses cannot be combined with --dir"),
+        },
+        Command::Matrix { o } => cmd_matrix(o),
+        Command::Akshara { i, query, top } => cmd_akshara(i, query, top),
+        Command::Stats {
+            i,
+            words,
+            top,
+            accents,
+            quotations,
+        } => cmd_stats(i, words, top, accents, quotations),
+        Command::Grep {
+            i,
+            query,
+            context,
+            lemmas,
+        } => cmd_grep(i, query, context, lemmas),
+        Command::Preview { i, scripts } => cmd_preview(i, scripts),
+        Command::Fidelity { i, scripts } => cmd_fidelity(i, scripts),
+        Command::Timing { i, o } => cmd_timing(i, o),
+        Command::Proof { i, against, scheme } => cmd_proof(i, against, scheme),
+        Command::Scheme { name, json } => cmd_scheme(name, json),
+        Command::Golden { corpus } => cmd_golden(corpus),
+        Command::Roundtrip { text } => cmd_roundtrip(text),
+    }
+}
+
+// ── Encode ──
+
+fn cmd_encode(args: EncodeArgs) -> Result<()> {
+    let text = match (args.text, args.i) {
+        (Some(t), _) => t,
+        (None, Some(path)) => read_text(&path)?,
+        (None, None) => bail!("provide text or -i <file>"),
+    };
+
+    let text = text.trim();
+    let options = args.vedic
+        || args.pranava
+        || args.yama
+        || args.iti
+        || args.tone_marks != "direct"
+        || args.fold_case != "off"
+        || args.newlines != "keep"
+        || args.punctuation != "keep"
+        || args.lenient
+        || args.phon_frames != "none"
+        || args.samasa != "off"
+        || args.dot_dandas;
+    let phon_payload = match args.from.as_str() {
+        _ if args.markup && args.emend => bail!("--markup and --emend cannot be combined"),
+        "auto" if args.markup || args.emend || options => {
+            bail!("--from auto cannot be combined with --markup, --emend or encoding options (--vedic, --pranava, --yama, --iti, --tone-marks, --fold-case, --newlines, --punctuation, --lenient, --phon-frames, --samasa, --dot-dandas)")
+        }
+        "auto" => encode_auto(text)?,
+        _ if options && (args.markup || args.emend) => {
+            bail!("encoding options (--vedic, --pranava, --yama, --iti, --tone-marks, --fold-case, --newlines, --punctuation, --lenient, --phon-frames, --samasa, --dot-dandas) cannot be combined with --markup or --emend")
+        }
+        from => match parse_script(from)? {
+            Script::Iast if args.markup => lang::encode_mixed(text),
+            _ if args.markup => bail!("--markup requires IAST input"),
+            script if args.emend => emend::encode_emended(text, script),
+            script => {
+                let opts = EncodeOptions {
+                    vedic: args.vedic,
+                    pranava: args.pranava,
+                    yama: args.yama,
+                    iti: args.iti,
+                    tone_marks: ToneMarks::from_name(&args.tone_marks).ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "unknown tone-mark convention '{}' (use 'direct' or 'samhita')",
+                            args.tone_marks
+                        )
+                    })?,
+                    fold_case: CaseFolding::from_name(&args.fold_case).ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "unknown case folding '{}' (use 'off', 'fold' or 'preserve')",
+                            args.fold_case
+                        )
+                    })?,
+                    newlines: Newlines::from_name(&args.newlines).ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "unknown newline reading '{}' (use 'keep', 'space' or 'danda')",
+                            args.newlines
+                        )
+                    })?,
+                    punctuation: Punctuation::from_name(&args.punctuation).ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "unknown punctuation policy '{}' (use 'keep', 'reject', 'drop' or 'danda')",
+                            args.punctuation
+                        )
+                    })?,
+                    lenient: args.lenient,
+                    phon_frames: PhonFrames::from_name(&args.phon_frames).ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "unknown PHON framing '{}' (use 'none', 'verses', 'sentences' or 'text')",
+                            args.phon_frames
+                        )
+                    })?,
+                    samasa: SamasaMarker::from_name(&args.samasa).ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "unknown compound marker '{}' (use 'off', 'hyphen' or 'plus')",
+                            args.samasa
+                        )
+                    })?,
+                    dot_dandas: args.dot_dandas,
+                    ..Default::default()
+                };
+                encoder::encode_script_with(text, script, &opts)
+            }
+        }
+        .map_err(parse_error)?,
+    };
+
+    let mut extra = Vec::new();
+    let mut meta = MetaChunk {
+        title: args.title,
+        author: args.author,
+        work: args.work,
+        section: args.section,
+        source: args.source,
+        language: args.language,
+        custom: Vec::new(),
+    };
+    for field in &args.meta {
+        let (key, value) = field
+            .split_once('=')
+            .filter(|(key, _)| !key.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("metadata field '{}' is not key=value", field))?;
+        meta.set(key, value);
+    }
+    if !meta.is_empty() {
+        extra.push(meta.to_chunk().map_err(parse_error)?);
+    }
+    if args.akshara_index {
+        let index = AksharaIndex::build(&phon_payload, 0).map_err(parse_error)?;
+        extra.push(index.to_chunk());
+    }
+    let (stored, records) = match args.dict {
+        Some(path) => {
+            let pada_dict = read_dict(&path)?;
+            let packed = dict::compress(&phon_payload, &pada_dict).map_err(parse_error)?;
+            let name = path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let reference = DictReference::to(&pada_dict, args.dict_id, &name);
+            (packed, vec![reference.to_ext_record()])
+        }
+        None => (phon_payload, Vec::new()),
+    };
+    let stored = if args.pack {
+        let table = pack::train([stored.as_slice()], pack::MAX_ENTRIES).map_err(parse_error)?;
+        let packed = pack::pack(&stored, &table).map_err(parse_error)?;
+        extra.push(table.to_chunk());
+        packed
+    } else {
+        stored
+    };
+    // Checksums cover the payload as stored, so verify needs no dictionary
+    if args.verse_sums {
+        let sums = VerseSums::build(&stored, 0).map_err(parse_error)?;
+        extra.push(sums.to_chunk());
+    }
+    if args.pada_index {
+        let index = PadaIndex::build(&stored, 0).map_err(parse_error)?;
+        extra.push(index.to_chunk());
+    }
+    let mut slbc_data = container::build_slbc_ext(&records, &stored, &extra);
+    if args.vedic {
+        // Header byte 11 holds the flags
+        slbc_data[11] |= FLAG_VEDIC;
+    }
+    if args.separated {
+        slbc_data = layers::separate(&slbc_data).map_err(parse_error)?;
+    }
+
+    if args.hex {
+        print_hex(&slbc_data);
+        return Ok(());
+    }
+
+    match args.o {
+        Some(path) => {
+            fs::write(&path, &slbc_data).with_context(|| format!("writing {}", path.display()))?;
+            eprintln!("wrote {} bytes to {}", slbc_data.len(), path.display());
+        }
+        None => {
+            print_hex(&slbc_data);
+        }
+    }
+
+    Ok(())
+}
+
+/// Encode a mixed-script document, reporting how many padas were read in
+/// each script.
+fn encode_auto(text: &str) -> Result<Vec<u8>> {
+    let auto = encoder::encode_auto(text).map_err(parse_error)?;
+    let mut counts: Vec<(Script, usize)> = Vec::new();
+    for &(_, script) in &auto.scripts {
+        match counts.iter_mut().find(|(s, _)| *s == script) {
+            Some((_, n)) => *n += 1,
+            None => counts.push((script, 1)),
+        }
+    }
+    for (script, n) in counts {
+        eprintln!("{}: {} pada(s)", script.name(), n);
+    }
+    Ok(auto.payload)
+}
+
+// ── Pair ──
+
+fn cmd_pair(
+    input: PathBuf,
+    translations: PathBuf,
+    lang: String,
+    output: Option<PathBuf>,
+) -> Result<()> {
+    let mut inputs = Vec::new();
+    let mut pending = vec![input.clone()];
+    while let Some(path) = pending.pop() {
+        if path.is_dir() {
+            let mut children: Vec<PathBuf> = fs::read_dir(&path)
+                .with_context(|| format!("reading {}", path.display()))?
+                .map(|e| e.map(|e| e.path()))
+                .collect::<std::io::Result<_>>()?;
+            children.sort();
+            pending.extend(children.into_iter().rev());
+        } else if path.extension().is_some_and(|e| e == "slbc") {
+            inputs.push(path);
+        }
+    }
+
+    let (mut pairs, mut verses, mut segments, mut missing) = (0, 0, 0, 0);
+    for path in &inputs {
+        let rel = path.strip_prefix(&input).unwrap_or(path);
+        let text_path = match input.is_dir() {
+            true => translations.join(rel).with_extension("txt"),
+            false => translations.clone(),
+        };
+        let name = path.display();
+        if !text_path.is_file() {
+            println!(
+                "{}: no translation ({} not found)",
+                name,
+                text_path.display()
+            );
+            missing += 1;
+            continue;
+        }
+        let text = read_text(&text_path)?;
+        let data = fs::read(path).with_context(|| format!("reading {}", name))?;
+        let dirs = DictDirs(vec![path.parent().unwrap_or(Path::new(".")).to_path_buf()]);
+        let (_header, chunks, _) = dict::open(&data, &dirs)
+            .map_err(parse_error)
+            .with_context(|| name.to_string())?;
+        let phon = chunks
+            .iter()
+            .find(|c| c.chunk_type == CHUNK_PHON)
+            .ok_or_else(|| anyhow::anyhow!("{}: no PHON chunk", name))?;
+        let pairing = translation::pair(&phon.payload, 0, &text, &lang)
+            .map_err(parse_error)
+            .with_context(|| name.to_string())?;
+        let out = translation::attach(&data, &pairing.translation)
+            .map_err(parse_error)
+            .with_context(|| name.to_string())?;
+
+        let target = match &output {
+            Some(o) if input.is_dir() => o.join(rel),
+            Some(o) => o.clone(),
+            None => path.clone(),
+        };
+        if let Some(dir) = target.parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+        }
+        fs::write(&target, &out).with_context(|| format!("writing {}", target.display()))?;
+
+        let numbered = |ns: &[usize]| {
+            ns.iter()
+                .map(|n| (n + 1).to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        println!("{}: {} pair(s)", name, pairing.translation.verses.len());
+        if !pairing.unmatched_verses.is_empty() {
+            println!(
+                "  unmatched verse(s): {}",
+                numbered(&pairing.unmatched_verses)
+            );
+        }
+        if !pairing.unmatched_segments.is_empty() {
+            println!(
+                "  unmatched translation segment(s): {}",
+                numbered(&pairing.unmatched_segments)
+            );
+        }
+        pairs += pairing.translation.verses.len();
+        verses += pairing.unmatched_verses.len();
+        segments += pairing.unmatched_segments.len();
+    }
+
+    println!("Files:               {}", inputs.len());
+    println!("Without translation: {}", missing);
+    println!("Pairs:               {}", pairs);
+    println!("Unmatched verses:    {}", verses);
+    println!("Unmatched segments:  {}", segments);
+    Ok(())
+}
+
+// ── Decode ──
+
+fn cmd_decode(args: DecodeArgs) -> Result<()> {
+    let DecodeArgs {
+        i: input,
+        to,
+        o: output,
+        numerals,
+        ascii,
+        iso_style,
+        tamil_style,
+        conjuncts,
+        capitalize,
+        reading,
+        morph,
+        compounds,
+        restore_elision,
+        visarga_allophones,
+        phon,
+        sentences,
+        verse,
+        annotations,
+        audit: audit_mode,
+        show_meta,
+        dict: dict_path,
+        dict_dir,
+    } = args;
+    let open = || -> Result<Vec<Chunk>> {
+        let data = fs::read(&input).with_context(|| format!("reading {}", input.display()))?;
+        let (_header, chunks, _) = match &dict_path {
+            Some(path) => {
+                let pada_dict = read_dict(path)?;
+                let resolver = move |_: &DictReference| -> Result<Option<PadaDict>, SlbcError> {
+                    Ok(Some(pada_dict.clone()))
+                };
+                dict::open(&data, &resolver)
+            }
+            None => {
+                let mut dirs = dict_dir;
+                dirs.push(input.parent().unwrap_or(Path::new(".")).to_path_buf());
+                dict::open(&data, &DictDirs(dirs))
+            }
+        }
+        .map_err(parse_error)?;
+        Ok(chunks)
+    };
+    let chunks = match verse {
+        Some(n) => vec![Chunk {
+            chunk_type: CHUNK_PHON,
+            payload: read_verse(&input, n, open)?,
+        }],
+        None => open()?,
+    };
+
+    let script = parse_script(&to)?;
+    let fix = match audit_mode.as_deref() {
+        None => None,
+        Some("report") => Some(false),
+        Some("fix") => Some(true),
+        Some(other) => bail!("unknown audit mode '{}' (use 'report' or 'fix')", other),
+    };
+
+    let opts = DecodeOptions {
+        numerals: match numerals.as_str() {
+            "digits" => NumeralStyle::Digits,
+            "words" => NumeralStyle::Words,
+            "both" => NumeralStyle::Both,
+            "composed" => NumeralStyle::Composed,
+            _ => bail!(
+                "unknown numeral style '{}' (use 'digits', 'words', 'both', or 'composed')",
+                numerals
+            ),
+        },
+        ascii: match ascii.as_deref() {
+            None => AsciiFallback::Off,
+            Some(name) => AsciiFallback::from_name(name).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "unknown ASCII fallback '{}' (use 'velthuis', 'itrans', or 'hk')",
+                    name
+                )
+            })?,
+        },
+        iso: IsoStyle::from_name(&iso_style).ok_or_else(|| {
+            anyhow::anyhow!(
+                "unknown ISO 15919 style '{}' (use 'strict' or 'library')",
+                iso_style
+            )
+        })?,
+        tamil: TamilStyle::from_name(&tamil_style).ok_or_else(|| {
+            anyhow::anyhow!(
+                "unknown Tamil style '{}' (use 'superscript' or 'plain')",
+                tamil_style
+            )
+        })?,
+        conjuncts: Conjuncts::from_name(&conjuncts).ok_or_else(|| {
+            anyhow::anyhow!(
+                "unknown conjunct style '{}' (use 'natural', 'explicit' or 'half')",
+                conjuncts
+            )
+        })?,
+        capitalize_verses: capitalize,
+        reading: Reading::from_name(&reading).ok_or_else(|| {
+            anyhow::anyhow!(
+                "unknown reading '{}' (use 'diplomatic' or 'edited')",
+                reading
+            )
+        })?,
+        morph_boundaries: morph,
+        compound_boundaries: compounds,
+        restore_elision,
+        visarga_allophones,
+        phon_boundaries: phon,
+        annotations,
+    };
+
+    // Decoded text goes straight to the output, chunk by chunk, unless the
+    // audit needs all of it first
+    let mut full_text = String::new();
+    let mut stream = match fix {
+        Some(_) => None,
+        None => Some(TextWriter::new(open_output(output.as_deref())?)),
+    };
+    let mut skipped = 0;
+    let mut skipped_ext = 0;
+    for (n, chunk) in chunks.iter().enumerate() {
+        if chunk.chunk_type == CHUNK_PHON {
+            let text: &mut dyn fmt::Write = match &mut stream {
+                Some(stream) => stream,
+                None => &mut full_text,
+            };
+            let written = if sentences {
+                decoder::decode_sentences(&chunk.payload, script, &opts)
+                    .map_err(parse_error)?
+                    .iter()
+                    .try_for_each(|sentence| writeln!(text, "{}", sentence))
+                    .map_err(anyhow::Error::new)
+            } else {
+                decoder::decode_phon_with_to(&chunk.payload, script, &opts, text)
+                    .map_err(parse_error)
+            };
+            if let Err(e) = written {
+                return Err(match stream.as_mut().and_then(|s| s.error.take()) {
+                    Some(io) => anyhow::Error::new(io).context("writing decoded text"),
+                    None => e,
+                });
+            }
+            let skipped_meta = decoder::skipped_meta(&chunk.payload, &opts).map_err(parse_error)?;
+            let long_exts = frame::long_exts(&chunk.payload).map_err(parse_error)?;
+            if show_meta {
+                for span in &skipped_meta {
+                    let hex: Vec<String> =
+                        span.bytes.iter().map(|b| format!("{:02X}", b)).collect();
+                    eprintln!(
+                        "chunk {}, offset {}: META{} {}",
+                        n,
+                        span.offset,
+                        if span.closed { "" } else { " (unclosed)" },
+                        hex.join(" ")
+                    );
+                }
+                for ext in &long_exts {
+                    let hex: Vec<String> =
+                        ext.payload.iter().map(|b| format!("{:02X}", b)).collect();
+                    eprintln!("chunk {}, offset {}: EXT {}", n, ext.offset, hex.join(" "));
+                }
+            }
+            skipped += skipped_meta.len();
+            skipped_ext += long_exts.len();
+        }
+    }
+    if skipped > 0 && !show_meta {
+        eprintln!(
+            "note: skipped {} META block(s); --show-meta lists them",
+            skipped
+        );
+    }
+    if skipped_ext > 0 && !show_meta {
+        eprintln!(
+            "note: skipped {} long extension(s); --show-meta lists them",
+            skipped_ext
+        );
+    }
+
+    if let Some(fix) = fix {
+        let findings = audit::audit(&full_text);
+        for f in &findings {
+            let issues: Vec<&str> = f.issues.iter().map(|i| i.describe()).collect();
+            eprintln!(
+                "offset {}: {}: {} → {}",
+                f.offset,
+                issues.join(", "),
+                audit::codepoints(&f.found),
+                audit::codepoints(&f.fixed)
+            );
+        }
+        if fix {
+            full_text = audit::normalize(&full_text);
+            eprintln!("audit: fixed {} clusters", findings.len());
+        } else {
+            eprintln!("audit: {} clusters flagged", findings.len());
+        }
+    }
+
+    let mut stream = match stream {
+        Some(stream) => stream,
+        None => {
+            let mut stream = TextWriter::new(open_output(output.as_deref())?);
+            stream.inner.write_all(full_text.as_bytes())?;
+            stream.written = full_text.len();
+            stream
+        }
+    };
+    match output {
+        Some(path) => {
+            stream
+                .inner
+                .flush()
+                .with_context(|| format!("writing {}", path.display()))?;
+            eprintln!("wrote {} chars to {}", stream.written, path.display());
+        }
+        None => {
+            writeln!(stream.inner)?;
+            stream.inner.flush()?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Verse `n` (from 1) of a container. With a pada index the verse is
+/// sought and read alone, unless it needs the pack table or the pada
+/// dictionary; otherwise it is taken from the whole container, opened.
+fn read_verse(
+    input: &Path,
+    n: usize,
+    open: impl FnOnce() -> Result<Vec<Chunk>>,
+) -> Result<Vec<u8>> {
+    let Some(k) = n.checked_sub(1) else {
+        bail!("verses are numbered from 1");
+    };
+    let mut file = fs::File::open(input).with_context(|| format!("reading {}", input.display()))?;
+    if let Ok(bytes) = container::seek_verse(&mut file, k) {
+        if !pack::has_packed(&bytes).map_err(parse_error)?
+            && !dict::has_refs(&bytes).map_err(parse_error)?
+        {
+            return Ok(bytes);
+        }
+    }
+    let chunks = open()?;
+    let phon_chunk = PadaIndex::find(&chunks)
+        .map_err(parse_error)?
+        .map_or(0, |index| index.phon_chunk as usize);
+    let payload = &chunks
+        .iter()
+        .filter(|c| c.chunk_type == CHUNK_PHON)
+        .nth(phon_chunk)
+        .ok_or_else(|| failure(Failure::Parse, "container has no PHON chunk"))?
+        .payload;
+    let verses = frame::verses(payload).map_err(parse_error)?;
+    match verses.get(k) {
+        Some(span) => Ok(payload[span.clone()].to_vec()),
+        None => bail!("verse {} out of range ({} verses)", n, verses.len()),
+    }
+}
+
+/// The decoded text's destination: the file given, or standard output.
+fn open_output(path: Option<&Path>) -> Result<io::BufWriter<Box<dyn io::Write>>> {
+    let inner: Box<dyn io::Write> = match path {
+        Some(path) => {
+            Box::new(fs::File::create(path).with_context(|| format!("writing {}", path.display()))?)
+        }
+        None => Box::new(io::stdout().lock()),
+    };
+    Ok(io::BufWriter::new(inner))
+}
+
+/// Decoded text written through to a byte writer, counting its bytes and
+/// keeping the I/O error that stopped it, which `fmt::Error` cannot carry.
+struct TextWriter<W: io::Write> {
+    inner: W,
+    written: usize,
+    error: Option<io::Error>,
+}
+
+impl<W: io::Write> TextWriter<W> {
+    fn new(inner: W) -> Self {
+        TextWriter {
+            inner,
+            written: 0,
+            error: None,
+        }
+    }
+}
+
+impl<W: io::Write> fmt::Write for TextWriter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        match self.inner.write_all(s.as_bytes()) {
+            Ok(()) => {
+                self.written += s.len();
+                Ok(())
+            }
+            Err(e) => {
+                self.error = Some(e);
+                Err(fmt::Error)
+            }
+        }
+    }
+}
+
+// ── Ingest ──
+
+fn cmd_ingest(args: IngestArgs) -> Result<()> {
+    let opts = IngestOptions {
+        script: args.from.as_deref().map(parse_script).transpose()?,
+        normalize_framing: !args.no_normalize,
+        repair_numerals: parse_repair_source(args.repair.as_deref())?,
+        phonotactics: args.phonotactics,
+        akshara_index: !args.no_akshara_index,
+        verse_sums: !args.no_verse_sums,
+    };
+
+    let mut inputs = Vec::new();
+    let mut pending = vec![args.i.clone()];
+    while let Some(path) = pending.pop() {
+        if path.is_dir() {
+            let mut children: Vec<PathBuf> = fs::read_dir(&path)
+                .with_context(|| format!("reading {}", path.display()))?
+                .map(|e| e.map(|e| e.path()))
+                .collect::<std::io::Result<_>>()?;
+            children.sort();
+            pending.extend(children.into_iter().rev());
+        } else if path.extension().is_none_or(|e| e != "slbc") {
+            inputs.push(path);
+        }
+    }
+
+    let mut payloads = Vec::new();
+    let mut flagged = 0;
+    for input in &inputs {
+        let output = match &args.o {
+            Some(o) if args.i.is_dir() => {
+                let rel = input.strip_prefix(&args.i).unwrap_or(input);
+                o.join(rel).with_extension("slbc")
+            }
+            Some(o) => o.clone(),
+            None => input.with_extension("slbc"),
+        };
+        let text = read_text(input)?;
+        let out = pipeline::ingest(&text, &opts)
+            .map_err(parse_error)
+            .with_context(|| input.display().to_string())?;
+
+        let name = input.display();
+        for m in &out.numeral_mismatches {
+            println!(
+                "{}: numeral mismatch — SAṄKHYĀ at offset {} reads {}, NUM at offset {} reads {}",
+                name,
+                m.sankhya_offset,
+                digit_string(&m.words),
+                m.num_offset,
+                digit_string(&m.glyphs)
+            );
+        }
+        for issue in &out.framing_issues {
+            println!(
+                "{}: {} at offset {}",
+                name,
+                issue.kind.describe(),
+                issue.offset
+            );
+        }
+        for issue in &out.phonotactic_issues {
+            println!(
+                "{}: {} at offset {} ({} pada)",
+                name,
+                issue.message,
+                issue.offset,
+                issue.language.name()
+            );
+        }
+        for op in &out.operations {
+            println!("{}: applied {}", name, op);
+        }
+        if out.has_issues() {
+            flagged += 1;
+        }
+
+        if let Some(dir) = output.parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+        }
+        fs::write(&output, &out.container)
+            .with_context(|| format!("writing {}", output.display()))?;
+        eprintln!(
+            "{} ({}) → {}: {} verses, {} padas, {} bytes",
+            name,
+            out.script.name(),
+            output.display(),
+            out.stats.verses,
+            out.stats.padas,
+            out.container.len()
+        );
+        payloads.push(out.payload);
+    }
+
+    let stats =
+        pipeline::Stats::collect(payloads.iter().map(|p| p.as_slice())).map_err(parse_error)?;
+    println!("Documents:      {}", stats.documents);
+    println!("Verses:         {}", stats.verses);
+    println!("Padas:          {}", stats.padas);
+    println!("Distinct padas: {}", stats.distinct_padas);
+    println!("Akṣaras:        {}", stats.aksharas);
+    println!("Bytes:          {}", stats.bytes);
+
+    if flagged > 0 {
+        return Err(failure(
+            Failure::Validation,
+            format!("{} file(s) with unresolved validation issues", flagged),
+        ));
+    }
+    Ok(())
+}
+
+// ── Inspect ──
+
+fn cmd_inspect(
+    byte: Option<String>,
+    from_hex: Option<String>,
+    input: Option<PathBuf>,
+    json: bool,
+) -> Result<()> {
+    if let Some(byte_str) = byte {
+        let b = parse_hex_byte(&byte_str)?;
+        let info = inspect::inspect_byte(b);
+        if json {
+            println!("{}", inspect::byte_info_json(&info));
+        } else {
+            println!("{}", inspect::format_byte_info(&info));
+        }
+        return Ok(());
+    }
+
+    if let Some(hex_str) = from_hex {
+        let infos = inspect::inspect_hex_stream(&hex_str).map_err(parse_error)?;
+        if json {
+            println!("{}", byte_infos_json(&infos));
+            return Ok(());
+        }
+        for (i, info) in infos.iter().enumerate() {
+            if i > 0 {
+                println!("  ───");
+            }
+            println!("{}", inspect::format_byte_info(info));
+        }
+        return Ok(());
+    }
+
+    if let Some(path) = input {
+        let data = fs::read(&path).with_context(|| format!("reading {}", path.display()))?;
+
+        let (header, chunks) = container::parse_slbc(&data).map_err(parse_error)?;
+
+        if json {
+            let chunk_objs: Vec<String> = chunks
+                .iter()
+                .map(|chunk| {
+                    let bytes = if chunk.chunk_type == CHUNK_PHON {
+                        byte_infos_json(&inspect::inspect_stream(&chunk.payload))
+                    } else {
+                        "[]".to_string()
+                    };
+                    let name = chunk
+                        .kind()
+                        .name()
+                        .map_or("null".to_string(), |n| format!("\"{}\"", n));
+                    format!(
+                        "{{\"type\":{},\"name\":{},\"length\":{},\"bytes\":{}}}",
+                        chunk.chunk_type,
+                        name,
+                        chunk.payload.len(),
+                        bytes
+                    )
+                })
+                .collect();
+            println!(
+                "{{\"version\":{:?},\"flags\":{},\"extended_header_len\":{},\"chunks\":[{}]}}",
+                header.version,
+                header.flags,
+                header.extended_header_len,
+                chunk_objs.join(",")
+            );
+            return Ok(());
+        }
+
+        println!("=== SLBC Container ===");
+        println!(
+            "  Version: {}.{}.{}.{}",
+            header.version[0], header.version[1], header.version[2], header.version[3]
+        );
+        println!("  Flags:   0b{:08b} (0x{:02X})", header.flags, header.flags);
+        println!("    HAS_LIPI:     {}", header.has_lipi());
+        println!("    HAS_META:     {}", header.has_meta());
+        println!("    INTERLEAVED:  {}", header.is_interleaved());
+        println!("    VEDIC:        {}", header.is_vedic());
+        println!("    VYA:          {}", header.has_vya());
+        println!("  Extended header: {} bytes", header.extended_header_len);
+        if let Ok(Some(r)) = DictReference::locate(&header, &chunks) {
+            println!(
+                "  Dictionary: '{}' (id {}, hash {:016x})",
+                r.name, r.id, r.hash
+            );
+        }
+        for (n, op) in provenance::read(&header)
+            .map_err(parse_error)?
+            .iter()
+            .enumerate()
+        {
+            if n == 0 {
+                println!("  Provenance:");
+            }
+            println!("    {}. {}", n + 1, op);
+        }
+        println!("  Chunks: {}", chunks.len());
+
+        for (ci, chunk) in chunks.iter().enumerate() {
+            let kind = chunk.kind();
+            println!(
+                "\n  Chunk {} — {} (0x{:02X}), {} bytes payload",
+                ci,
+                kind.name().unwrap_or("???"),
+                chunk.chunk_type,
+                chunk.payload.len()
+            );
+
+            if chunk.chunk_type == CHUNK_META {
+                for (key, value) in MetaChunk::read(&chunk.payload)
+                    .map_err(parse_error)?
+                    .fields()
+                {
+                    println!("    {}: {}", key, value);
+                }
+            }
+            if chunk.chunk_type == CHUNK_EXT {
+                let ext = ExtChunk::from_chunk_payload(&chunk.payload).map_err(parse_error)?;
+                println!("    {}", extension::describe(&ext));
+            }
+            if chunk.chunk_type == CHUNK_ANVY {
+                let anvaya = Anvaya::from_chunk_payload(&chunk.payload).map_err(parse_error)?;
+                print_anvaya(&anvaya, &chunks)?;
+            }
+            if matches!(chunk.chunk_type, CHUNK_PHON | CHUNK_BHA) && !chunk.payload.is_empty() {
+                println!("    Bytes:");
+                for info in inspect::inspect_stream(&chunk.payload) {
+                    println!("      {:>4}  {}", info.hex, info.description);
+                }
+            }
+            if chunk.chunk_type == CHUNK_LIPI {
+                for (at, run) in layers::runs(&chunk.payload).map_err(parse_error)? {
+                    println!("    At bhāṣā offset {}:", at);
+                    for info in inspect::inspect_stream(run) {
+                        println!("      {:>4}  {}", info.hex, info.description);
+                    }
+                }
+            }
+        }
+
+        return Ok(());
+    }
+
+    bail!("provide --byte, --from-hex, or -i <file>");
+}
+
+/// Print an anvaya's sentences with the padas of the PHON chunk it
+/// describes; padas that do not decode on their own (dictionary
+/// references, packed codes) are shown by number.
+fn print_anvaya(anvaya: &Anvaya, chunks: &[Chunk]) -> Result<()> {
+    let phon = chunks
+        .iter()
+        .filter(|c| c.chunk_type == CHUNK_PHON)
+        .nth(anvaya.phon_chunk as usize)
+        .ok_or_else(|| {
+            anyhow::anyhow!("anvaya refers to missing PHON chunk {}", anvaya.phon_chunk)
+        })?;
+    let padas = lang::padas(&phon.payload).map_err(parse_error)?;
+    let word = |n: u32| {
+        padas
+            .get(n as usize)
+            .and_then(|(_, body)| {
+                let pada = [&[PADA_START], *body, &[PADA_END]].concat();
+                decoder::decode_phon(&pada, Script::Iast).ok()
+            })
+            .unwrap_or_else(|| format!("#{}", n + 1))
+    };
+
+    println!(
+        "    PHON chunk {}, {} sentence(s)",
+        anvaya.phon_chunk,
+        anvaya.sentences.len()
+    );
+    for (n, s) in anvaya.sentences.iter().enumerate() {
+        let first = s.padas.start;
+        println!(
+            "    Sentence {} (padas {}–{}): root {}",
+            n + 1,
+            first + 1,
+            s.padas.end,
+            word(first + s.root)
+        );
+        if !s.order.is_empty() {
+            let order: Vec<String> = s.order.iter().map(|&p| word(first + p)).collect();
+            println!("      anvaya: {}", order.join(" "));
+        }
+        for e in &s.edges {
+            println!(
+                "      {} → {} ({})",
+                word(first + e.dependent),
+                word(first + e.governor),
+                e.relation.name()
+            );
+        }
+    }
+    Ok(())
+}
+
+// ── Transform ──
+
+fn cmd_transform(
+    op: String,
+    byte_str: String,
+    byte2_str: Option<String>,
+    accent: String,
+    strict: bool,
+) -> Result<()> {
+    let b = parse_hex_byte(&byte_str)?;
+
+    let result = match op.as_str() {
+        "savarna-dirgha" => {
+            let b2_str = byte2_str.ok_or_else(|| anyhow::anyhow!("savarṇa-dīrgha requires two bytes"))?;
+            let b2 = parse_hex_byte(&b2_str)?;
+            let policy = transform::AccentPolicy::from_name(&accent).ok_or_else(|| {
+                anyhow::anyhow!("unknown accent policy '{}' (use first, second, udatta, equal)", accent)
+            })?;
+            let opts = transform::SandhiOptions {
+                accent: policy,
+                strict,
+            };
+            let r = transform::savarna_dirgha_with(b, b2, &opts)
+                .map_err(parse_error)?;
+            println!(
+                "{}: {} (0x{:02X} + 0x{:02X}) → {} (0x{:02X})",
+                r.transform.operation,
+                r.transform.input_iast,
+                r.operands[0],
+                r.operands[1],
+                r.transform.output_iast,
+                r.transform.output_byte
+            );
+            println!("  rule: {}, accent policy: {}", r.rule, r.accent_policy.name());
+            return Ok(());
+        }
+        "assimilate-nasal" => {
+            let b2_str = byte2_str
+                .ok_or_else(|| anyhow::anyhow!("nasal assimilation requires two bytes (nasal, following)"))?;
+            transform::assimilate_nasal(b, parse_hex_byte(&b2_str)?)
+        }
+        name => match transform::unary_op(name) {
+            Some(f) => f(b),
+            None => bail!("unknown operation '{}'\nValid: guna, vrddhi, dirgha, hrasva, jastva, toggle-voice, toggle-aspiration, nasal, homorganic-nasal, samprasarana-svara, samprasarana-sonorant, savarna-dirgha, assimilate-nasal", op),
+        },
+    }
+    .map_err(parse_error)?;
+
+    let output: Vec<String> = result
+        .output_bytes()
+        .iter()
+        .map(|b| format!("0x{:02X}", b))
+        .collect();
+    println!(
+        "{}: {} (0x{:02X}) → {} ({})",
+        result.operation,
+        result.input_iast,
+        result.input_byte,
+        result.output_iast,
+        output.join(" ")
+    );
+
+    Ok(())
+}
+
+// ── Dict ──
+
+fn cmd_dict(action: DictCommand) -> Result<()> {
+    match action {
+        DictCommand::Train { dir, o, size } => {
+            let payloads = read_corpus(&dir)?;
+            let pada_dict = dict::train(payloads.iter().map(|(_, p)| p.as_slice()), size)
+                .map_err(parse_error)?;
+            fs::write(&o, pada_dict.to_bytes())
+                .with_context(|| format!("writing {}", o.display()))?;
+            eprintln!(
+                "trained {} entries from {} file(s), hash {:016x}, wrote {}",
+                pada_dict.len(),
+                payloads.len(),
+                pada_dict.hash(),
+                o.display()
+            );
+        }
+        DictCommand::Show { i } => {
+            let pada_dict = read_dict(&i)?;
+            println!("Hash:    {:016x}", pada_dict.hash());
+            println!("Entries: {}", pada_dict.len());
+            for (n, entry) in pada_dict.entries().iter().enumerate() {
+                let iast: String = entry.iter().map(|&b| decoder::byte_to_iast(b)).collect();
+                println!("  {:>5}  {}", n, iast);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn read_dict(path: &Path) -> Result<PadaDict> {
+    let data = fs::read(path).with_context(|| format!("reading {}", path.display()))?;
+    PadaDict::from_bytes(&data)
+        .map_err(parse_error)
+        .with_context(|| path.display().to_string())
+}
+
+/// Collect one PHON payload per file in a corpus directory, recursively (or
+/// from a single file). `.slbc` files contribute their PHON chunks, with any
+/// pada-dictionary references expanded; other files are read as IAST.
+fn read_corpus(dir: &Path) -> Result<Vec<(PathBuf, Vec<u8>)>> {
+    let mut payloads = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(path) = pending.pop() {
+        if path.is_dir() {
+            let mut children: Vec<PathBuf> = fs::read_dir(&path)
+                .with_context(|| format!("reading {}", path.display()))?
+                .map(|e| e.map(|e| e.path()))
+                .collect::<std::io::Result<_>>()?;
+            children.sort();
+            pending.extend(children.into_iter().rev());
+            continue;
+        }
+        if path.extension().is_some_and(|e| e == "slbc") {
+            let data = fs::read(&path).with_context(|| format!("reading {}", path.display()))?;
+            let dirs = DictDirs(vec![path.parent().unwrap_or(Path::new(".")).to_path_buf()]);
+            let (_, chunks, _) = dict::open(&data, &dirs)
+                .map_err(parse_error)
+                .with_context(|| path.display().to_string())?;
+            let payload = chunks
+                .into_iter()
+                .filter(|c| c.chunk_type == CHUNK_PHON)
+                .flat_map(|c| c.payload)
+                .collect();
+            payloads.push((path, payload));
+        } else {
+            let text = read_text(&path)?;
+            let payload = encoder::encode_iast(text.trim())
+                .map_err(parse_error)
+                .with_context(|| path.display().to_string())?;
+            payloads.push((path, payload));
+        }
+    }
+    Ok(payloads)
+}
+
+// ── Validate ──
+
+fn cmd_validate(
+    input: PathBuf,
+    repair: Option<String>,
+    output: Option<PathBuf>,
+    phonotactics: bool,
+    normalize: bool,
+) -> Result<()> {
+    let data = fs::read(&input).with_context(|| format!("reading {}", input.display()))?;
+    let (header, chunks) = container::parse_slbc(&data).map_err(parse_error)?;
+    let chunks = layers::merge_chunks(chunks).map_err(parse_error)?;
+
+    let source = parse_repair_source(repair.as_deref())?;
+
+    let mut problems = 0;
+    let mut phonotactic_issues = 0;
+    let mut framing_issues = 0;
+    for (ci, chunk) in chunks.iter().enumerate() {
+        if chunk.chunk_type != CHUNK_PHON {
+            continue;
+        }
+        let mismatches = numeral::validate_numerals(&chunk.payload).map_err(parse_error)?;
+        for m in &mismatches {
+            println!(
+                "chunk {}: numeral mismatch — SAṄKHYĀ at offset {} reads {}, NUM at offset {} reads {}",
+                ci,
+                m.sankhya_offset,
+                digit_string(&m.words),
+                m.num_offset,
+                digit_string(&m.glyphs)
+            );
+        }
+        problems += mismatches.len();
+
+        let issues = frame::check_framing(&chunk.payload).map_err(parse_error)?;
+        for issue in &issues {
+            println!(
+                "chunk {}: {} at offset {}",
+                ci,
+                issue.kind.describe(),
+                issue.offset
+            );
+        }
+        framing_issues += issues.len();
+        // Balanced PHON frames must still nest around whole padas
+        if issues.is_empty() {
+            frame::phon_spans(&chunk.payload)
+                .map_err(|e| located(Failure::Validation, e))
+                .with_context(|| format!("chunk {}", ci))?;
+        }
+
+        if phonotactics {
+            let issues = lang::check_phonotactics(&chunk.payload).map_err(parse_error)?;
+            for issue in &issues {
+                println!(
+                    "chunk {}: {} at offset {} ({} pada)",
+                    ci,
+                    issue.message,
+                    issue.offset,
+                    issue.language.name()
+                );
+            }
+            phonotactic_issues += issues.len();
+        }
+    }
+
+    if source.is_none() && !normalize {
+        if problems > 0 {
+            let message = format!("{} numeral mismatch(es)", problems);
+            return Err(failure(Failure::Validation, message));
+        }
+        if framing_issues > 0 {
+            let message = format!(
+                "{} framing issue(s) (rewrite with --normalize)",
+                framing_issues
+            );
+            return Err(failure(Failure::Validation, message));
+        }
+        if phonotactic_issues > 0 {
+            let message = format!("{} phonotactic issue(s)", phonotactic_issues);
+            return Err(failure(Failure::Validation, message));
+        }
+        println!("✓ {} valid", input.display());
+        return Ok(());
+    }
+
+    let mut body = Vec::new();
+    let mut fixed = 0;
+    let mut normalized = 0;
+    for chunk in &chunks {
+        if chunk.chunk_type == CHUNK_PHON {
+            let mut payload = chunk.payload.clone();
+            if normalize {
+                let (clean, n) = frame::normalize_framing(&payload).map_err(parse_error)?;
+                payload = clean;
+                normalized += n;
+            }
+            if let Some(source) = source {
+                let (repaired, n) =
+                    numeral::repair_numerals(&payload, source).map_err(parse_error)?;
+                payload = repaired;
+                fixed += n;
+            }
+            if header.is_interleaved() {
+                container::write_chunk(&mut body, chunk.chunk_type, &payload);
+            } else {
+                let (bha, lipi) = layers::split(&payload).map_err(parse_error)?;
+                container::write_chunk(&mut body, CHUNK_BHA, &bha);
+                container::write_chunk(&mut body, CHUNK_LIPI, &lipi);
+            }
+        } else {
+            container::write_chunk(&mut body, chunk.chunk_type, &chunk.payload);
+        }
+    }
+
+    let mut ops = Vec::new();
+    if normalize {
+        ops.push(Operation::new("normalize-framing").param("sites", normalized));
+    }
+    if let Some(name) = &repair {
+        ops.push(
+            Operation::new("repair-numerals")
+                .param("source", name)
+                .param("repaired", fixed),
+        );
+    }
+    let mut out = provenance::rewrite_header(&header, &ops).map_err(parse_error)?;
+    out.extend_from_slice(&body);
+
+    let path = output.unwrap_or(input);
+    fs::write(&path, &out).with_context(|| format!("writing {}", path.display()))?;
+    if source.is_some() {
+        eprintln!("repaired {} numeral(s)", fixed);
+    }
+    if normalize {
+        eprintln!("normalized {} framing site(s)", normalized);
+    }
+    eprintln!("wrote {}", path.display());
+
+    Ok(())
+}
+
+fn digit_string(digits: &[u8]) -> String {
+    digits
+        .iter()
+        .map(|&d| char::from_digit(d as u32, 16).unwrap_or('?'))
+        .collect()
+}
+
+// ── Verify ──
+
+fn cmd_verify(input: PathBuf, fast: bool, verses: Option<String>) -> Result<()> {
+    let wanted = verses.as_deref().map(parse_verse_list).transpose()?;
+    let checked = if fast {
+        let file =
+            fs::File::open(&input).with_context(|| format!("opening {}", input.display()))?;
+        let report = checksum::verify_fast(std::io::BufReader::new(file), wanted.as_deref())
+            .map_err(parse_error)?;
+        eprintln!("read {} byte(s)", report.bytes_read);
+        report.checked
+    } else {
+        let data = fs::read(&input).with_context(|| format!("reading {}", input.display()))?;
+        let (_header, chunks) = container::parse_slbc(&data).map_err(parse_error)?;
+        let chunks = layers::merge_chunks(chunks).map_err(parse_error)?;
+        let sums = VerseSums::find(&chunks)
+            .map_err(parse_error)?
+            .ok_or_else(|| anyhow::anyhow!("no verse checksums (encode with --verse-sums)"))?;
+        let payload = &chunks
+            .iter()
+            .filter(|c| c.chunk_type == CHUNK_PHON)
+            .nth(sums.phon_chunk as usize)
+            .ok_or_else(|| anyhow::anyhow!("missing PHON chunk {}", sums.phon_chunk))?
+            .payload;
+        let bad = sums.mismatches(payload);
+        let all: Vec<usize> = (0..sums.verses.len()).collect();
+        let mut checked = Vec::new();
+        for &n in wanted.as_deref().unwrap_or(&all) {
+            if n >= sums.verses.len() {
+                bail!(
+                    "verse {} out of range ({} verses)",
+                    n + 1,
+                    sums.verses.len()
+                );
+            }
+            checked.push((n, !bad.contains(&n)));
+        }
+        checked
+    };
+
+    let failed: Vec<usize> = checked.iter().filter(|c| !c.1).map(|c| c.0 + 1).collect();
+    for n in &failed {
+        println!("verse {}: checksum mismatch", n);
+    }
+    if !failed.is_empty() {
+        let message = format!("{} of {} verse(s) failed", failed.len(), checked.len());
+        return Err(failure(Failure::Validation, message));
+    }
+    println!("✓ {} verse(s) verified", checked.len());
+    Ok(())
+}
+
+/// Check one container for `verify --dir`. A panic in the checks fails
+/// this file, not the whole sweep.
+fn check_file(path: &Path) -> Result<pipeline::Checked, SlbcError> {
+    let data = fs::read(path).map_err(|e| SlbcError::Io {
+        message: e.to_string(),
+    })?;
+    let dirs = DictDirs(vec![path.parent().unwrap_or(Path::new(".")).to_path_buf()]);
+    std::panic::catch_unwind(|| pipeline::check(&data, &dirs))
+        .unwrap_or_else(|_| Err(SlbcError::invalid("internal error in the checks (a bug)")))
+}
+
+/// Check every container under `dir` on `jobs` threads and report each
+/// failing file, then totals.
+fn cmd_verify_dir(dir: PathBuf, jobs: Option<usize>) -> Result<()> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.clone()];
+    while let Some(path) = pending.pop() {
+        if path.is_dir() {
+            let mut children: Vec<PathBuf> = fs::read_dir(&path)
+                .with_context(|| format!("reading {}", path.display()))?
+                .map(|e| e.map(|e| e.path()))
+                .collect::<std::io::Result<_>>()?;
+            children.sort();
+            pending.extend(children.into_iter().rev());
+        } else if path.extension().is_some_and(|e| e == "slbc") {
+            files.push(path);
+        }
+    }
+    if files.is_empty() {
+        bail!("no .slbc files under {}", dir.display());
+    }
+
+    let jobs = jobs
+        .unwrap_or_else(|| std::thread::available_parallelism().map_or(1, |n| n.get()))
+        .clamp(1, files.len());
+    let started = std::time::Instant::now();
+    // Workers take the next file by index; results are reported in file order
+    let next = AtomicUsize::new(0);
+    let mut results: Vec<(usize, Result<pipeline::Checked, SlbcError>)> = std::thread::scope(|s| {
+        let workers: Vec<_> = (0..jobs)
+            .map(|_| {
+                s.spawn(|| {
+                    let mut done = Vec::new();
+                    loop {
+                        let n = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(path) = files.get(n) else {
+                            return done;
+                        };
+                        done.push((n, check_file(path)));
+                    }
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|w| w.join().expect("verify worker panicked"))
+            .collect()
+    });
+    results.sort_by_key(|r| r.0);
+
+    let (mut unreadable, mut checksums, mut invalid, mut roundtrip, mut unsummed) = (0, 0, 0, 0, 0);
+    let mut bytes = 0;
+    for (n, result) in &results {
+        let name = files[*n].display();
+        let checked = match result {
+            Ok(checked) => checked,
+            Err(e) => {
+                println!("{}: unreadable: {}", name, e);
+                unreadable += 1;
+                continue;
+            }
+        };
+        bytes += checked.stats.bytes;
+        match &checked.checksum_mismatches {
+            Some(bad) => {
+                for v in bad {
+                    println!("{}: verse {}: checksum mismatch", name, v + 1);
+                }
+                checksums += usize::from(!bad.is_empty());
+            }
+            None => unsummed += 1,
+        }
+        for m in &checked.numeral_mismatches {
+            println!(
+                "{}: numeral mismatch — SAṄKHYĀ at offset {} reads {}, NUM at offset {} reads {}",
+                name,
+                m.sankhya_offset,
+                digit_string(&m.words),
+                m.num_offset,
+                digit_string(&m.glyphs)
+            );
+        }
+        for issue in &checked.framing_issues {
+            println!(
+                "{}: {} at offset {}",
+                name,
+                issue.kind.describe(),
+                issue.offset
+            );
+        }
+        for (chunk, e) in &checked.frame_errors {
+            println!("{}: chunk {}: {}", name, chunk, e);
+        }
+        invalid += usize::from(
+            !checked.numeral_mismatches.is_empty()
+                || !checked.framing_issues.is_empty()
+                || !checked.frame_errors.is_empty(),
+        );
+        for (chunk, e) in &checked.roundtrip_failures {
+            println!("{}: chunk {}: roundtrip failed: {}", name, chunk, e);
+        }
+        roundtrip += usize::from(!checked.roundtrip_failures.is_empty());
+    }
+
+    let failed = results
+        .iter()
+        .filter(|(_, r)| r.as_ref().map_or(true, |c| c.has_issues()))
+        .count();
+    println!("Files:             {}", files.len());
+    println!("Passed:            {}", files.len() - failed);
+    println!("Unreadable:        {}", unreadable);
+    println!("Checksum failures: {}", checksums);
+    println!("Invalid:           {}", invalid);
+    println!("Roundtrip failed:  {}", roundtrip);
+    println!("Without checksums: {}", unsummed);
+    println!("PHON bytes:        {}", bytes);
+    eprintln!(
+        "checked {} file(s) on {} thread(s) in {:.1?}",
+        files.len(),
+        jobs,
+        started.elapsed()
+    );
+
+    if failed > 0 {
+        let message = format!("{} of {} file(s) failed", failed, files.len());
+        return Err(failure(Failure::Validation, message));
+    }
+    println!("✓ {} container(s) verified", files.len());
+    Ok(())
+}
 
-    /// Round-trip test: encode IAST → .slbc → decode IAST and compare
-    Roundtrip {
-        /// IAST text to test
-        text: String,
-    },
+/// Parse "1,5-9" into 0-based verse indices.
+fn parse_verse_list(list: &str) -> Result<Vec<usize>> {
+    let mut out = Vec::new();
+    for part in list.split(',').map(str::trim) {
+        let (lo, hi) = part.split_once('-').unwrap_or((part, part));
+        let parse = |s: &str| -> Result<usize> {
+            match s.trim().parse::<usize>() {
+                Ok(n) if n > 0 => Ok(n - 1),
+                _ => bail!("invalid verse number '{}' (verses are 1-based)", s),
+            }
+        };
+        out.extend(parse(lo)?..=parse(hi)?);
+    }
+    Ok(out)
 }
 
-fn main() -> Result<()> {
-    let cli = Cli::parse();
+// ── Matrix ──
 
-    match cli.command {
-        Command::Encode { text, i, o, hex } => cmd_encode(text, i, o, hex),
-        Command::Decode { i, to, o } => cmd_decode(i, to, o),
-        Command::Inspect { byte, from_hex, i } => cmd_inspect(byte, from_hex, i),
-        Command::Transform { op, byte, byte2 } => cmd_transform(op, byte, byte2),
-        Command::Roundtrip { text } => cmd_roundtrip(text),
+fn cmd_matrix(output: Option<PathBuf>) -> Result<()> {
+    let rows = transform::coverage_matrix();
+    let table = transform::format_matrix(&rows);
+
+    match output {
+        Some(path) => {
+            fs::write(&path, &table).with_context(|| format!("writing {}", path.display()))?;
+            eprintln!("wrote {} rows to {}", rows.len(), path.display());
+        }
+        None => print!("{}", table),
     }
+
+    Ok(())
 }
 
-// ── Encode ──
+// ── Akṣara ──
 
-fn cmd_encode(
-    text: Option<String>,
-    input: Option<PathBuf>,
-    output: Option<PathBuf>,
-    hex: bool,
-) -> Result<()> {
-    let iast = match (text, input) {
-        (Some(t), _) => t,
-        (None, Some(path)) => {
-            fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?
+fn cmd_akshara(input: PathBuf, query: Option<String>, top: usize) -> Result<()> {
+    let data = fs::read(&input).with_context(|| format!("reading {}", input.display()))?;
+    let (_header, chunks) = container::parse_slbc(&data).map_err(parse_error)?;
+    let index = AksharaIndex::find(&chunks)
+        .map_err(parse_error)?
+        .ok_or_else(|| anyhow::anyhow!("{} has no akṣara index", input.display()))?;
+
+    let Some(query) = query else {
+        for (key, offsets) in index.entries.iter().take(top) {
+            println!("{:>6}  {}", offsets.len(), pada_iast(key)?);
         }
-        (None, None) => bail!("provide IAST text or -i <file>"),
+        return Ok(());
     };
 
-    let iast = iast.trim();
-    let phon_payload = encoder::encode_iast(iast).map_err(|e| anyhow::anyhow!("{}", e))?;
-    let slbc_data = container::build_slbc(&phon_payload);
+    let encoded = encoder::encode_iast(query.trim()).map_err(parse_error)?;
+    let key = match encoded.as_slice() {
+        [PADA_START, key @ .., PADA_END] => key,
+        _ => bail!("'{}' is not a single akṣara", query),
+    };
+    let offsets = index.occurrences(key);
+    println!("{}: {} occurrence(s)", query.trim(), offsets.len());
+    for o in offsets {
+        println!("  offset {}", o);
+    }
+    Ok(())
+}
 
-    if hex {
-        print_hex(&slbc_data);
+// ── Stats ──
+
+fn cmd_stats(
+    input: PathBuf,
+    words: bool,
+    top: Option<usize>,
+    accents: bool,
+    quotations: bool,
+) -> Result<()> {
+    let docs = read_corpus(&input)?;
+    if accents {
+        return accent_stats(&docs);
+    }
+    if quotations {
+        return quotation_stats(&docs);
+    }
+    let freqs =
+        analysis::word_frequencies(docs.iter().map(|(_, d)| d.as_slice())).map_err(parse_error)?;
+
+    if !words {
+        println!("Documents:      {}", docs.len());
+        println!(
+            "Padas:          {}",
+            freqs.iter().map(|w| w.count).sum::<usize>()
+        );
+        println!("Distinct padas: {}", freqs.len());
+        println!(
+            "Bytes:          {}",
+            docs.iter().map(|(_, d)| d.len()).sum::<usize>()
+        );
         return Ok(());
     }
 
-    match output {
-        Some(path) => {
-            fs::write(&path, &slbc_data).with_context(|| format!("writing {}", path.display()))?;
-            eprintln!("wrote {} bytes to {}", slbc_data.len(), path.display());
+    println!("pada\tcount\tdocuments");
+    for w in freqs.iter().take(top.unwrap_or(usize::MAX)) {
+        println!("{}\t{}\t{}", pada_iast(&w.pada)?, w.count, w.documents);
+    }
+    Ok(())
+}
+
+fn accent_stats(docs: &[(PathBuf, Vec<u8>)]) -> Result<()> {
+    let mut by_accent = [0usize; 4];
+    let mut mixed = 0;
+    for (path, data) in docs {
+        let report = analysis::accent_report(data).map_err(parse_error)?;
+        for m in &report.mixed {
+            println!(
+                "{} offset {}: {} ({} accented, {} unaccented)",
+                path.display(),
+                m.offset,
+                pada_iast(&m.pada)?,
+                m.accented,
+                m.unaccented
+            );
         }
-        None => {
-            print_hex(&slbc_data);
+        mixed += report.mixed.len();
+        for (total, n) in by_accent.iter_mut().zip(report.by_accent) {
+            *total += n;
         }
     }
 
+    let total = analysis::AccentReport {
+        by_accent,
+        mixed: Vec::new(),
+    };
+    println!(
+        "Svaras: {}, accented: {} ({:.1}%)",
+        total.svaras(),
+        total.accented(),
+        total.coverage() * 100.0
+    );
+    println!(
+        "  udātta {}, anudātta {}, svarita {}, unaccented {}",
+        by_accent[1], by_accent[2], by_accent[3], by_accent[0]
+    );
+    println!("Padas mixing accented and unaccented svaras: {}", mixed);
     Ok(())
 }
 
-// ── Decode ──
+fn quotation_stats(docs: &[(PathBuf, Vec<u8>)]) -> Result<()> {
+    let mut total = 0;
+    for (path, data) in docs {
+        let quotes = analysis::quotations(data).map_err(parse_error)?;
+        for q in &quotes {
+            let text =
+                decoder::decode_phon(&data[q.body.clone()], Script::Iast).map_err(parse_error)?;
+            println!(
+                "{} offset {}:{} {}",
+                path.display(),
+                q.body.start - 1,
+                "  ".repeat(q.depth),
+                text.trim()
+            );
+        }
+        total += quotes.len();
+    }
+    println!("Quotations: {}", total);
+    Ok(())
+}
 
-fn cmd_decode(input: PathBuf, to: String, output: Option<PathBuf>) -> Result<()> {
-    let data = fs::read(&input).with_context(|| format!("reading {}", input.display()))?;
+// ── Grep ──
 
-    let (_header, chunks) = container::parse_slbc(&data).map_err(|e| anyhow::anyhow!("{}", e))?;
+fn cmd_grep(input: PathBuf, query: String, context: usize, lemmas: Option<PathBuf>) -> Result<()> {
+    let data = fs::read(&input).with_context(|| format!("reading {}", input.display()))?;
+    let dirs = DictDirs(vec![input.parent().unwrap_or(Path::new(".")).to_path_buf()]);
+    let (_header, chunks, _) = dict::open(&data, &dirs).map_err(parse_error)?;
 
-    let script = match to.as_str() {
-        "iast" => Script::Iast,
-        "devanagari" | "deva" => Script::Devanagari,
-        _ => bail!("unknown script '{}' (use 'iast' or 'devanagari')", to),
+    let table = match &lemmas {
+        Some(path) => {
+            let tsv = read_text(path)?;
+            Some(LemmaTable::from_tsv(&tsv).map_err(parse_error)?)
+        }
+        None => None,
     };
+    let lemmatizer: &dyn Lemmatizer = match &table {
+        Some(t) => t,
+        None => &NoLemmas,
+    };
+    let key = search::query_pada(&query).map_err(parse_error)?;
 
-    let mut full_text = String::new();
-    for chunk in &chunks {
-        if chunk.chunk_type == CHUNK_PHON {
-            let text = decoder::decode_phon(&chunk.payload, script)
-                .map_err(|e| anyhow::anyhow!("{}", e))?;
-            full_text.push_str(&text);
+    let text = |b: &[u8]| decoder::decode_phon(b, Script::Iast).map_err(parse_error);
+    let mut total = 0;
+    for (ci, chunk) in chunks.iter().enumerate() {
+        if chunk.chunk_type != CHUNK_PHON {
+            continue;
+        }
+        let hits = search::grep(&chunk.payload, &key, lemmatizer).map_err(parse_error)?;
+        let lines = search::concordance(&chunk.payload, &hits, context).map_err(parse_error)?;
+        for line in &lines {
+            println!(
+                "chunk {} offset {:>6}: {}[{}]{}",
+                ci,
+                line.hit.offset,
+                text(line.left)?,
+                text(line.keyword)?,
+                text(line.right)?
+            );
         }
+        total += hits.len();
     }
+    eprintln!("{} match(es)", total);
+    Ok(())
+}
 
-    match output {
-        Some(path) => {
-            fs::write(&path, &full_text).with_context(|| format!("writing {}", path.display()))?;
-            eprintln!("wrote {} chars to {}", full_text.len(), path.display());
-        }
-        None => {
-            println!("{}", full_text);
+// ── Preview ──
+
+fn cmd_preview(input: PathBuf, scripts: Vec<String>) -> Result<()> {
+    let scripts = scripts
+        .iter()
+        .map(|name| parse_script(name.trim()))
+        .collect::<Result<Vec<_>>>()?;
+    let data = fs::read(&input).with_context(|| format!("reading {}", input.display()))?;
+    let dirs = DictDirs(vec![input.parent().unwrap_or(Path::new(".")).to_path_buf()]);
+    let (_header, chunks, _) = dict::open(&data, &dirs).map_err(parse_error)?;
+
+    let width = scripts.iter().map(|s| s.name().len()).max().unwrap_or(0);
+    let mut n = 0;
+    for chunk in chunks.iter().filter(|c| c.chunk_type == CHUNK_PHON) {
+        for range in frame::verses(&chunk.payload).map_err(parse_error)? {
+            n += 1;
+            if n > 1 {
+                println!();
+            }
+            println!("[{}]", n);
+            for &script in &scripts {
+                let text = decoder::decode_phon(&chunk.payload[range.clone()], script)
+                    .map_err(parse_error)?;
+                println!("  {:<width$}  {}", script.name(), text.trim_end());
+            }
         }
     }
-
     Ok(())
 }
 
-// ── Inspect ──
+// ── Fidelity ──
 
-fn cmd_inspect(
-    byte: Option<String>,
-    from_hex: Option<String>,
-    input: Option<PathBuf>,
-) -> Result<()> {
-    if let Some(byte_str) = byte {
-        let b = parse_hex_byte(&byte_str)?;
-        let info = inspect::inspect_byte(b);
-        println!("{}", inspect::format_byte_info(&info));
-        return Ok(());
+fn cmd_fidelity(input: PathBuf, scripts: Vec<String>) -> Result<()> {
+    let scripts = match scripts.is_empty() {
+        true => Script::ALL.to_vec(),
+        false => scripts
+            .iter()
+            .map(|name| parse_script(name.trim()))
+            .collect::<Result<Vec<_>>>()?,
+    };
+    let data = fs::read(&input).with_context(|| format!("reading {}", input.display()))?;
+    let dirs = DictDirs(vec![input.parent().unwrap_or(Path::new(".")).to_path_buf()]);
+    let (_header, chunks, _) = dict::open(&data, &dirs).map_err(parse_error)?;
+    let payload: Vec<u8> = chunks
+        .iter()
+        .filter(|c| c.chunk_type == CHUNK_PHON)
+        .flat_map(|c| c.payload.iter().copied())
+        .collect();
+
+    let opts = DecodeOptions::default();
+    for &script in &scripts {
+        let losses = fidelity::losses(&payload, script, &opts).map_err(parse_error)?;
+        if losses.is_empty() {
+            println!("{:<14} lossless", script.name());
+            continue;
+        }
+        println!("{:<14} degrades", script.name());
+        for loss in losses {
+            println!(
+                "  {:<12} x{:<5} {}",
+                loss.unit.label(),
+                loss.count,
+                loss.degradation
+            );
+        }
     }
+    Ok(())
+}
 
-    if let Some(hex_str) = from_hex {
-        let infos = inspect::inspect_hex_stream(&hex_str).map_err(|e| anyhow::anyhow!("{}", e))?;
-        for (i, info) in infos.iter().enumerate() {
-            if i > 0 {
-                println!("  ───");
-            }
-            println!("{}", inspect::format_byte_info(info));
+// ── Timing ──
+
+fn cmd_timing(input: PathBuf, output: Option<PathBuf>) -> Result<()> {
+    let data = fs::read(&input).with_context(|| format!("reading {}", input.display()))?;
+    let dirs = DictDirs(vec![input.parent().unwrap_or(Path::new(".")).to_path_buf()]);
+    let (_header, chunks, _) = dict::open(&data, &dirs).map_err(parse_error)?;
+    let payload: Vec<u8> = chunks
+        .iter()
+        .filter(|c| c.chunk_type == CHUNK_PHON)
+        .flat_map(|c| c.payload.iter().copied())
+        .collect();
+
+    let track = analysis::timing(&payload).map_err(parse_error)?;
+    let json = analysis::timing_json(&payload, &track).map_err(parse_error)?;
+    match output {
+        Some(path) => {
+            fs::write(&path, json + "\n").with_context(|| format!("writing {}", path.display()))?;
+            eprintln!("wrote {} akṣaras to {}", track.len(), path.display());
         }
-        return Ok(());
+        None => println!("{}", json),
     }
+    Ok(())
+}
 
-    if let Some(path) = input {
-        let data = fs::read(&path).with_context(|| format!("reading {}", path.display()))?;
+// ── Proof ──
 
-        let (header, chunks) =
-            container::parse_slbc(&data).map_err(|e| anyhow::anyhow!("{}", e))?;
+fn cmd_proof(input: PathBuf, against: PathBuf, scheme: String) -> Result<()> {
+    let script = parse_script(&scheme)?;
+    let original = read_text(&against)?;
+    let data = fs::read(&input).with_context(|| format!("reading {}", input.display()))?;
+    let dirs = DictDirs(vec![input.parent().unwrap_or(Path::new(".")).to_path_buf()]);
+    let (_header, chunks, _) = dict::open(&data, &dirs).map_err(parse_error)?;
 
-        println!("=== SLBC Container ===");
+    let mut decoded = String::new();
+    for chunk in chunks.iter().filter(|c| c.chunk_type == CHUNK_PHON) {
+        decoded.push_str(&decoder::decode_phon(&chunk.payload, script).map_err(parse_error)?);
+    }
+
+    let divergences = proof::align(&original, &decoded);
+    for d in &divergences {
         println!(
-            "  Version: {}.{}.{}.{}",
-            header.version[0], header.version[1], header.version[2], header.version[3]
+            "{}:{} (decoded {}:{}): expected {:?}, found {:?}",
+            d.original.line,
+            d.original.column,
+            d.decoded.line,
+            d.decoded.column,
+            d.expected,
+            d.found
         );
-        println!("  Flags:   0b{:08b} (0x{:02X})", header.flags, header.flags);
-        println!("    HAS_LIPI:     {}", header.has_lipi());
-        println!("    HAS_META:     {}", header.has_meta());
-        println!("    INTERLEAVED:  {}", header.is_interleaved());
-        println!("    VEDIC:        {}", header.is_vedic());
-        println!("    VYA:          {}", header.has_vya());
-        println!("  Extended header: {} bytes", header.extended_header_len);
-        println!("  Chunks: {}", chunks.len());
+    }
+    if !divergences.is_empty() {
+        let message = format!(
+            "{} divergence(s) from {}",
+            divergences.len(),
+            against.display()
+        );
+        return Err(failure(Failure::Roundtrip, message));
+    }
+    println!("✓ {} matches {}", input.display(), against.display());
+    Ok(())
+}
 
-        for (ci, chunk) in chunks.iter().enumerate() {
-            let type_name = match chunk.chunk_type {
-                CHUNK_PHON => "PHON",
-                CHUNK_BHA => "BHA",
-                CHUNK_LIPI => "LIPI",
-                CHUNK_META => "META",
-                CHUNK_DICT => "DICT",
-                CHUNK_IDX => "IDX",
-                CHUNK_ANVY => "ANVY",
-                CHUNK_EXT => "EXT",
-                CHUNK_EOF => "EOF",
-                _ => "???",
-            };
-            println!(
-                "\n  Chunk {} — {} (0x{:02X}), {} bytes payload",
-                ci,
-                type_name,
-                chunk.chunk_type,
-                chunk.payload.len()
-            );
+// ── Scheme ──
 
-            if chunk.chunk_type == CHUNK_PHON && !chunk.payload.is_empty() {
-                println!("    Bytes:");
-                for &b in &chunk.payload {
-                    let info = inspect::inspect_byte(b);
-                    println!("      {:>4}  {}", info.hex, info.description);
-                }
-            }
+fn cmd_scheme(name: Option<String>, json: bool) -> Result<()> {
+    let Some(name) = name else {
+        for s in scheme::SCHEMES {
+            println!("{:<8} {} ({} entries)", s.name, s.label, s.table.len());
         }
-
         return Ok(());
+    };
+    let s = scheme::scheme(&name).ok_or_else(|| anyhow::anyhow!("unknown scheme '{}'", name))?;
+    if json {
+        println!("{}", s.to_json());
+    } else {
+        print!("{}", s.to_tsv());
     }
-
-    bail!("provide --byte, --from-hex, or -i <file>");
+    Ok(())
 }
 
-// ── Transform ──
+// ── Golden ──
 
-fn cmd_transform(op: String, byte_str: String, byte2_str: Option<String>) -> Result<()> {
-    let b = parse_hex_byte(&byte_str)?;
+fn cmd_golden(corpus: Option<PathBuf>) -> Result<()> {
+    let tsv = match &corpus {
+        Some(path) => read_text(path)?,
+        None => golden::BUNDLED.to_string(),
+    };
+    let pairs = golden::parse_pairs(&tsv).map_err(parse_error)?;
+    let divergences = golden::check(&pairs);
 
-    let result = match op.as_str() {
-        "guna" => transform::guna(b),
-        "vrddhi" => transform::vrddhi(b),
-        "dirgha" => transform::dirgha(b),
-        "hrasva" => transform::hrasva(b),
-        "jastva" => transform::jastva(b),
-        "toggle-voice" => transform::toggle_voice(b),
-        "toggle-aspiration" => transform::toggle_aspiration(b),
-        "nasal" => transform::make_nasal(b),
-        "homorganic-nasal" => transform::homorganic_nasal(b),
-        "samprasarana-svara" => transform::samprasarana_to_svara(b),
-        "samprasarana-sonorant" => transform::samprasarana_to_sonorant(b),
-        "savarna-dirgha" => {
-            let b2_str = byte2_str.ok_or_else(|| anyhow::anyhow!("savarṇa-dīrgha requires two bytes"))?;
-            let b2 = parse_hex_byte(&b2_str)?;
-            transform::savarna_dirgha(b, b2)
+    for d in &divergences {
+        println!("line {} ({}), {}:", d.line, d.source, d.direction.name());
+        println!("  expected: {}", d.expected);
+        match &d.actual {
+            Ok(text) => println!("  actual:   {}", text),
+            Err(e) => println!("  error:    {}", e),
         }
-        _ => bail!("unknown operation '{}'\nValid: guna, vrddhi, dirgha, hrasva, jastva, toggle-voice, toggle-aspiration, nasal, homorganic-nasal, samprasarana-svara, samprasarana-sonorant, savarna-dirgha", op),
     }
-    .map_err(|e| anyhow::anyhow!("{}", e))?;
-
-    println!(
-        "{}: {} (0x{:02X}) → {} (0x{:02X})",
-        result.operation,
-        result.input_iast,
-        result.input_byte,
-        result.output_iast,
-        result.output_byte
-    );
 
+    if !divergences.is_empty() {
+        let message = format!(
+            "{} divergence(s) in {} pair(s)",
+            divergences.len(),
+            pairs.len()
+        );
+        return Err(failure(Failure::Roundtrip, message));
+    }
+    println!("✓ {} pair(s) agree", pairs.len());
     Ok(())
 }
 
@@ -303,7 +2498,7 @@ fn cmd_roundtrip(text: String) -> Result<()> {
     eprintln!("Input (IAST):  {}", input);
 
     // Encode
-    let phon_payload = encoder::encode_iast(input).map_err(|e| anyhow::anyhow!("{}", e))?;
+    let phon_payload = encoder::encode_iast(input).map_err(parse_error)?;
     let slbc_data = container::build_slbc(&phon_payload);
 
     eprintln!("Encoded:       {} bytes (.slbc container)", slbc_data.len());
@@ -314,13 +2509,12 @@ fn cmd_roundtrip(text: String) -> Result<()> {
     eprintln!();
 
     // Decode back to IAST
-    let (_, chunks) = container::parse_slbc(&slbc_data).map_err(|e| anyhow::anyhow!("{}", e))?;
+    let (_, chunks) = container::parse_slbc(&slbc_data).map_err(parse_error)?;
 
     let mut decoded = String::new();
     for chunk in &chunks {
         if chunk.chunk_type == CHUNK_PHON {
-            let text = decoder::decode_phon(&chunk.payload, Script::Iast)
-                .map_err(|e| anyhow::anyhow!("{}", e))?;
+            let text = decoder::decode_phon(&chunk.payload, Script::Iast).map_err(parse_error)?;
             decoded.push_str(&text);
         }
     }
@@ -331,8 +2525,8 @@ fn cmd_roundtrip(text: String) -> Result<()> {
     let mut deva = String::new();
     for chunk in &chunks {
         if chunk.chunk_type == CHUNK_PHON {
-            let text = decoder::decode_phon(&chunk.payload, Script::Devanagari)
-                .map_err(|e| anyhow::anyhow!("{}", e))?;
+            let text =
+                decoder::decode_phon(&chunk.payload, Script::Devanagari).map_err(parse_error)?;
             deva.push_str(&text);
         }
     }
@@ -344,14 +2538,261 @@ fn cmd_roundtrip(text: String) -> Result<()> {
         eprintln!("\n✗ Round-trip FAILED");
         eprintln!("  expected: {:?}", input);
         eprintln!("  got:      {:?}", decoded);
-        std::process::exit(1);
+        return Err(failure(Failure::Roundtrip, "round-trip mismatch"));
     }
 
     Ok(())
 }
 
+// ── Failures ──
+//
+// Exit codes are stable so that scripts can branch on them: 0 on success,
+// 1 for any other error, 2 for a command-line usage error (from clap), and
+// one code per `Failure` class.
+
+/// A class of failure with its own exit code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Failure {
+    /// Input text or a container could not be read: an unrecognized
+    /// character, malformed bytes, a truncated chunk.
+    Parse,
+    /// `validate` or `verify` found problems in a well-formed container.
+    Validation,
+    /// Decoded text differs from what it should be (`roundtrip`, `proof`,
+    /// `golden`).
+    Roundtrip,
+    /// A file could not be read or written.
+    Io,
+}
+
+impl Failure {
+    fn exit_code(self) -> u8 {
+        match self {
+            Failure::Parse => 3,
+            Failure::Validation => 4,
+            Failure::Roundtrip => 5,
+            Failure::Io => 6,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Failure::Parse => "parse",
+            Failure::Validation => "validation",
+            Failure::Roundtrip => "roundtrip",
+            Failure::Io => "io",
+        }
+    }
+}
+
+/// An error message tagged with its failure class, and the library error
+/// it came from, which says where the failure is.
+#[derive(Debug)]
+struct Tagged {
+    failure: Failure,
+    message: String,
+    error: Option<SlbcError>,
+}
+
+impl fmt::Display for Tagged {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for Tagged {}
+
+fn failure(failure: Failure, message: impl Into<String>) -> anyhow::Error {
+    anyhow::Error::new(Tagged {
+        failure,
+        message: message.into(),
+        error: None,
+    })
+}
+
+/// A library error in the given class.
+fn located(failure: Failure, e: SlbcError) -> anyhow::Error {
+    anyhow::Error::new(Tagged {
+        failure,
+        message: e.to_string(),
+        error: Some(e),
+    })
+}
+
+/// A library error, which is about its input.
+fn parse_error(e: SlbcError) -> anyhow::Error {
+    located(Failure::Parse, e)
+}
+
+/// The class of an error: a tag anywhere in its chain, or an I/O error.
+fn classify(e: &anyhow::Error) -> Option<Failure> {
+    e.chain()
+        .find_map(|c| c.downcast_ref::<Tagged>().map(|t| t.failure))
+        .or_else(|| {
+            e.chain()
+                .any(|c| c.is::<std::io::Error>())
+                .then_some(Failure::Io)
+        })
+}
+
+/// The library error anywhere in an error's chain, tagged or not.
+fn library_error(e: &anyhow::Error) -> Option<&SlbcError> {
+    e.chain().find_map(|c| {
+        c.downcast_ref::<SlbcError>()
+            .or_else(|| c.downcast_ref::<Tagged>()?.error.as_ref())
+    })
+}
+
+/// Print an error in the requested format and pick the exit code.
+fn report(e: &anyhow::Error, format: &str) -> ExitCode {
+    let failure = classify(e);
+    let code = failure.map_or(1, Failure::exit_code);
+    if format == "json" {
+        let error = library_error(e);
+        let number = |n: Option<usize>| n.map_or("null".to_string(), |n| n.to_string());
+        eprintln!(
+            "{{\"kind\":{},\"code\":{},\"message\":{},\"position\":{},\"snippet\":{},\"offset\":{}}}",
+            json::quote(failure.map_or("error", Failure::name)),
+            code,
+            json::quote(&format!("{:#}", e)),
+            number(error.and_then(SlbcError::position)),
+            error
+                .and_then(SlbcError::snippet)
+                .map_or("null".to_string(), json::quote),
+            number(error.and_then(SlbcError::offset))
+        );
+    } else {
+        eprintln!("Error: {:?}", e);
+    }
+    ExitCode::from(code)
+}
+
 // ── Helpers ──
 
+/// IAST for pada contents (without PADA_START/PADA_END).
+fn pada_iast(contents: &[u8]) -> Result<String> {
+    let mut pada = vec![PADA_START];
+    pada.extend_from_slice(contents);
+    pada.push(PADA_END);
+    decoder::decode_phon(&pada, Script::Iast).map_err(parse_error)
+}
+
+/// Read a text file saved as UTF-8 (with or without a byte-order mark) or
+/// UTF-16, as Windows editors often save it: little- or big-endian, told
+/// by its byte-order mark or, without one, by where its NUL bytes fall.
+fn read_text(path: &Path) -> Result<String> {
+    let data = fs::read(path).with_context(|| format!("reading {}", path.display()))?;
+    decode_text(&data).with_context(|| format!("reading {}", path.display()))
+}
+
+fn decode_text(data: &[u8]) -> Result<String> {
+    match data {
+        [0xEF, 0xBB, 0xBF, rest @ ..] => decode_utf8(rest),
+        [0xFF, 0xFE, rest @ ..] => decode_utf16(rest, false),
+        [0xFE, 0xFF, rest @ ..] => decode_utf16(rest, true),
+        _ => match sniff_utf16(data) {
+            Some(big_endian) => decode_utf16(data, big_endian),
+            None => decode_utf8(data),
+        },
+    }
+}
+
+fn decode_utf8(data: &[u8]) -> Result<String> {
+    String::from_utf8(data.to_vec()).map_err(|e| {
+        let at = e.utf8_error().valid_up_to();
+        parse_error(SlbcError::malformed(
+            at,
+            format_args!(
+                "not UTF-8 text (save the file as UTF-8, or as UTF-16 with a byte-order mark): invalid byte 0x{:02X}",
+                data[at]
+            ),
+        ))
+    })
+}
+
+fn decode_utf16(data: &[u8], big_endian: bool) -> Result<String> {
+    if !data.len().is_multiple_of(2) {
+        return Err(failure(
+            Failure::Parse,
+            "UTF-16 text has an odd number of bytes",
+        ));
+    }
+    let units: Vec<u16> = data
+        .chunks_exact(2)
+        .map(|p| match big_endian {
+            true => u16::from_be_bytes([p[0], p[1]]),
+            false => u16::from_le_bytes([p[0], p[1]]),
+        })
+        .collect();
+    String::from_utf16(&units).map_err(|_| failure(Failure::Parse, "invalid UTF-16 text"))
+}
+
+/// UTF-16 without a byte-order mark: text never holds NUL, but the high
+/// byte of every ASCII character in UTF-16 is one. `Some(true)` when they
+/// all fall on even offsets (big-endian), `Some(false)` on odd ones. Text
+/// with no ASCII in it, and that is not UTF-8 text (invalid, or holding
+/// control characters), is told by its high bytes instead: the letters of
+/// one script share one or two (0x09 for Devanāgarī), where the low bytes
+/// vary.
+fn sniff_utf16(data: &[u8]) -> Option<bool> {
+    if !data.len().is_multiple_of(2) {
+        return None;
+    }
+    let head = &data[..data.len().min(1024)];
+    let nuls = |parity: usize| {
+        head.iter()
+            .enumerate()
+            .filter(|&(i, &b)| b == 0 && i % 2 == parity)
+            .count()
+    };
+    match (nuls(0), nuls(1)) {
+        (0, 0) => {}
+        (_, 0) => return Some(true),
+        (0, _) => return Some(false),
+        _ => return None,
+    }
+    let control = |b: &u8| b.is_ascii_control() && !b" \t\n\r".contains(b);
+    if std::str::from_utf8(data).is_ok() && !head.iter().any(control) {
+        return None;
+    }
+    let distinct = |parity: usize| {
+        let mut seen = [false; 256];
+        for &b in head.iter().skip(parity).step_by(2) {
+            seen[b as usize] = true;
+        }
+        seen.iter().filter(|&&s| s).count()
+    };
+    match (distinct(0), distinct(1)) {
+        (even, odd) if even * 2 <= odd => Some(true),
+        (even, odd) if odd * 2 <= even => Some(false),
+        _ => None,
+    }
+}
+
+fn parse_repair_source(name: Option<&str>) -> Result<Option<numeral::RepairSource>> {
+    Ok(match name {
+        None => None,
+        Some("words") => Some(numeral::RepairSource::Words),
+        Some("glyphs") => Some(numeral::RepairSource::Glyphs),
+        Some(other) => bail!(
+            "unknown repair source '{}' (use 'words' or 'glyphs')",
+            other
+        ),
+    })
+}
+
+fn parse_script(name: &str) -> Result<Script> {
+    Script::from_name(name).ok_or_else(|| {
+        let names: Vec<&str> = Script::ALL.iter().map(|s| s.name()).collect();
+        anyhow::anyhow!("unknown script '{}' (use {})", name, names.join(", "))
+    })
+}
+
+fn byte_infos_json(infos: &[inspect::ByteInfo]) -> String {
+    let objs: Vec<String> = infos.iter().map(inspect::byte_info_json).collect();
+    format!("[{}]", objs.join(","))
+}
+
 fn parse_hex_byte(s: &str) -> Result<u8> {
     let s = s.trim().trim_start_matches("0x").trim_start_matches("0X");
     u8::from_str_radix(s, 16).with_context(|| format!("invalid hex byte: '{}'", s))
@@ -386,3 +2827,102 @@ fn print_hex(data: &[u8]) {
         println!("|");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16(text: &str, big_endian: bool) -> Vec<u8> {
+        text.encode_utf16()
+            .flat_map(|u| match big_endian {
+                true => u.to_be_bytes(),
+                false => u.to_le_bytes(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_decode_text() {
+        let text = "धर्मक्षेत्रे कुरुक्षेत्रे ॥ 1 ॥";
+        assert_eq!(decode_text(text.as_bytes()).unwrap(), text);
+        let bom = [&[0xEF, 0xBB, 0xBF], text.as_bytes()].concat();
+        assert_eq!(decode_text(&bom).unwrap(), text);
+        let le = [&[0xFF, 0xFE][..], &utf16(text, false)].concat();
+        assert_eq!(decode_text(&le).unwrap(), text);
+        let be = [&[0xFE, 0xFF][..], &utf16(text, true)].concat();
+        assert_eq!(decode_text(&be).unwrap(), text);
+
+        // No byte-order mark: told by the NUL high bytes of ASCII, or by
+        // the shared high byte of Devanāgarī
+        assert_eq!(decode_text(&utf16("rāmaḥ", true)).unwrap(), "rāmaḥ");
+        assert_eq!(decode_text(&utf16("rāmaḥ", false)).unwrap(), "rāmaḥ");
+        let deva = "अग्निमीळे";
+        assert_eq!(sniff_utf16(&utf16(deva, true)), Some(true));
+        assert_eq!(decode_text(&utf16(deva, true)).unwrap(), deva);
+        assert_eq!(decode_text(&utf16(deva, false)).unwrap(), deva);
+
+        // Neither encoding
+        let e = decode_text(b"caf\xE9").unwrap_err();
+        assert!(e.to_string().contains("UTF-16 with a byte-order mark"));
+        assert_eq!(library_error(&e).and_then(SlbcError::offset), Some(3));
+        assert!(decode_text(&[0xFF, 0xFE, 0x00]).is_err());
+    }
+
+    /// The exit code `slbc <args>` ends with.
+    fn exit_code(args: &[&str]) -> u8 {
+        let cli = Cli::try_parse_from(std::iter::once("slbc").chain(args.iter().copied())).unwrap();
+        match run(cli.command) {
+            Ok(()) => 0,
+            Err(e) => classify(&e).map_or(1, Failure::exit_code),
+        }
+    }
+
+    #[test]
+    fn test_exit_codes() {
+        assert_eq!(exit_code(&["roundtrip", "rāmo vanam"]), 0);
+        assert_eq!(exit_code(&["roundtrip", "rāmaW"]), 3);
+        assert_eq!(exit_code(&["roundtrip", "rāma  vanam"]), 5);
+
+        // A doubled space, then no file at all
+        let path = std::env::temp_dir().join(format!("slbc-cli-{}.slbc", std::process::id()));
+        let mut data = encoder::encode_iast("na ca").unwrap();
+        data.insert(4, SPACE);
+        fs::write(&path, container::build_slbc(&data)).unwrap();
+        let validate = ["validate", "-i", path.to_str().unwrap()];
+        assert_eq!(exit_code(&validate), 4);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(exit_code(&validate), 6);
+    }
+
+    #[test]
+    fn test_verify_dir_survives_bad_file() {
+        let dir = std::env::temp_dir().join(format!("slbc-cli-dir-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let good = encoder::encode_iast("rāmo vanam").unwrap();
+        fs::write(dir.join("good.slbc"), container::build_slbc(&good)).unwrap();
+        // A lone numeral byte with no digit value
+        fs::write(
+            dir.join("bad.slbc"),
+            container::build_slbc(&[NUM, 0x01, 0x0A]),
+        )
+        .unwrap();
+        assert_eq!(exit_code(&["verify", "--dir", dir.to_str().unwrap()]), 4);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_error_location() {
+        let cli = Cli::try_parse_from(["slbc", "roundtrip", "rāmaW"]).unwrap();
+        let e = run(cli.command).unwrap_err();
+        let error = library_error(&e).unwrap();
+        assert_eq!(error.position(), Some(4));
+        assert_eq!(error.snippet(), Some("rāmaW"));
+        assert_eq!(error.offset(), None);
+
+        // Found through added context, and in an untagged library error
+        let e = parse_error(SlbcError::malformed(12, "bad chunk")).context("reading a.slbc");
+        assert_eq!(library_error(&e).and_then(SlbcError::offset), Some(12));
+        let e = anyhow::Error::new(SlbcError::DictRef { offset: 7 }).context("reading b.slbc");
+        assert_eq!(library_error(&e).and_then(SlbcError::offset), Some(7));
+    }
+}
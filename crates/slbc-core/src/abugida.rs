@@ -0,0 +1,38 @@
+//! Brahmic abugidas as decode targets.
+//!
+//! The `Abugida` table type and the regional scripts come from
+//! `slbc-scripts` (the latter with the `scripts` feature); Devanāgarī is
+//! defined here, next to the encoder and decoder that also read it.
+
+use crate::decoder;
+
+pub use slbc_scripts::{Abugida, ExtLetter};
+#[cfg(feature = "scripts")]
+pub use slbc_scripts::{
+    GRANTHA, GUJARATI, GURMUKHI, KANNADA, ODIA, SHARADA, SIDDHAM, TAMIL, TELUGU,
+};
+
+pub const DEVANAGARI: Abugida = Abugida {
+    name: "Devanāgarī",
+    consonant: decoder::byte_to_devanagari_consonant,
+    independent: decoder::byte_to_devanagari_independent,
+    matra: decoder::byte_to_devanagari_matra,
+    extension: devanagari_extension,
+    virama: "्",
+    anusvara: "ं",
+    candrabindu: "ँ",
+    visarga: "ः",
+    jihvamuliya: "ᳵ",
+    upadhmaniya: "ᳶ",
+    danda: "।",
+    double_danda: "॥",
+    avagraha: "ऽ",
+    om: "ॐ",
+    digits: decoder::DEVANAGARI_DIGITS,
+    accents: ["", "\u{0951}", "\u{0952}", "\u{1CDA}"],
+    consonant_mark: slbc_scripts::no_mark,
+};
+
+fn devanagari_extension(code: u8) -> Option<ExtLetter> {
+    crate::codepage::ext_phoneme(code).map(|p| (p.devanagari, p.matra))
+}
@@ -0,0 +1,405 @@
+//! Akṣara segmentation and the akṣara index (IDX chunk, kind 0x02).
+//!
+//! An akṣara is a consonant cluster plus its vowel, with any following
+//! visarga or anusvāra; consonants left at the end of a pada join the last
+//! akṣara. The index maps each distinct akṣara to its occurrence offsets so
+//! readers can answer frequency queries and highlight without a scan.
+//!
+//! IDX payload (akṣara kind):
+//! ```text
+//! IDX_AKSHARA | PHON chunk ordinal (ULEB128) | entry count (ULEB128)
+//! per entry: key (ULEB128 len + bytes) | count (ULEB128) | offsets (ULEB128 deltas)
+//! ```
+//! Offsets are into the PHON payload after any pada-dictionary expansion.
+
+use crate::codepage::{self, ExtKind, EXT_LONG};
+use crate::container::{self, Chunk};
+use crate::error::SlbcError;
+use crate::numeral;
+use crate::types::*;
+
+/// One akṣara: payload offset of its first byte, and its length in bytes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Akshara {
+    pub offset: usize,
+    pub len: usize,
+}
+
+/// What a pada byte (or META_EXT pair) contributes to segmentation.
+enum Unit {
+    Consonant,
+    Vowel,
+    Postfix,
+}
+
+/// Segment a PHON payload into akṣaras. Numeral spans, META blocks, and
+/// morpheme seams are skipped; lipi bytes inside a pada (avagraha) end the
+/// current akṣara.
+pub fn aksharas(data: &[u8]) -> Result<Vec<Akshara>, SlbcError> {
+    let mut out: Vec<Akshara> = Vec::new();
+    let mut i = 0;
+    let mut in_pada = false;
+    // Start of the akṣara being built, and whether its vowel has been seen
+    let mut start: Option<usize> = None;
+    let mut voweled = false;
+
+    while i < data.len() {
+        let b = data[i];
+        let (unit, width) = match b {
+            SANKHYA_START => {
+                i += numeral::decode_sankhya(data, i)?.1;
+                continue;
+            }
+            NUM => {
+                i += numeral::decode_num(data, i)?.1;
+                continue;
+            }
+            META_START => {
+                while i < data.len() && data[i] != META_END {
+                    i += 1;
+                }
+                i += 1;
+                continue;
+            }
+            PADA_START => {
+                in_pada = true;
+                i += 1;
+                continue;
+            }
+            // Morpheme seams cut across akṣaras; candrabindu, the pranava
+            // sign, and the yama and nāsikya ride on the phonemes before
+            // them; case marks say how the pada is written
+            MORPH | CANDRABINDU | PRANAVA | YAMA | NASIKYA | CAPITAL | UPPERCASE => {
+                i += 1;
+                continue;
+            }
+            // So do compound boundaries
+            META_EXT if codepage::is_samasa(data, i) => {
+                i += 2;
+                continue;
+            }
+            META_EXT if data.get(i + 1) == Some(&EXT_LONG) => {
+                i += codepage::ext_len(data, i)?;
+                continue;
+            }
+            META_EXT if in_pada => match codepage::read_ext(data, i)?.kind {
+                ExtKind::Consonant => (Unit::Consonant, 2),
+                ExtKind::Vowel => (Unit::Vowel, 2),
+            },
+            0x39 | 0x3A if in_pada => (Unit::Postfix, 1),
+            b if in_pada && is_svara(b) => (Unit::Vowel, 1),
+            b if in_pada && is_vyanjana(b) => (Unit::Consonant, 1),
+            _ => {
+                // PADA_END, lipi, or stray bytes: close the akṣara. Trailing
+                // consonants of a pada join the previous akṣara.
+                if let Some(s) = start.take() {
+                    match out.last_mut() {
+                        Some(last) if !voweled && last.offset + last.len == s => {
+                            last.len = i - last.offset;
+                        }
+                        _ => out.push(Akshara {
+                            offset: s,
+                            len: i - s,
+                        }),
+                    }
+                }
+                voweled = false;
+                if b == PADA_END {
+                    in_pada = false;
+                }
+                i += 1;
+                continue;
+            }
+        };
+
+        match unit {
+            Unit::Consonant if voweled => {
+                // A consonant after the vowel opens the next akṣara
+                let s = start.replace(i).unwrap();
+                out.push(Akshara {
+                    offset: s,
+                    len: i - s,
+                });
+                voweled = false;
+            }
+            Unit::Vowel if voweled => {
+                let s = start.replace(i).unwrap();
+                out.push(Akshara {
+                    offset: s,
+                    len: i - s,
+                });
+            }
+            Unit::Consonant => {
+                start.get_or_insert(i);
+            }
+            Unit::Vowel => {
+                start.get_or_insert(i);
+                voweled = true;
+            }
+            Unit::Postfix => {
+                start.get_or_insert(i);
+            }
+        }
+        i += width;
+    }
+
+    if let Some(s) = start {
+        out.push(Akshara {
+            offset: s,
+            len: data.len() - s,
+        });
+    }
+    Ok(out)
+}
+
+fn read_field(payload: &[u8], pos: &mut usize, what: &str) -> Result<u64, SlbcError> {
+    let (v, consumed) = container::read_uleb128(&payload[(*pos).min(payload.len())..])
+        .map_err(|e| e.context(*pos, format_args!("akṣara index {} error", what)))?;
+    *pos += consumed;
+    Ok(v)
+}
+
+// ═══════════════════════════════════════════════
+//  Index chunk
+// ═══════════════════════════════════════════════
+
+/// Akṣara → occurrence offsets for one PHON chunk.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct AksharaIndex {
+    /// Which PHON chunk (0-based, among PHON chunks) the offsets refer to.
+    pub phon_chunk: u32,
+    /// (akṣara bytes, ascending offsets), most frequent first.
+    pub entries: Vec<(Vec<u8>, Vec<u32>)>,
+}
+
+impl AksharaIndex {
+    /// Index a PHON payload.
+    pub fn build(data: &[u8], phon_chunk: u32) -> Result<Self, SlbcError> {
+        let mut entries: Vec<(Vec<u8>, Vec<u32>)> = Vec::new();
+        let mut slot: std::collections::HashMap<&[u8], usize> = Default::default();
+        for a in aksharas(data)? {
+            let key = &data[a.offset..a.offset + a.len];
+            let n = *slot.entry(key).or_insert_with(|| {
+                entries.push((key.to_vec(), Vec::new()));
+                entries.len() - 1
+            });
+            entries[n].1.push(a.offset as u32);
+        }
+        entries.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then_with(|| a.0.cmp(&b.0)));
+        Ok(AksharaIndex {
+            phon_chunk,
+            entries,
+        })
+    }
+
+    /// Occurrence offsets of an akṣara.
+    pub fn occurrences(&self, akshara: &[u8]) -> &[u32] {
+        self.entries
+            .iter()
+            .find(|(k, _)| k == akshara)
+            .map_or(&[], |(_, offsets)| offsets.as_slice())
+    }
+
+    /// Number of occurrences of an akṣara.
+    pub fn frequency(&self, akshara: &[u8]) -> usize {
+        self.occurrences(akshara).len()
+    }
+
+    /// Update the index after the payload bytes `start..start + old_len` were
+    /// replaced by `replacement`, which must hold whole akṣaras (e.g. a whole
+    /// pada or verse).
+    pub fn splice(
+        &mut self,
+        start: u32,
+        old_len: u32,
+        replacement: &[u8],
+    ) -> Result<(), SlbcError> {
+        let end = start + old_len;
+        let new_end = start as i64 + replacement.len() as i64;
+        for (_, offsets) in &mut self.entries {
+            offsets.retain(|&o| o < start || o >= end);
+            for o in offsets.iter_mut().filter(|o| **o >= end) {
+                *o = (*o as i64 - end as i64 + new_end) as u32;
+            }
+        }
+        for a in aksharas(replacement)? {
+            let key = &replacement[a.offset..a.offset + a.len];
+            let at = start + a.offset as u32;
+            match self.entries.iter_mut().find(|(k, _)| k == key) {
+                Some((_, offsets)) => {
+                    let i = offsets.partition_point(|&o| o < at);
+                    offsets.insert(i, at);
+                }
+                None => self.entries.push((key.to_vec(), vec![at])),
+            }
+        }
+        self.entries.retain(|(_, offsets)| !offsets.is_empty());
+        self.entries
+            .sort_by(|a, b| b.1.len().cmp(&a.1.len()).then_with(|| a.0.cmp(&b.0)));
+        Ok(())
+    }
+
+    /// Build the IDX chunk holding this index.
+    pub fn to_chunk(&self) -> Chunk {
+        let mut payload = vec![IDX_AKSHARA];
+        container::write_uleb128(&mut payload, self.phon_chunk as u64);
+        container::write_uleb128(&mut payload, self.entries.len() as u64);
+        for (key, offsets) in &self.entries {
+            container::write_uleb128(&mut payload, key.len() as u64);
+            payload.extend_from_slice(key);
+            container::write_uleb128(&mut payload, offsets.len() as u64);
+            let mut prev = 0;
+            for &o in offsets {
+                container::write_uleb128(&mut payload, (o - prev) as u64);
+                prev = o;
+            }
+        }
+        Chunk {
+            chunk_type: CHUNK_IDX,
+            payload,
+        }
+    }
+
+    /// Parse an IDX chunk payload. Returns `None` for other index kinds.
+    pub fn from_chunk_payload(payload: &[u8]) -> Result<Option<Self>, SlbcError> {
+        if payload.first() != Some(&IDX_AKSHARA) {
+            return Ok(None);
+        }
+        let mut pos = 1;
+        let phon_chunk = read_field(payload, &mut pos, "chunk ordinal")? as u32;
+        let count = read_field(payload, &mut pos, "entry count")?;
+        let mut entries = Vec::with_capacity(count.min(1 << 16) as usize);
+        for _ in 0..count {
+            let len = read_field(payload, &mut pos, "key length")? as usize;
+            let key = payload
+                .get(pos..pos + len)
+                .ok_or_else(|| SlbcError::truncated(pos, "akṣara index key extends beyond chunk"))?
+                .to_vec();
+            pos += len;
+            let n = read_field(payload, &mut pos, "occurrence count")?;
+            let mut offsets = Vec::with_capacity(n.min(1 << 16) as usize);
+            let mut at = 0u32;
+            for _ in 0..n {
+                let field = pos;
+                let delta = read_field(payload, &mut pos, "offset")? as u32;
+                at = at
+                    .checked_add(delta)
+                    .ok_or_else(|| SlbcError::malformed(field, "akṣara index offset overflows"))?;
+                offsets.push(at);
+            }
+            entries.push((key, offsets));
+        }
+        Ok(Some(AksharaIndex {
+            phon_chunk,
+            entries,
+        }))
+    }
+
+    /// Find the akṣara index among a container's chunks.
+    pub fn find(chunks: &[Chunk]) -> Result<Option<Self>, SlbcError> {
+        for chunk in chunks.iter().filter(|c| c.chunk_type == CHUNK_IDX) {
+            if let Some(index) = AksharaIndex::from_chunk_payload(&chunk.payload)? {
+                return Ok(Some(index));
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoder::{self, Script};
+    use crate::encoder;
+
+    fn iast_aksharas(text: &str) -> Vec<String> {
+        let data = encoder::encode_iast(text).unwrap();
+        aksharas(&data)
+            .unwrap()
+            .iter()
+            .map(|a| {
+                let mut pada = vec![PADA_START];
+                pada.extend_from_slice(&data[a.offset..a.offset + a.len]);
+                pada.push(PADA_END);
+                decoder::decode_phon(&pada, Script::Iast).unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_segmentation() {
+        assert_eq!(iast_aksharas("dharmakṣetre"), ["dha", "rma", "kṣe", "tre"]);
+        assert_eq!(iast_aksharas("yuyutsavaḥ"), ["yu", "yu", "tsa", "vaḥ"]);
+        assert_eq!(iast_aksharas("marut 108 agniṃ"), ["ma", "rut", "a", "gniṃ"]);
+        assert_eq!(iast_aksharas("qalam"), ["qa", "lam"]);
+    }
+
+    #[test]
+    fn test_index_roundtrip() {
+        let data = encoder::encode_iast("dharmakṣetre kurukṣetre").unwrap();
+        let index = AksharaIndex::build(&data, 0).unwrap();
+        let kse = encoder::encode_iast("kṣe").unwrap();
+        let kse = &kse[1..kse.len() - 1];
+        assert_eq!(index.frequency(kse), 2);
+        for &o in index.occurrences(kse) {
+            assert_eq!(&data[o as usize..o as usize + kse.len()], kse);
+        }
+        assert_eq!(index.entries[0].1.len(), 2); // most frequent first
+
+        let chunk = index.to_chunk();
+        assert_eq!(AksharaIndex::find(&[chunk]).unwrap(), Some(index));
+    }
+
+    #[test]
+    fn test_malformed_index() {
+        // Entry and occurrence counts near u32::MAX with nothing after them
+        let huge = [IDX_AKSHARA, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0x0F];
+        assert!(matches!(
+            AksharaIndex::from_chunk_payload(&huge),
+            Err(SlbcError::Truncated {
+                offset: Some(7),
+                ..
+            })
+        ));
+        let huge = [
+            IDX_AKSHARA,
+            0x00,
+            0x01,
+            0x01,
+            0x40,
+            0xFF,
+            0xFF,
+            0xFF,
+            0xFF,
+            0x0F,
+        ];
+        assert!(AksharaIndex::from_chunk_payload(&huge).is_err());
+        // Offset deltas that sum past u32::MAX
+        let overflow = [
+            IDX_AKSHARA,
+            0x00,
+            0x01,
+            0x01,
+            0x40,
+            0x02,
+            0xFF,
+            0xFF,
+            0xFF,
+            0xFF,
+            0x0F,
+            0x01,
+        ];
+        assert_eq!(
+            AksharaIndex::from_chunk_payload(&overflow),
+            Err(SlbcError::malformed(11, "akṣara index offset overflows"))
+        );
+        let key = [IDX_AKSHARA, 0x00, 0x01, 0x05, 0x40];
+        assert!(matches!(
+            AksharaIndex::from_chunk_payload(&key),
+            Err(SlbcError::Truncated {
+                offset: Some(4),
+                ..
+            })
+        ));
+    }
+}
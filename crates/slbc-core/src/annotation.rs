@@ -0,0 +1,208 @@
+//! Inline annotations (§8.3, sub-tag 0xFA).
+//!
+//! An annotation is a key and a value, both UTF-8 text, in a META block of
+//! its own between padas:
+//! ```text
+//! META_START 0xFA <key> 0x00 <value> META_END
+//! ```
+//! It records what an edition prints around the text — a verse number, a
+//! speaker's name, a note — without touching the bhāṣā stream. Decoders
+//! skip annotations unless asked to write them as `{key: value}`.
+
+use std::fmt;
+
+use crate::codepage;
+use crate::container;
+use crate::error::SlbcError;
+use crate::numeral;
+use crate::types::*;
+
+/// Keys with an agreed meaning. Any other key may be used.
+pub const KEY_VERSE: &str = "verse";
+pub const KEY_SPEAKER: &str = "speaker";
+pub const KEY_NOTE: &str = "note";
+
+/// One annotation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Annotation {
+    pub key: String,
+    pub value: String,
+}
+
+impl Annotation {
+    pub fn new(key: impl Into<String>, value: impl Into<String>) -> Self {
+        Annotation {
+            key: key.into(),
+            value: value.into(),
+        }
+    }
+
+    /// The META block carrying this annotation. The key may not be empty
+    /// or hold NUL, and neither may hold U+000E, whose byte would end the
+    /// block early.
+    pub fn block(&self) -> Result<Vec<u8>, SlbcError> {
+        if self.key.is_empty() || self.key.contains('\0') {
+            return Err(SlbcError::invalid(format_args!(
+                "invalid annotation key '{}'",
+                self.key
+            )));
+        }
+        if self.key.contains('\u{E}') || self.value.contains('\u{E}') {
+            return Err(SlbcError::invalid("an annotation may not contain U+000E"));
+        }
+        let mut out = vec![META_START, SUBTAG_NOTE];
+        out.extend_from_slice(self.key.as_bytes());
+        out.push(0x00);
+        out.extend_from_slice(self.value.as_bytes());
+        out.push(META_END);
+        Ok(out)
+    }
+
+    /// The annotation in the bytes between META_START and META_END, or
+    /// None for any other block.
+    pub fn parse(body: &[u8]) -> Option<Annotation> {
+        let text = std::str::from_utf8(body.strip_prefix(&[SUBTAG_NOTE])?).ok()?;
+        let (key, value) = text.split_once('\0')?;
+        (!key.is_empty()).then(|| Annotation::new(key, value))
+    }
+}
+
+impl fmt::Display for Annotation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{{{}: {}}}", self.key, self.value)
+    }
+}
+
+/// Every annotation in a payload, with the offset of its META_START.
+pub fn annotations(data: &[u8]) -> Result<Vec<(usize, Annotation)>, SlbcError> {
+    Ok(crate::frame::meta_spans(data)?
+        .into_iter()
+        .filter(|span| span.closed)
+        .filter_map(|span| Some((span.offset, Annotation::parse(&span.bytes)?)))
+        .collect())
+}
+
+/// `data` with `note` inserted at `offset`, which must lie outside every
+/// pada, numeral span, extension, and META block, and not directly after a
+/// NUM span, whose digits would run on into the block.
+pub fn insert(data: &[u8], offset: usize, note: &Annotation) -> Result<Vec<u8>, SlbcError> {
+    if !boundaries(data)?.contains(&offset) {
+        return Err(SlbcError::invalid(format_args!(
+            "offset {} is not between padas; an annotation cannot go there",
+            offset
+        )));
+    }
+    let mut out = data[..offset].to_vec();
+    out.extend(note.block()?);
+    out.extend_from_slice(&data[offset..]);
+    Ok(out)
+}
+
+/// `data` with `note` inserted just ahead of pada `n`, counting from 0: a
+/// verse number ahead of the verse's first word, a speaker's name ahead of
+/// the speech.
+pub fn insert_before_pada(data: &[u8], n: usize, note: &Annotation) -> Result<Vec<u8>, SlbcError> {
+    let padas = crate::lang::padas(data)?;
+    let (start, _) = padas.get(n).ok_or_else(|| {
+        SlbcError::invalid(format_args!(
+            "no pada {} (the payload has {})",
+            n,
+            padas.len()
+        ))
+    })?;
+    insert(data, *start, note)
+}
+
+/// The offsets an annotation may go at, the end included.
+fn boundaries(data: &[u8]) -> Result<Vec<usize>, SlbcError> {
+    let mut out = Vec::new();
+    let mut in_pada = false;
+    let mut after_num = false;
+    let mut i = 0;
+    while i < data.len() {
+        if !in_pada && !after_num {
+            out.push(i);
+        }
+        after_num = data[i] == NUM;
+        i += match data[i] {
+            SANKHYA_START => numeral::decode_sankhya(data, i)?.1,
+            NUM => numeral::decode_num(data, i)?.1,
+            DICT_REF => {
+                let (_, consumed) = container::read_uleb128(&data[i + 1..])
+                    .map_err(|e| e.context(i + 1, "DICT_REF index error"))?;
+                1 + consumed
+            }
+            META_EXT => codepage::ext_len(data, i)?,
+            META_START => data[i..]
+                .iter()
+                .position(|&b| b == META_END)
+                .map_or(data.len() - i, |p| p + 1),
+            b @ (PADA_START | PADA_END) => {
+                in_pada = b == PADA_START;
+                1
+            }
+            _ => 1,
+        };
+    }
+    if !after_num {
+        out.push(data.len());
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoder::{decode_phon, decode_phon_with, DecodeOptions, Script};
+    use crate::encoder::encode_iast;
+
+    #[test]
+    fn test_block_round_trip() {
+        let note = Annotation::new(KEY_SPEAKER, "Dhṛtarāṣṭra");
+        let block = note.block().unwrap();
+        assert_eq!(&block[..3], &[META_START, SUBTAG_NOTE, b's']);
+        assert_eq!(Annotation::parse(&block[1..block.len() - 1]), Some(note));
+        assert_eq!(Annotation::parse(&[SUBTAG_LANG, 0x01]), None);
+        assert!(Annotation::new("", "x").block().is_err());
+        assert!(Annotation::new("note", "a\u{E}b").block().is_err());
+    }
+
+    #[test]
+    fn test_insert() {
+        let data = encode_iast("dharmakṣetre kurukṣetre | samavetā 12 |").unwrap();
+        let speaker = Annotation::new(KEY_SPEAKER, "Dhṛtarāṣṭra");
+        let verse = Annotation::new(KEY_VERSE, "1.1");
+        let noted = insert_before_pada(&data, 0, &speaker).unwrap();
+        let noted = insert(&noted, noted.len(), &verse).unwrap();
+        let found: Vec<Annotation> = annotations(&noted)
+            .unwrap()
+            .into_iter()
+            .map(|(_, note)| note)
+            .collect();
+        assert_eq!(found, [speaker, verse.clone()]);
+
+        // Inside a pada, or after a NUM span, whose digits would run on
+        assert!(insert(&data, 1, &verse).is_err());
+        assert!(insert(&data, data.len() - 2, &verse).is_err());
+        assert!(insert(&data, data.len() - 1, &verse).is_ok());
+        assert!(insert_before_pada(&data, 9, &verse).is_err());
+
+        // Skipped unless asked for
+        assert_eq!(
+            decode_phon(&noted, Script::Iast).unwrap(),
+            "dharmakṣetre kurukṣetre | samavetā 12 |"
+        );
+        let opts = DecodeOptions {
+            annotations: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            decode_phon_with(&noted, Script::Iast, &opts).unwrap(),
+            "{speaker: Dhṛtarāṣṭra} dharmakṣetre kurukṣetre | samavetā 12 | {verse: 1.1}"
+        );
+        assert_eq!(
+            decode_phon_with(&noted, Script::Devanagari, &opts).unwrap(),
+            "{speaker: Dhṛtarāṣṭra} धर्मक्षेत्रे कुरुक्षेत्रे । समवेता १२ । {verse: 1.1}"
+        );
+    }
+}
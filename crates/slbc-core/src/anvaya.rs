@@ -0,0 +1,468 @@
+//! Anvaya: prose word order and dependency links (ANVY chunk, 0x07, §8.4).
+//!
+//! An ANVY chunk annotates the sentences of one PHON chunk. Padas are
+//! referred to by index among the payload's top-level padas (see
+//! `lang::padas`; packed codes and dictionary references sit inside padas,
+//! so stored and expanded payloads agree), and within a sentence by index
+//! from its first pada:
+//! ```text
+//! PHON chunk ordinal (ULEB128) | sentence count (ULEB128)
+//! per sentence: first pada (ULEB128, gap from the previous sentence's end)
+//!               pada count (ULEB128) | root (ULEB128)
+//!               order count (ULEB128) | order (ULEB128 each)
+//!               edge count (ULEB128)
+//!               per edge: dependent (ULEB128) | governor (ULEB128)
+//!                         relation (1 byte)
+//! ```
+//! The order is the commentator's prose construal, a permutation of the
+//! sentence's padas, or empty. Padas outside every sentence, and padas of a
+//! sentence without an edge, are left unannotated. A container MAY carry
+//! one ANVY chunk per PHON chunk.
+
+use std::ops::Range;
+
+use crate::container::{self, Chunk};
+use crate::error::SlbcError;
+use crate::lang;
+use crate::types::*;
+
+/// How a dependent pada relates to its governor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Relation {
+    /// A relation not listed here.
+    Other,
+    Karta,
+    Karma,
+    Karana,
+    Sampradana,
+    Apadana,
+    Adhikarana,
+    /// Genitive (ṣaṣṭhī) relation to a noun.
+    Sambandha,
+    /// Adjective agreeing with its noun.
+    Visesana,
+    /// Adverb or adverbial accusative.
+    KriyaVisesana,
+    /// Vocative address.
+    Sambodhana,
+    /// Coordination (ca, vā) with the governor.
+    Samuccaya,
+}
+
+/// (relation, code byte, markup tag, display name)
+const RELATIONS: [(Relation, u8, &str, &str); 12] = [
+    (Relation::Other, 0x00, "other", "other"),
+    (Relation::Karta, 0x01, "karta", "kartā"),
+    (Relation::Karma, 0x02, "karma", "karma"),
+    (Relation::Karana, 0x03, "karana", "karaṇa"),
+    (Relation::Sampradana, 0x04, "sampradana", "sampradāna"),
+    (Relation::Apadana, 0x05, "apadana", "apādāna"),
+    (Relation::Adhikarana, 0x06, "adhikarana", "adhikaraṇa"),
+    (Relation::Sambandha, 0x07, "sambandha", "sambandha"),
+    (Relation::Visesana, 0x08, "visesana", "viśeṣaṇa"),
+    (
+        Relation::KriyaVisesana,
+        0x09,
+        "kriyavisesana",
+        "kriyāviśeṣaṇa",
+    ),
+    (Relation::Sambodhana, 0x0A, "sambodhana", "sambodhana"),
+    (Relation::Samuccaya, 0x0B, "samuccaya", "samuccaya"),
+];
+
+impl Relation {
+    fn row(self) -> &'static (Relation, u8, &'static str, &'static str) {
+        RELATIONS.iter().find(|r| r.0 == self).unwrap()
+    }
+
+    /// Relation byte of an edge.
+    pub fn code(self) -> u8 {
+        self.row().1
+    }
+
+    pub fn from_code(code: u8) -> Option<Relation> {
+        RELATIONS.iter().find(|r| r.1 == code).map(|r| r.0)
+    }
+
+    /// ASCII tag (e.g. "karta").
+    pub fn tag(self) -> &'static str {
+        self.row().2
+    }
+
+    pub fn from_tag(tag: &str) -> Option<Relation> {
+        RELATIONS.iter().find(|r| r.2 == tag).map(|r| r.0)
+    }
+
+    pub fn name(self) -> &'static str {
+        self.row().3
+    }
+}
+
+/// A dependency link, by pada index within the sentence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Edge {
+    pub dependent: u32,
+    pub governor: u32,
+    pub relation: Relation,
+}
+
+/// One sentence's anvaya.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Sentence {
+    /// The sentence's padas, as indices among the payload's padas.
+    pub padas: Range<u32>,
+    /// The main verb, by index within the sentence.
+    pub root: u32,
+    /// Prose order, by index within the sentence; empty if not given.
+    pub order: Vec<u32>,
+    pub edges: Vec<Edge>,
+}
+
+/// The anvaya of one PHON chunk.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Anvaya {
+    /// Which PHON chunk (0-based, among PHON chunks) the padas belong to.
+    pub phon_chunk: u32,
+    /// Ascending and non-overlapping.
+    pub sentences: Vec<Sentence>,
+}
+
+fn read_field(data: &[u8], pos: &mut usize, what: &str) -> Result<u64, SlbcError> {
+    let (v, consumed) = container::read_uleb128(&data[(*pos).min(data.len())..])
+        .map_err(|e| e.context(*pos, format_args!("anvaya {} error", what)))?;
+    *pos += consumed;
+    Ok(v)
+}
+
+fn read_index(data: &[u8], pos: &mut usize, what: &str) -> Result<u32, SlbcError> {
+    let at = *pos;
+    u32::try_from(read_field(data, pos, what)?)
+        .map_err(|_| SlbcError::malformed(at, format_args!("anvaya {} out of range", what)))
+}
+
+impl Sentence {
+    /// Check the sentence's indices, order, and edges.
+    pub fn check(&self) -> Result<(), SlbcError> {
+        let count = self.padas.len() as u32;
+        if count == 0 {
+            return Err(SlbcError::invalid("empty sentence"));
+        }
+        if self.root >= count {
+            return Err(SlbcError::invalid(format_args!(
+                "root {} out of range ({} padas)",
+                self.root, count
+            )));
+        }
+        if !self.order.is_empty() {
+            let mut seen = vec![false; count as usize];
+            for &p in &self.order {
+                match seen.get_mut(p as usize) {
+                    Some(s @ false) => *s = true,
+                    _ => {
+                        return Err(SlbcError::invalid(format_args!(
+                            "order is not a permutation of {} padas",
+                            count
+                        )))
+                    }
+                }
+            }
+            if seen.contains(&false) {
+                return Err(SlbcError::invalid(format_args!(
+                    "order is not a permutation of {} padas",
+                    count
+                )));
+            }
+        }
+
+        let mut governor: Vec<Option<u32>> = vec![None; count as usize];
+        for e in &self.edges {
+            if e.dependent >= count || e.governor >= count {
+                return Err(SlbcError::invalid(format_args!(
+                    "edge {} → {} out of range ({} padas)",
+                    e.dependent, e.governor, count
+                )));
+            }
+            if e.dependent == self.root {
+                return Err(SlbcError::invalid(format_args!(
+                    "root {} has a governor",
+                    self.root
+                )));
+            }
+            if governor[e.dependent as usize].replace(e.governor).is_some() {
+                return Err(SlbcError::invalid(format_args!(
+                    "pada {} has two governors",
+                    e.dependent
+                )));
+            }
+        }
+        for start in 0..count {
+            let mut p = start;
+            for _ in 0..count {
+                match governor[p as usize] {
+                    Some(g) => p = g,
+                    None => break,
+                }
+                if p == start {
+                    return Err(SlbcError::invalid(format_args!(
+                        "dependency cycle through pada {}",
+                        start
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Anvaya {
+    /// Check every sentence, and that sentences are ascending and do not
+    /// overlap.
+    pub fn check(&self) -> Result<(), SlbcError> {
+        let mut end = 0;
+        for (n, s) in self.sentences.iter().enumerate() {
+            if s.padas.start < end {
+                return Err(SlbcError::invalid(format_args!(
+                    "sentence {} overlaps the one before it",
+                    n
+                )));
+            }
+            s.check()
+                .map_err(|e| SlbcError::invalid(format_args!("sentence {}: {}", n, e)))?;
+            end = s.padas.end;
+        }
+        Ok(())
+    }
+
+    /// Check the annotation against the PHON payload it describes.
+    pub fn validate(&self, payload: &[u8]) -> Result<(), SlbcError> {
+        self.check()?;
+        let padas = lang::padas(payload)?.len();
+        match self.sentences.last() {
+            Some(s) if s.padas.end as usize > padas => Err(SlbcError::invalid(format_args!(
+                "sentence {} runs past the last pada ({} padas)",
+                self.sentences.len() - 1,
+                padas
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Build the ANVY chunk.
+    pub fn to_chunk(&self) -> Result<Chunk, SlbcError> {
+        self.check()?;
+        let mut payload = Vec::new();
+        container::write_uleb128(&mut payload, self.phon_chunk as u64);
+        container::write_uleb128(&mut payload, self.sentences.len() as u64);
+        let mut end = 0;
+        for s in &self.sentences {
+            container::write_uleb128(&mut payload, (s.padas.start - end) as u64);
+            container::write_uleb128(&mut payload, s.padas.len() as u64);
+            container::write_uleb128(&mut payload, s.root as u64);
+            container::write_uleb128(&mut payload, s.order.len() as u64);
+            for &p in &s.order {
+                container::write_uleb128(&mut payload, p as u64);
+            }
+            container::write_uleb128(&mut payload, s.edges.len() as u64);
+            for e in &s.edges {
+                container::write_uleb128(&mut payload, e.dependent as u64);
+                container::write_uleb128(&mut payload, e.governor as u64);
+                payload.push(e.relation.code());
+            }
+            end = s.padas.end;
+        }
+        Ok(Chunk {
+            chunk_type: CHUNK_ANVY,
+            payload,
+        })
+    }
+
+    /// Parse and check an ANVY chunk payload.
+    pub fn from_chunk_payload(payload: &[u8]) -> Result<Self, SlbcError> {
+        let mut pos = 0;
+        let phon_chunk = read_index(payload, &mut pos, "chunk ordinal")?;
+        let count = read_field(payload, &mut pos, "sentence count")?;
+        let mut sentences = Vec::with_capacity(count.min(1 << 16) as usize);
+        let mut end = 0u32;
+        for _ in 0..count {
+            let field = pos;
+            let start = end
+                .checked_add(read_index(payload, &mut pos, "sentence start")?)
+                .ok_or_else(|| SlbcError::malformed(field, "anvaya sentence start out of range"))?;
+            let field = pos;
+            end = start
+                .checked_add(read_index(payload, &mut pos, "pada count")?)
+                .ok_or_else(|| SlbcError::malformed(field, "anvaya pada count out of range"))?;
+            let root = read_index(payload, &mut pos, "root")?;
+            let order_len = read_field(payload, &mut pos, "order count")?;
+            let order = (0..order_len)
+                .map(|_| read_index(payload, &mut pos, "order"))
+                .collect::<Result<_, _>>()?;
+            let edge_count = read_field(payload, &mut pos, "edge count")?;
+            let mut edges = Vec::with_capacity(edge_count.min(1 << 16) as usize);
+            for _ in 0..edge_count {
+                let dependent = read_index(payload, &mut pos, "dependent")?;
+                let governor = read_index(payload, &mut pos, "governor")?;
+                let code = *payload
+                    .get(pos)
+                    .ok_or_else(|| SlbcError::truncated(pos, "truncated anvaya relation"))?;
+                let relation = Relation::from_code(code).ok_or_else(|| {
+                    SlbcError::malformed(
+                        pos,
+                        format_args!("unknown anvaya relation 0x{:02X}", code),
+                    )
+                })?;
+                pos += 1;
+                edges.push(Edge {
+                    dependent,
+                    governor,
+                    relation,
+                });
+            }
+            sentences.push(Sentence {
+                padas: start..end,
+                root,
+                order,
+                edges,
+            });
+        }
+        if pos != payload.len() {
+            return Err(SlbcError::malformed(pos, "trailing bytes in anvaya chunk"));
+        }
+        let anvaya = Anvaya {
+            phon_chunk,
+            sentences,
+        };
+        anvaya.check()?;
+        Ok(anvaya)
+    }
+
+    /// Every anvaya among a container's chunks.
+    pub fn all(chunks: &[Chunk]) -> Result<Vec<Self>, SlbcError> {
+        chunks
+            .iter()
+            .filter(|c| c.chunk_type == CHUNK_ANVY)
+            .map(|c| Anvaya::from_chunk_payload(&c.payload))
+            .collect()
+    }
+}
+
+/// A container with `anvaya` added before EOF, replacing any ANVY chunk for
+/// the same PHON chunk. The annotation is validated against that chunk.
+pub fn attach(data: &[u8], anvaya: &Anvaya) -> Result<Vec<u8>, SlbcError> {
+    let (header, chunks) = container::parse_slbc(data)?;
+    let phon = chunks
+        .iter()
+        .filter(|c| c.chunk_type == CHUNK_PHON)
+        .nth(anvaya.phon_chunk as usize)
+        .ok_or_else(|| {
+            SlbcError::invalid(format_args!(
+                "container has no PHON chunk {}",
+                anvaya.phon_chunk
+            ))
+        })?;
+    anvaya.validate(&phon.payload)?;
+    let new = anvaya.to_chunk()?;
+
+    let mut out = header.to_bytes_with(&header.ext_records()?)?;
+    for chunk in &chunks {
+        if chunk.chunk_type == CHUNK_ANVY
+            && Anvaya::from_chunk_payload(&chunk.payload)?.phon_chunk == anvaya.phon_chunk
+        {
+            continue;
+        }
+        if chunk.chunk_type == CHUNK_EOF {
+            container::write_chunk(&mut out, new.chunk_type, &new.payload);
+        }
+        container::write_chunk(&mut out, chunk.chunk_type, &chunk.payload);
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoder;
+
+    fn edge(dependent: u32, governor: u32, relation: Relation) -> Edge {
+        Edge {
+            dependent,
+            governor,
+            relation,
+        }
+    }
+
+    // rāmaḥ vanaṃ gacchati || sītā ca tam anugacchati ||
+    fn sample() -> Anvaya {
+        Anvaya {
+            phon_chunk: 0,
+            sentences: vec![
+                Sentence {
+                    padas: 0..3,
+                    root: 2,
+                    order: vec![],
+                    edges: vec![edge(0, 2, Relation::Karta), edge(1, 2, Relation::Karma)],
+                },
+                Sentence {
+                    padas: 3..7,
+                    root: 3,
+                    order: vec![0, 2, 1, 3],
+                    edges: vec![
+                        edge(0, 3, Relation::Karta),
+                        edge(1, 0, Relation::Samuccaya),
+                        edge(2, 3, Relation::Karma),
+                    ],
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_chunk_roundtrip_and_attach() {
+        let anvaya = sample();
+        let chunk = anvaya.to_chunk().unwrap();
+        assert_eq!(Anvaya::from_chunk_payload(&chunk.payload).unwrap(), anvaya);
+        assert!(Anvaya::from_chunk_payload(&chunk.payload[..chunk.payload.len() - 1]).is_err());
+
+        let data =
+            encoder::encode_iast("rāmaḥ vanaṃ gacchati || sītā ca tam anugacchati ||").unwrap();
+        anvaya.validate(&data).unwrap();
+        let short = encoder::encode_iast("rāmaḥ vanaṃ gacchati ||").unwrap();
+        assert!(anvaya.validate(&short).is_err());
+        assert!(attach(&container::build_slbc(&short), &anvaya).is_err());
+
+        let file = container::build_slbc(&data);
+        let once = attach(&file, &anvaya).unwrap();
+        assert_eq!(attach(&once, &anvaya).unwrap(), once);
+        let (_, chunks) = container::parse_slbc(&once).unwrap();
+        assert_eq!(Anvaya::all(&chunks).unwrap(), [anvaya]);
+        assert_eq!(chunks.last().unwrap().chunk_type, CHUNK_EOF);
+    }
+
+    #[test]
+    fn test_check() {
+        let broken = |f: fn(&mut Sentence)| {
+            let mut anvaya = sample();
+            f(&mut anvaya.sentences[1]);
+            anvaya.check().unwrap_err().to_string()
+        };
+        assert!(broken(|s| s.root = 4).contains("root 4 out of range"));
+        assert!(broken(|s| s.order = vec![0, 1, 1, 3]).contains("permutation"));
+        assert!(broken(|s| s.edges.push(edge(3, 0, Relation::Other))).contains("governor"));
+        assert!(broken(|s| s.edges.push(edge(2, 1, Relation::Karma))).contains("two governors"));
+        assert!(broken(|s| {
+            s.edges[0].governor = 1;
+            s.edges[1].governor = 2;
+            s.edges[2].governor = 0;
+        })
+        .contains("cycle"));
+        assert!(broken(|s| s.padas = 2..6).contains("overlaps"));
+
+        let mut chunk = sample().to_chunk().unwrap();
+        let last = chunk.payload.len() - 1;
+        chunk.payload[last] = 0x7F;
+        assert!(Anvaya::from_chunk_payload(&chunk.payload)
+            .unwrap_err()
+            .to_string()
+            .contains("unknown anvaya relation 0x7F"));
+    }
+}
@@ -0,0 +1,406 @@
+//! Confusable / non-canonical sequence audit of decoded text.
+//!
+//! Text is split into clusters (a base character and the combining marks
+//! after it) and each cluster is checked against the forms downstream
+//! validators (ICU normalization, HarfBuzz cluster checks) expect:
+//! precomposed nukta letters that NFC decomposes, decomposed letters that
+//! NFC composes, nukta after another mark, doubled marks, vowel letter +
+//! vowel sign spellings of independent vowels, deprecated tone marks, and
+//! Cyrillic/Greek lookalikes inside Latin words. `normalize` applies every
+//! suggested fix.
+
+/// One kind of problem in a cluster.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Issue {
+    /// Deprecated code point (U+0340, U+0341, U+0343).
+    Deprecated,
+    /// Cyrillic or Greek letter inside a Latin word.
+    MixedScript,
+    /// Precomposed nukta letter (U+0958–U+095F), excluded from composition.
+    PrecomposedNukta,
+    /// Nukta after a vowel sign or virāma, or combining marks out of
+    /// canonical order.
+    MarkOrder,
+    /// The same combining mark twice in a row.
+    DuplicateMark,
+    /// Base + nukta for a letter that has a canonical precomposed form.
+    DecomposedNukta,
+    /// Vowel letter + vowel sign standing for an independent vowel.
+    VowelLetterWithSign,
+    /// Latin letter + combining mark with a precomposed form.
+    Decomposed,
+}
+
+impl Issue {
+    pub fn describe(self) -> &'static str {
+        match self {
+            Issue::Deprecated => "deprecated code point",
+            Issue::MixedScript => "non-Latin lookalike in Latin word",
+            Issue::PrecomposedNukta => "precomposed nukta letter",
+            Issue::MarkOrder => "combining marks out of order",
+            Issue::DuplicateMark => "doubled combining mark",
+            Issue::DecomposedNukta => "decomposed nukta letter",
+            Issue::VowelLetterWithSign => "vowel letter + vowel sign",
+            Issue::Decomposed => "decomposed letter + mark",
+        }
+    }
+}
+
+/// A cluster with one or more issues.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    /// Byte offset of the cluster in the audited text.
+    pub offset: usize,
+    pub found: String,
+    pub fixed: String,
+    pub issues: Vec<Issue>,
+}
+
+/// Render a string as code points, e.g. `U+0915 U+093C`.
+pub fn codepoints(s: &str) -> String {
+    s.chars()
+        .map(|c| format!("U+{:04X}", c as u32))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+const NUKTA: char = '\u{093C}';
+
+fn is_mark(c: char) -> bool {
+    matches!(c as u32,
+        0x0300..=0x036F
+        | 0x0900..=0x0903
+        | 0x093A..=0x094F
+        | 0x0951..=0x0957
+        | 0x0962..=0x0963)
+}
+
+/// Canonical combining class, for the marks it matters for.
+fn combining_class(c: char) -> u8 {
+    match c {
+        '\u{0323}' | '\u{0325}' | '\u{032E}' | '\u{0331}' => 220,
+        '\u{0300}'..='\u{0315}' => 230,
+        NUKTA => 7,
+        '\u{094D}' => 9,
+        _ => 0,
+    }
+}
+
+fn is_latin(c: char) -> bool {
+    c.is_ascii_alphabetic() || matches!(c as u32, 0x00C0..=0x024F | 0x1E00..=0x1EFF)
+}
+
+fn undeprecate(c: char) -> Option<char> {
+    Some(match c {
+        '\u{0340}' => '\u{0300}',
+        '\u{0341}' => '\u{0301}',
+        '\u{0343}' => '\u{0313}',
+        _ => return None,
+    })
+}
+
+/// Cyrillic and Greek letters drawn like Latin ones.
+fn latin_lookalike(c: char) -> Option<char> {
+    Some(match c {
+        'а' => 'a',
+        'е' => 'e',
+        'і' => 'i',
+        'ј' => 'j',
+        'о' => 'o',
+        'р' => 'p',
+        'с' => 'c',
+        'у' => 'y',
+        'х' => 'x',
+        'ѕ' => 's',
+        'һ' => 'h',
+        'А' => 'A',
+        'В' => 'B',
+        'Е' => 'E',
+        'К' => 'K',
+        'М' => 'M',
+        'Н' => 'H',
+        'О' => 'O',
+        'Р' => 'P',
+        'С' => 'C',
+        'Т' => 'T',
+        'Х' => 'X',
+        'ο' => 'o',
+        'α' => 'a',
+        'ν' => 'v',
+        _ => return None,
+    })
+}
+
+fn split_nukta(c: char) -> Option<char> {
+    Some(match c {
+        '\u{0958}' => 'क',
+        '\u{0959}' => 'ख',
+        '\u{095A}' => 'ग',
+        '\u{095B}' => 'ज',
+        '\u{095C}' => 'ड',
+        '\u{095D}' => 'ढ',
+        '\u{095E}' => 'फ',
+        '\u{095F}' => 'य',
+        _ => return None,
+    })
+}
+
+/// Compositions that are canonical (NFC) or required by the Unicode
+/// Devanāgarī block's "do not use" table.
+const COMPOSE: &[(char, char, char, Issue)] = &[
+    ('न', NUKTA, 'ऩ', Issue::DecomposedNukta),
+    ('र', NUKTA, 'ऱ', Issue::DecomposedNukta),
+    ('ळ', NUKTA, 'ऴ', Issue::DecomposedNukta),
+    ('अ', 'ा', 'आ', Issue::VowelLetterWithSign),
+    ('अ', 'ॆ', 'ऄ', Issue::VowelLetterWithSign),
+    ('अ', 'ॉ', 'ऑ', Issue::VowelLetterWithSign),
+    ('अ', 'ॊ', 'ऒ', Issue::VowelLetterWithSign),
+    ('अ', 'ो', 'ओ', Issue::VowelLetterWithSign),
+    ('अ', 'ौ', 'औ', Issue::VowelLetterWithSign),
+    ('अ', 'ॅ', 'ॲ', Issue::VowelLetterWithSign),
+    ('ए', 'ॅ', 'ऍ', Issue::VowelLetterWithSign),
+    ('ए', 'ॆ', 'ऎ', Issue::VowelLetterWithSign),
+    ('ए', 'े', 'ऐ', Issue::VowelLetterWithSign),
+    ('इ', 'ं', 'ई', Issue::VowelLetterWithSign),
+    ('उ', 'ु', 'ऊ', Issue::VowelLetterWithSign),
+    ('ऋ', 'ृ', 'ॠ', Issue::VowelLetterWithSign),
+    ('ऌ', 'ॢ', 'ॡ', Issue::VowelLetterWithSign),
+    ('a', '\u{0304}', 'ā', Issue::Decomposed),
+    ('i', '\u{0304}', 'ī', Issue::Decomposed),
+    ('u', '\u{0304}', 'ū', Issue::Decomposed),
+    ('e', '\u{0304}', 'ē', Issue::Decomposed),
+    ('o', '\u{0304}', 'ō', Issue::Decomposed),
+    ('A', '\u{0304}', 'Ā', Issue::Decomposed),
+    ('I', '\u{0304}', 'Ī', Issue::Decomposed),
+    ('U', '\u{0304}', 'Ū', Issue::Decomposed),
+    ('E', '\u{0304}', 'Ē', Issue::Decomposed),
+    ('O', '\u{0304}', 'Ō', Issue::Decomposed),
+    ('r', '\u{0323}', 'ṛ', Issue::Decomposed),
+    ('R', '\u{0323}', 'Ṛ', Issue::Decomposed),
+    ('ṛ', '\u{0304}', 'ṝ', Issue::Decomposed),
+    ('Ṛ', '\u{0304}', 'Ṝ', Issue::Decomposed),
+    ('l', '\u{0323}', 'ḷ', Issue::Decomposed),
+    ('L', '\u{0323}', 'Ḷ', Issue::Decomposed),
+    ('ḷ', '\u{0304}', 'ḹ', Issue::Decomposed),
+    ('Ḷ', '\u{0304}', 'Ḹ', Issue::Decomposed),
+    ('t', '\u{0323}', 'ṭ', Issue::Decomposed),
+    ('T', '\u{0323}', 'Ṭ', Issue::Decomposed),
+    ('d', '\u{0323}', 'ḍ', Issue::Decomposed),
+    ('D', '\u{0323}', 'Ḍ', Issue::Decomposed),
+    ('n', '\u{0323}', 'ṇ', Issue::Decomposed),
+    ('N', '\u{0323}', 'Ṇ', Issue::Decomposed),
+    ('s', '\u{0323}', 'ṣ', Issue::Decomposed),
+    ('S', '\u{0323}', 'Ṣ', Issue::Decomposed),
+    ('m', '\u{0323}', 'ṃ', Issue::Decomposed),
+    ('M', '\u{0323}', 'Ṃ', Issue::Decomposed),
+    ('h', '\u{0323}', 'ḥ', Issue::Decomposed),
+    ('H', '\u{0323}', 'Ḥ', Issue::Decomposed),
+    ('n', '\u{0307}', 'ṅ', Issue::Decomposed),
+    ('N', '\u{0307}', 'Ṅ', Issue::Decomposed),
+    ('m', '\u{0307}', 'ṁ', Issue::Decomposed),
+    ('M', '\u{0307}', 'Ṁ', Issue::Decomposed),
+    ('n', '\u{0303}', 'ñ', Issue::Decomposed),
+    ('N', '\u{0303}', 'Ñ', Issue::Decomposed),
+    ('s', '\u{0301}', 'ś', Issue::Decomposed),
+    ('S', '\u{0301}', 'Ś', Issue::Decomposed),
+    ('l', '\u{0331}', 'ḻ', Issue::Decomposed),
+    ('L', '\u{0331}', 'Ḻ', Issue::Decomposed),
+    ('h', '\u{0331}', 'ẖ', Issue::Decomposed),
+    ('h', '\u{032E}', 'ḫ', Issue::Decomposed),
+    ('a', '\u{0301}', 'á', Issue::Decomposed),
+    ('i', '\u{0301}', 'í', Issue::Decomposed),
+    ('u', '\u{0301}', 'ú', Issue::Decomposed),
+    ('e', '\u{0301}', 'é', Issue::Decomposed),
+    ('o', '\u{0301}', 'ó', Issue::Decomposed),
+    ('a', '\u{0300}', 'à', Issue::Decomposed),
+    ('i', '\u{0300}', 'ì', Issue::Decomposed),
+    ('u', '\u{0300}', 'ù', Issue::Decomposed),
+    ('e', '\u{0300}', 'è', Issue::Decomposed),
+    ('o', '\u{0300}', 'ò', Issue::Decomposed),
+];
+
+fn note(issues: &mut Vec<Issue>, issue: Issue) {
+    if !issues.contains(&issue) {
+        issues.push(issue);
+    }
+}
+
+/// Canonical form of one cluster, and the issues fixed to reach it.
+fn fix_cluster(cluster: &[char], latin_word: bool) -> (Vec<char>, Vec<Issue>) {
+    let mut issues = Vec::new();
+    let mut base = cluster[0];
+    let mut marks: Vec<char> = Vec::new();
+    for &m in &cluster[1..] {
+        match undeprecate(m) {
+            Some(fixed) => {
+                note(&mut issues, Issue::Deprecated);
+                marks.push(fixed);
+            }
+            None => marks.push(m),
+        }
+    }
+
+    if latin_word {
+        if let Some(latin) = latin_lookalike(base) {
+            note(&mut issues, Issue::MixedScript);
+            base = latin;
+        }
+    }
+    if let Some(plain) = split_nukta(base) {
+        note(&mut issues, Issue::PrecomposedNukta);
+        base = plain;
+        marks.insert(0, NUKTA);
+    }
+
+    if let Some(at) = marks.iter().position(|&m| m == NUKTA) {
+        if at > 0 {
+            note(&mut issues, Issue::MarkOrder);
+            marks.remove(at);
+            marks.insert(0, NUKTA);
+        }
+    }
+    if is_latin(base) {
+        let sorted = {
+            let mut s = marks.clone();
+            s.sort_by_key(|&m| combining_class(m));
+            s
+        };
+        if sorted != marks {
+            note(&mut issues, Issue::MarkOrder);
+            marks = sorted;
+        }
+    }
+    let before = marks.len();
+    marks.dedup();
+    if marks.len() != before {
+        note(&mut issues, Issue::DuplicateMark);
+    }
+
+    while let Some(&(_, _, composed, issue)) = marks
+        .first()
+        .and_then(|&m| COMPOSE.iter().find(|&&(b, mk, _, _)| b == base && mk == m))
+    {
+        note(&mut issues, issue);
+        base = composed;
+        marks.remove(0);
+    }
+
+    let mut fixed = vec![base];
+    fixed.extend(marks);
+    (fixed, issues)
+}
+
+/// Byte ranges of clusters in `text`, and whether each sits in a word
+/// containing Latin letters.
+fn clusters(text: &str) -> Vec<(usize, usize, bool)> {
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let mut out = Vec::new();
+    let mut word_start = 0;
+    let mut i = 0;
+    while i < chars.len() {
+        let mut j = i + 1;
+        while j < chars.len() && is_mark(chars[j].1) {
+            j += 1;
+        }
+        out.push((i, j, false));
+        i = j;
+        let word_ends = i == chars.len() || !chars[i].1.is_alphabetic();
+        if word_ends {
+            let cl = &mut out[word_start..];
+            let latin = cl.iter().any(|&(s, _, _)| is_latin(chars[s].1));
+            for c in cl.iter_mut() {
+                c.2 = latin;
+            }
+            word_start = out.len();
+        }
+    }
+    let byte = |k: usize| chars.get(k).map_or(text.len(), |&(b, _)| b);
+    out.into_iter()
+        .map(|(s, e, latin)| (byte(s), byte(e), latin))
+        .collect()
+}
+
+/// Every cluster of `text` that isn't in canonical form.
+pub fn audit(text: &str) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for (start, end, latin) in clusters(text) {
+        let found = &text[start..end];
+        let chars: Vec<char> = found.chars().collect();
+        let (fixed, issues) = fix_cluster(&chars, latin);
+        if !issues.is_empty() {
+            findings.push(Finding {
+                offset: start,
+                found: found.to_string(),
+                fixed: fixed.into_iter().collect(),
+                issues,
+            });
+        }
+    }
+    findings
+}
+
+/// `text` with every finding fixed.
+pub fn normalize(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut pos = 0;
+    for f in audit(text) {
+        out.push_str(&text[pos..f.offset]);
+        out.push_str(&f.fixed);
+        pos = f.offset + f.found.len();
+    }
+    out.push_str(&text[pos..]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codepage;
+    use crate::decoder::{self, Script};
+    use crate::types::*;
+
+    #[test]
+    fn test_findings_and_normalize() {
+        let text = "\u{0958}\u{093E} न\u{093C} क\u{093F}\u{093C} अ\u{093E} \
+                    kr\u{0323}s\u{0323}n\u{0323}a r\u{0304}\u{0323} \
+                    a\u{0341} dеva \u{094D}\u{094D}";
+        let kinds: Vec<Vec<Issue>> = audit(text).into_iter().map(|f| f.issues).collect();
+        assert_eq!(
+            kinds,
+            [
+                vec![Issue::PrecomposedNukta],
+                vec![Issue::DecomposedNukta],
+                vec![Issue::MarkOrder],
+                vec![Issue::VowelLetterWithSign],
+                vec![Issue::Decomposed],
+                vec![Issue::Decomposed],
+                vec![Issue::Decomposed],
+                vec![Issue::MarkOrder, Issue::Decomposed],
+                vec![Issue::Deprecated, Issue::Decomposed],
+                vec![Issue::MixedScript],
+                vec![Issue::DuplicateMark],
+            ]
+        );
+        let fixed = normalize(text);
+        assert_eq!(
+            fixed,
+            "क\u{093C}\u{093E} ऩ क\u{093C}\u{093F} आ kṛṣṇa ṝ á deva \u{094D}"
+        );
+        assert!(audit(&fixed).is_empty());
+        assert_eq!(codepoints("क\u{093C}"), "U+0915 U+093C");
+        // A Cyrillic word on its own is left alone.
+        assert!(audit("дом").is_empty());
+    }
+
+    #[test]
+    fn test_decoder_output_is_canonical() {
+        let mut data = crate::encoder::encode_iast("kṛṣṇa ṝ ḹ ṅ ñ ś ṃ ḥ").unwrap();
+        for p in codepage::CODE_PAGE {
+            data.extend_from_slice(&[META_EXT, p.code, 0x40, SPACE]);
+        }
+        for &script in Script::ALL {
+            let text = decoder::decode_phon(&data, script).unwrap();
+            assert!(audit(&text).is_empty(), "{}: {:?}", script.name(), text);
+        }
+    }
+}
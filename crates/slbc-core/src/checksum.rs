@@ -0,0 +1,303 @@
+//! Per-verse checksums (IDX chunk, kind 0x03).
+//!
+//! Each verse (see `frame::verses`) of one PHON chunk gets a CRC-32 over its
+//! stored bytes, so a reader can check any range of verses by seeking to
+//! just those bytes. Offsets are into the PHON payload as stored — before
+//! any pada-dictionary expansion — so no dictionary is needed to verify.
+//!
+//! IDX payload (verse-checksum kind):
+//! ```text
+//! IDX_VERSE_SUM | PHON chunk ordinal (ULEB128) | verse count (ULEB128)
+//! per verse: gap (ULEB128) | length (ULEB128) | CRC-32 (u32 LE)
+//! ```
+//! `gap` is the distance from the end of the previous verse (or the start
+//! of the payload) to the start of this one.
+
+use std::io::{Read, Seek, SeekFrom};
+
+use crate::container::{self, Chunk};
+use crate::error::SlbcError;
+use crate::frame;
+use crate::types::*;
+
+/// CRC-32 (IEEE 802.3, reflected).
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &b in data {
+        crc ^= b as u32;
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xEDB8_8320 & (crc & 1).wrapping_neg());
+        }
+    }
+    !crc
+}
+
+/// One verse's span in the PHON payload and its checksum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VerseSum {
+    pub offset: u32,
+    pub len: u32,
+    pub crc: u32,
+}
+
+/// Verse checksums for one PHON chunk.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct VerseSums {
+    /// Which PHON chunk (0-based, among PHON chunks) the offsets refer to.
+    pub phon_chunk: u32,
+    pub verses: Vec<VerseSum>,
+}
+
+fn read_field(data: &[u8], pos: &mut usize, what: &str) -> Result<u64, SlbcError> {
+    let (v, consumed) = container::read_uleb128(&data[(*pos).min(data.len())..])
+        .map_err(|e| e.context(*pos, format_args!("verse checksum {} error", what)))?;
+    *pos += consumed;
+    Ok(v)
+}
+
+impl VerseSums {
+    /// Checksum every verse of a stored PHON payload.
+    pub fn build(data: &[u8], phon_chunk: u32) -> Result<Self, SlbcError> {
+        let verses = frame::verses(data)?
+            .into_iter()
+            .map(|r| VerseSum {
+                offset: r.start as u32,
+                len: r.len() as u32,
+                crc: crc32(&data[r]),
+            })
+            .collect();
+        Ok(VerseSums { phon_chunk, verses })
+    }
+
+    /// Indices of verses whose bytes in `data` don't match their checksum.
+    pub fn mismatches(&self, data: &[u8]) -> Vec<usize> {
+        self.verses
+            .iter()
+            .enumerate()
+            .filter(|(_, v)| {
+                let start = v.offset as usize;
+                let end = start + v.len as usize;
+                data.get(start..end).map(crc32) != Some(v.crc)
+            })
+            .map(|(n, _)| n)
+            .collect()
+    }
+
+    /// Build the IDX chunk holding these checksums.
+    pub fn to_chunk(&self) -> Chunk {
+        let mut payload = vec![IDX_VERSE_SUM];
+        container::write_uleb128(&mut payload, self.phon_chunk as u64);
+        container::write_uleb128(&mut payload, self.verses.len() as u64);
+        let mut end = 0;
+        for v in &self.verses {
+            container::write_uleb128(&mut payload, (v.offset - end) as u64);
+            container::write_uleb128(&mut payload, v.len as u64);
+            payload.extend_from_slice(&v.crc.to_le_bytes());
+            end = v.offset + v.len;
+        }
+        Chunk {
+            chunk_type: CHUNK_IDX,
+            payload,
+        }
+    }
+
+    /// Parse an IDX chunk payload. Returns `None` for other index kinds.
+    pub fn from_chunk_payload(payload: &[u8]) -> Result<Option<Self>, SlbcError> {
+        if payload.first() != Some(&IDX_VERSE_SUM) {
+            return Ok(None);
+        }
+        let mut pos = 1;
+        let phon_chunk = read_field(payload, &mut pos, "chunk ordinal")? as u32;
+        let count = read_field(payload, &mut pos, "verse count")?;
+        let mut verses = Vec::with_capacity(count.min(1 << 16) as usize);
+        let mut end = 0u32;
+        for _ in 0..count {
+            let field = pos;
+            let gap = read_field(payload, &mut pos, "gap")?;
+            let len = read_field(payload, &mut pos, "length")?;
+            let out_of_range =
+                |_| SlbcError::malformed(field, "verse checksum offset out of range");
+            let offset = u32::try_from(end as u64 + gap).map_err(out_of_range)?;
+            end = u32::try_from(offset as u64 + len).map_err(out_of_range)?;
+            let len = len as u32;
+            let crc = payload
+                .get(pos..pos + 4)
+                .ok_or_else(|| SlbcError::truncated(pos, "truncated verse checksum"))?;
+            pos += 4;
+            verses.push(VerseSum {
+                offset,
+                len,
+                crc: u32::from_le_bytes([crc[0], crc[1], crc[2], crc[3]]),
+            });
+        }
+        Ok(Some(VerseSums { phon_chunk, verses }))
+    }
+
+    /// Find the verse checksums among a container's chunks.
+    pub fn find(chunks: &[Chunk]) -> Result<Option<Self>, SlbcError> {
+        for chunk in chunks.iter().filter(|c| c.chunk_type == CHUNK_IDX) {
+            if let Some(sums) = VerseSums::from_chunk_payload(&chunk.payload)? {
+                return Ok(Some(sums));
+            }
+        }
+        Ok(None)
+    }
+}
+
+// ═══════════════════════════════════════════════
+//  Seeking verification
+// ═══════════════════════════════════════════════
+
+/// Result of a seeking verification.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FastReport {
+    /// (verse index, checksum matched), for each verse checked.
+    pub checked: Vec<(usize, bool)>,
+    /// Total bytes read from the file.
+    pub bytes_read: u64,
+}
+
+fn read_at<R: Read + Seek>(r: &mut R, offset: u64, len: u64) -> Result<Vec<u8>, SlbcError> {
+    r.seek(SeekFrom::Start(offset)).map_err(|e| SlbcError::Io {
+        message: format!("seek error: {}", e),
+    })?;
+    let mut buf = vec![0; len as usize];
+    r.read_exact(&mut buf).map_err(|e| SlbcError::Io {
+        message: format!("read error: {}", e),
+    })?;
+    Ok(buf)
+}
+
+/// Check the requested verses (all when `wanted` is `None`) of a container,
+/// reading only the header, chunk headers, IDX payloads, and the bytes of
+/// those verses.
+pub fn verify_fast<R: Read + Seek>(
+    mut reader: R,
+    wanted: Option<&[usize]>,
+) -> Result<FastReport, SlbcError> {
+    let (header, entries) = container::chunk_directory(&mut reader)?;
+    let mut bytes_read = 14 + header.extended_header_len as u64;
+    bytes_read += entries
+        .iter()
+        .map(|e| e.payload_offset - e.offset)
+        .sum::<u64>();
+
+    let mut sums = None;
+    for e in entries.iter().filter(|e| e.chunk_type == CHUNK_IDX) {
+        bytes_read += e.len;
+        sums = VerseSums::from_chunk_payload(&read_at(&mut reader, e.payload_offset, e.len)?)?;
+        if sums.is_some() {
+            break;
+        }
+    }
+    let sums = sums.ok_or_else(|| {
+        SlbcError::invalid("container has no verse checksums (encode with --verse-sums)")
+    })?;
+    if entries.iter().any(|e| e.chunk_type == CHUNK_BHA) {
+        return Err(SlbcError::invalid(
+            "fast verification reads PHON chunks; this container stores BHA and LIPI chunks",
+        ));
+    }
+    let phon = entries
+        .iter()
+        .filter(|e| e.chunk_type == CHUNK_PHON)
+        .nth(sums.phon_chunk as usize)
+        .ok_or_else(|| {
+            SlbcError::invalid(format_args!(
+                "verse checksums refer to missing PHON chunk {}",
+                sums.phon_chunk
+            ))
+        })?;
+
+    let all: Vec<usize> = (0..sums.verses.len()).collect();
+    let mut checked = Vec::new();
+    for &n in wanted.unwrap_or(&all) {
+        let v = sums.verses.get(n).ok_or_else(|| {
+            SlbcError::invalid(format_args!(
+                "verse {} out of range ({} verses)",
+                n + 1,
+                sums.verses.len()
+            ))
+        })?;
+        if v.offset as u64 + v.len as u64 > phon.len {
+            checked.push((n, false));
+            continue;
+        }
+        let bytes = read_at(
+            &mut reader,
+            phon.payload_offset + v.offset as u64,
+            v.len as u64,
+        )?;
+        bytes_read += v.len as u64;
+        checked.push((n, crc32(&bytes) == v.crc));
+    }
+    Ok(FastReport {
+        checked,
+        bytes_read,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoder;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b""), 0);
+    }
+
+    #[test]
+    fn test_verse_sums_roundtrip_and_fast_verify() {
+        let text = "tat savitur vareṇyaṃ || bhargo devasya dhīmahi || dhiyo yo naḥ pracodayāt ||";
+        let data = encoder::encode_iast(text).unwrap();
+        let sums = VerseSums::build(&data, 0).unwrap();
+        assert_eq!(sums.verses.len(), 3);
+        assert!(sums.mismatches(&data).is_empty());
+        let chunk = sums.to_chunk();
+        assert_eq!(VerseSums::find(&[sums.to_chunk()]).unwrap(), Some(sums));
+
+        let mut file = container::build_slbc_with(&data, &[chunk]);
+        let report = verify_fast(Cursor::new(&file), Some(&[1])).unwrap();
+        assert_eq!(report.checked, [(1, true)]);
+        assert!(report.bytes_read < file.len() as u64);
+
+        // Corrupt a byte of the last verse
+        let at = file.len() - 6;
+        file[at] ^= 0x01;
+        let report = verify_fast(Cursor::new(&file), None).unwrap();
+        assert_eq!(report.checked, [(0, true), (1, true), (2, false)]);
+        assert!(verify_fast(Cursor::new(&file), Some(&[3])).is_err());
+    }
+
+    #[test]
+    fn test_offset_overflow() {
+        // Two verses whose gaps add up past u32::MAX
+        let mut payload = vec![IDX_VERSE_SUM, 0, 2];
+        for gap in [u32::MAX as u64, 1] {
+            container::write_uleb128(&mut payload, gap);
+            payload.extend_from_slice(&[0, 0, 0, 0, 0]);
+        }
+        let e = VerseSums::from_chunk_payload(&payload).unwrap_err();
+        assert!(matches!(
+            e,
+            SlbcError::Malformed {
+                offset: Some(13),
+                ..
+            }
+        ));
+
+        // A span past the payload is a mismatch, not an overflow
+        let sums = VerseSums {
+            phon_chunk: 0,
+            verses: vec![VerseSum {
+                offset: u32::MAX,
+                len: 1,
+                crc: 0,
+            }],
+        };
+        assert_eq!(sums.mismatches(&[]), [0]);
+    }
+}
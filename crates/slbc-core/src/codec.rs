@@ -0,0 +1,173 @@
+//! Pluggable scripts.
+//!
+//! A `ScriptCodec` turns text into tokens and renders PHON payloads back to
+//! text. The built-in scripts (`decoder::Script`) and any romanization table
+//! (`scheme::Scheme`) implement it; other crates implement it for new
+//! scripts and `register` them, after which `lookup` finds them by name
+//! alongside the built-ins.
+
+use std::sync::{Arc, RwLock};
+
+use crate::decoder::{self, DecodeOptions, Script};
+use crate::encoder::{self, Token};
+use crate::error::SlbcError;
+use crate::scheme::Scheme;
+
+/// A script SLBC can be encoded from and decoded to.
+pub trait ScriptCodec: Send + Sync {
+    /// Identifier used on the command line (e.g. "iast").
+    fn name(&self) -> &str;
+
+    /// Split text in this script into tokens.
+    fn tokenize(&self, input: &str) -> Result<Vec<Token>, SlbcError>;
+
+    /// Render a PHON chunk payload in this script.
+    fn render(&self, payload: &[u8], opts: &DecodeOptions) -> Result<String, SlbcError>;
+}
+
+impl ScriptCodec for Script {
+    fn name(&self) -> &str {
+        Script::name(*self)
+    }
+
+    fn tokenize(&self, input: &str) -> Result<Vec<Token>, SlbcError> {
+        encoder::tokenize_script(input, *self)
+    }
+
+    fn render(&self, payload: &[u8], opts: &DecodeOptions) -> Result<String, SlbcError> {
+        decoder::decode_phon_with(payload, *self, opts)
+    }
+}
+
+impl ScriptCodec for Scheme {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn tokenize(&self, input: &str) -> Result<Vec<Token>, SlbcError> {
+        Scheme::tokenize(self, input)
+    }
+
+    fn render(&self, payload: &[u8], opts: &DecodeOptions) -> Result<String, SlbcError> {
+        decoder::decode_scheme(payload, self, opts)
+    }
+}
+
+static REGISTRY: RwLock<Vec<Arc<dyn ScriptCodec>>> = RwLock::new(Vec::new());
+
+/// Make a codec available to `lookup`. Names must not collide with a
+/// built-in script or an earlier registration.
+pub fn register(codec: Arc<dyn ScriptCodec>) -> Result<(), SlbcError> {
+    let mut registry = REGISTRY.write().unwrap_or_else(|e| e.into_inner());
+    let name = codec.name();
+    if Script::from_name(name).is_some() || registry.iter().any(|c| c.name() == name) {
+        return Err(SlbcError::invalid(format_args!(
+            "script '{}' is already registered",
+            name
+        )));
+    }
+    registry.push(codec);
+    Ok(())
+}
+
+/// Find a script by name: built-ins first, then registered codecs.
+pub fn lookup(name: &str) -> Option<Arc<dyn ScriptCodec>> {
+    if let Some(script) = Script::from_name(name) {
+        return Some(Arc::new(script));
+    }
+    let registry = REGISTRY.read().unwrap_or_else(|e| e.into_inner());
+    registry.iter().find(|c| c.name() == name).cloned()
+}
+
+/// Names of every available script, built-ins first.
+pub fn names() -> Vec<String> {
+    let registry = REGISTRY.read().unwrap_or_else(|e| e.into_inner());
+    Script::ALL
+        .iter()
+        .map(|s| s.name().to_string())
+        .chain(registry.iter().map(|c| c.name().to_string()))
+        .collect()
+}
+
+/// Encode text with any codec.
+pub fn encode(input: &str, codec: &dyn ScriptCodec) -> Result<Vec<u8>, SlbcError> {
+    Ok(encoder::tokens_to_bytes(&codec.tokenize(input)?))
+}
+
+/// Decode a PHON chunk payload with any codec.
+pub fn decode(
+    payload: &[u8],
+    codec: &dyn ScriptCodec,
+    opts: &DecodeOptions,
+) -> Result<String, SlbcError> {
+    codec.render(payload, opts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::*;
+
+    /// Toy script: IAST with every letter upper-cased.
+    struct Upper;
+
+    impl ScriptCodec for Upper {
+        fn name(&self) -> &str {
+            "test-upper"
+        }
+
+        fn tokenize(&self, input: &str) -> Result<Vec<Token>, SlbcError> {
+            Script::Iast.tokenize(&input.to_lowercase())
+        }
+
+        fn render(&self, payload: &[u8], opts: &DecodeOptions) -> Result<String, SlbcError> {
+            Ok(Script::Iast.render(payload, opts)?.to_uppercase())
+        }
+    }
+
+    const DOTS: Scheme = Scheme {
+        name: "test-dots",
+        label: "Dots",
+        table: &[
+            ("a", 0x40),
+            ("k", 0x00),
+            ("r", 0x33),
+            ("m", 0x24),
+            (".", DANDA),
+        ],
+        extensions: &[],
+        vedic: &[],
+        clusters: &[],
+    };
+
+    #[test]
+    fn test_registry() {
+        register(Arc::new(Upper)).unwrap();
+        register(Arc::new(DOTS)).unwrap();
+        assert!(register(Arc::new(Upper)).is_err());
+        assert!(register(Arc::new(Script::Iast)).is_err());
+        assert!(names().iter().any(|n| n == "test-upper"));
+
+        let upper = lookup("test-upper").unwrap();
+        let bytes = encode("RĀMA KARMA", upper.as_ref()).unwrap();
+        assert_eq!(bytes, encoder::encode_iast("rāma karma").unwrap());
+        let opts = DecodeOptions::default();
+        assert_eq!(decode(&bytes, upper.as_ref(), &opts).unwrap(), "RĀMA KARMA");
+
+        let dots = lookup("test-dots").unwrap();
+        let bytes = encode("karma .", dots.as_ref()).unwrap();
+        assert_eq!(bytes, encoder::encode_iast("karma |").unwrap());
+        assert_eq!(decode(&bytes, dots.as_ref(), &opts).unwrap(), "karma .");
+        let e = encode("karma x", dots.as_ref()).unwrap_err();
+        assert_eq!(e.position(), Some(6));
+
+        // Built-ins behave as their enum counterparts
+        let hk = lookup("harvard-kyoto").unwrap();
+        assert_eq!(hk.name(), "hk");
+        assert_eq!(
+            encode("kRSNa", hk.as_ref()).unwrap(),
+            encoder::encode_hk("kRSNa").unwrap()
+        );
+        assert!(lookup("klingon").is_none());
+    }
+}
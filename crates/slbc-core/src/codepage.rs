@@ -0,0 +1,260 @@
+//! Extension code page for sounds outside the Pāṇinian inventory.
+//!
+//! An extension phoneme is the two-byte sequence `META_EXT <code>`, appearing
+//! inside a pada wherever a svara or vyañjana could. Code bytes are never
+//! control bytes, so scanners looking for PADA_END or META_END stay correct
+//! without knowing the code page.
+//!
+//! Code `EXT_LONG` instead opens a long extension, a length-prefixed payload
+//! for data no code page entry covers. Its bytes are arbitrary, so every
+//! scanner steps over the whole unit with `ext_len`; decoders skip it and
+//! report it (see `decoder::decode_phon_logged`).
+
+use crate::container;
+use crate::error::SlbcError;
+use crate::types::META_EXT;
+
+/// Whether an extension phoneme behaves as a consonant or a vowel in script
+/// rendering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExtKind {
+    Consonant,
+    Vowel,
+}
+
+/// One code page entry.
+#[derive(Debug)]
+pub struct ExtPhoneme {
+    pub code: u8,
+    pub kind: ExtKind,
+    pub description: &'static str,
+    /// Devanāgarī consonant or independent vowel.
+    pub devanagari: &'static str,
+    /// Devanāgarī vowel sign, for vowels.
+    pub matra: Option<&'static str>,
+}
+
+pub const CODE_PAGE: &[ExtPhoneme] = &[
+    // ── Dravidian ──
+    ExtPhoneme {
+        code: 0x01,
+        kind: ExtKind::Consonant,
+        description: "retroflex approximant (Tamil ழ)",
+        devanagari: "ऴ",
+        matra: None,
+    },
+    ExtPhoneme {
+        code: 0x02,
+        kind: ExtKind::Consonant,
+        description: "alveolar trill (Tamil ற)",
+        devanagari: "ऱ",
+        matra: None,
+    },
+    ExtPhoneme {
+        code: 0x03,
+        kind: ExtKind::Consonant,
+        description: "alveolar nasal (Tamil ன)",
+        devanagari: "ऩ",
+        matra: None,
+    },
+    // ── Vedic laterals (intervocalic ḍ, ḍh in the Ṛgveda) ──
+    ExtPhoneme {
+        code: 0x04,
+        kind: ExtKind::Consonant,
+        description: "Vedic retroflex lateral (ळ)",
+        devanagari: "ळ",
+        matra: None,
+    },
+    ExtPhoneme {
+        code: 0x05,
+        kind: ExtKind::Consonant,
+        description: "aspirated Vedic retroflex lateral (ळ्ह)",
+        devanagari: "ळ्ह",
+        matra: None,
+    },
+    // ── Nukta consonants ──
+    ExtPhoneme {
+        code: 0x08,
+        kind: ExtKind::Consonant,
+        description: "voiceless uvular stop",
+        devanagari: "क़",
+        matra: None,
+    },
+    ExtPhoneme {
+        code: 0x09,
+        kind: ExtKind::Consonant,
+        description: "voiceless velar fricative",
+        devanagari: "ख़",
+        matra: None,
+    },
+    ExtPhoneme {
+        code: 0x0A,
+        kind: ExtKind::Consonant,
+        description: "voiced velar fricative",
+        devanagari: "ग़",
+        matra: None,
+    },
+    ExtPhoneme {
+        code: 0x0B,
+        kind: ExtKind::Consonant,
+        description: "voiced alveolar fricative",
+        devanagari: "ज़",
+        matra: None,
+    },
+    ExtPhoneme {
+        code: 0x0C,
+        kind: ExtKind::Consonant,
+        description: "voiceless labiodental fricative",
+        devanagari: "फ़",
+        matra: None,
+    },
+    // ── Short mid vowels (Dravidian, Prakrit) ──
+    ExtPhoneme {
+        code: 0x10,
+        kind: ExtKind::Vowel,
+        description: "short e",
+        devanagari: "ऎ",
+        matra: Some("ॆ"),
+    },
+    ExtPhoneme {
+        code: 0x11,
+        kind: ExtKind::Vowel,
+        description: "short o",
+        devanagari: "ऒ",
+        matra: Some("ॊ"),
+    },
+];
+
+/// Look up an extension code.
+pub fn ext_phoneme(code: u8) -> Option<&'static ExtPhoneme> {
+    CODE_PAGE.iter().find(|p| p.code == code)
+}
+
+/// Punctuation of critical editions, the two-byte sequence `META_EXT
+/// <code>` between padas (never inside one), by code and mark.
+pub const PUNCTUATION: &[(u8, char)] = &[
+    (0x40, ','),
+    (0x41, ';'),
+    (0x42, '-'),
+    (0x43, '–'),
+    (0x44, '—'),
+    (0x45, '('),
+    (0x46, ')'),
+    (0x47, '"'),
+    (0x48, '“'),
+    (0x49, '”'),
+    (0x4A, '‘'),
+    (0x4B, '’'),
+];
+
+/// Code of a compound (samāsa) boundary, `META_EXT SAMASA` between two
+/// members of one pada. Like a morpheme seam it carries no sound.
+pub const SAMASA: u8 = 0x4C;
+
+/// Whether the bytes at `pos` are a compound boundary.
+pub fn is_samasa(data: &[u8], pos: usize) -> bool {
+    data.get(pos..pos + 2) == Some(&[META_EXT, SAMASA])
+}
+
+/// The punctuation mark of an extension code.
+pub fn punctuation(code: u8) -> Option<char> {
+    PUNCTUATION.iter().find(|p| p.0 == code).map(|p| p.1)
+}
+
+/// The extension code of a punctuation mark.
+pub fn punctuation_code(mark: char) -> Option<u8> {
+    PUNCTUATION.iter().find(|p| p.1 == mark).map(|p| p.0)
+}
+
+/// Code byte of a long extension: `META_EXT EXT_LONG <length> <payload>`,
+/// the length a ULEB128 byte count.
+pub const EXT_LONG: u8 = 0x00;
+
+/// A long extension, by payload offset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LongExt {
+    /// Offset of META_EXT.
+    pub offset: usize,
+    pub payload: Vec<u8>,
+}
+
+/// The payload of the long extension at `pos` (META_EXT), and the length
+/// of the whole unit.
+pub fn read_long_ext(data: &[u8], pos: usize) -> Result<(&[u8], usize), SlbcError> {
+    let start = pos + 2;
+    let (len, consumed) = container::read_uleb128(data.get(start..).unwrap_or(&[]))
+        .map_err(|e| e.context(start, "long extension length error"))?;
+    let body = start + consumed;
+    let payload = data
+        .get(body..body + len as usize)
+        .ok_or_else(|| SlbcError::truncated(pos, "truncated long extension"))?;
+    Ok((payload, body + payload.len() - pos))
+}
+
+/// Length in bytes of the extension unit at `pos` (META_EXT): two for a
+/// code page phoneme or packed code, more for a long extension.
+pub fn ext_len(data: &[u8], pos: usize) -> Result<usize, SlbcError> {
+    match data.get(pos + 1) {
+        Some(&EXT_LONG) => Ok(read_long_ext(data, pos)?.1),
+        Some(_) => Ok(2),
+        None => Err(SlbcError::truncated(pos, "truncated META_EXT")),
+    }
+}
+
+/// Read the extension code following META_EXT at `pos`.
+pub fn read_ext(data: &[u8], pos: usize) -> Result<&'static ExtPhoneme, SlbcError> {
+    let code = *data
+        .get(pos + 1)
+        .ok_or_else(|| SlbcError::truncated(pos, "truncated META_EXT"))?;
+    ext_phoneme(code).ok_or_else(|| {
+        SlbcError::malformed(
+            pos + 1,
+            format_args!("unknown extension code 0x{:02X}", code),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::*;
+
+    #[test]
+    fn test_codes_are_not_controls() {
+        for p in CODE_PAGE {
+            assert!(!is_bhasha_control(p.code) && !is_lipi_control(p.code));
+            assert_eq!(p.matra.is_some(), p.kind == ExtKind::Vowel);
+        }
+    }
+
+    #[test]
+    fn test_punctuation() {
+        for &(code, mark) in PUNCTUATION {
+            assert!(!is_bhasha_control(code) && !is_lipi_control(code));
+            assert!(ext_phoneme(code).is_none() && code != EXT_LONG);
+            assert_eq!(punctuation_code(mark), Some(code));
+        }
+        assert_eq!(punctuation(0x40), Some(','));
+        assert_eq!(punctuation(0x08), None);
+        assert!(punctuation(SAMASA).is_none() && ext_phoneme(SAMASA).is_none());
+        assert!(is_samasa(&[0x40, META_EXT, SAMASA], 1));
+        assert!(!is_samasa(&[META_EXT], 0));
+    }
+
+    #[test]
+    fn test_read_ext() {
+        assert_eq!(read_ext(&[META_EXT, 0x08], 0).unwrap().devanagari, "क़");
+        assert!(read_ext(&[META_EXT], 0).is_err());
+        assert!(read_ext(&[META_EXT, 0x7A], 0).is_err());
+    }
+
+    #[test]
+    fn test_long_ext() {
+        let data = [META_EXT, EXT_LONG, 3, PADA_END, 0xFF, 0x00, SPACE];
+        assert_eq!(read_long_ext(&data, 0).unwrap(), (&data[3..6], 6));
+        assert_eq!(ext_len(&data, 0).unwrap(), 6);
+        assert_eq!(ext_len(&[META_EXT, 0x08], 0).unwrap(), 2);
+        assert!(ext_len(&data[..5], 0).is_err());
+        assert!(ext_len(&[META_EXT], 0).is_err());
+    }
+}
@@ -0,0 +1,378 @@
+//! Enumerating the code space (§3, §4).
+//!
+//! `svaras()` and `vyanjanas()` iterate every byte that names a phoneme, in
+//! byte order, and narrow by field: e.g.
+//! `svaras().with_quantity(Quantity::Dirgha).with_accent(Accent::Udatta)`
+//! yields the long udātta vowels, for test generators, documentation tables,
+//! and rule engines.
+
+use crate::types::*;
+
+// ═══════════════════════════════════════════════
+//  Svara fields
+// ═══════════════════════════════════════════════
+
+/// Q field (bits 7:6).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Quantity {
+    Hrasva,
+    Dirgha,
+    Pluta,
+}
+
+impl Quantity {
+    pub const ALL: [Quantity; 3] = [Quantity::Hrasva, Quantity::Dirgha, Quantity::Pluta];
+
+    pub fn bits(self) -> u8 {
+        match self {
+            Quantity::Hrasva => 0b01,
+            Quantity::Dirgha => 0b10,
+            Quantity::Pluta => 0b11,
+        }
+    }
+
+    /// The Q field of a svara byte.
+    pub fn of(b: u8) -> Option<Quantity> {
+        Quantity::ALL.into_iter().find(|q| q.bits() == svara_q(b))
+    }
+}
+
+/// A field (bits 5:4).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Accent {
+    Neutral,
+    Udatta,
+    Anudatta,
+    Svarita,
+}
+
+impl Accent {
+    pub const ALL: [Accent; 4] = [
+        Accent::Neutral,
+        Accent::Udatta,
+        Accent::Anudatta,
+        Accent::Svarita,
+    ];
+
+    pub fn bits(self) -> u8 {
+        self as u8
+    }
+
+    /// The A field of a svara byte.
+    pub fn of(b: u8) -> Accent {
+        Accent::ALL[svara_a(b) as usize]
+    }
+}
+
+/// S field (bits 3:2).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Series {
+    A,
+    I,
+    U,
+    R,
+}
+
+impl Series {
+    pub const ALL: [Series; 4] = [Series::A, Series::I, Series::U, Series::R];
+
+    pub fn bits(self) -> u8 {
+        self as u8
+    }
+
+    /// The S field of a svara byte.
+    pub fn of(b: u8) -> Series {
+        Series::ALL[svara_s(b) as usize]
+    }
+}
+
+/// G field (bits 1:0).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Grade {
+    Suddha,
+    Guna,
+    Vrddhi,
+    Special,
+}
+
+impl Grade {
+    pub const ALL: [Grade; 4] = [Grade::Suddha, Grade::Guna, Grade::Vrddhi, Grade::Special];
+
+    pub fn bits(self) -> u8 {
+        self as u8
+    }
+
+    /// The G field of a svara byte.
+    pub fn of(b: u8) -> Grade {
+        Grade::ALL[svara_g(b) as usize]
+    }
+}
+
+/// Whether a byte is one of the §4.1 vowels, at any accent, or its pluta.
+/// Guṇa and vṛddhi vowels are never hrasva.
+pub fn is_phonemic_svara(b: u8) -> bool {
+    if !is_svara(b) {
+        return false;
+    }
+    let quality = matches!(
+        (Series::of(b), Grade::of(b)),
+        (_, Grade::Suddha)
+            | (Series::R, Grade::Special)
+            | (Series::I | Series::U, Grade::Guna | Grade::Vrddhi)
+    );
+    quality
+        && !(Quantity::of(b) == Some(Quantity::Hrasva)
+            && matches!(Grade::of(b), Grade::Guna | Grade::Vrddhi))
+}
+
+/// Svara bytes narrowed by field. Each `with_*` call adds a filter.
+#[derive(Debug, Clone)]
+pub struct Svaras {
+    next: u16,
+    quantity: Option<Quantity>,
+    accent: Option<Accent>,
+    series: Option<Series>,
+    grade: Option<Grade>,
+}
+
+/// Every phonemic svara byte (see `is_phonemic_svara`), in byte order.
+pub fn svaras() -> Svaras {
+    Svaras {
+        next: 0x40,
+        quantity: None,
+        accent: None,
+        series: None,
+        grade: None,
+    }
+}
+
+impl Svaras {
+    pub fn with_quantity(mut self, q: Quantity) -> Self {
+        self.quantity = Some(q);
+        self
+    }
+
+    pub fn with_accent(mut self, a: Accent) -> Self {
+        self.accent = Some(a);
+        self
+    }
+
+    pub fn with_series(mut self, s: Series) -> Self {
+        self.series = Some(s);
+        self
+    }
+
+    pub fn with_grade(mut self, g: Grade) -> Self {
+        self.grade = Some(g);
+        self
+    }
+
+    fn accepts(&self, b: u8) -> bool {
+        is_phonemic_svara(b)
+            && self.quantity.is_none_or(|q| Quantity::of(b) == Some(q))
+            && self.accent.is_none_or(|a| Accent::of(b) == a)
+            && self.series.is_none_or(|s| Series::of(b) == s)
+            && self.grade.is_none_or(|g| Grade::of(b) == g)
+    }
+}
+
+impl Iterator for Svaras {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        while self.next <= 0xFF {
+            let b = self.next as u8;
+            self.next += 1;
+            if self.accepts(b) {
+                return Some(b);
+            }
+        }
+        None
+    }
+}
+
+// ═══════════════════════════════════════════════
+//  Vyañjana fields
+// ═══════════════════════════════════════════════
+
+/// PLACE field (bits 5:3).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Place {
+    Kanthya,
+    Talavya,
+    Murdhanya,
+    Dantya,
+    Osthya,
+    Usman,
+    Antastha,
+    Glottal,
+}
+
+impl Place {
+    pub const ALL: [Place; 8] = [
+        Place::Kanthya,
+        Place::Talavya,
+        Place::Murdhanya,
+        Place::Dantya,
+        Place::Osthya,
+        Place::Usman,
+        Place::Antastha,
+        Place::Glottal,
+    ];
+
+    pub fn bits(self) -> u8 {
+        self as u8
+    }
+
+    /// The PLACE field of a vyañjana byte.
+    pub fn of(b: u8) -> Place {
+        Place::ALL[place(b) as usize]
+    }
+
+    /// PLACE 0–4, where COLUMN carries manner (§3.2).
+    pub fn is_varga(self) -> bool {
+        self.bits() <= 4
+    }
+}
+
+/// Whether a byte is one of the §3.3/§3.4 consonants.
+pub fn is_phonemic_vyanjana(b: u8) -> bool {
+    is_varga(b) || matches!(b, 0x29..=0x2B | 0x31..=0x34 | 0x38..=0x3C)
+}
+
+/// Vyañjana bytes narrowed by field. Voicing, aspiration, and nasality
+/// filters keep only varga consonants, the only ones whose COLUMN carries
+/// manner.
+#[derive(Debug, Clone)]
+pub struct Vyanjanas {
+    next: u16,
+    place: Option<Place>,
+    column: Option<u8>,
+    voiced: Option<bool>,
+    aspirated: Option<bool>,
+    nasal: Option<bool>,
+}
+
+/// Every phonemic vyañjana byte (see `is_phonemic_vyanjana`), in byte order.
+pub fn vyanjanas() -> Vyanjanas {
+    Vyanjanas {
+        next: 0x00,
+        place: None,
+        column: None,
+        voiced: None,
+        aspirated: None,
+        nasal: None,
+    }
+}
+
+impl Vyanjanas {
+    pub fn with_place(mut self, p: Place) -> Self {
+        self.place = Some(p);
+        self
+    }
+
+    pub fn with_column(mut self, c: u8) -> Self {
+        self.column = Some(c);
+        self
+    }
+
+    /// Varga stops by voicing (COLUMN 2–3 voiced, 0–1 voiceless).
+    pub fn voiced(mut self, voiced: bool) -> Self {
+        self.voiced = Some(voiced);
+        self
+    }
+
+    /// Varga stops by aspiration (COLUMN 1 and 3).
+    pub fn aspirated(mut self, aspirated: bool) -> Self {
+        self.aspirated = Some(aspirated);
+        self
+    }
+
+    /// Varga nasals (COLUMN 4), or with `false` the varga stops.
+    pub fn nasal(mut self, nasal: bool) -> Self {
+        self.nasal = Some(nasal);
+        self
+    }
+
+    fn accepts(&self, b: u8) -> bool {
+        let c = column(b);
+        let stop = is_varga(b) && c < 4;
+        is_phonemic_vyanjana(b)
+            && self.place.is_none_or(|p| Place::of(b) == p)
+            && self.column.is_none_or(|col| c == col)
+            && self.voiced.is_none_or(|v| stop && (c >= 2) == v)
+            && self.aspirated.is_none_or(|a| stop && (c & 1 == 1) == a)
+            && self.nasal.is_none_or(|n| is_varga(b) && (c == 4) == n)
+    }
+}
+
+impl Iterator for Vyanjanas {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        while self.next <= 0x3F {
+            let b = self.next as u8;
+            self.next += 1;
+            if self.accepts(b) {
+                return Some(b);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoder;
+
+    #[test]
+    fn test_svaras() {
+        // 14 vowels × 4 accents, plus pluta of each
+        assert_eq!(svaras().count(), (14 + 9) * 4);
+        let plain: Vec<u8> = svaras()
+            .with_accent(Accent::Neutral)
+            .filter(|&b| Quantity::of(b) != Some(Quantity::Pluta))
+            .collect();
+        assert_eq!(plain.len(), 14);
+        assert!(plain.iter().all(|&b| decoder::byte_to_iast(b) != "?"));
+
+        let udatta: Vec<u8> = svaras()
+            .with_quantity(Quantity::Dirgha)
+            .with_accent(Accent::Udatta)
+            .with_series(Series::U)
+            .collect();
+        assert_eq!(udatta, vec![0x98, 0x99, 0x9A]); // ū́ ó áu
+        assert_eq!(
+            svaras()
+                .with_grade(Grade::Special)
+                .collect::<Vec<_>>()
+                .len(),
+            3 * 4
+        );
+        assert!(!is_phonemic_svara(0x45)); // hrasva guṇa: short e is an extension
+        assert!(!is_phonemic_svara(0x41)); // a-series guṇa
+    }
+
+    #[test]
+    fn test_vyanjanas() {
+        assert_eq!(vyanjanas().count(), 37);
+        assert!(vyanjanas().all(|b| decoder::byte_to_iast(b) != "?"));
+        assert_eq!(
+            vyanjanas().voiced(true).aspirated(true).collect::<Vec<_>>(),
+            vec![0x03, 0x0B, 0x13, 0x1B, 0x23]
+        );
+        assert_eq!(
+            vyanjanas()
+                .with_place(Place::Dantya)
+                .nasal(false)
+                .collect::<Vec<_>>(),
+            vec![0x18, 0x19, 0x1A, 0x1B]
+        );
+        assert_eq!(
+            vyanjanas().with_place(Place::Usman).collect::<Vec<_>>(),
+            vec![0x29, 0x2A, 0x2B]
+        );
+        assert!(vyanjanas().nasal(true).all(|b| column(b) == 4));
+    }
+}
@@ -2,6 +2,11 @@
 //!
 //! §7: 14-byte header + chunk sequence + EOF chunk.
 
+use std::io::{Read, Seek, SeekFrom};
+use std::ops::Range;
+
+use crate::error::SlbcError;
+use crate::index::PadaIndex;
 use crate::types::*;
 
 // ── ULEB128 ──
@@ -23,25 +28,34 @@ pub fn write_uleb128(out: &mut Vec<u8>, mut value: u64) {
 
 /// Decode a ULEB128 from a byte slice.
 /// Returns (value, bytes_consumed).
-pub fn read_uleb128(data: &[u8]) -> Result<(u64, usize), String> {
+pub fn read_uleb128(data: &[u8]) -> Result<(u64, usize), SlbcError> {
     let mut result: u64 = 0;
     let mut shift = 0;
 
     for (i, &byte) in data.iter().enumerate() {
         if i >= 5 {
-            return Err("ULEB128 exceeds 5 bytes (max u32)".into());
+            return Err(SlbcError::Malformed {
+                message: "ULEB128 exceeds 5 bytes (max u32)".into(),
+                offset: None,
+            });
         }
         result |= ((byte & 0x7F) as u64) << shift;
         shift += 7;
         if byte & 0x80 == 0 {
             if result > u32::MAX as u64 {
-                return Err("ULEB128 value exceeds u32 range".into());
+                return Err(SlbcError::Malformed {
+                    message: "ULEB128 value exceeds u32 range".into(),
+                    offset: None,
+                });
             }
             return Ok((result, i + 1));
         }
     }
 
-    Err("truncated ULEB128".into())
+    Err(SlbcError::Truncated {
+        message: "truncated ULEB128".into(),
+        offset: None,
+    })
 }
 
 // ── Header ──
@@ -91,11 +105,32 @@ pub fn write_eof(out: &mut Vec<u8>) {
 
 /// Build a complete .slbc file from a PHON payload (pāṭha mode).
 pub fn build_slbc(phon_payload: &[u8]) -> Vec<u8> {
+    build_slbc_with(phon_payload, &[])
+}
+
+/// Build a complete .slbc file from a PHON payload, preceded by extra chunks
+/// (e.g. DICT) that a decoder needs before reading the PHON stream.
+pub fn build_slbc_with(phon_payload: &[u8], extra: &[Chunk]) -> Vec<u8> {
+    build_slbc_ext(&[], phon_payload, extra)
+}
+
+/// Build a complete .slbc file with extended header records.
+///
+/// Panics if the encoded records exceed the 16-bit extended header length.
+pub fn build_slbc_ext(records: &[ExtRecord], phon_payload: &[u8], extra: &[Chunk]) -> Vec<u8> {
     let mut out = Vec::new();
 
     // Header: pāṭha mode with lipi controls, meta markers, interleaved
-    let header = build_header(true, true, true);
+    let mut header = build_header(true, true, true);
+    let ext = write_ext_records(records);
+    let ext_len = u16::try_from(ext.len()).expect("extended header exceeds 65535 bytes");
+    header[12..14].copy_from_slice(&ext_len.to_le_bytes());
     out.extend_from_slice(&header);
+    out.extend_from_slice(&ext);
+
+    for chunk in extra {
+        write_chunk(&mut out, chunk.chunk_type, &chunk.payload);
+    }
 
     // PHON chunk
     write_chunk(&mut out, CHUNK_PHON, phon_payload);
@@ -106,6 +141,55 @@ pub fn build_slbc(phon_payload: &[u8]) -> Vec<u8> {
     out
 }
 
+// ── Extended header ──
+
+/// One extended header record: tag + ULEB128 length + payload.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtRecord {
+    pub tag: u8,
+    pub payload: Vec<u8>,
+}
+
+/// Encode extended header records.
+pub fn write_ext_records(records: &[ExtRecord]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for r in records {
+        out.push(r.tag);
+        write_uleb128(&mut out, r.payload.len() as u64);
+        out.extend_from_slice(&r.payload);
+    }
+    out
+}
+
+/// Decode extended header records.
+pub fn parse_ext_records(data: &[u8]) -> Result<Vec<ExtRecord>, SlbcError> {
+    let mut records = Vec::new();
+    let mut pos = 0;
+    while pos < data.len() {
+        let tag = data[pos];
+        pos += 1;
+        let (len, consumed) = read_uleb128(&data[pos..])
+            .map_err(|e| e.context(pos, "extended header record length error"))?;
+        pos += consumed;
+        let end = pos + len as usize;
+        if end > data.len() {
+            return Err(SlbcError::Truncated {
+                message: format!(
+                    "extended header record 0x{:02X} extends beyond header (offset {})",
+                    tag, pos
+                ),
+                offset: Some(pos),
+            });
+        }
+        records.push(ExtRecord {
+            tag,
+            payload: data[pos..end].to_vec(),
+        });
+        pos = end;
+    }
+    Ok(records)
+}
+
 // ── Parsing ──
 
 /// Parsed container header.
@@ -114,9 +198,34 @@ pub struct SlbcHeader {
     pub version: [u8; 4],
     pub flags: u8,
     pub extended_header_len: u16,
+    /// Raw extended header bytes (see `ext_records`).
+    pub extended_header: Vec<u8>,
 }
 
 impl SlbcHeader {
+    /// The extended header as records.
+    pub fn ext_records(&self) -> Result<Vec<ExtRecord>, SlbcError> {
+        parse_ext_records(&self.extended_header)
+    }
+
+    /// Re-serialize the header with a new set of extended header records.
+    pub fn to_bytes_with(&self, records: &[ExtRecord]) -> Result<Vec<u8>, SlbcError> {
+        let ext = write_ext_records(records);
+        let ext_len = u16::try_from(ext.len()).map_err(|_| {
+            SlbcError::invalid(format_args!(
+                "extended header of {} bytes exceeds 65535",
+                ext.len()
+            ))
+        })?;
+        let mut out = Vec::with_capacity(14 + ext.len());
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&self.version);
+        out.extend_from_slice(&[0, 0, 0, self.flags]);
+        out.extend_from_slice(&ext_len.to_le_bytes());
+        out.extend_from_slice(&ext);
+        Ok(out)
+    }
+
     pub fn has_lipi(&self) -> bool {
         self.flags & FLAG_HAS_LIPI != 0
     }
@@ -141,15 +250,24 @@ pub struct Chunk {
     pub payload: Vec<u8>,
 }
 
+impl Chunk {
+    pub fn kind(&self) -> ChunkType {
+        ChunkType::from(self.chunk_type)
+    }
+}
+
 /// Parse a .slbc file into header + chunks.
-pub fn parse_slbc(data: &[u8]) -> Result<(SlbcHeader, Vec<Chunk>), String> {
+pub fn parse_slbc(data: &[u8]) -> Result<(SlbcHeader, Vec<Chunk>), SlbcError> {
     if data.len() < 14 {
-        return Err("file too short for SLBC header".into());
+        return Err(SlbcError::Truncated {
+            message: "file too short for SLBC header".into(),
+            offset: Some(data.len()),
+        });
     }
 
     // Verify magic
     if &data[0..4] != MAGIC {
-        return Err("invalid magic bytes (expected 'SLBC')".into());
+        return Err(bad_magic());
     }
 
     let mut version = [0u8; 4];
@@ -158,14 +276,21 @@ pub fn parse_slbc(data: &[u8]) -> Result<(SlbcHeader, Vec<Chunk>), String> {
     let flags = data[11];
     let ext_len = u16::from_le_bytes([data[12], data[13]]);
 
+    let mut pos = 14 + ext_len as usize;
+    if pos > data.len() {
+        return Err(SlbcError::Truncated {
+            message: format!("extended header ({} bytes) extends beyond file", ext_len),
+            offset: Some(data.len()),
+        });
+    }
+
     let header = SlbcHeader {
         version,
         flags,
         extended_header_len: ext_len,
+        extended_header: data[14..pos].to_vec(),
     };
 
-    // Skip extended header
-    let mut pos = 14 + ext_len as usize;
     let mut chunks = Vec::new();
 
     // Parse chunks
@@ -173,16 +298,19 @@ pub fn parse_slbc(data: &[u8]) -> Result<(SlbcHeader, Vec<Chunk>), String> {
         let chunk_type = data[pos];
         pos += 1;
 
-        let (payload_len, consumed) = read_uleb128(&data[pos..])
-            .map_err(|e| format!("chunk length ULEB128 error at offset {}: {}", pos, e))?;
+        let (payload_len, consumed) =
+            read_uleb128(&data[pos..]).map_err(|e| e.context(pos, "chunk length ULEB128 error"))?;
         pos += consumed;
 
         let payload_len = payload_len as usize;
         if pos + payload_len > data.len() {
-            return Err(format!(
-                "chunk payload extends beyond file (offset {}, len {})",
-                pos, payload_len
-            ));
+            return Err(SlbcError::Truncated {
+                message: format!(
+                    "chunk payload extends beyond file (offset {}, len {})",
+                    pos, payload_len
+                ),
+                offset: Some(pos),
+            });
         }
 
         let payload = data[pos..pos + payload_len].to_vec();
@@ -202,10 +330,378 @@ pub fn parse_slbc(data: &[u8]) -> Result<(SlbcHeader, Vec<Chunk>), String> {
     Ok((header, chunks))
 }
 
+// ── Metadata chunk ──
+
+/// The bibliographic fields of a META chunk (§7.3): what the text is and
+/// where it came from. Every field is optional; keys other than the six
+/// standard ones go in `custom`, in order.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct MetaChunk {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    /// The larger work the text belongs to (e.g. "Mahābhārata").
+    pub work: Option<String>,
+    /// Where in the work (e.g. "Bhīṣmaparvan 25").
+    pub section: Option<String>,
+    /// The edition or manuscript transcribed.
+    pub source: Option<String>,
+    /// Language tag of the text (e.g. "sa").
+    pub language: Option<String>,
+    pub custom: Vec<(String, String)>,
+}
+
+impl MetaChunk {
+    /// The standard keys, in the order they are written.
+    pub const KEYS: [&'static str; 6] =
+        ["title", "author", "work", "section", "source", "language"];
+
+    fn field(&mut self, key: &str) -> Option<&mut Option<String>> {
+        match key {
+            "title" => Some(&mut self.title),
+            "author" => Some(&mut self.author),
+            "work" => Some(&mut self.work),
+            "section" => Some(&mut self.section),
+            "source" => Some(&mut self.source),
+            "language" => Some(&mut self.language),
+            _ => None,
+        }
+    }
+
+    /// The value of `key`, standard or custom.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields()
+            .into_iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| v)
+    }
+
+    /// Set `key`, standard or custom, replacing any value it had.
+    pub fn set(&mut self, key: &str, value: impl Into<String>) {
+        let value = value.into();
+        match self.field(key) {
+            Some(field) => *field = Some(value),
+            None => match self.custom.iter_mut().find(|(k, _)| k == key) {
+                Some((_, v)) => *v = value,
+                None => self.custom.push((key.to_string(), value)),
+            },
+        }
+    }
+
+    /// Every field that is set, standard keys first.
+    pub fn fields(&self) -> Vec<(&str, &str)> {
+        let standard = [
+            &self.title,
+            &self.author,
+            &self.work,
+            &self.section,
+            &self.source,
+            &self.language,
+        ];
+        Self::KEYS
+            .iter()
+            .zip(standard)
+            .filter_map(|(k, v)| Some((*k, v.as_deref()?)))
+            .chain(self.custom.iter().map(|(k, v)| (k.as_str(), v.as_str())))
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields().is_empty()
+    }
+
+    /// Parse a META chunk payload:
+    /// `field count (ULEB128)`, then per field a key and a value, each
+    /// `ULEB128 length + UTF-8`.
+    pub fn read(payload: &[u8]) -> Result<MetaChunk, SlbcError> {
+        let mut pos = 0;
+        let (count, consumed) =
+            read_uleb128(payload).map_err(|e| e.context(0, "metadata field count"))?;
+        pos += consumed;
+        let mut meta = MetaChunk::default();
+        for _ in 0..count {
+            let at = pos;
+            let key = read_meta_text(payload, &mut pos, "metadata key")?;
+            let value = read_meta_text(payload, &mut pos, "metadata value")?;
+            if key.is_empty() || meta.get(&key).is_some() {
+                return Err(SlbcError::malformed(
+                    at,
+                    format_args!("empty or repeated metadata key '{}'", key),
+                ));
+            }
+            meta.set(&key, value);
+        }
+        if pos != payload.len() {
+            return Err(SlbcError::malformed(
+                pos,
+                "trailing bytes in metadata chunk",
+            ));
+        }
+        Ok(meta)
+    }
+
+    /// The META chunk payload. Fails on an empty key, or a custom key that
+    /// repeats another.
+    pub fn write(&self) -> Result<Vec<u8>, SlbcError> {
+        let fields = self.fields();
+        let mut out = Vec::new();
+        write_uleb128(&mut out, fields.len() as u64);
+        for (n, (key, value)) in fields.iter().enumerate() {
+            if key.is_empty() || fields[..n].iter().any(|(k, _)| k == key) {
+                return Err(SlbcError::Malformed {
+                    message: format!("empty or repeated metadata key '{}'", key),
+                    offset: None,
+                });
+            }
+            for text in [key, value] {
+                write_uleb128(&mut out, text.len() as u64);
+                out.extend_from_slice(text.as_bytes());
+            }
+        }
+        Ok(out)
+    }
+
+    /// Build the META chunk.
+    pub fn to_chunk(&self) -> Result<Chunk, SlbcError> {
+        Ok(Chunk {
+            chunk_type: CHUNK_META,
+            payload: self.write()?,
+        })
+    }
+
+    /// The metadata among a container's chunks, if it has a META chunk.
+    pub fn find(chunks: &[Chunk]) -> Result<Option<MetaChunk>, SlbcError> {
+        chunks
+            .iter()
+            .find(|c| c.chunk_type == CHUNK_META)
+            .map(|c| MetaChunk::read(&c.payload))
+            .transpose()
+    }
+}
+
+fn read_meta_text(data: &[u8], pos: &mut usize, what: &str) -> Result<String, SlbcError> {
+    let (len, consumed) = read_uleb128(data.get(*pos..).unwrap_or(&[]))
+        .map_err(|e| e.context(*pos, format_args!("{} length", what)))?;
+    *pos += consumed;
+    let bytes = usize::try_from(len)
+        .ok()
+        .and_then(|len| data.get(*pos..pos.checked_add(len)?))
+        .ok_or_else(|| SlbcError::truncated(*pos, format_args!("truncated {}", what)))?;
+    let text = std::str::from_utf8(bytes)
+        .map_err(|_| SlbcError::malformed(*pos, format_args!("{} is not UTF-8", what)))?;
+    *pos += bytes.len();
+    Ok(text.to_string())
+}
+
+// ── Seeking access ──
+
+/// Where one chunk sits in a container file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkEntry {
+    pub chunk_type: u8,
+    /// Offset of the chunk type byte.
+    pub offset: u64,
+    /// Offset of the first payload byte.
+    pub payload_offset: u64,
+    pub len: u64,
+}
+
+impl ChunkEntry {
+    pub fn kind(&self) -> ChunkType {
+        ChunkType::from(self.chunk_type)
+    }
+
+    /// Offset just past the payload.
+    pub fn end(&self) -> u64 {
+        self.payload_offset + self.len
+    }
+}
+
+fn bad_magic() -> SlbcError {
+    SlbcError::Malformed {
+        message: "invalid magic bytes (expected 'SLBC')".into(),
+        offset: Some(0),
+    }
+}
+
+fn io_error(what: &str, e: std::io::Error) -> SlbcError {
+    SlbcError::Io {
+        message: format!("{} error: {}", what, e),
+    }
+}
+
+fn read_exact<R: Read>(r: &mut R, buf: &mut [u8]) -> Result<(), SlbcError> {
+    r.read_exact(buf).map_err(|e| io_error("read", e))
+}
+
+/// Read the header and list every chunk (through EOF) without reading
+/// payloads: each payload is skipped with a seek.
+pub fn chunk_directory<R: Read + Seek>(
+    r: &mut R,
+) -> Result<(SlbcHeader, Vec<ChunkEntry>), SlbcError> {
+    r.seek(SeekFrom::Start(0))
+        .map_err(|e| io_error("seek", e))?;
+    let mut fixed = [0u8; 14];
+    read_exact(r, &mut fixed)?;
+    if &fixed[0..4] != MAGIC {
+        return Err(bad_magic());
+    }
+    let ext_len = u16::from_le_bytes([fixed[12], fixed[13]]);
+    let mut extended_header = vec![0u8; ext_len as usize];
+    read_exact(r, &mut extended_header)?;
+    let header = SlbcHeader {
+        version: [fixed[4], fixed[5], fixed[6], fixed[7]],
+        flags: fixed[11],
+        extended_header_len: ext_len,
+        extended_header,
+    };
+
+    let mut entries = Vec::new();
+    let mut pos = 14 + ext_len as u64;
+    loop {
+        let mut byte = [0u8; 1];
+        read_exact(r, &mut byte)?;
+        let chunk_type = byte[0];
+        // At most five ULEB128 bytes of payload length
+        let mut len_bytes = Vec::with_capacity(5);
+        loop {
+            read_exact(r, &mut byte)?;
+            len_bytes.push(byte[0]);
+            if byte[0] & 0x80 == 0 || len_bytes.len() == 5 {
+                break;
+            }
+        }
+        let (len, consumed) = read_uleb128(&len_bytes)
+            .map_err(|e| e.context(pos as usize + 1, "chunk length ULEB128 error"))?;
+        let entry = ChunkEntry {
+            chunk_type,
+            offset: pos,
+            payload_offset: pos + 1 + consumed as u64,
+            len,
+        };
+        entries.push(entry);
+        if chunk_type == CHUNK_EOF {
+            return Ok((header, entries));
+        }
+        pos = entry.end();
+        r.seek(SeekFrom::Start(pos))
+            .map_err(|e| io_error("seek", e))?;
+    }
+}
+
+/// The stored bytes of pada `n` (from 0) of the PHON chunk a pada index
+/// (`index::PadaIndex`) covers, read by seeking: only the header, the chunk
+/// headers, IDX payloads up to the pada index, and the pada itself are read.
+pub fn seek_pada<R: Read + Seek>(r: &mut R, n: usize) -> Result<Vec<u8>, SlbcError> {
+    seek_span(r, n, "pada", |index| &index.padas)
+}
+
+/// The stored bytes of verse `n` (from 0), read by seeking as for
+/// `seek_pada`.
+pub fn seek_verse<R: Read + Seek>(r: &mut R, n: usize) -> Result<Vec<u8>, SlbcError> {
+    seek_span(r, n, "verse", |index| &index.verses)
+}
+
+fn seek_span<R: Read + Seek>(
+    r: &mut R,
+    n: usize,
+    what: &str,
+    spans: impl Fn(&PadaIndex) -> &[Range<u32>],
+) -> Result<Vec<u8>, SlbcError> {
+    let (_, entries) = chunk_directory(r)?;
+    let mut index = None;
+    for e in entries.iter().filter(|e| e.chunk_type == CHUNK_IDX) {
+        index = PadaIndex::from_chunk_payload(&read_at(r, e.payload_offset, e.len)?)?;
+        if index.is_some() {
+            break;
+        }
+    }
+    let index = index.ok_or_else(|| {
+        SlbcError::invalid("container has no pada index (encode with --pada-index)")
+    })?;
+    if entries.iter().any(|e| e.chunk_type == CHUNK_BHA) {
+        return Err(SlbcError::invalid(
+            "seeking reads PHON chunks; this container stores BHA and LIPI chunks",
+        ));
+    }
+    let phon = entries
+        .iter()
+        .filter(|e| e.chunk_type == CHUNK_PHON)
+        .nth(index.phon_chunk as usize)
+        .ok_or_else(|| {
+            SlbcError::invalid(format_args!(
+                "pada index refers to missing PHON chunk {}",
+                index.phon_chunk
+            ))
+        })?;
+    let list = spans(&index);
+    let span = list.get(n).ok_or_else(|| {
+        SlbcError::invalid(format_args!(
+            "{} {} out of range ({} {}s)",
+            what,
+            n + 1,
+            list.len(),
+            what
+        ))
+    })?;
+    if span.end as u64 > phon.len {
+        return Err(SlbcError::truncated(
+            phon.payload_offset as usize,
+            format_args!("{} {} runs past the end of its PHON chunk", what, n + 1),
+        ));
+    }
+    read_at(
+        r,
+        phon.payload_offset + span.start as u64,
+        span.len() as u64,
+    )
+}
+
+fn read_at<R: Read + Seek>(r: &mut R, offset: u64, len: u64) -> Result<Vec<u8>, SlbcError> {
+    r.seek(SeekFrom::Start(offset))
+        .map_err(|e| io_error("seek", e))?;
+    let mut buf = vec![0; len as usize];
+    read_exact(r, &mut buf)?;
+    Ok(buf)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_meta_chunk() {
+        let mut meta = MetaChunk {
+            title: Some("Bhagavadgītā".into()),
+            section: Some("2.11–25".into()),
+            ..Default::default()
+        };
+        meta.set("editor", "S. K. Belvalkar");
+        meta.set("language", "sa");
+        let chunk = meta.to_chunk().unwrap();
+        assert_eq!(chunk.chunk_type, CHUNK_META);
+        assert_eq!(chunk.payload[0], 4);
+        let read = MetaChunk::read(&chunk.payload).unwrap();
+        assert_eq!(read, meta);
+        assert_eq!(read.get("editor"), Some("S. K. Belvalkar"));
+        assert_eq!(
+            read.fields().iter().map(|(k, _)| *k).collect::<Vec<_>>(),
+            ["title", "section", "language", "editor"]
+        );
+
+        let file = build_slbc_with(&[PADA_START, 0x00, 0x40, PADA_END], &[chunk]);
+        let (_, chunks) = parse_slbc(&file).unwrap();
+        assert_eq!(MetaChunk::find(&chunks).unwrap(), Some(meta));
+        assert_eq!(MetaChunk::find(&chunks[1..]).unwrap(), None);
+
+        // A custom key may not shadow a standard one
+        let mut bad = MetaChunk::default();
+        bad.custom.push(("title".into(), "x".into()));
+        bad.title = Some("y".into());
+        assert!(bad.write().is_err());
+        assert!(MetaChunk::read(&[1, 1, b'k']).is_err());
+        assert!(MetaChunk::read(&[0, 0]).is_err());
+    }
+
     #[test]
     fn test_uleb128_roundtrip() {
         for val in [0u64, 1, 127, 128, 300, 16383, 16384, 100_000] {
@@ -233,5 +729,59 @@ mod tests {
         assert_eq!(chunks[0].chunk_type, CHUNK_PHON);
         assert_eq!(chunks[0].payload, payload);
         assert_eq!(chunks[1].chunk_type, CHUNK_EOF);
+        assert_eq!(chunks[0].kind(), ChunkType::Phon);
+    }
+
+    #[test]
+    fn test_chunk_type_names() {
+        for t in ChunkType::KNOWN {
+            assert!(t.is_known());
+            assert_eq!(ChunkType::from(t.byte()), t);
+            assert_eq!(t.to_string().parse::<ChunkType>(), Ok(t));
+        }
+        assert_eq!(ChunkType::from(CHUNK_IDX).to_string(), "IDX");
+        assert_eq!("phon".parse::<ChunkType>(), Ok(ChunkType::Phon));
+        let unknown = ChunkType::from(0x42);
+        assert!(!unknown.is_known());
+        assert_eq!(unknown.to_string(), "0x42");
+        assert_eq!("0x42".parse::<ChunkType>(), Ok(unknown));
+        assert!("PHONE".parse::<ChunkType>().is_err());
+    }
+
+    #[test]
+    fn test_extended_header_records() {
+        let records = vec![ExtRecord {
+            tag: EXT_HDR_DICT,
+            payload: vec![1, 2, 3],
+        }];
+        let slbc = build_slbc_ext(&records, &[0x26, 0x00, 0x2E], &[]);
+        let (header, chunks) = parse_slbc(&slbc).unwrap();
+        assert_eq!(header.extended_header_len, 5);
+        assert_eq!(header.ext_records().unwrap(), records);
+        assert_eq!(chunks[0].payload, vec![0x26, 0x00, 0x2E]);
+
+        let mut truncated = slbc[..14].to_vec();
+        truncated[12] = 0x40;
+        assert!(parse_slbc(&truncated).is_err());
+    }
+
+    #[test]
+    fn test_chunk_directory() {
+        let payload = vec![0x00; 200]; // two-byte ULEB128 length
+        let slbc = build_slbc_ext(
+            &[ExtRecord {
+                tag: EXT_HDR_DICT,
+                payload: vec![1, 2, 3],
+            }],
+            &payload,
+            &[],
+        );
+        let (header, entries) = chunk_directory(&mut std::io::Cursor::new(&slbc)).unwrap();
+        assert_eq!(header.extended_header_len, 5);
+        assert_eq!(entries.len(), 2);
+        assert_eq!((entries[0].offset, entries[0].payload_offset), (19, 22));
+        assert_eq!(entries[0].len, 200);
+        assert_eq!(entries[1].chunk_type, CHUNK_EOF);
+        assert_eq!(entries[1].end(), slbc.len() as u64);
     }
 }
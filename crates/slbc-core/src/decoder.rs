@@ -1,33 +1,997 @@
-//! SLBC → IAST / Devanāgarī decoder.
+//! SLBC → IAST / SLP1 / Harvard-Kyoto / ITRANS / Velthuis / WX / ISO 15919 /
+//! Devanāgarī / Telugu / Kannada / Grantha / Śāradā / Tamil / Gujarati /
+//! Gurmukhī / Siddhaṃ / Odia decoder.
 //!
 //! Walks a PHON chunk payload byte-by-byte, emitting text.
-//! Devanāgarī output follows §4.2 explicit vowel convention.
+//! Abugida output follows §4.2 explicit vowel convention.
 
+use std::borrow::Cow;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::abugida::{self, Abugida};
+use crate::annotation::Annotation;
+use crate::codepage::{self, ExtKind, LongExt, EXT_LONG};
+use crate::document::{self, Document};
+use crate::emend::{self, Reading};
+use crate::encoder;
+use crate::error::SlbcError;
+use crate::frame::{self, MetaSpan};
 use crate::numeral;
+use crate::phoneme::PhonemeIter;
+use crate::scheme::{self, Scheme};
+use crate::transform;
 use crate::types::*;
 
 /// Output script target.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Script {
     Iast,
+    Slp1,
+    HarvardKyoto,
+    Itrans,
+    Velthuis,
+    Wx,
+    Iso15919,
     Devanagari,
+    #[cfg(feature = "scripts")]
+    Telugu,
+    #[cfg(feature = "scripts")]
+    Kannada,
+    #[cfg(feature = "scripts")]
+    Grantha,
+    #[cfg(feature = "scripts")]
+    Sharada,
+    #[cfg(feature = "scripts")]
+    Tamil,
+    #[cfg(feature = "scripts")]
+    Gujarati,
+    #[cfg(feature = "scripts")]
+    Gurmukhi,
+    #[cfg(feature = "scripts")]
+    Siddham,
+    #[cfg(feature = "scripts")]
+    Odia,
+}
+
+impl Script {
+    pub const ALL: &'static [Script] = &[
+        Script::Iast,
+        Script::Slp1,
+        Script::HarvardKyoto,
+        Script::Itrans,
+        Script::Velthuis,
+        Script::Wx,
+        Script::Iso15919,
+        Script::Devanagari,
+        #[cfg(feature = "scripts")]
+        Script::Telugu,
+        #[cfg(feature = "scripts")]
+        Script::Kannada,
+        #[cfg(feature = "scripts")]
+        Script::Grantha,
+        #[cfg(feature = "scripts")]
+        Script::Sharada,
+        #[cfg(feature = "scripts")]
+        Script::Tamil,
+        #[cfg(feature = "scripts")]
+        Script::Gujarati,
+        #[cfg(feature = "scripts")]
+        Script::Gurmukhi,
+        #[cfg(feature = "scripts")]
+        Script::Siddham,
+        #[cfg(feature = "scripts")]
+        Script::Odia,
+    ];
+
+    /// The scripts text can be encoded from; the rest are output only.
+    pub const INPUT: &'static [Script] = &[
+        Script::Iast,
+        Script::Slp1,
+        Script::HarvardKyoto,
+        Script::Itrans,
+        Script::Velthuis,
+        Script::Wx,
+        Script::Iso15919,
+        Script::Devanagari,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Script::Iast => "iast",
+            Script::Slp1 => "slp1",
+            Script::HarvardKyoto => "hk",
+            Script::Itrans => "itrans",
+            Script::Velthuis => "velthuis",
+            Script::Wx => "wx",
+            Script::Iso15919 => "iso15919",
+            Script::Devanagari => "devanagari",
+            #[cfg(feature = "scripts")]
+            Script::Telugu => "telugu",
+            #[cfg(feature = "scripts")]
+            Script::Kannada => "kannada",
+            #[cfg(feature = "scripts")]
+            Script::Grantha => "grantha",
+            #[cfg(feature = "scripts")]
+            Script::Sharada => "sharada",
+            #[cfg(feature = "scripts")]
+            Script::Tamil => "tamil",
+            #[cfg(feature = "scripts")]
+            Script::Gujarati => "gujarati",
+            #[cfg(feature = "scripts")]
+            Script::Gurmukhi => "gurmukhi",
+            #[cfg(feature = "scripts")]
+            Script::Siddham => "siddham",
+            #[cfg(feature = "scripts")]
+            Script::Odia => "odia",
+        }
+    }
+
+    /// The romanization table, for romanized scripts.
+    pub fn scheme(self) -> Option<&'static Scheme> {
+        match self {
+            Script::Iast => Some(&scheme::IAST),
+            Script::Slp1 => Some(&scheme::SLP1),
+            Script::HarvardKyoto => Some(&scheme::HK),
+            Script::Itrans => Some(&scheme::ITRANS),
+            Script::Velthuis => Some(&scheme::VELTHUIS),
+            Script::Wx => Some(&scheme::WX),
+            Script::Iso15919 => Some(&scheme::ISO15919),
+            _ => None,
+        }
+    }
+
+    /// The abugida table, for Brahmic scripts.
+    pub fn abugida(self) -> Option<&'static Abugida> {
+        match self {
+            Script::Devanagari => Some(&abugida::DEVANAGARI),
+            #[cfg(feature = "scripts")]
+            Script::Telugu => Some(&abugida::TELUGU),
+            #[cfg(feature = "scripts")]
+            Script::Kannada => Some(&abugida::KANNADA),
+            #[cfg(feature = "scripts")]
+            Script::Grantha => Some(&abugida::GRANTHA),
+            #[cfg(feature = "scripts")]
+            Script::Sharada => Some(&abugida::SHARADA),
+            #[cfg(feature = "scripts")]
+            Script::Tamil => Some(&abugida::TAMIL),
+            #[cfg(feature = "scripts")]
+            Script::Gujarati => Some(&abugida::GUJARATI),
+            #[cfg(feature = "scripts")]
+            Script::Gurmukhi => Some(&abugida::GURMUKHI),
+            #[cfg(feature = "scripts")]
+            Script::Siddham => Some(&abugida::SIDDHAM),
+            #[cfg(feature = "scripts")]
+            Script::Odia => Some(&abugida::ODIA),
+            _ => None,
+        }
+    }
+
+    fn target(self) -> Target<'static> {
+        match (self.scheme(), self.abugida()) {
+            (Some(roman), _) => Target::Roman(roman),
+            (None, Some(abugida)) => Target::Abugida(abugida),
+            (None, None) => unreachable!("every script is romanized or an abugida"),
+        }
+    }
+
+    /// Parse a script name as given on the command line.
+    pub fn from_name(name: &str) -> Option<Script> {
+        match name {
+            "iast" => Some(Script::Iast),
+            "slp1" => Some(Script::Slp1),
+            "hk" | "harvard-kyoto" => Some(Script::HarvardKyoto),
+            "itrans" => Some(Script::Itrans),
+            "velthuis" => Some(Script::Velthuis),
+            "wx" => Some(Script::Wx),
+            "iso15919" | "iso" => Some(Script::Iso15919),
+            "devanagari" | "deva" => Some(Script::Devanagari),
+            #[cfg(feature = "scripts")]
+            "telugu" | "telu" => Some(Script::Telugu),
+            #[cfg(feature = "scripts")]
+            "kannada" | "knda" => Some(Script::Kannada),
+            #[cfg(feature = "scripts")]
+            "grantha" | "gran" => Some(Script::Grantha),
+            #[cfg(feature = "scripts")]
+            "sharada" | "shrd" => Some(Script::Sharada),
+            #[cfg(feature = "scripts")]
+            "tamil" | "taml" => Some(Script::Tamil),
+            #[cfg(feature = "scripts")]
+            "gujarati" | "gujr" => Some(Script::Gujarati),
+            #[cfg(feature = "scripts")]
+            "gurmukhi" | "guru" => Some(Script::Gurmukhi),
+            #[cfg(feature = "scripts")]
+            "siddham" | "sidd" => Some(Script::Siddham),
+            #[cfg(feature = "scripts")]
+            "odia" | "oriya" | "orya" => Some(Script::Odia),
+            _ => None,
+        }
+    }
+}
+
+/// How SAṄKHYĀ numeral spans are rendered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NumeralStyle {
+    /// Digit glyphs (e.g. "108", "१०८").
+    #[default]
+    Digits,
+    /// The bhāṣā-layer digit-words in recitation order, units first
+    /// (e.g. "aṣṭa śūnya eka").
+    Words,
+    /// Digit glyphs followed by the digit-words in parentheses.
+    Both,
+    /// The number as one composed word (e.g. "aṣṭottaraśatam"), up to 999;
+    /// larger numbers fall back to the digit-words.
+    Composed,
+}
+
+/// ASCII-only romanized output: graphemes with diacritics are replaced by
+/// their spelling in a fallback scheme, or by "?" — as the scheme itself
+/// writes them — for sounds it has no spelling for (ḻ, ṟ, …); dashes and
+/// curly quotes become their ASCII forms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AsciiFallback {
+    #[default]
+    Off,
+    Velthuis,
+    Itrans,
+    /// Harvard-Kyoto: capitals for long vowels and retroflexes (`A`, `S`).
+    HarvardKyoto,
+}
+
+impl AsciiFallback {
+    /// The scheme supplying replacement graphemes.
+    pub fn scheme(self) -> Option<&'static Scheme> {
+        match self {
+            AsciiFallback::Off => None,
+            AsciiFallback::Velthuis => Some(&scheme::VELTHUIS),
+            AsciiFallback::Itrans => Some(&scheme::ITRANS),
+            AsciiFallback::HarvardKyoto => Some(&scheme::HK),
+        }
+    }
+
+    /// Parse a fallback name as given on the command line.
+    pub fn from_name(name: &str) -> Option<AsciiFallback> {
+        match name {
+            "off" => Some(AsciiFallback::Off),
+            "velthuis" => Some(AsciiFallback::Velthuis),
+            "itrans" => Some(AsciiFallback::Itrans),
+            "hk" => Some(AsciiFallback::HarvardKyoto),
+            _ => None,
+        }
+    }
+}
+
+/// ISO 15919 output style.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IsoStyle {
+    /// `ṁ`, `ē`/`ō`.
+    #[default]
+    Strict,
+    /// Library catalog style: `ṃ`, unmarked `e`/`o` (short ĕ/ŏ marked).
+    Library,
+}
+
+impl IsoStyle {
+    /// Parse a style name as given on the command line.
+    pub fn from_name(name: &str) -> Option<IsoStyle> {
+        match name {
+            "strict" => Some(IsoStyle::Strict),
+            "library" => Some(IsoStyle::Library),
+            _ => None,
+        }
+    }
+
+    /// Library-style replacement for a byte or extension code.
+    fn library_grapheme(b: u8) -> Option<&'static str> {
+        match b {
+            0x3A => Some("ṃ"),
+            0x85 => Some("e"),
+            0x89 => Some("o"),
+            _ => None,
+        }
+    }
+
+    fn library_ext_grapheme(code: u8) -> Option<&'static str> {
+        match code {
+            0x10 => Some("ĕ"),
+            0x11 => Some("ŏ"),
+            _ => None,
+        }
+    }
+}
+
+/// How Tamil output tells apart the varga columns its script merges.
+///
+/// Unicode encodes no Tamil letters for kha, ga, gha and the like, so the
+/// superscript convention is the only lossless one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TamilStyle {
+    /// க க² க³ க⁴: a superscript digit after the vowel sign or pulli.
+    #[default]
+    Superscript,
+    /// Bare letters, as in ordinary Tamil text (lossy).
+    Plain,
+}
+
+impl TamilStyle {
+    /// Parse a style name as given on the command line.
+    pub fn from_name(name: &str) -> Option<TamilStyle> {
+        match name {
+            "superscript" => Some(TamilStyle::Superscript),
+            "plain" => Some(TamilStyle::Plain),
+            _ => None,
+        }
+    }
+}
+
+/// How abugida output writes a consonant cluster.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Conjuncts {
+    /// Virāma between the members, leaving the conjunct to the font.
+    #[default]
+    Natural,
+    /// ZWNJ after each virāma, so every member keeps a visible virāma
+    /// (क्‌ष), as pedagogical editions print it.
+    Explicit,
+    /// ZWJ after each virāma, asking for half forms (क्‍ष).
+    HalfForms,
+}
+
+impl Conjuncts {
+    /// Short name, as accepted by the CLI `--conjuncts` flag.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Conjuncts::Natural => "natural",
+            Conjuncts::Explicit => "explicit",
+            Conjuncts::HalfForms => "half",
+        }
+    }
+
+    /// Parse a style from its short name.
+    pub fn from_name(name: &str) -> Option<Conjuncts> {
+        match name {
+            "natural" => Some(Conjuncts::Natural),
+            "explicit" => Some(Conjuncts::Explicit),
+            "half" => Some(Conjuncts::HalfForms),
+            _ => None,
+        }
+    }
+
+    /// What follows the virāma inside a cluster.
+    fn joiner(&self) -> &'static str {
+        match self {
+            Conjuncts::Natural => "",
+            Conjuncts::Explicit => "\u{200C}",
+            Conjuncts::HalfForms => "\u{200D}",
+        }
+    }
+}
+
+/// Decoder options.
+#[derive(Debug, Clone, Default)]
+pub struct DecodeOptions {
+    pub numerals: NumeralStyle,
+    /// Romanized output only: replace non-ASCII graphemes. Extension sounds
+    /// the fallback scheme cannot write keep their diacritic form.
+    pub ascii: AsciiFallback,
+    /// ISO 15919 output only.
+    pub iso: IsoStyle,
+    /// Tamil output only.
+    pub tamil: TamilStyle,
+    /// Abugida output only.
+    pub conjuncts: Conjuncts,
+    /// IAST and ISO 15919 output only (not with an ASCII fallback):
+    /// capitalize the first word of the text and of each verse, i.e. after
+    /// every double daṇḍa.
+    pub capitalize_verses: bool,
+    /// Which reading of emended spans to render.
+    pub reading: Reading,
+    /// Write morpheme seams as "+"; otherwise they are left out.
+    pub morph_boundaries: bool,
+    /// Write compound boundaries as "-"; otherwise they are left out.
+    pub compound_boundaries: bool,
+    /// Write an avagraha for an elided a as the a (`te api` for `te 'pi`,
+    /// see `transform::restore_elision`).
+    pub restore_elision: bool,
+    /// Write a visarga before k, kh, p, or ph as jihvāmūlīya or
+    /// upadhmānīya (see `transform::visarga_allophones`).
+    pub visarga_allophones: bool,
+    /// Write PHON frames as ⟨ … ⟩ (`encoder::PHON_OPEN`, `PHON_CLOSE`);
+    /// otherwise they are left out.
+    pub phon_boundaries: bool,
+    /// Write inline annotations as `{key: value}`; otherwise they are
+    /// skipped like any other META block.
+    pub annotations: bool,
 }
 
 /// Decode a PHON chunk payload to text.
-pub fn decode_phon(payload: &[u8], script: Script) -> Result<String, String> {
-    match script {
-        Script::Iast => decode_to_iast(payload),
-        Script::Devanagari => decode_to_devanagari(payload),
+pub fn decode_phon(payload: &[u8], script: Script) -> Result<String, SlbcError> {
+    decode_phon_with(payload, script, &DecodeOptions::default())
+}
+
+/// Decode a PHON chunk payload to text with explicit options.
+pub fn decode_phon_with(
+    payload: &[u8],
+    script: Script,
+    opts: &DecodeOptions,
+) -> Result<String, SlbcError> {
+    decode_target(payload, script.target(), opts)
+}
+
+/// Decode a PHON chunk payload straight into `w`, with no `String` built
+/// along the way, so a large corpus can go to a file chunk by chunk.
+pub fn decode_phon_to<W: fmt::Write + ?Sized>(
+    payload: &[u8],
+    script: Script,
+    w: &mut W,
+) -> Result<(), SlbcError> {
+    decode_phon_with_to(payload, script, &DecodeOptions::default(), w)
+}
+
+/// `decode_phon_to` with explicit options. A failed write is an
+/// `SlbcError::Io`; the text written before it stays written.
+pub fn decode_phon_with_to<W: fmt::Write + ?Sized>(
+    payload: &[u8],
+    script: Script,
+    opts: &DecodeOptions,
+    w: &mut W,
+) -> Result<(), SlbcError> {
+    let mut sink = Sink::new(w);
+    render(payload, script.target(), opts, &mut sink)?;
+    sink.finish()
+}
+
+/// The exact length in bytes of the UTF-8 text `decode_phon_with` returns,
+/// found by decoding without keeping the text, to size a buffer or a file
+/// ahead of writing it.
+pub fn decoded_len(
+    payload: &[u8],
+    script: Script,
+    opts: &DecodeOptions,
+) -> Result<usize, SlbcError> {
+    let mut count = Count::default();
+    render(payload, script.target(), opts, &mut count)?;
+    Ok(count.len)
+}
+
+/// The payload as a tree of sentences, padas, and phonemes with their byte
+/// offsets (see `document`), for tools that need structure rather than
+/// text.
+pub fn decode_document(payload: &[u8]) -> Result<Document, SlbcError> {
+    document::build(payload)
+}
+
+/// The payload as a stream of typed events — pada boundaries, phonemes,
+/// numerals, punctuation, META blocks — pulled one at a time (see
+/// `phoneme`).
+pub fn phonemes(payload: &[u8]) -> PhonemeIter<'_> {
+    PhonemeIter::new(payload)
+}
+
+/// Decode each outermost PHON frame of a payload on its own: the sentences
+/// of a text encoded with `PhonFrames::Sentences`, or its verses or
+/// quotations. Text outside every frame is a sentence of its own where it
+/// has any, so a payload without frames is one sentence. Each is trimmed
+/// of surrounding whitespace.
+pub fn decode_sentences(
+    payload: &[u8],
+    script: Script,
+    opts: &DecodeOptions,
+) -> Result<Vec<String>, SlbcError> {
+    let data = prepared(payload, opts)?;
+    let mut stretches = Vec::new();
+    let mut rest = 0;
+    for span in frame::phon_spans(&data)? {
+        if span.depth == 0 {
+            stretches.push(rest..span.body.start - 1);
+            stretches.push(span.body.clone());
+            rest = span.body.end + 1;
+        }
+    }
+    stretches.push(rest..data.len());
+
+    let mut sentences = Vec::new();
+    for stretch in stretches {
+        let text = decode_phon_with(&data[stretch], script, opts)?;
+        if !text.trim().is_empty() {
+            sentences.push(text.trim().to_string());
+        }
+    }
+    Ok(sentences)
+}
+
+/// Decoded text, with the META blocks and long extensions that were
+/// skipped to produce it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decoded {
+    pub text: String,
+    /// Offsets are into the payload as given.
+    pub skipped_meta: Vec<MetaSpan>,
+    pub skipped_ext: Vec<LongExt>,
+}
+
+/// `decode_phon_with`, also reporting the META blocks and long extensions
+/// the text leaves out.
+pub fn decode_phon_logged(
+    payload: &[u8],
+    script: Script,
+    opts: &DecodeOptions,
+) -> Result<Decoded, SlbcError> {
+    Ok(Decoded {
+        text: decode_phon_with(payload, script, opts)?,
+        skipped_meta: skipped_meta(payload, opts)?,
+        skipped_ext: frame::long_exts(payload)?,
+    })
+}
+
+/// The META blocks the text leaves out: all but the emendation blocks,
+/// and the annotations if `opts` write them.
+pub fn skipped_meta(payload: &[u8], opts: &DecodeOptions) -> Result<Vec<MetaSpan>, SlbcError> {
+    let mut spans = frame::meta_spans(payload)?;
+    spans.retain(|span| match span.bytes.first() {
+        Some(&SUBTAG_EMEND) => false,
+        Some(&SUBTAG_NOTE) if span.closed => !opts.annotations,
+        _ => true,
+    });
+    Ok(spans)
+}
+
+/// Decode a PHON chunk payload with any romanization table, built-in or not.
+pub fn decode_scheme(
+    payload: &[u8],
+    roman: &Scheme,
+    opts: &DecodeOptions,
+) -> Result<String, SlbcError> {
+    decode_target(payload, Target::Roman(roman), opts)
+}
+
+/// A reusable decoding context: one script and set of options, with every
+/// romanized grapheme resolved up front. Cheap to clone and `Send + Sync`,
+/// so one instance can serve many threads.
+#[derive(Debug, Clone)]
+pub struct Decoder {
+    script: Script,
+    opts: DecodeOptions,
+    table: Option<Arc<GraphemeTable>>,
+}
+
+impl Decoder {
+    pub fn new(script: Script, opts: DecodeOptions) -> Self {
+        let table = script
+            .scheme()
+            .map(|roman| Arc::new(GraphemeTable::new(roman, &opts)));
+        Decoder {
+            script,
+            opts,
+            table,
+        }
+    }
+
+    pub fn script(&self) -> Script {
+        self.script
+    }
+
+    pub fn options(&self) -> &DecodeOptions {
+        &self.opts
+    }
+
+    /// Decode a PHON chunk payload to text.
+    pub fn decode(&self, payload: &[u8]) -> Result<String, SlbcError> {
+        let mut out = String::new();
+        self.decode_into(payload, &mut out)?;
+        Ok(out)
+    }
+
+    /// `decode`, also reporting the META blocks and long extensions the
+    /// text leaves out.
+    pub fn decode_logged(&self, payload: &[u8]) -> Result<Decoded, SlbcError> {
+        Ok(Decoded {
+            text: self.decode(payload)?,
+            skipped_meta: skipped_meta(payload, &self.opts)?,
+            skipped_ext: frame::long_exts(payload)?,
+        })
+    }
+
+    /// Decode a PHON chunk payload, appending the text to `out`.
+    pub fn decode_into(&self, payload: &[u8], out: &mut String) -> Result<(), SlbcError> {
+        self.render(payload, out)
+    }
+
+    /// Decode a PHON chunk payload straight into `w` (see `decode_phon_to`).
+    pub fn decode_to<W: fmt::Write + ?Sized>(
+        &self,
+        payload: &[u8],
+        w: &mut W,
+    ) -> Result<(), SlbcError> {
+        let mut sink = Sink::new(w);
+        self.render(payload, &mut sink)?;
+        sink.finish()
+    }
+
+    /// The exact length in bytes of the text `decode` returns.
+    pub fn decoded_len(&self, payload: &[u8]) -> Result<usize, SlbcError> {
+        let mut count = Count::default();
+        self.render(payload, &mut count)?;
+        Ok(count.len)
+    }
+
+    fn render(&self, payload: &[u8], out: &mut impl Out) -> Result<(), SlbcError> {
+        let data = prepared(payload, &self.opts)?;
+        match (self.script.target(), &self.table) {
+            (Target::Roman(roman), Some(table)) => {
+                decode_to_roman(&data, table.as_ref(), roman, &self.opts, out)
+            }
+            (Target::Roman(roman), None) => decode_to_roman(
+                &data,
+                &RomanGraphemes::new(roman, &self.opts),
+                roman,
+                &self.opts,
+                out,
+            ),
+            (Target::Abugida(abugida), _) => decode_to_abugida(&data, abugida, &self.opts, out),
+        }
+    }
+}
+
+/// What a payload is rendered in.
+#[derive(Clone, Copy)]
+enum Target<'a> {
+    Roman(&'a Scheme),
+    Abugida(&'static Abugida),
+}
+
+fn decode_target(
+    payload: &[u8],
+    target: Target,
+    opts: &DecodeOptions,
+) -> Result<String, SlbcError> {
+    let mut out = String::new();
+    render(payload, target, opts, &mut out)?;
+    Ok(out)
+}
+
+fn render(
+    payload: &[u8],
+    target: Target,
+    opts: &DecodeOptions,
+    out: &mut impl Out,
+) -> Result<(), SlbcError> {
+    let data = prepared(payload, opts)?;
+    match target {
+        Target::Roman(roman) => {
+            decode_to_roman(&data, &RomanGraphemes::new(roman, opts), roman, opts, out)
+        }
+        Target::Abugida(abugida) => decode_to_abugida(&data, abugida, opts, out),
+    }
+}
+
+/// Where decoded text goes: a `String`, any `fmt::Write` (`Sink`), or
+/// nowhere, only its length kept (`Count`).
+trait Out {
+    fn push_str(&mut self, s: &str);
+    /// The last char written, if any.
+    fn last(&self) -> Option<char>;
+
+    fn push(&mut self, c: char) {
+        self.push_str(c.encode_utf8(&mut [0; 4]));
+    }
+}
+
+impl Out for String {
+    fn push_str(&mut self, s: &str) {
+        String::push_str(self, s);
+    }
+
+    fn last(&self) -> Option<char> {
+        self.chars().next_back()
+    }
+
+    fn push(&mut self, c: char) {
+        String::push(self, c);
+    }
+}
+
+/// Text passed on to a writer as it is decoded. After a failed write the
+/// rest is dropped, and `finish` reports the failure.
+struct Sink<'w, W: ?Sized> {
+    w: &'w mut W,
+    last: Option<char>,
+    failed: bool,
+}
+
+impl<'w, W: fmt::Write + ?Sized> Sink<'w, W> {
+    fn new(w: &'w mut W) -> Self {
+        Sink {
+            w,
+            last: None,
+            failed: false,
+        }
+    }
+
+    fn finish(self) -> Result<(), SlbcError> {
+        match self.failed {
+            true => Err(SlbcError::Io {
+                message: "writing decoded text failed".into(),
+            }),
+            false => Ok(()),
+        }
+    }
+}
+
+impl<W: fmt::Write + ?Sized> Out for Sink<'_, W> {
+    fn push_str(&mut self, s: &str) {
+        if self.failed || s.is_empty() {
+            return;
+        }
+        self.failed = self.w.write_str(s).is_err();
+        self.last = s.chars().next_back();
+    }
+
+    fn last(&self) -> Option<char> {
+        self.last
+    }
+}
+
+/// Decoded text measured and dropped.
+#[derive(Default)]
+struct Count {
+    len: usize,
+    last: Option<char>,
+}
+
+impl Out for Count {
+    fn push_str(&mut self, s: &str) {
+        self.len += s.len();
+        self.last = s.chars().next_back().or(self.last);
+    }
+
+    fn last(&self) -> Option<char> {
+        self.last
+    }
+}
+
+/// The payload in the chosen reading, with degenerate framing normalized
+/// away, and elided a restored and visarga allophones written if asked.
+fn prepared<'a>(payload: &'a [u8], opts: &DecodeOptions) -> Result<Cow<'a, [u8]>, SlbcError> {
+    let data = match emend::select(payload, opts.reading)? {
+        Cow::Borrowed(data) => framed(data)?,
+        Cow::Owned(data) => Cow::Owned(framed(&data)?.into_owned()),
+    };
+    let data = match opts.restore_elision {
+        true => Cow::Owned(transform::restore_elision(&data)?.0),
+        false => data,
+    };
+    if opts.visarga_allophones {
+        return Ok(Cow::Owned(transform::visarga_allophones(&data)?.0));
+    }
+    Ok(data)
+}
+
+/// The payload with degenerate framing (empty padas, doubled SPACEs, nested
+/// PHON frames) normalized away; borrowed when there is none.
+fn framed(payload: &[u8]) -> Result<Cow<'_, [u8]>, SlbcError> {
+    if frame::check_framing(payload)?.is_empty() {
+        Ok(Cow::Borrowed(payload))
+    } else {
+        Ok(Cow::Owned(frame::normalize_framing(payload)?.0))
     }
 }
 
 // ═══════════════════════════════════════════════
-//  IAST decoder
+//  Numeral spans
 // ═══════════════════════════════════════════════
 
-fn decode_to_iast(data: &[u8]) -> Result<String, String> {
-    let mut out = String::new();
+/// Render a SAṄKHYĀ span at `pos` (and the NUM span that follows it, if any).
+/// Returns the number of bytes consumed.
+///
+/// Roman digit glyphs come from the bhāṣā digit-words; script-specific glyphs
+/// come from the NUM span, per §6.3.5.
+fn decode_numeral_span(
+    data: &[u8],
+    pos: usize,
+    target: Target,
+    opts: &DecodeOptions,
+    out: &mut impl Out,
+) -> Result<usize, SlbcError> {
+    let (digits, mut consumed) = numeral::decode_sankhya(data, pos)?;
+    let mut glyphs = Vec::new();
+    if pos + consumed < data.len() && data[pos + consumed] == NUM {
+        let (g, num_consumed) = numeral::decode_num(data, pos + consumed)?;
+        glyphs = g;
+        consumed += num_consumed;
+    }
+
+    if opts.numerals == NumeralStyle::Composed {
+        if let Some(word) = numeral::cardinal(&digits) {
+            let pada = encoder::encode_iast(&word)?;
+            out.push_str(&decode_target(&pada, target, opts)?);
+            return Ok(consumed);
+        }
+    }
+
+    if matches!(opts.numerals, NumeralStyle::Digits | NumeralStyle::Both) {
+        match target {
+            Target::Abugida(abugida) => push_digit_glyphs(&glyphs, &abugida.digits, out),
+            Target::Roman(_) => {
+                for d in &digits {
+                    out.push(char::from_digit(*d as u32, 10).unwrap());
+                }
+            }
+        }
+    }
+
+    if opts.numerals != NumeralStyle::Digits {
+        let mut words = Vec::with_capacity(digits.len());
+        // Recitation order: units first, as stored in the bhāṣā layer
+        for &d in digits.iter().rev() {
+            let mut pada = vec![PADA_START];
+            pada.extend_from_slice(numeral::digit_word(d));
+            pada.push(PADA_END);
+            words.push(decode_target(&pada, target, opts)?);
+        }
+        if opts.numerals == NumeralStyle::Both {
+            out.push_str(" (");
+            out.push_str(&words.join(" "));
+            out.push(')');
+        } else {
+            out.push_str(&words.join(" "));
+        }
+    }
+
+    Ok(consumed)
+}
+
+/// Digit glyphs of the romanized schemes.
+const ROMAN_DIGITS: [char; 10] = ['0', '1', '2', '3', '4', '5', '6', '7', '8', '9'];
+
+/// Push NUM-span glyph bytes using a script's digit table.
+/// Bytes outside 0–9 (separators, signs) have no glyph yet and render as "?".
+fn push_digit_glyphs(glyphs: &[u8], table: &[char; 10], out: &mut impl Out) {
+    for &g in glyphs {
+        out.push(table.get(g as usize).copied().unwrap_or('?'));
+    }
+}
+
+// ═══════════════════════════════════════════════
+//  Romanized decoder (IAST, SLP1, HK, ITRANS, Velthuis, WX, ISO 15919)
+// ═══════════════════════════════════════════════
+
+/// `grapheme`, or its fallback spelling ("?" if the fallback scheme has
+/// none) when it is not ASCII.
+fn ascii_or(grapheme: &'static str, fallback: Option<&'static str>) -> &'static str {
+    match fallback {
+        Some(f) if !grapheme.is_ascii() => f,
+        _ => grapheme,
+    }
+}
+
+/// The ASCII form of a punctuation mark that is not ASCII (see
+/// `codepage::PUNCTUATION`).
+fn ascii_punctuation(mark: char) -> Option<&'static str> {
+    match mark {
+        '–' => Some("-"),
+        '—' => Some("--"),
+        '“' | '”' => Some("\""),
+        '‘' | '’' => Some("'"),
+        _ => None,
+    }
+}
+
+/// Graphemes for each byte kind, with the decode options applied.
+trait Graphemes {
+    /// Svara or vyañjana byte.
+    fn phoneme(&self, b: u8) -> &'static str;
+    /// DANDA, DOUBLE_DANDA, AVAGRAHA, or CANDRABINDU ("?" if the scheme
+    /// has no grapheme for it).
+    fn lipi(&self, b: u8) -> &'static str;
+    /// Extension code.
+    fn ext(&self, code: u8) -> &'static str;
+}
+
+/// Graphemes looked up in the scheme tables as they are needed.
+struct RomanGraphemes<'a> {
+    roman: &'a Scheme,
+    fallback: Option<&'static Scheme>,
+    library: bool,
+}
+
+impl<'a> RomanGraphemes<'a> {
+    fn new(roman: &'a Scheme, opts: &DecodeOptions) -> Self {
+        RomanGraphemes {
+            roman,
+            fallback: opts.ascii.scheme(),
+            library: roman.name == scheme::ISO15919.name && opts.iso == IsoStyle::Library,
+        }
+    }
+}
+
+impl Graphemes for RomanGraphemes<'_> {
+    fn phoneme(&self, b: u8) -> &'static str {
+        let base = if is_svara(b) { b & 0b11_00_11_11 } else { b };
+        let grapheme = IsoStyle::library_grapheme(base)
+            .filter(|_| self.library)
+            .unwrap_or_else(|| byte_to_roman(self.roman, b));
+        ascii_or(grapheme, self.fallback.map(|f| byte_to_roman(f, b)))
+    }
+
+    fn lipi(&self, b: u8) -> &'static str {
+        ascii_or(
+            self.roman.grapheme(b).unwrap_or("?"),
+            self.fallback.map(|f| f.grapheme(b).unwrap_or("?")),
+        )
+    }
+
+    fn ext(&self, code: u8) -> &'static str {
+        let grapheme = IsoStyle::library_ext_grapheme(code)
+            .filter(|_| self.library)
+            .or_else(|| self.roman.ext_grapheme(code));
+        ascii_or(
+            grapheme.unwrap_or("?"),
+            self.fallback.map(|f| f.ext_grapheme(code).unwrap_or("?")),
+        )
+    }
+}
+
+/// Every byte's and extension code's grapheme, resolved once.
+#[derive(Debug, Clone)]
+struct GraphemeTable {
+    bytes: [&'static str; 256],
+    ext: [&'static str; 256],
+}
+
+impl GraphemeTable {
+    fn new(roman: &Scheme, opts: &DecodeOptions) -> Self {
+        let graphemes = RomanGraphemes::new(roman, opts);
+        let mut table = GraphemeTable {
+            bytes: ["?"; 256],
+            ext: ["?"; 256],
+        };
+        for b in 0..=255u8 {
+            if is_svara(b) || is_vyanjana(b) {
+                table.bytes[b as usize] = graphemes.phoneme(b);
+            } else if matches!(b, DANDA | DOUBLE_DANDA | AVAGRAHA | CANDRABINDU) {
+                table.bytes[b as usize] = graphemes.lipi(b);
+            }
+            table.ext[b as usize] = graphemes.ext(b);
+        }
+        table
+    }
+}
+
+impl Graphemes for GraphemeTable {
+    fn phoneme(&self, b: u8) -> &'static str {
+        self.bytes[b as usize]
+    }
+
+    fn lipi(&self, b: u8) -> &'static str {
+        self.bytes[b as usize]
+    }
+
+    fn ext(&self, code: u8) -> &'static str {
+        self.ext[code as usize]
+    }
+}
+
+fn decode_to_roman(
+    data: &[u8],
+    graphemes: &impl Graphemes,
+    roman: &Scheme,
+    opts: &DecodeOptions,
+    out: &mut impl Out,
+) -> Result<(), SlbcError> {
     let mut i = 0;
+    // Only IAST and ISO 15919 have case and accents to give
+    let cased = matches!(roman.name, "iast" | "iso15919") && opts.ascii == AsciiFallback::Off;
+    let accent = |grapheme, b| match cased {
+        true => accented(grapheme, svara_a(b)),
+        false => Cow::Borrowed(grapheme),
+    };
+    let capitalize = opts.capitalize_verses && cased;
+    let mut casing = Casing {
+        initial: capitalize,
+        pada: false,
+    };
 
     while i < data.len() {
         let b = data[i];
@@ -35,32 +999,30 @@ fn decode_to_iast(data: &[u8]) -> Result<String, String> {
         // ── Bhāṣā controls ──
         if is_bhasha_control(b) {
             match b {
-                PADA_START | PADA_END | PHON_START | PHON_END => {
+                PADA_START | PADA_END => {
+                    casing.pada &= b == PADA_START;
                     i += 1;
                     continue;
                 }
-                META_START => {
-                    // Skip META block (not present in pāṭha, but defensive)
-                    i += 1;
-                    while i < data.len() && data[i] != META_END {
-                        i += 1;
+                PHON_START | PHON_END => {
+                    if opts.phon_boundaries {
+                        out.push(phon_mark(b));
                     }
-                    i += 1; // skip META_END
+                    i += 1;
+                    continue;
+                }
+                META_START => {
+                    // Skip META block (not present in pāṭha) unless it is an
+                    // annotation to write; `decode_phon_logged` reports what
+                    // was skipped
+                    i += write_annotation(data, i, opts, out);
                     continue;
                 }
                 SANKHYA_START => {
-                    let (digits, consumed) = numeral::decode_sankhya(data, i)?;
-                    for d in &digits {
-                        out.push(char::from_digit(*d as u32, 10).unwrap());
-                    }
-                    i += consumed;
-                    // Skip the following NUM span (lipi-layer)
-                    if i < data.len() && data[i] == NUM {
-                        let (_, num_consumed) = numeral::decode_num(data, i)?;
-                        i += num_consumed;
-                    }
+                    i += decode_numeral_span(data, i, Target::Roman(roman), opts, out)?;
                     continue;
                 }
+                DICT_REF => return Err(SlbcError::DictRef { offset: i }),
                 _ => {
                     i += 1;
                     continue;
@@ -68,20 +1030,79 @@ fn decode_to_iast(data: &[u8]) -> Result<String, String> {
             }
         }
 
+        if b == MORPH {
+            if opts.morph_boundaries {
+                out.push('+');
+            }
+            i += 1;
+            continue;
+        }
+
+        if codepage::is_samasa(data, i) {
+            if opts.compound_boundaries {
+                out.push('-');
+            }
+            i += 2;
+            continue;
+        }
+
+        // Written together with its anusvāra below; alone it says nothing.
+        // Romanized oṃ is the same written as ॐ or not, and no script
+        // writes the yama or nāsikya.
+        if matches!(b, CANDRABINDU | PRANAVA | YAMA | NASIKYA) {
+            i += 1;
+            continue;
+        }
+
+        if b == CAPITAL || b == UPPERCASE {
+            casing.initial |= cased && b == CAPITAL;
+            casing.pada |= cased && b == UPPERCASE;
+            i += 1;
+            continue;
+        }
+
+        if b == LINE_BREAK {
+            out.push('\n');
+            i += 1;
+            continue;
+        }
+
         // ── Lipi controls ──
         if is_lipi_control(b) {
             match b {
                 SPACE => out.push(' '),
-                DANDA => out.push('|'),
-                DOUBLE_DANDA => out.push_str("||"),
-                AVAGRAHA => out.push('\''),
+                DANDA | DOUBLE_DANDA | AVAGRAHA => {
+                    out.push_str(graphemes.lipi(b));
+                    casing.initial |= capitalize && b == DOUBLE_DANDA;
+                }
+                ILLEGIBLE => out.push_str(encoder::ILLEGIBLE_MARK),
+                LACUNA => out.push_str(encoder::LACUNA_MARK),
+                // A long extension says nothing a reader can be shown
+                META_EXT if data.get(i + 1) == Some(&EXT_LONG) => {
+                    i += codepage::ext_len(data, i)?;
+                    continue;
+                }
+                META_EXT => {
+                    match data.get(i + 1).and_then(|&c| codepage::punctuation(c)) {
+                        Some(mark) => match ascii_punctuation(mark)
+                            .filter(|_| opts.ascii != AsciiFallback::Off)
+                        {
+                            Some(ascii) => out.push_str(ascii),
+                            None => out.push(mark),
+                        },
+                        None => {
+                            let p = codepage::read_ext(data, i)?;
+                            push_cased(graphemes.ext(p.code), &mut casing, out);
+                        }
+                    }
+                    i += 2;
+                    continue;
+                }
                 NUM => {
                     // Standalone NUM span (shouldn't appear without SAṄKHYĀ in pāṭha,
                     // but handle gracefully)
-                    let (digits, consumed) = numeral::decode_num(data, i)?;
-                    for d in &digits {
-                        out.push(char::from_digit(*d as u32, 10).unwrap());
-                    }
+                    let (glyphs, consumed) = numeral::decode_num(data, i)?;
+                    push_digit_glyphs(&glyphs, &ROMAN_DIGITS, out);
                     i += consumed;
                     continue;
                 }
@@ -91,35 +1112,117 @@ fn decode_to_iast(data: &[u8]) -> Result<String, String> {
             continue;
         }
 
-        // ── Svara ──
+        // ── Svara / Vyañjana ──
+        if let Some(base) = pluta_base(b) {
+            push_cased(&accent(graphemes.phoneme(base), b), &mut casing, out);
+            out.push_str(PLUTA_MARK);
+            i += 1;
+            continue;
+        }
+        if b == 0x3A && data.get(i + 1) == Some(&CANDRABINDU) {
+            let grapheme = match graphemes.lipi(CANDRABINDU) {
+                "?" => graphemes.phoneme(b),
+                g => g,
+            };
+            push_cased(grapheme, &mut casing, out);
+            i += 2;
+            continue;
+        }
         if is_svara(b) {
-            out.push_str(byte_to_iast(b));
+            push_cased(&accent(graphemes.phoneme(b), b), &mut casing, out);
             i += 1;
             continue;
         }
-
-        // ── Vyañjana ──
         if is_vyanjana(b) {
-            out.push_str(byte_to_iast(b));
+            push_cased(graphemes.phoneme(b), &mut casing, out);
             i += 1;
             continue;
         }
 
         // Unknown byte
-        return Err(format!("unexpected byte 0x{:02X} at offset {}", b, i));
+        return Err(SlbcError::malformed(
+            i,
+            format_args!("unexpected byte 0x{:02X}", b),
+        ));
     }
 
-    Ok(out)
+    Ok(())
+}
+
+/// How the next romanized phonemes are cased.
+struct Casing {
+    /// The next phoneme begins a verse or a capitalized word.
+    initial: bool,
+    /// The open pada is written in capitals.
+    pada: bool,
+}
+
+/// Push a grapheme, upper-casing its first letter if it starts a verse or
+/// a capitalized word, and all of it in a pada written in capitals.
+/// Vowels with a precomposed letter for each accent: udātta (acute),
+/// anudātta (grave), and svarita (circumflex).
+const ACCENTED: [(char, [char; 3]); 5] = [
+    ('a', ['á', 'à', 'â']),
+    ('i', ['í', 'ì', 'î']),
+    ('u', ['ú', 'ù', 'û']),
+    ('e', ['é', 'è', 'ê']),
+    ('o', ['ó', 'ò', 'ô']),
+];
+
+/// A romanized vowel with its accent (`svara_a`): the combining acute,
+/// grave, or circumflex the encoder reads, composed with the vowel's last
+/// letter where Unicode has the accented letter ("ai" → "aí", "ā" → "ā́").
+fn accented(grapheme: &str, a: u8) -> Cow<'_, str> {
+    let mark = match a {
+        0b01 => encoder::IAST_UDATTA,
+        0b10 => encoder::IAST_ANUDATTA,
+        0b11 => encoder::IAST_SVARITA,
+        _ => return Cow::Borrowed(grapheme),
+    };
+    let mut out = grapheme.to_string();
+    match ACCENTED.iter().find(|(v, _)| grapheme.ends_with(*v)) {
+        Some((_, letters)) => {
+            out.pop();
+            out.push(letters[a as usize - 1]);
+        }
+        None => out.push(mark),
+    }
+    Cow::Owned(out)
+}
+
+fn push_cased(grapheme: &str, casing: &mut Casing, out: &mut impl Out) {
+    let initial = std::mem::take(&mut casing.initial);
+    if casing.pada {
+        out.push_str(&grapheme.to_uppercase());
+        return;
+    }
+    let mut chars = grapheme.chars();
+    match chars.next() {
+        Some(first) if initial => {
+            for c in first.to_uppercase() {
+                out.push(c);
+            }
+            out.push_str(chars.as_str());
+        }
+        _ => out.push_str(grapheme),
+    }
 }
 
 // ═══════════════════════════════════════════════
-//  Devanāgarī decoder
+//  Abugida decoder (Devanāgarī and the regional scripts)
 // ═══════════════════════════════════════════════
 
-fn decode_to_devanagari(data: &[u8]) -> Result<String, String> {
-    let mut out = String::new();
+fn decode_to_abugida(
+    data: &[u8],
+    script: &'static Abugida,
+    opts: &DecodeOptions,
+    out: &mut impl Out,
+) -> Result<(), SlbcError> {
     let mut i = 0;
-    let mut consonant_pending = false;
+    // Consonant awaiting its vowel sign or virāma, with the mark (Tamil
+    // superscript) written after that sign
+    let mut pending: Option<&'static str> = None;
+    let marks = opts.tamil == TamilStyle::Superscript;
 
     while i < data.len() {
         let b = data[i];
@@ -133,43 +1236,30 @@ fn decode_to_devanagari(data: &[u8]) -> Result<String, String> {
                 }
                 PADA_END => {
                     // Pada end: if consonant pending, add virāma
-                    if consonant_pending {
-                        out.push('्');
-                        consonant_pending = false;
-                    }
+                    close_consonant(&mut pending, script.virama, out);
                     i += 1;
                     continue;
                 }
                 PHON_START | PHON_END => {
+                    if opts.phon_boundaries {
+                        close_consonant(&mut pending, script.virama, out);
+                        out.push(phon_mark(b));
+                    }
                     i += 1;
                     continue;
                 }
                 META_START => {
-                    i += 1;
-                    while i < data.len() && data[i] != META_END {
-                        i += 1;
-                    }
-                    i += 1;
+                    close_consonant(&mut pending, script.virama, out);
+                    i += write_annotation(data, i, opts, out);
                     continue;
                 }
                 SANKHYA_START => {
-                    if consonant_pending {
-                        out.push('्');
-                        consonant_pending = false;
-                    }
-                    // Skip SAṄKHYĀ span (bhāṣā layer), use NUM span for glyphs
-                    let (_, consumed) = numeral::decode_sankhya(data, i)?;
-                    i += consumed;
-                    // Now read the NUM span for Devanāgarī digit glyphs
-                    if i < data.len() && data[i] == NUM {
-                        i += 1; // skip NUM marker
-                        while i < data.len() && data[i] < 0x10 {
-                            out.push(DEVANAGARI_DIGITS[data[i] as usize]);
-                            i += 1;
-                        }
-                    }
+                    close_consonant(&mut pending, script.virama, out);
+                    // Glyphs come from the NUM span (lipi layer)
+                    i += decode_numeral_span(data, i, Target::Abugida(script), opts, out)?;
                     continue;
                 }
+                DICT_REF => return Err(SlbcError::DictRef { offset: i }),
                 _ => {
                     i += 1;
                     continue;
@@ -177,23 +1267,88 @@ fn decode_to_devanagari(data: &[u8]) -> Result<String, String> {
             }
         }
 
+        // ── Extension phonemes ──
+        // A long extension leaves a pending consonant open, like a seam
+        if b == META_EXT && data.get(i + 1) == Some(&EXT_LONG) {
+            i += codepage::ext_len(data, i)?;
+            continue;
+        }
+        // A hidden compound boundary does too
+        if codepage::is_samasa(data, i) {
+            if opts.compound_boundaries {
+                close_consonant(&mut pending, script.virama, out);
+                out.push('-');
+            }
+            i += 2;
+            continue;
+        }
+        if b == META_EXT {
+            if let Some(mark) = data.get(i + 1).and_then(|&c| codepage::punctuation(c)) {
+                close_consonant(&mut pending, script.virama, out);
+                out.push(mark);
+                i += 2;
+                continue;
+            }
+            let p = codepage::read_ext(data, i)?;
+            let (letter, matra) = (script.extension)(p.code).unwrap_or(("?", None));
+            match (p.kind, matra) {
+                (ExtKind::Vowel, Some(matra)) if pending.is_some() => {
+                    close_consonant(&mut pending, matra, out);
+                }
+                (ExtKind::Vowel, _) => out.push_str(letter),
+                (ExtKind::Consonant, _) => {
+                    close_consonant(&mut pending, script.virama, out);
+                    out.push_str(letter);
+                    pending = Some("");
+                }
+            }
+            i += 2;
+            continue;
+        }
+
+        // A hidden seam leaves a pending consonant open for its vowel
+        if b == MORPH {
+            if opts.morph_boundaries {
+                close_consonant(&mut pending, script.virama, out);
+                out.push('+');
+            }
+            i += 1;
+            continue;
+        }
+
+        // Written together with its anusvāra below, or with the oṃ before
+        // it where the script has no sign for it. A yama or nāsikya is
+        // recited, not written, and leaves the conjunct around it whole.
+        // The script has no case.
+        if matches!(
+            b,
+            CANDRABINDU | PRANAVA | YAMA | NASIKYA | CAPITAL | UPPERCASE
+        ) {
+            i += 1;
+            continue;
+        }
+
+        if b == LINE_BREAK {
+            close_consonant(&mut pending, script.virama, out);
+            out.push('\n');
+            i += 1;
+            continue;
+        }
+
         // ── Lipi controls ──
         if is_lipi_control(b) {
-            if consonant_pending {
-                out.push('्');
-                consonant_pending = false;
-            }
+            close_consonant(&mut pending, script.virama, out);
             match b {
                 SPACE => out.push(' '),
-                DANDA => out.push('।'),
-                DOUBLE_DANDA => out.push('॥'),
-                AVAGRAHA => out.push('ऽ'),
+                DANDA => out.push_str(script.danda),
+                DOUBLE_DANDA => out.push_str(script.double_danda),
+                AVAGRAHA => out.push_str(script.avagraha),
+                ILLEGIBLE => out.push_str(encoder::ILLEGIBLE_MARK),
+                LACUNA => out.push_str(encoder::LACUNA_MARK),
                 NUM => {
-                    i += 1;
-                    while i < data.len() && data[i] < 0x10 {
-                        out.push(DEVANAGARI_DIGITS[data[i] as usize]);
-                        i += 1;
-                    }
+                    let (glyphs, consumed) = numeral::decode_num(data, i)?;
+                    push_digit_glyphs(&glyphs, &script.digits, out);
+                    i += consumed;
                     continue;
                 }
                 _ => {}
@@ -203,18 +1358,34 @@ fn decode_to_devanagari(data: &[u8]) -> Result<String, String> {
         }
 
         // ── Svara ──
+        if b == 0x89
+            && pending.is_none()
+            && data.get(i + 1..i + 3) == Some(&[0x3A, PRANAVA])
+            && !script.om.is_empty()
+        {
+            out.push_str(script.om);
+            i += 3;
+            continue;
+        }
         if is_svara(b) {
-            if consonant_pending {
-                // Consonant + vowel: use mātrā (or bare for 'a')
-                if b == 0x40 {
-                    // 'a': inherent vowel — no mātrā
-                } else if let Some(matra) = byte_to_devanagari_matra(b) {
-                    out.push_str(matra);
-                }
-                consonant_pending = false;
+            // Pluta: the dīrgha, then the digit three
+            let pluta = pluta_base(b);
+            let v = pluta.unwrap_or(b);
+            if pending.is_some() {
+                // Consonant + vowel: use mātrā (or bare for 'a', the
+                // inherent vowel)
+                let matra = match v {
+                    0x40 => None,
+                    _ => (script.matra)(v),
+                };
+                close_consonant(&mut pending, matra.unwrap_or(""), out);
             } else {
                 // Standalone vowel: independent form
-                out.push_str(byte_to_devanagari_independent(b));
+                out.push_str((script.independent)(v));
+            }
+            out.push_str(script.accents[svara_a(b) as usize]);
+            if pluta.is_some() {
+                out.push(script.digits[3]);
             }
             i += 1;
             continue;
@@ -222,125 +1393,105 @@ fn decode_to_devanagari(data: &[u8]) -> Result<String, String> {
 
         // ── Vyañjana ──
         if is_vyanjana(b) {
-            // Visarga and anusvāra render as postfix marks, not as consonants
+            // Visarga (with its allophones) and anusvāra render as postfix
+            // marks, not as consonants
             if is_postfix_mark(b) {
-                if consonant_pending {
-                    // Consonant + visarga/anusvāra: no virāma needed
-                    consonant_pending = false;
-                }
-                out.push_str(postfix_mark_devanagari(b));
+                // Consonant + visarga/anusvāra: no virāma needed
+                close_consonant(&mut pending, "", out);
+                let candrabindu =
+                    data.get(i + 1) == Some(&CANDRABINDU) && !script.candrabindu.is_empty();
+                out.push_str(match b {
+                    0x3A if candrabindu => script.candrabindu,
+                    0x3A => script.anusvara,
+                    0x3B if !script.jihvamuliya.is_empty() => script.jihvamuliya,
+                    0x3C if !script.upadhmaniya.is_empty() => script.upadhmaniya,
+                    _ => script.visarga,
+                });
                 i += 1;
                 continue;
             }
 
-            if consonant_pending {
-                // Consecutive consonants: insert virāma before new consonant
-                out.push('्');
+            // Consecutive consonants: insert virāma before new consonant
+            if pending.is_some() {
+                close_consonant(&mut pending, script.virama, out);
+                out.push_str(opts.conjuncts.joiner());
             }
-            out.push_str(byte_to_devanagari_consonant(b));
-            consonant_pending = true;
+            out.push_str((script.consonant)(b));
+            pending = Some(if marks {
+                (script.consonant_mark)(b)
+            } else {
+                ""
+            });
             i += 1;
             continue;
         }
 
-        return Err(format!("unexpected byte 0x{:02X} at offset {}", b, i));
+        return Err(SlbcError::malformed(
+            i,
+            format_args!("unexpected byte 0x{:02X}", b),
+        ));
     }
 
     // Trailing consonant at end of stream
-    if consonant_pending {
-        out.push('्');
-    }
+    close_consonant(&mut pending, script.virama, out);
 
-    Ok(out)
+    Ok(())
+}
+
+/// Finish a pending consonant with its vowel sign or virāma, then its mark.
+fn close_consonant(pending: &mut Option<&'static str>, sign: &str, out: &mut impl Out) {
+    if let Some(mark) = pending.take() {
+        out.push_str(sign);
+        out.push_str(mark);
+    }
 }
 
 // ═══════════════════════════════════════════════
-//  IAST lookup table
+//  Romanized lookup
 // ═══════════════════════════════════════════════
 
 /// Map an SLBC byte to its IAST representation.
 pub fn byte_to_iast(b: u8) -> &'static str {
-    if is_svara(b) {
-        return svara_to_iast(b);
-    }
-    if is_vyanjana(b) {
-        return vyanjana_to_iast(b);
-    }
-    "?"
+    byte_to_roman(&scheme::IAST, b)
 }
 
-fn svara_to_iast(b: u8) -> &'static str {
-    // Mask out accent bits for lookup (A field = bits 5:4)
-    let base = b & 0b11_00_11_11; // zero out accent
-    match base {
-        0x40 => "a",
-        0x80 => "ā",
-        0x44 => "i",
-        0x84 => "ī",
-        0x48 => "u",
-        0x88 => "ū",
-        0x4C => "ṛ",
-        0x8C => "ṝ",
-        0x4F => "ḷ",
-        0x8F => "ḹ",
-        0x85 => "e",
-        0x86 => "ai",
-        0x89 => "o",
-        0x8A => "au",
-        _ => "?",
-    }
+/// Written after a pluta vowel in romanized output.
+pub const PLUTA_MARK: &str = "3";
+
+/// A pluta svara's dīrgha, which it is written with (followed by "3" or
+/// the script's digit three); `None` for other bytes.
+pub fn pluta_base(b: u8) -> Option<u8> {
+    (is_svara(b) && svara_q(b) == 0b11).then_some((b & 0b00_11_11_11) | (0b10 << 6))
 }
 
-fn vyanjana_to_iast(b: u8) -> &'static str {
-    match b {
-        0x00 => "k",
-        0x01 => "kh",
-        0x02 => "g",
-        0x03 => "gh",
-        0x04 => "ṅ",
-        0x08 => "c",
-        0x09 => "ch",
-        0x0A => "j",
-        0x0B => "jh",
-        0x0C => "ñ",
-        0x10 => "ṭ",
-        0x11 => "ṭh",
-        0x12 => "ḍ",
-        0x13 => "ḍh",
-        0x14 => "ṇ",
-        0x18 => "t",
-        0x19 => "th",
-        0x1A => "d",
-        0x1B => "dh",
-        0x1C => "n",
-        0x20 => "p",
-        0x21 => "ph",
-        0x22 => "b",
-        0x23 => "bh",
-        0x24 => "m",
-        0x29 => "ś",
-        0x2A => "ṣ",
-        0x2B => "s",
-        0x31 => "y",
-        0x32 => "v",
-        0x33 => "r",
-        0x34 => "l",
-        0x38 => "h",
-        0x39 => "ḥ",
-        0x3A => "ṃ",
-        0x3B => "ẖ",
-        0x3C => "ḫ",
-        _ => "?",
+/// IAST for a phoneme byte; unlike `byte_to_iast`, a pluta vowel is
+/// written as its dīrgha + "3".
+pub fn phoneme_to_iast(b: u8) -> String {
+    match pluta_base(b) {
+        Some(base) => format!("{}{}", byte_to_iast(base), PLUTA_MARK),
+        None => byte_to_iast(b).to_string(),
     }
 }
 
+/// Map an SLBC byte to its representation in a romanization scheme.
+pub fn byte_to_roman(roman: &Scheme, b: u8) -> &'static str {
+    let base = if is_svara(b) {
+        b & 0b11_00_11_11 // accent bits (A field = bits 5:4) are not written
+    } else if is_vyanjana(b) {
+        b
+    } else {
+        return "?";
+    };
+    roman.grapheme(base).unwrap_or("?")
+}
+
 // ═══════════════════════════════════════════════
 //  Devanāgarī tables
 // ═══════════════════════════════════════════════
 
-const DEVANAGARI_DIGITS: [char; 10] = ['०', '१', '२', '३', '४', '५', '६', '७', '८', '९'];
+pub(crate) const DEVANAGARI_DIGITS: [char; 10] = ['०', '१', '२', '३', '४', '५', '६', '७', '८', '९'];
 
-fn byte_to_devanagari_consonant(b: u8) -> &'static str {
+pub(crate) fn byte_to_devanagari_consonant(b: u8) -> &'static str {
     match b {
         0x00 => "क",
         0x01 => "ख",
@@ -379,7 +1530,7 @@ fn byte_to_devanagari_consonant(b: u8) -> &'static str {
     }
 }
 
-fn byte_to_devanagari_independent(b: u8) -> &'static str {
+pub(crate) fn byte_to_devanagari_independent(b: u8) -> &'static str {
     let base = b & 0b11_00_11_11;
     match base {
         0x40 => "अ",
@@ -400,7 +1551,7 @@ fn byte_to_devanagari_independent(b: u8) -> &'static str {
     }
 }
 
-fn byte_to_devanagari_matra(b: u8) -> Option<&'static str> {
+pub(crate) fn byte_to_devanagari_matra(b: u8) -> Option<&'static str> {
     let base = b & 0b11_00_11_11;
     match base {
         0x40 => None, // 'a' — inherent, no mātrā
@@ -421,25 +1572,43 @@ fn byte_to_devanagari_matra(b: u8) -> Option<&'static str> {
     }
 }
 
-/// Handle visarga and anusvāra in Devanāgarī context.
-/// These are technically vyañjana bytes but render as post-vowel marks.
-fn is_postfix_mark(b: u8) -> bool {
-    matches!(b, 0x39 | 0x3A) // visarga, anusvāra
+/// Write the META block at `i` as `{key: value}` if it is an annotation
+/// and `opts` ask for annotations, spaced off from the text on either
+/// side. Returns the block's length.
+fn write_annotation(data: &[u8], i: usize, opts: &DecodeOptions, out: &mut impl Out) -> usize {
+    let end = data[i + 1..].iter().position(|&b| b == META_END);
+    let len = end.map_or(data.len() - i, |p| p + 2);
+    let note = end.and_then(|p| Annotation::parse(&data[i + 1..i + 1 + p]));
+    if let Some(note) = note.filter(|_| opts.annotations) {
+        if out.last().is_some_and(|c| !c.is_whitespace()) {
+            out.push(' ');
+        }
+        out.push_str(&note.to_string());
+        if !matches!(data.get(i + len), None | Some(&SPACE | &LINE_BREAK)) {
+            out.push(' ');
+        }
+    }
+    len
 }
 
-/// Devanāgarī rendering of visarga/anusvāra (appended after vowel).
-fn postfix_mark_devanagari(b: u8) -> &'static str {
+/// Written form of PHON_START or PHON_END.
+fn phon_mark(b: u8) -> char {
     match b {
-        0x39 => "ः", // visarga
-        0x3A => "ं",  // anusvāra
-        _ => "",
+        PHON_START => encoder::PHON_OPEN,
+        _ => encoder::PHON_CLOSE,
     }
 }
 
+/// Handle visarga and anusvāra in Devanāgarī context.
+/// These are technically vyañjana bytes but render as post-vowel marks.
+fn is_postfix_mark(b: u8) -> bool {
+    matches!(b, 0x39..=0x3C) // visarga, anusvāra, jihvāmūlīya, upadhmānīya
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::encoder;
+    use crate::encoder::{self, CaseFolding};
 
     #[test]
     fn test_iast_roundtrip_simple() {
@@ -473,6 +1642,342 @@ mod tests {
         assert_eq!(output, "कि");
     }
 
+    #[test]
+    fn test_numeral_styles() {
+        let bytes = encoder::encode_iast("108").unwrap();
+        let words = DecodeOptions {
+            numerals: NumeralStyle::Words,
+            ..Default::default()
+        };
+        let both = DecodeOptions {
+            numerals: NumeralStyle::Both,
+            ..Default::default()
+        };
+        assert_eq!(decode_phon(&bytes, Script::Iast).unwrap(), "108");
+        assert_eq!(
+            decode_phon_with(&bytes, Script::Iast, &words).unwrap(),
+            "aṣṭa śūnya eka"
+        );
+        assert_eq!(
+            decode_phon_with(&bytes, Script::Devanagari, &both).unwrap(),
+            "१०८ (अष्ट शून्य एक)"
+        );
+
+        let composed = DecodeOptions {
+            numerals: NumeralStyle::Composed,
+            ..Default::default()
+        };
+        let bytes = encoder::encode_iast("108 | 23 | 1008").unwrap();
+        assert_eq!(
+            decode_phon_with(&bytes, Script::Iast, &composed).unwrap(),
+            "aṣṭottaraśatam | trayoviṃśati | aṣṭa śūnya śūnya eka"
+        );
+        assert_eq!(
+            decode_phon_with(
+                &encoder::encode_iast("108").unwrap(),
+                Script::Devanagari,
+                &composed
+            )
+            .unwrap(),
+            "अष्टोत्तरशतम्"
+        );
+
+        // A standalone NUM span, with a glyph byte past 9
+        let bytes = [NUM, 0x01, 0x0A];
+        assert_eq!(decode_phon(&bytes, Script::Iast).unwrap(), "1?");
+        assert_eq!(decode_phon(&bytes, Script::Devanagari).unwrap(), "१?");
+    }
+
+    #[test]
+    fn test_extension_phonemes() {
+        let bytes = encoder::encode_iast("qalam tamiḻ fĕn").unwrap();
+        assert_eq!(&bytes[..3], &[PADA_START, META_EXT, 0x08]);
+        assert_eq!(
+            decode_phon(&bytes, Script::Iast).unwrap(),
+            "qalam tamiḻ fĕn"
+        );
+        assert_eq!(
+            decode_phon(&bytes, Script::Devanagari).unwrap(),
+            "क़लम् तमिऴ् फ़ॆन्"
+        );
+        assert!(decode_phon(&[PADA_START, META_EXT, 0x7A, PADA_END], Script::Iast).is_err());
+    }
+
+    #[test]
+    fn test_tolerates_degenerate_framing() {
+        let mut bytes = vec![PHON_START, PHON_START, PADA_START, PADA_END];
+        bytes.extend(encoder::encode_iast("na").unwrap());
+        bytes.extend_from_slice(&[SPACE, SPACE, PADA_START, PADA_END, SPACE]);
+        bytes.extend(encoder::encode_iast("tat").unwrap());
+        bytes.extend_from_slice(&[PHON_END, PHON_END]);
+        assert_eq!(decode_phon(&bytes, Script::Iast).unwrap(), "na tat");
+        assert_eq!(decode_phon(&bytes, Script::Devanagari).unwrap(), "न तत्");
+    }
+
+    #[test]
+    fn test_phon_boundaries() {
+        let text = "āha ⟨rāmaḥ ⟨na⟩⟩ iti";
+        let bytes = encoder::encode_iast(text).unwrap();
+        assert_eq!(bytes.iter().filter(|&&b| b == PHON_START).count(), 2);
+        assert_eq!(
+            decode_phon(&bytes, Script::Iast).unwrap(),
+            "āha rāmaḥ na iti"
+        );
+        let opts = DecodeOptions {
+            phon_boundaries: true,
+            ..Default::default()
+        };
+        let iast = Decoder::new(Script::Iast, opts.clone())
+            .decode(&bytes)
+            .unwrap();
+        assert_eq!(iast, text);
+        let deva = Decoder::new(Script::Devanagari, opts)
+            .decode(&bytes)
+            .unwrap();
+        assert_eq!(deva, "आह ⟨रामः ⟨न⟩⟩ इति");
+        assert_eq!(encoder::encode_devanagari(&deva).unwrap(), bytes);
+    }
+
+    #[test]
+    fn test_gap_markers() {
+        let bytes = encoder::encode_iast("dha[?]ma [...] kṣe[…]").unwrap();
+        assert_eq!(&bytes[..5], &[PADA_START, 0x1B, 0x40, ILLEGIBLE, 0x24]);
+        assert_eq!(bytes.iter().filter(|&&b| b == LACUNA).count(), 2);
+        assert_eq!(
+            decode_phon(&bytes, Script::Iast).unwrap(),
+            "dha[?]ma [...] kṣe[...]"
+        );
+        let deva = decode_phon(&bytes, Script::Devanagari).unwrap();
+        assert_eq!(deva, "ध[?]म [...] क्षे[...]");
+        assert_eq!(encoder::encode_devanagari(&deva).unwrap(), bytes);
+        for &script in Script::INPUT {
+            let rendered = decode_phon(&bytes, script).unwrap();
+            assert_eq!(
+                encoder::encode_script(&rendered, script).unwrap(),
+                bytes,
+                "{}",
+                script.name()
+            );
+        }
+    }
+
+    #[test]
+    fn test_sentences() {
+        let opts = encoder::EncodeOptions {
+            phon_frames: encoder::PhonFrames::Sentences,
+            ..Default::default()
+        };
+        let input = "rāmo vanaṃ gacchati | sītā ca || 1 || lakṣmaṇo 'pi";
+        let bytes = encoder::encode_iast_with(input, &opts).unwrap();
+        assert_eq!(
+            bytes,
+            encoder::encode_iast("⟨rāmo vanaṃ gacchati |⟩ ⟨sītā ca || 1 ||⟩ ⟨lakṣmaṇo 'pi⟩")
+                .unwrap()
+        );
+        assert_eq!(decode_phon(&bytes, Script::Iast).unwrap(), input);
+        assert_eq!(
+            decode_sentences(&bytes, Script::Devanagari, &DecodeOptions::default()).unwrap(),
+            ["रामो वनं गच्छति ।", "सीता च ॥ १ ॥", "लक्ष्मणो ऽपि"]
+        );
+        // Text outside the frames, and no frames at all
+        let bytes = encoder::encode_iast("⟨rāma |⟩ sītā ⟨ca⟩").unwrap();
+        let sentences = decode_sentences(&bytes, Script::Iast, &DecodeOptions::default());
+        assert_eq!(sentences.unwrap(), ["rāma |", "sītā", "ca"]);
+        let bytes = encoder::encode_iast(" rāma ").unwrap();
+        let sentences = decode_sentences(&bytes, Script::Iast, &DecodeOptions::default());
+        assert_eq!(sentences.unwrap(), ["rāma"]);
+    }
+
+    #[test]
+    fn test_compound_boundaries() {
+        let opts = |samasa| encoder::EncodeOptions {
+            samasa,
+            ..Default::default()
+        };
+        let hyphen = opts(encoder::SamasaMarker::Hyphen);
+        let bytes = encoder::encode_iast_with("dharma-kṣetre jagat-pati", &hyphen).unwrap();
+        assert_eq!(bytes.iter().filter(|&&b| b == PADA_START).count(), 2);
+        assert!(codepage::is_samasa(&bytes, 6));
+        let plus = opts(encoder::SamasaMarker::Plus);
+        assert_eq!(
+            encoder::encode_iast_with("dharma+kṣetre jagat+pati", &plus).unwrap(),
+            bytes
+        );
+        assert_eq!(
+            encoder::encode_script_with("धर्म-क्षेत्रे जगत्-पति", Script::Devanagari, &hyphen).unwrap(),
+            bytes
+        );
+        let plain = encoder::encode_iast("dharmakṣetre jagatpati").unwrap();
+        assert_eq!(
+            crate::akshara::aksharas(&bytes).unwrap().len(),
+            crate::akshara::aksharas(&plain).unwrap().len()
+        );
+
+        assert_eq!(
+            decode_phon(&bytes, Script::Iast).unwrap(),
+            "dharmakṣetre jagatpati"
+        );
+        assert_eq!(
+            decode_phon(&bytes, Script::Devanagari).unwrap(),
+            "धर्मक्षेत्रे जगत्पति"
+        );
+        let shown = DecodeOptions {
+            compound_boundaries: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            decode_phon_with(&bytes, Script::Iast, &shown).unwrap(),
+            "dharma-kṣetre jagat-pati"
+        );
+        assert_eq!(
+            decode_phon_with(&bytes, Script::Devanagari, &shown).unwrap(),
+            "धर्म-क्षेत्रे जगत्-पति"
+        );
+    }
+
+    #[test]
+    fn test_morph_boundaries() {
+        let bytes = encoder::encode_iast("rām+a+ḥ +gaja+ ca").unwrap();
+        assert_eq!(
+            &bytes[..8],
+            &[PADA_START, 0x33, 0x80, 0x24, MORPH, 0x40, MORPH, 0x39]
+        );
+        assert_eq!(bytes.iter().filter(|&&b| b == MORPH).count(), 2);
+        assert_eq!(decode_phon(&bytes, Script::Iast).unwrap(), "rāmaḥ gaja ca");
+        assert_eq!(
+            decode_phon(&bytes, Script::Devanagari).unwrap(),
+            "रामः गज च"
+        );
+
+        let opts = DecodeOptions {
+            morph_boundaries: true,
+            ..Default::default()
+        };
+        let shown = decode_phon_with(&bytes, Script::Devanagari, &opts).unwrap();
+        assert_eq!(shown, "राम्+अ+ः गज च");
+        assert_eq!(
+            decode_phon_with(&bytes, Script::Iast, &opts).unwrap(),
+            "rām+a+ḥ gaja ca"
+        );
+        assert_eq!(
+            encoder::encode_devanagari("राम्+अः").unwrap()[..6],
+            bytes[..6]
+        );
+    }
+
+    #[test]
+    fn test_pluta() {
+        let bytes = encoder::encode_iast("agnā3i a3 108").unwrap();
+        assert_eq!(&bytes[..6], &[PADA_START, 0x40, 0x02, 0x1C, 0xC0, 0x44]);
+        assert_eq!(bytes[9], 0xC0);
+        assert_eq!(decode_phon(&bytes, Script::Iast).unwrap(), "agnā3i ā3 108");
+        assert_eq!(decode_phon(&bytes, Script::Slp1).unwrap(), "agnA3i A3 108");
+        let deva = decode_phon(&bytes, Script::Devanagari).unwrap();
+        assert_eq!(deva, "अग्ना३इ आ३ १०८");
+        assert_eq!(encoder::encode_devanagari(&deva).unwrap(), bytes);
+        assert_eq!(phoneme_to_iast(0xC5), "e3");
+        assert_eq!(pluta_base(0x80), None);
+    }
+
+    #[test]
+    fn test_jihvamuliya_upadhmaniya() {
+        let bytes = encoder::encode_iast("antaẖkaraṇam punaḫ punaḥ").unwrap();
+        let deva = decode_phon(&bytes, Script::Devanagari).unwrap();
+        assert_eq!(deva, "अन्तᳵकरणम् पुनᳶ पुनः");
+        assert_eq!(encoder::encode_devanagari(&deva).unwrap(), bytes);
+        assert_eq!(
+            decode_phon(&bytes, Script::Iast).unwrap(),
+            "antaẖkaraṇam punaḫ punaḥ"
+        );
+    }
+
+    #[test]
+    fn test_vedic_laterals() {
+        let bytes = encoder::encode_vedic("agnim īḻe purohitam | mīḻhuṣe", Script::Iast).unwrap();
+        assert_eq!(&bytes[9..13], &[0x84, META_EXT, 0x04, 0x85]);
+        let deva = decode_phon(&bytes, Script::Devanagari).unwrap();
+        assert_eq!(deva, "अग्निम् ईळे पुरोहितम् । मीळ्हुषे");
+        assert_eq!(encoder::encode_devanagari(&deva).unwrap(), bytes);
+        assert_eq!(
+            decode_phon(&bytes, Script::Iast).unwrap(),
+            "agnim īḻe purohitam | mīḻhuṣe"
+        );
+        // Outside Vedic text ḻ is Tamil ழ
+        let tamil = encoder::encode_iast("īḻe").unwrap();
+        assert_eq!(&tamil[1..5], &[0x84, META_EXT, 0x01, 0x85]);
+    }
+
+    #[test]
+    fn test_pranava() {
+        let opts = encoder::EncodeOptions {
+            pranava: true,
+            ..Default::default()
+        };
+        let text = "oṃ namaḥ | om̐ oṃkāraḥ";
+        let bytes = encoder::encode_script_with(text, Script::Iast, &opts).unwrap();
+        assert_eq!(&bytes[..5], &[PADA_START, 0x89, 0x3A, PRANAVA, PADA_END]);
+        let deva = decode_phon(&bytes, Script::Devanagari).unwrap();
+        assert_eq!(deva, "ॐ नमः । ॐ ओंकारः");
+        assert_eq!(
+            encoder::encode_script_with(&deva, Script::Devanagari, &opts).unwrap(),
+            bytes
+        );
+        assert_eq!(
+            decode_phon(&bytes, Script::Iast).unwrap(),
+            "oṃ namaḥ | oṃ oṃkāraḥ"
+        );
+        // Without the option ॐ is just its phonemes
+        assert_eq!(
+            encoder::encode_devanagari("ॐ").unwrap(),
+            encoder::encode_iast("oṃ").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_yama_nasikya() {
+        let opts = encoder::EncodeOptions {
+            yama: true,
+            ..Default::default()
+        };
+        let text = "paliknī yajñaḥ brahma vāṅmayam ag+nim";
+        let bytes = encoder::encode_script_with(text, Script::Iast, &opts).unwrap();
+        let plain = encoder::encode_iast(text).unwrap();
+        assert_eq!(
+            &bytes[..8],
+            &[PADA_START, 0x20, 0x40, 0x34, 0x44, 0x00, YAMA, 0x1C]
+        );
+        assert_eq!(bytes.iter().filter(|&&b| b == YAMA).count(), 3);
+        let h = bytes.iter().position(|&b| b == NASIKYA).unwrap();
+        assert_eq!(&bytes[h - 1..h + 2], &[0x38, NASIKYA, 0x24]);
+        // Recited, not written
+        for script in [Script::Iast, Script::Devanagari, Script::Slp1] {
+            assert_eq!(
+                decode_phon(&bytes, script).unwrap(),
+                decode_phon(&plain, script).unwrap()
+            );
+        }
+        assert_eq!(
+            crate::akshara::aksharas(&bytes).unwrap().len(),
+            crate::akshara::aksharas(&plain).unwrap().len()
+        );
+    }
+
+    #[test]
+    fn test_candrabindu() {
+        let bytes = encoder::encode_iast("ham̐saḥ saṃ").unwrap();
+        assert_eq!(&bytes[..5], &[PADA_START, 0x38, 0x40, 0x3A, CANDRABINDU]);
+        assert_eq!(decode_phon(&bytes, Script::Iast).unwrap(), "ham̐saḥ saṃ");
+        assert_eq!(decode_phon(&bytes, Script::Slp1).unwrap(), "ha~saH saM");
+        // No candrabindu in Harvard-Kyoto: the anusvāra stands in
+        assert_eq!(
+            decode_phon(&bytes, Script::HarvardKyoto).unwrap(),
+            "haMsaH saM"
+        );
+        let deva = decode_phon(&bytes, Script::Devanagari).unwrap();
+        assert_eq!(deva, "हँसः सं");
+        assert_eq!(encoder::encode_devanagari(&deva).unwrap(), bytes);
+    }
+
     #[test]
     fn test_devanagari_cluster() {
         // kṛ = k + ṛ → क + ृ = कृ
@@ -480,4 +1985,565 @@ mod tests {
         let output = decode_phon(&bytes, Script::Devanagari).unwrap();
         assert_eq!(output, "कृ");
     }
+
+    #[test]
+    fn test_roman_accents() {
+        for text in ["agnímīḷe puróhitam", "ā́ aì ṛ̂ vā́3 índraḥ"] {
+            let bytes = encoder::encode_iast(text).unwrap();
+            assert_eq!(decode_phon(&bytes, Script::Iast).unwrap(), text);
+        }
+        let bytes = encoder::encode_script("ē\u{301}ṣá", Script::Iso15919).unwrap();
+        assert_eq!(decode_phon(&bytes, Script::Iso15919).unwrap(), "ē\u{301}ṣá");
+        // Schemes without accents, and ASCII output, leave them out
+        let bytes = encoder::encode_iast("agnímīḍe").unwrap();
+        assert_eq!(
+            decode_phon(&bytes, Script::HarvardKyoto).unwrap(),
+            "agnimIDe"
+        );
+        let opts = DecodeOptions {
+            ascii: AsciiFallback::HarvardKyoto,
+            ..Default::default()
+        };
+        assert_eq!(
+            decode_phon_with(&bytes, Script::Iast, &opts).unwrap(),
+            "agnimIDe"
+        );
+    }
+
+    #[test]
+    fn test_devanagari_accents() {
+        for text in ["अ॒ग्निमी॑ळे पु॒रोहि॑तं", "क᳚ इ॒न्द्रा॑३"]
+        {
+            let bytes = encoder::encode_devanagari(text).unwrap();
+            assert_eq!(decode_phon(&bytes, Script::Devanagari).unwrap(), text);
+        }
+        let bytes = encoder::encode_iast("agnímīḍe kvà").unwrap();
+        assert_eq!(
+            decode_phon(&bytes, Script::Devanagari).unwrap(),
+            "अग्नि॑मीडे क्व॒"
+        );
+    }
+
+    #[test]
+    fn test_slp1_both_directions() {
+        let iast = "dharmakṣetre kurukṣetre samavetā yuyutsavaḥ | 108 so 'ham ||";
+        let slp1 = "Darmakzetre kurukzetre samavetA yuyutsavaH . 108 so 'ham ..";
+        let bytes = encoder::encode_iast(iast).unwrap();
+        assert_eq!(encoder::encode_slp1(slp1).unwrap(), bytes);
+        assert_eq!(decode_phon(&bytes, Script::Slp1).unwrap(), slp1);
+        // ai/au are single letters, so "ai" is two svaras in SLP1
+        assert_eq!(
+            encoder::encode_slp1("kEvalam").unwrap(),
+            encoder::encode_iast("kaivalam").unwrap()
+        );
+        assert_ne!(
+            encoder::encode_slp1("kaivalam").unwrap(),
+            encoder::encode_iast("kaivalam").unwrap()
+        );
+        assert_eq!(Script::from_name("slp1"), Some(Script::Slp1));
+    }
+
+    #[test]
+    fn test_harvard_kyoto_both_directions() {
+        let iast = "kṛṣṇaḥ pitṝn kḷptaṃ jñānaṃ auṣadham ||";
+        let hk = "kRSNaH pitRRn klRptaM jJAnaM auSadham ||";
+        let bytes = encoder::encode_iast(iast).unwrap();
+        assert_eq!(encoder::encode_hk(hk).unwrap(), bytes);
+        assert_eq!(decode_phon(&bytes, Script::HarvardKyoto).unwrap(), hk);
+        for &script in Script::ALL {
+            assert_eq!(Script::from_name(script.name()), Some(script));
+        }
+    }
+
+    #[test]
+    fn test_wx_both_directions() {
+        let iast = "dharmakṣetre kurukṣetre samavetā yuyutsavaḥ | māmakāḥ pāṇḍavāś caiva";
+        let wx = "XarmakRewre kurukRewre samavewA yuyuwsavaH | mAmakAH pANdavAS cEva";
+        let bytes = encoder::encode_iast(iast).unwrap();
+        assert_eq!(decode_phon(&bytes, Script::Wx).unwrap(), wx);
+        assert_eq!(encoder::encode_wx(wx).unwrap(), bytes);
+        assert_eq!(Script::from_name("wx"), Some(Script::Wx));
+    }
+
+    #[test]
+    fn test_iso15919_styles() {
+        let bytes = encoder::encode_iast("kṛṣṇaḥ saṃskṛtaṃ kḷptaṃ devo").unwrap();
+        assert_eq!(
+            decode_phon(&bytes, Script::Iso15919).unwrap(),
+            "kr̥ṣṇaḥ saṁskr̥taṁ kl̥ptaṁ dēvō"
+        );
+        let library = DecodeOptions {
+            iso: IsoStyle::Library,
+            ..Default::default()
+        };
+        assert_eq!(
+            decode_phon_with(&bytes, Script::Iso15919, &library).unwrap(),
+            "kr̥ṣṇaḥ saṃskr̥taṃ kl̥ptaṃ devo"
+        );
+        // The style applies to ISO output only
+        assert_eq!(
+            decode_phon_with(&bytes, Script::Iast, &library),
+            decode_phon(&bytes, Script::Iast)
+        );
+        assert_eq!(
+            encoder::encode_script("kr̥ṣṇaḥ saṃskr̥taṁ kl̥ptaṁ dēvō", Script::Iso15919).unwrap(),
+            bytes
+        );
+    }
+
+    #[test]
+    fn test_capitalize_verses() {
+        let bytes =
+            encoder::encode_iast("ṛtaṃ ca satyaṃ | ca || 1 || ūrjaṃ vahantīḥ || śam").unwrap();
+        let opts = DecodeOptions {
+            capitalize_verses: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            decode_phon_with(&bytes, Script::Iast, &opts).unwrap(),
+            "Ṛtaṃ ca satyaṃ | ca || 1 || Ūrjaṃ vahantīḥ || Śam"
+        );
+        assert_eq!(
+            decode_phon_with(&bytes, Script::Iso15919, &opts).unwrap(),
+            "R̥taṁ ca satyaṁ | ca || 1 || Ūrjaṁ vahantīḥ || Śam"
+        );
+        // Case carries meaning in the ASCII schemes
+        assert_eq!(
+            decode_phon_with(&bytes, Script::HarvardKyoto, &opts),
+            decode_phon(&bytes, Script::HarvardKyoto)
+        );
+    }
+
+    #[test]
+    fn test_conjuncts() {
+        let bytes = encoder::encode_iast("kṣatriyaḥ śuddhaṃ").unwrap();
+        let with = |conjuncts, script| {
+            let opts = DecodeOptions {
+                conjuncts,
+                ..Default::default()
+            };
+            decode_phon_with(&bytes, script, &opts).unwrap()
+        };
+        assert_eq!(with(Conjuncts::Natural, Script::Devanagari), "क्षत्रियः शुद्धं");
+        let explicit = with(Conjuncts::Explicit, Script::Devanagari);
+        assert_eq!(explicit, "क्\u{200C}षत्\u{200C}रियः शुद्\u{200C}धं");
+        assert_eq!(
+            with(Conjuncts::HalfForms, Script::Devanagari),
+            "क्\u{200D}षत्\u{200D}रियः शुद्\u{200D}धं"
+        );
+        #[cfg(feature = "scripts")]
+        assert_eq!(
+            with(Conjuncts::Explicit, Script::Telugu),
+            "క్\u{200C}షత్\u{200C}రియః శుద్\u{200C}ధం"
+        );
+        // Romanized output and word-final virāma are untouched
+        assert_eq!(with(Conjuncts::Explicit, Script::Iast), "kṣatriyaḥ śuddhaṃ");
+        let vak = encoder::encode_iast("vāk").unwrap();
+        let opts = DecodeOptions {
+            conjuncts: Conjuncts::Explicit,
+            ..Default::default()
+        };
+        assert_eq!(
+            decode_phon_with(&vak, Script::Devanagari, &opts).unwrap(),
+            "वाक्"
+        );
+        // Either form reads back as the same bytes
+        assert_eq!(encoder::encode_devanagari(&explicit).unwrap(), bytes);
+        assert_eq!(Conjuncts::from_name("half"), Some(Conjuncts::HalfForms));
+    }
+
+    #[test]
+    fn test_line_breaks() {
+        let text = "dharmakṣetre kurukṣetre samavetā yuyutsavaḥ |\nmāmakāḥ pāṇḍavāś caiva kim akurvata sañjaya || 1 ||\n";
+        let bytes = encoder::encode_iast(text).unwrap();
+        assert_eq!(bytes.iter().filter(|&&b| b == LINE_BREAK).count(), 2);
+        assert_eq!(decode_phon(&bytes, Script::Iast).unwrap(), text);
+        let deva = decode_phon(&bytes, Script::Devanagari).unwrap();
+        assert_eq!(deva.lines().count(), 2);
+        assert_eq!(encoder::encode_devanagari(&deva).unwrap(), bytes);
+
+        // Spaces at the ends of a line go; blank lines stay
+        let bytes = encoder::encode_iast("rāma  \n\n  kṛṣṇa").unwrap();
+        assert_eq!(decode_phon(&bytes, Script::Iast).unwrap(), "rāma\n\nkṛṣṇa");
+        let opts = encoder::EncodeOptions {
+            newlines: encoder::Newlines::Space,
+            ..Default::default()
+        };
+        let bytes = encoder::encode_iast_with("rāma\nkṛṣṇa", &opts).unwrap();
+        assert_eq!(decode_phon(&bytes, Script::Iast).unwrap(), "rāma kṛṣṇa");
+    }
+
+    #[test]
+    fn test_punctuation() {
+        let text = "“nāham,” iti (sañjaya-uvāca); paśya — ‘etān’";
+        let bytes = encoder::encode_iast(text).unwrap();
+        assert_eq!(&bytes[..3], &[META_EXT, 0x48, PADA_START]);
+        assert_eq!(decode_phon(&bytes, Script::Iast).unwrap(), text);
+        let deva = decode_phon(&bytes, Script::Devanagari).unwrap();
+        assert_eq!(deva, "“नाहम्,” इति (सञ्जय-उवाच); पश्य — ‘एतान्’");
+        assert_eq!(encoder::encode_devanagari(&deva).unwrap(), bytes);
+
+        let opts = encoder::EncodeOptions {
+            punctuation: encoder::Punctuation::Reject,
+            ..Default::default()
+        };
+        assert!(encoder::encode_iast_with(text, &opts).is_err());
+    }
+
+    #[test]
+    fn test_fold_case() {
+        let opts = |fold_case| encoder::EncodeOptions {
+            fold_case,
+            ..Default::default()
+        };
+        let text = "Dharma ŚRĪ rāmaḥ | A";
+        let plain = encoder::encode_iast("dharma śrī rāmaḥ | a").unwrap();
+        assert!(encoder::encode_iast(text).is_err());
+        let folded =
+            encoder::encode_script_with(text, Script::Iast, &opts(CaseFolding::Fold)).unwrap();
+        assert_eq!(folded, plain);
+        let bytes =
+            encoder::encode_script_with(text, Script::Iast, &opts(CaseFolding::Preserve)).unwrap();
+        assert_eq!(&bytes[..3], &[PADA_START, CAPITAL, 0x1B]);
+        assert_eq!(bytes.iter().filter(|&&b| b == UPPERCASE).count(), 1);
+        assert_eq!(decode_phon(&bytes, Script::Iast).unwrap(), text);
+        assert_eq!(
+            decode_phon(&bytes, Script::Iso15919).unwrap(),
+            "Dharma ŚRĪ rāmaḥ | A"
+        );
+        for script in [Script::Devanagari, Script::HarvardKyoto] {
+            assert_eq!(
+                decode_phon(&bytes, script).unwrap(),
+                decode_phon(&plain, script).unwrap()
+            );
+        }
+        // Case tells letters apart in Harvard-Kyoto
+        let hk =
+            encoder::encode_script_with("rAma", Script::HarvardKyoto, &opts(CaseFolding::Preserve));
+        assert_eq!(hk, encoder::encode_script("rAma", Script::HarvardKyoto));
+    }
+
+    #[test]
+    fn test_ascii_fallback() {
+        let bytes = encoder::encode_iast("kṛṣṇaḥ śaṅkaraṃ dhyāyet 8 qalam tamiḻ").unwrap();
+        let opts = |ascii| DecodeOptions {
+            numerals: NumeralStyle::Both,
+            ascii,
+            ..Default::default()
+        };
+        let velthuis = decode_phon_with(&bytes, Script::Iast, &opts(AsciiFallback::Velthuis));
+        assert_eq!(
+            velthuis.unwrap(),
+            "k.r.s.na.h \"sa\"nkara.m dhyaayet 8 (a.s.ta) qalam tami?"
+        );
+        let itrans = decode_phon_with(&bytes, Script::Iast, &opts(AsciiFallback::Itrans));
+        assert_eq!(
+            itrans.unwrap(),
+            "kRRiShNaH sha~NkaraM dhyAyet 8 (aShTa) qalam tami?"
+        );
+        let hk = decode_phon_with(&bytes, Script::Iast, &opts(AsciiFallback::HarvardKyoto));
+        assert_eq!(hk.unwrap(), "kRSNaH zaGkaraM dhyAyet 8 (aSTa) qalam tami?");
+        // Whatever the text holds, the output is ASCII
+        let mixed =
+            encoder::encode_script("ḻa ṟa ṉa fa — “vāk” ‘r̥’ – agnímīḷe | ||", Script::Iso15919)
+                .unwrap();
+        for ascii in [
+            AsciiFallback::Velthuis,
+            AsciiFallback::Itrans,
+            AsciiFallback::HarvardKyoto,
+        ] {
+            for script in [Script::Iast, Script::Iso15919] {
+                let out = decode_phon_with(&mixed, script, &opts(ascii)).unwrap();
+                assert!(out.is_ascii(), "{:?} {:?}: {}", ascii, script, out);
+            }
+        }
+        assert_eq!(
+            decode_phon(&bytes, Script::Velthuis).unwrap(),
+            "k.r.s.na.h \"sa\"nkara.m dhyaayet 8 qalam tami?"
+        );
+        assert_eq!(
+            encoder::encode_script("k.r.s.na.h \"sa\"nkara.m", Script::Velthuis).unwrap(),
+            encoder::encode_iast("kṛṣṇaḥ śaṅkaraṃ").unwrap()
+        );
+        // Devanāgarī output is unaffected
+        assert_eq!(
+            decode_phon_with(
+                &bytes[..8],
+                Script::Devanagari,
+                &opts(AsciiFallback::Itrans)
+            ),
+            decode_phon(&bytes[..8], Script::Devanagari)
+        );
+    }
+
+    #[test]
+    fn test_itrans_both_directions() {
+        let iast = "kṛṣṇaḥ chandasāṃ jñānaṃ kṣetre so 'ham ||";
+        let itrans = "kRRiShNaH ChandasAM j~nAnaM kShetre so .aham ||";
+        let bytes = encoder::encode_iast(iast).unwrap();
+        assert_eq!(encoder::encode_itrans(itrans).unwrap(), bytes);
+        assert_eq!(
+            encoder::encode_itrans("kR^iShNaH chhandasaa.n GYaanaM xetre so .aham ||").unwrap(),
+            bytes
+        );
+        assert_eq!(decode_phon(&bytes, Script::Itrans).unwrap(), itrans);
+    }
+
+    #[test]
+    fn test_decoder_context() {
+        let text = include_str!("../testdata/bhagavadgita_2_11-25.txt").trim();
+        let mut data = encoder::encode_iast(text).unwrap();
+        data.extend(encoder::encode_iast(" qalam ḻa 108 ||").unwrap());
+
+        let styles = [
+            NumeralStyle::Digits,
+            NumeralStyle::Words,
+            NumeralStyle::Both,
+            NumeralStyle::Composed,
+        ];
+        let fallbacks = [
+            AsciiFallback::Off,
+            AsciiFallback::Velthuis,
+            AsciiFallback::Itrans,
+            AsciiFallback::HarvardKyoto,
+        ];
+        for &script in Script::ALL {
+            for numerals in styles {
+                for ascii in fallbacks {
+                    for iso in [IsoStyle::Strict, IsoStyle::Library] {
+                        let opts = DecodeOptions {
+                            numerals,
+                            ascii,
+                            iso,
+                            ..DecodeOptions::default()
+                        };
+                        let expected = decode_phon_with(&data, script, &opts).unwrap();
+                        let decoder = Decoder::new(script, opts);
+                        assert_eq!(decoder.decode(&data).unwrap(), expected);
+                    }
+                }
+            }
+        }
+
+        // One instance shared across threads, appending into reused buffers
+        let decoder = Arc::new(Decoder::new(Script::Devanagari, DecodeOptions::default()));
+        let expected = decode_phon(&data, Script::Devanagari).unwrap();
+        std::thread::scope(|s| {
+            for _ in 0..4 {
+                let decoder = Arc::clone(&decoder);
+                let (data, expected) = (&data, &expected);
+                s.spawn(move || {
+                    let mut out = String::new();
+                    for _ in 0..3 {
+                        out.clear();
+                        decoder.decode_into(data, &mut out).unwrap();
+                        assert_eq!(&out, expected);
+                    }
+                });
+            }
+        });
+    }
+
+    #[test]
+    fn test_decode_to_writer() {
+        let text = include_str!("../testdata/bhagavadgita_2_11-25.txt").trim();
+        let data = encoder::encode_iast(text).unwrap();
+        let opts = DecodeOptions {
+            numerals: NumeralStyle::Both,
+            ..Default::default()
+        };
+        for &script in Script::ALL {
+            let expected = decode_phon_with(&data, script, &opts).unwrap();
+            let mut out = String::new();
+            decode_phon_with_to(&data, script, &opts, &mut out).unwrap();
+            assert_eq!(out, expected, "{}", script.name());
+            assert_eq!(decoded_len(&data, script, &opts).unwrap(), expected.len());
+
+            let decoder = Decoder::new(script, opts.clone());
+            let mut out = String::new();
+            decoder.decode_to(&data, &mut out).unwrap();
+            assert_eq!(out, expected, "{}", script.name());
+            assert_eq!(decoder.decoded_len(&data).unwrap(), expected.len());
+        }
+
+        // A writer that gives out after a few bytes
+        struct Short(usize);
+        impl fmt::Write for Short {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                self.0 = self.0.checked_sub(s.len()).ok_or(fmt::Error)?;
+                Ok(())
+            }
+        }
+        assert!(matches!(
+            decode_phon_to(&data, Script::Iast, &mut Short(10)),
+            Err(SlbcError::Io { .. })
+        ));
+        assert!(decode_phon_to(&data, Script::Iast, &mut Short(usize::MAX)).is_ok());
+    }
+
+    #[test]
+    fn test_decode_logged() {
+        let mut data = encoder::encode_iast("rāma 6 ||").unwrap();
+        // The NUM glyph byte 6 equals META_START and must not be reported
+        assert!(data.contains(&META_START));
+        let meta = [
+            PADA_START, META_START, 0x01, 0x02, META_END, 0x00, 0x40, PADA_END,
+        ];
+        let at = data.len();
+        data.extend_from_slice(&meta);
+        data.extend_from_slice(&[SPACE, META_START, 0x03]);
+
+        let decoded = decode_phon_logged(&data, Script::Iast, &DecodeOptions::default()).unwrap();
+        assert_eq!(decoded.text, "rāma 6 ||ka ");
+        assert_eq!(
+            decoded.skipped_meta,
+            vec![
+                MetaSpan {
+                    offset: at + 1,
+                    bytes: vec![0x01, 0x02],
+                    closed: true,
+                },
+                MetaSpan {
+                    offset: data.len() - 2,
+                    bytes: vec![0x03],
+                    closed: false,
+                },
+            ]
+        );
+        let decoder = Decoder::new(Script::Devanagari, DecodeOptions::default());
+        assert_eq!(
+            decoder.decode_logged(&data).unwrap().skipped_meta,
+            decoded.skipped_meta
+        );
+    }
+
+    #[test]
+    fn test_long_ext_skipped() {
+        let mut data = encoder::encode_iast("rāma || gacchati").unwrap();
+        assert_eq!(data[..3], [PADA_START, 0x33, 0x80]);
+        // The payload holds a PADA_END and a DOUBLE_DANDA of its own
+        let ext = [META_EXT, EXT_LONG, 3, PADA_END, DOUBLE_DANDA, 0x00];
+        data.splice(3..3, ext);
+
+        for script in [Script::Iast, Script::Devanagari] {
+            let plain = encoder::encode_iast("rāma || gacchati").unwrap();
+            let expected = decode_phon(&plain, script).unwrap();
+            let decoded = decode_phon_logged(&data, script, &DecodeOptions::default()).unwrap();
+            assert_eq!(decoded.text, expected);
+            assert_eq!(
+                decoded.skipped_ext,
+                vec![LongExt {
+                    offset: 3,
+                    payload: vec![PADA_END, DOUBLE_DANDA, 0x00],
+                }]
+            );
+        }
+        assert_eq!(crate::lang::padas(&data).unwrap().len(), 2);
+        assert_eq!(frame::verses(&data).unwrap().len(), 2);
+        assert!(decode_phon(&data[..7], Script::Iast).is_err());
+    }
+
+    #[cfg(feature = "scripts")]
+    #[test]
+    fn test_telugu() {
+        let bytes = encoder::encode_iast("kṛṣṇaḥ saṃjayo 'bravīt 18 || vāk").unwrap();
+        assert_eq!(
+            decode_phon(&bytes, Script::Telugu).unwrap(),
+            "కృష్ణః సంజయో ఽబ్రవీత్ ౧౮ ॥ వాక్"
+        );
+        // Short e/o are the extension vowels; Sanskrit e/o stay long
+        let mut bytes = encoder::encode_script("kēka", Script::Iso15919).unwrap();
+        bytes.extend(encoder::encode_script(" kekoḻ", Script::Iso15919).unwrap());
+        assert_eq!(decode_phon(&bytes, Script::Telugu).unwrap(), "కేక కెకొఴ్");
+        assert!(encoder::encode_script("క", Script::Telugu).is_err());
+    }
+
+    #[cfg(feature = "scripts")]
+    #[test]
+    fn test_kannada() {
+        let bytes = encoder::encode_iast("kṛṣṇaḥ saṃjayo 'bravīt 18 || vāk").unwrap();
+        assert_eq!(
+            decode_phon(&bytes, Script::Kannada).unwrap(),
+            "ಕೃಷ್ಣಃ ಸಂಜಯೋ ಽಬ್ರವೀತ್ ೧೮ ॥ ವಾಕ್"
+        );
+        let bytes = encoder::encode_script("kēka kekoḻ ṟa", Script::Iso15919).unwrap();
+        assert_eq!(decode_phon(&bytes, Script::Kannada).unwrap(), "ಕೇಕ ಕೆಕೊೞ್ ಱ");
+    }
+
+    #[cfg(feature = "scripts")]
+    #[test]
+    fn test_grantha() {
+        let bytes = encoder::encode_iast("kṛṣṇaḥ saṃjayo 'bravīt 18 || vāk").unwrap();
+        assert_eq!(
+            decode_phon(&bytes, Script::Grantha).unwrap(),
+            "𑌕𑍃𑌷𑍍𑌣𑌃 𑌸𑌂𑌜𑌯𑍋 𑌽𑌬𑍍𑌰𑌵𑍀𑌤𑍍 ௧௮ ॥ 𑌵𑌾𑌕𑍍"
+        );
+        // Accents follow the vowel, as in Devanāgarī; the other abugidas
+        // drop them
+        let bytes = encoder::encode_devanagari("अ॒ग्नि॑म्").unwrap();
+        assert_eq!(decode_phon(&bytes, Script::Grantha).unwrap(), "𑌅॒𑌗𑍍𑌨𑌿॑𑌮𑍍");
+        assert_eq!(decode_phon(&bytes, Script::Telugu).unwrap(), "అగ్నిమ్");
+    }
+
+    #[cfg(feature = "scripts")]
+    #[test]
+    fn test_sharada() {
+        let bytes = encoder::encode_iast("kṛṣṇaḥ saṃjayo 'bravīt 18 || vāk").unwrap();
+        assert_eq!(
+            decode_phon(&bytes, Script::Sharada).unwrap(),
+            "𑆑𑆸𑆰𑇀𑆟𑆂 𑆱𑆁𑆘𑆪𑆾 𑇁𑆧𑇀𑆫𑆮𑆵𑆠𑇀 𑇑𑇘 𑇆 𑆮𑆳𑆑𑇀"
+        );
+    }
+
+    #[cfg(feature = "scripts")]
+    #[test]
+    fn test_tamil() {
+        let bytes = encoder::encode_iast("kṛṣṇaḥ saṃjayo 'bravīt gāṇḍīvaṃ 18 | jhaṣa").unwrap();
+        assert_eq!(
+            decode_phon(&bytes, Script::Tamil).unwrap(),
+            "க்ருஷ்ணஃ ஸஂஜயோ ऽப்³ரவீத் கா³ண்டீ³வஂ ௧௮ । ஜ⁴ஷ"
+        );
+        let plain = DecodeOptions {
+            tamil: TamilStyle::Plain,
+            ..DecodeOptions::default()
+        };
+        assert_eq!(
+            decode_phon_with(&bytes, Script::Tamil, &plain).unwrap(),
+            "க்ருஷ்ணஃ ஸஂஜயோ ऽப்ரவீத் காண்டீவஂ ௧௮ । ஜஷ"
+        );
+    }
+
+    #[cfg(feature = "scripts")]
+    #[test]
+    fn test_gujarati_gurmukhi() {
+        let bytes = encoder::encode_iast("kṛṣṇaḥ saṃjayo 'bravīt 18 || vāk").unwrap();
+        assert_eq!(
+            decode_phon(&bytes, Script::Gujarati).unwrap(),
+            "કૃષ્ણઃ સંજયો ઽબ્રવીત્ ૧૮ ॥ વાક્"
+        );
+        assert_eq!(
+            decode_phon(&bytes, Script::Gurmukhi).unwrap(),
+            "ਕ੍ਰਿਖ੍ਣਃ ਸਂਜਯੋ ऽਬ੍ਰਵੀਤ੍ ੧੮ ॥ ਵਾਕ੍"
+        );
+    }
+
+    #[cfg(feature = "scripts")]
+    #[test]
+    fn test_siddham() {
+        let bytes = encoder::encode_iast("kṛṣṇaḥ saṃjayo 'bravīt 18 || ṛṣiḥ kḷpta").unwrap();
+        assert_eq!(
+            decode_phon(&bytes, Script::Siddham).unwrap(),
+            "𑖎𑖴𑖬𑖿𑖜𑖾 𑖭𑖽𑖕𑖧𑖺 ऽ𑖤𑖿𑖨𑖪𑖱𑖝𑖿 १८ 𑗃 𑖆𑖬𑖰𑖾 𑖎𑖿𑖈𑖢𑖿𑖝"
+        );
+    }
+
+    #[cfg(feature = "scripts")]
+    #[test]
+    fn test_odia() {
+        let bytes = encoder::encode_iast("kṛṣṇaḥ saṃjayo 'bravīt 18 || vāk").unwrap();
+        assert_eq!(
+            decode_phon(&bytes, Script::Odia).unwrap(),
+            "କୃଷ୍ଣଃ ସଂଜଯୋ ଽବ୍ରଵୀତ୍ ୧୮ ॥ ଵାକ୍"
+        );
+    }
 }
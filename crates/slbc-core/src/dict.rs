@@ -0,0 +1,689 @@
+//! Shared pada dictionaries (§9.6, registry type 0x04).
+//!
+//! A pada dictionary lists frequent padas. A PHON payload compressed against
+//! it replaces each listed pada's contents with `DICT_REF` + ULEB128 index,
+//! and the container's DICT chunk pins the dictionary by hash, so thousands
+//! of small files can share one dictionary file.
+//!
+//! Dictionary file layout:
+//! ```text
+//! "SLBD" (magic) | version (1 byte) | entry count (ULEB128)
+//! per entry: length (ULEB128) + pada bytes
+//! ```
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::container::{self, Chunk, ExtRecord, SlbcHeader};
+use crate::error::SlbcError;
+use crate::layers;
+use crate::numeral;
+use crate::pack::{self, PackTable};
+use crate::types::*;
+
+pub const DICT_MAGIC: &[u8; 4] = b"SLBD";
+pub const DICT_VERSION: u8 = 1;
+
+/// A shared pada dictionary.
+#[derive(Debug, Clone, Default)]
+pub struct PadaDict {
+    entries: Vec<Vec<u8>>,
+    index: HashMap<Vec<u8>, u32>,
+}
+
+impl PadaDict {
+    /// Build a dictionary from pada contents (without PADA_START/PADA_END).
+    pub fn from_entries(entries: Vec<Vec<u8>>) -> Self {
+        let index = entries
+            .iter()
+            .enumerate()
+            .map(|(i, e)| (e.clone(), i as u32))
+            .collect();
+        PadaDict { entries, index }
+    }
+
+    pub fn entries(&self) -> &[Vec<u8>] {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The pada stored at `index`.
+    pub fn get(&self, index: u32) -> Option<&[u8]> {
+        self.entries.get(index as usize).map(|e| e.as_slice())
+    }
+
+    /// The index of a pada, if it is in the dictionary.
+    pub fn lookup(&self, pada: &[u8]) -> Option<u32> {
+        self.index.get(pada).copied()
+    }
+
+    /// Serialize to the dictionary file format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(DICT_MAGIC);
+        out.push(DICT_VERSION);
+        container::write_uleb128(&mut out, self.entries.len() as u64);
+        for entry in &self.entries {
+            container::write_uleb128(&mut out, entry.len() as u64);
+            out.extend_from_slice(entry);
+        }
+        out
+    }
+
+    /// Parse the dictionary file format.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, SlbcError> {
+        if data.len() < 5 || &data[0..4] != DICT_MAGIC {
+            return Err(SlbcError::malformed(
+                0,
+                "invalid magic bytes (expected 'SLBD')",
+            ));
+        }
+        if data[4] != DICT_VERSION {
+            return Err(SlbcError::malformed(
+                4,
+                format_args!("unsupported dictionary version {}", data[4]),
+            ));
+        }
+        let mut pos = 5;
+        let (count, consumed) = container::read_uleb128(&data[pos..])
+            .map_err(|e| e.context(pos, "entry count ULEB128 error"))?;
+        pos += consumed;
+        // Every entry takes at least its one-byte length
+        if count > (data.len() - pos) as u64 {
+            return Err(SlbcError::truncated(
+                pos,
+                format_args!(
+                    "entry count {} exceeds the {} bytes left",
+                    count,
+                    data.len() - pos
+                ),
+            ));
+        }
+
+        let mut entries = Vec::with_capacity(count.min(1 << 16) as usize);
+        for n in 0..count {
+            let (len, consumed) = container::read_uleb128(&data[pos..])
+                .map_err(|e| e.context(pos, format_args!("entry {} length ULEB128 error", n)))?;
+            pos += consumed;
+            let end = pos + len as usize;
+            if end > data.len() {
+                return Err(SlbcError::truncated(
+                    pos,
+                    format_args!("entry {} extends beyond file", n),
+                ));
+            }
+            entries.push(data[pos..end].to_vec());
+            pos = end;
+        }
+        Ok(PadaDict::from_entries(entries))
+    }
+
+    /// Content hash (FNV-1a 64 over the serialized dictionary).
+    pub fn hash(&self) -> u64 {
+        fnv1a64(&self.to_bytes())
+    }
+}
+
+/// FNV-1a 64-bit hash.
+pub fn fnv1a64(data: &[u8]) -> u64 {
+    let mut h: u64 = 0xcbf2_9ce4_8422_2325;
+    for &b in data {
+        h ^= b as u64;
+        h = h.wrapping_mul(0x0000_0100_0000_01B3);
+    }
+    h
+}
+
+// ═══════════════════════════════════════════════
+//  Training
+// ═══════════════════════════════════════════════
+
+/// Build a dictionary of the most valuable padas across a corpus of PHON
+/// payloads.
+///
+/// Padas are ranked by estimated saving — occurrences × (length − 2), the
+/// reference costing DICT_REF plus a one-byte index. Padas seen only once, or
+/// too short to save anything, are left out.
+pub fn train<'a, I>(payloads: I, max_entries: usize) -> Result<PadaDict, SlbcError>
+where
+    I: IntoIterator<Item = &'a [u8]>,
+{
+    let mut counts: HashMap<Vec<u8>, usize> = HashMap::new();
+    for payload in payloads {
+        rewrite_padas(payload, |content, _| {
+            if is_plain_pada(content) {
+                *counts.entry(content.to_vec()).or_insert(0) += 1;
+            }
+            Ok(())
+        })?;
+    }
+
+    let mut ranked: Vec<(usize, Vec<u8>)> = counts
+        .into_iter()
+        .filter(|(pada, count)| *count >= 2 && pada.len() > 2)
+        .map(|(pada, count)| (count * (pada.len() - 2), pada))
+        .collect();
+    ranked.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+    ranked.truncate(max_entries);
+
+    Ok(PadaDict::from_entries(
+        ranked.into_iter().map(|(_, pada)| pada).collect(),
+    ))
+}
+
+// ═══════════════════════════════════════════════
+//  Compression
+// ═══════════════════════════════════════════════
+
+/// Replace every dictionary pada in a PHON payload with a DICT_REF.
+pub fn compress(data: &[u8], dict: &PadaDict) -> Result<Vec<u8>, SlbcError> {
+    rewrite_padas(data, |content, out| {
+        match dict.lookup(content).filter(|_| is_plain_pada(content)) {
+            Some(index) => {
+                out.push(DICT_REF);
+                container::write_uleb128(out, index as u64);
+            }
+            None => out.extend_from_slice(content),
+        }
+        Ok(())
+    })
+}
+
+/// Resolve every DICT_REF in a PHON payload back to its pada bytes.
+pub fn expand(data: &[u8], dict: &PadaDict) -> Result<Vec<u8>, SlbcError> {
+    rewrite_padas(data, |content, out| {
+        if content.first() != Some(&DICT_REF) {
+            out.extend_from_slice(content);
+            return Ok(());
+        }
+        // `rewrite_padas` has read the index and checked the pada's end
+        let (index, _) = container::read_uleb128(&content[1..])?;
+        let entry = dict.get(index as u32).ok_or_else(|| {
+            SlbcError::invalid(format_args!("DICT_REF index {} not in dictionary", index))
+        })?;
+        out.extend_from_slice(entry);
+        Ok(())
+    })
+}
+
+/// Returns true if a payload contains any dictionary reference.
+pub fn has_refs(data: &[u8]) -> Result<bool, SlbcError> {
+    let mut found = false;
+    rewrite_padas(data, |content, _| {
+        found |= content.first() == Some(&DICT_REF);
+        Ok(())
+    })?;
+    Ok(found)
+}
+
+/// A pada made only of phoneme bytes (no META, avagraha, or references).
+fn is_plain_pada(content: &[u8]) -> bool {
+    !content.is_empty() && content.iter().all(|&b| is_svara(b) || is_vyanjana(b))
+}
+
+/// Copy a payload, passing the contents of each top-level pada through `f`.
+/// Numeral spans, META blocks, and lipi bytes are copied verbatim.
+pub(crate) fn rewrite_padas<F>(data: &[u8], mut f: F) -> Result<Vec<u8>, SlbcError>
+where
+    F: FnMut(&[u8], &mut Vec<u8>) -> Result<(), SlbcError>,
+{
+    let mut out = Vec::with_capacity(data.len());
+    let mut i = 0;
+
+    while i < data.len() {
+        match data[i] {
+            SANKHYA_START => {
+                let (_, consumed) = numeral::decode_sankhya(data, i)?;
+                out.extend_from_slice(&data[i..i + consumed]);
+                i += consumed;
+            }
+            NUM => {
+                let (_, consumed) = numeral::decode_num(data, i)?;
+                out.extend_from_slice(&data[i..i + consumed]);
+                i += consumed;
+            }
+            META_START => {
+                let end = data[i..]
+                    .iter()
+                    .position(|&b| b == META_END)
+                    .map_or(data.len(), |p| i + p + 1);
+                out.extend_from_slice(&data[i..end]);
+                i = end;
+            }
+            // A reference index may itself encode as PADA_END (index 46),
+            // so its length comes from the ULEB128, not a scan
+            PADA_START if data.get(i + 1) == Some(&DICT_REF) => {
+                let (_, consumed) = container::read_uleb128(&data[i + 2..])
+                    .map_err(|e| e.context(i + 2, "DICT_REF index error"))?;
+                let end = i + 2 + consumed;
+                if data.get(end) != Some(&PADA_END) {
+                    return Err(SlbcError::malformed(i, "DICT_REF pada has trailing bytes"));
+                }
+                out.push(PADA_START);
+                f(&data[i + 1..end], &mut out)?;
+                out.push(PADA_END);
+                i = end + 1;
+            }
+            PADA_START => {
+                let Some(len) = data[i + 1..].iter().position(|&b| b == PADA_END) else {
+                    out.extend_from_slice(&data[i..]);
+                    break;
+                };
+                out.push(PADA_START);
+                f(&data[i + 1..i + 1 + len], &mut out)?;
+                out.push(PADA_END);
+                i += len + 2;
+            }
+            b => {
+                out.push(b);
+                i += 1;
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+// ═══════════════════════════════════════════════
+//  External dictionary reference
+// ═══════════════════════════════════════════════
+
+/// A container's reference to an external pada dictionary.
+///
+/// Carried either as an extended header record (tag `EXT_HDR_DICT`) or as a
+/// DICT chunk in External mode. The reference body is the same in both:
+/// ```text
+/// id (ULEB128) | hash (u64 LE) | name (ULEB128 len + UTF-8)
+/// ```
+/// and the DICT chunk prefixes it with `REGISTRY_PADA | DICT_MODE_EXTERNAL`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DictReference {
+    /// Corpus-assigned dictionary id.
+    pub id: u32,
+    pub hash: u64,
+    /// Advisory file name of the dictionary.
+    pub name: String,
+}
+
+impl DictReference {
+    /// Reference a dictionary under the given id and file name.
+    pub fn to(dict: &PadaDict, id: u32, name: &str) -> Self {
+        DictReference {
+            id,
+            hash: dict.hash(),
+            name: name.into(),
+        }
+    }
+
+    fn write_body(&self, out: &mut Vec<u8>) {
+        container::write_uleb128(out, self.id as u64);
+        out.extend_from_slice(&self.hash.to_le_bytes());
+        container::write_uleb128(out, self.name.len() as u64);
+        out.extend_from_slice(self.name.as_bytes());
+    }
+
+    /// Parse the reference body that starts at `start` of `body`.
+    fn parse_body(body: &[u8], start: usize) -> Result<Self, SlbcError> {
+        let (id, consumed) = container::read_uleb128(&body[start..])
+            .map_err(|e| e.context(start, "dictionary id ULEB128 error"))?;
+        let mut pos = start + consumed;
+        if body.len() < pos + 8 {
+            return Err(SlbcError::truncated(
+                pos,
+                "truncated pada dictionary reference",
+            ));
+        }
+        let mut hash = [0u8; 8];
+        hash.copy_from_slice(&body[pos..pos + 8]);
+        pos += 8;
+        let (len, consumed) = container::read_uleb128(&body[pos..])
+            .map_err(|e| e.context(pos, "dictionary name ULEB128 error"))?;
+        pos += consumed;
+        let end = pos + len as usize;
+        if end > body.len() {
+            return Err(SlbcError::truncated(
+                pos,
+                "dictionary name extends beyond reference",
+            ));
+        }
+        let name = String::from_utf8(body[pos..end].to_vec())
+            .map_err(|_| SlbcError::malformed(pos, "dictionary name is not valid UTF-8"))?;
+        Ok(DictReference {
+            id: id as u32,
+            hash: u64::from_le_bytes(hash),
+            name,
+        })
+    }
+
+    /// Build the extended header record holding this reference.
+    pub fn to_ext_record(&self) -> ExtRecord {
+        let mut payload = Vec::new();
+        self.write_body(&mut payload);
+        ExtRecord {
+            tag: EXT_HDR_DICT,
+            payload,
+        }
+    }
+
+    /// Build the DICT chunk holding this reference.
+    pub fn to_chunk(&self) -> Chunk {
+        let mut payload = vec![REGISTRY_PADA, DICT_MODE_EXTERNAL];
+        self.write_body(&mut payload);
+        Chunk {
+            chunk_type: CHUNK_DICT,
+            payload,
+        }
+    }
+
+    /// Parse a DICT chunk payload. Returns `None` for other registry types.
+    pub fn from_chunk_payload(payload: &[u8]) -> Result<Option<Self>, SlbcError> {
+        if payload.first() != Some(&REGISTRY_PADA) {
+            return Ok(None);
+        }
+        if payload.get(1) != Some(&DICT_MODE_EXTERNAL) {
+            return Err(SlbcError::malformed(
+                1,
+                "pada dictionary DICT chunk must use external mode",
+            ));
+        }
+        DictReference::parse_body(payload, 2).map(Some)
+    }
+
+    /// Find the pada dictionary reference among a container's chunks.
+    pub fn find(chunks: &[Chunk]) -> Result<Option<Self>, SlbcError> {
+        for chunk in chunks.iter().filter(|c| c.chunk_type == CHUNK_DICT) {
+            if let Some(r) = DictReference::from_chunk_payload(&chunk.payload)? {
+                return Ok(Some(r));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Find the pada dictionary reference in the extended header, falling
+    /// back to DICT chunks.
+    pub fn locate(header: &SlbcHeader, chunks: &[Chunk]) -> Result<Option<Self>, SlbcError> {
+        for record in header.ext_records()? {
+            if record.tag == EXT_HDR_DICT {
+                return DictReference::parse_body(&record.payload, 0).map(Some);
+            }
+        }
+        DictReference::find(chunks)
+    }
+
+    /// Check that `dict` is the dictionary this reference names.
+    pub fn verify(&self, dict: &PadaDict) -> Result<(), SlbcError> {
+        let actual = dict.hash();
+        if actual != self.hash {
+            return Err(SlbcError::invalid(format_args!(
+                "dictionary hash mismatch: container expects {:016x} ('{}'), got {:016x}",
+                self.hash, self.name, actual
+            )));
+        }
+        Ok(())
+    }
+}
+
+// ═══════════════════════════════════════════════
+//  Resolution
+// ═══════════════════════════════════════════════
+
+/// Supplies the dictionary a container references.
+///
+/// Return `Ok(None)` when the dictionary is not available; `open` turns that
+/// into a missing-dictionary error. Hash checking is done by `open`.
+pub trait DictResolver {
+    fn resolve(&self, reference: &DictReference) -> Result<Option<PadaDict>, SlbcError>;
+}
+
+impl<F> DictResolver for F
+where
+    F: Fn(&DictReference) -> Result<Option<PadaDict>, SlbcError>,
+{
+    fn resolve(&self, reference: &DictReference) -> Result<Option<PadaDict>, SlbcError> {
+        self(reference)
+    }
+}
+
+/// Resolves nothing — for callers that have no dictionaries.
+pub struct NoDicts;
+
+impl DictResolver for NoDicts {
+    fn resolve(&self, _: &DictReference) -> Result<Option<PadaDict>, SlbcError> {
+        Ok(None)
+    }
+}
+
+/// Looks up a dictionary by its referenced file name, or `<hash>.dict`,
+/// in each directory in turn.
+pub struct DictDirs(pub Vec<PathBuf>);
+
+impl DictResolver for DictDirs {
+    fn resolve(&self, reference: &DictReference) -> Result<Option<PadaDict>, SlbcError> {
+        let by_hash = format!("{:016x}.dict", reference.hash);
+        for dir in &self.0 {
+            for name in [reference.name.as_str(), by_hash.as_str()] {
+                if name.is_empty() || name.contains(['/', '\\']) {
+                    continue;
+                }
+                let path = dir.join(name);
+                if let Ok(data) = fs::read(&path) {
+                    let dict = PadaDict::from_bytes(&data).map_err(|e| SlbcError::Malformed {
+                        message: format!("{}: {}", path.display(), e),
+                        offset: e.offset(),
+                    })?;
+                    if dict.hash() == reference.hash {
+                        return Ok(Some(dict));
+                    }
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Parse a container and resolve its dictionary reference, returning PHON
+/// chunks unpacked (see `pack`) and with every DICT_REF expanded. A
+/// separated container's BHA and LIPI chunks come back merged into PHON
+/// chunks (see `layers`).
+///
+/// Fails if the referenced dictionary cannot be resolved or has the wrong
+/// hash, or if a PHON payload holds references but no dictionary is named.
+pub fn open(
+    data: &[u8],
+    resolver: &dyn DictResolver,
+) -> Result<(SlbcHeader, Vec<Chunk>, Option<DictReference>), SlbcError> {
+    let (header, chunks) = container::parse_slbc(data)?;
+    let mut chunks = layers::merge_chunks(chunks)?;
+    let reference = DictReference::locate(&header, &chunks)?;
+
+    let dict = match &reference {
+        Some(r) => {
+            let dict = resolver.resolve(r)?.ok_or_else(|| {
+                SlbcError::invalid(format_args!(
+                    "pada dictionary '{}' (id {}, hash {:016x}) not found",
+                    r.name, r.id, r.hash
+                ))
+            })?;
+            r.verify(&dict)?;
+            Some(dict)
+        }
+        None => None,
+    };
+
+    let table = PackTable::find(&chunks)?;
+    for chunk in chunks.iter_mut().filter(|c| c.chunk_type == CHUNK_PHON) {
+        match &table {
+            Some(t) => chunk.payload = pack::unpack(&chunk.payload, t)?,
+            None if pack::has_packed(&chunk.payload)? => {
+                return Err(SlbcError::invalid(
+                    "PHON payload has packed codes but the container has no pack table",
+                ));
+            }
+            None => {}
+        }
+        match &dict {
+            Some(d) => chunk.payload = expand(&chunk.payload, d)?,
+            None if has_refs(&chunk.payload)? => {
+                return Err(SlbcError::invalid(
+                    "PHON payload has dictionary references but the container names no dictionary",
+                ));
+            }
+            None => {}
+        }
+    }
+    Ok((header, chunks, reference))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoder;
+
+    fn corpus() -> Vec<Vec<u8>> {
+        [
+            "dharmakṣetre kurukṣetre",
+            "dharmakṣetre ca",
+            "kurukṣetre na",
+        ]
+        .iter()
+        .map(|t| encoder::encode_iast(t).unwrap())
+        .collect()
+    }
+
+    #[test]
+    fn test_train_picks_repeated_padas() {
+        let corpus = corpus();
+        let dict = train(corpus.iter().map(|p| p.as_slice()), 16).unwrap();
+        assert_eq!(dict.len(), 2); // dharmakṣetre, kurukṣetre — "ca"/"na" too short
+        let dharma = &encoder::encode_iast("dharmakṣetre").unwrap();
+        assert!(dict.lookup(&dharma[1..dharma.len() - 1]).is_some());
+    }
+
+    #[test]
+    fn test_compress_expand_roundtrip() {
+        let corpus = corpus();
+        let dict = train(corpus.iter().map(|p| p.as_slice()), 16).unwrap();
+        for payload in &corpus {
+            let packed = compress(payload, &dict).unwrap();
+            assert!(packed.len() < payload.len());
+            assert!(has_refs(&packed).unwrap());
+            assert_eq!(&expand(&packed, &dict).unwrap(), payload);
+        }
+    }
+
+    #[test]
+    fn test_index_encoding_as_control_byte() {
+        // Index 46 is 0x2E = PADA_END, index 38 is 0x26 = PADA_START
+        let entries: Vec<Vec<u8>> = (1..=64).map(|n| [0x00, 0x40].repeat(n)).collect();
+        let dict = PadaDict::from_entries(entries);
+        let mut payload = Vec::new();
+        for n in [46, 38, 31] {
+            payload.push(PADA_START);
+            payload.extend_from_slice(dict.get(n).unwrap());
+            payload.extend_from_slice(&[PADA_END, SPACE]);
+        }
+        let packed = compress(&payload, &dict).unwrap();
+        assert!(packed.len() < payload.len());
+        assert_eq!(expand(&packed, &dict).unwrap(), payload);
+    }
+
+    #[test]
+    fn test_numerals_untouched() {
+        let payload = encoder::encode_iast("aṣṭa 8 aṣṭa").unwrap();
+        let dict = train([payload.as_slice()], 16).unwrap();
+        assert_eq!(dict.len(), 1);
+        let packed = compress(&payload, &dict).unwrap();
+        assert_eq!(expand(&packed, &dict).unwrap(), payload);
+        let (digits, _) = numeral::decode_sankhya(
+            &packed,
+            packed.iter().position(|&b| b == SANKHYA_START).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(digits, vec![8]);
+    }
+
+    #[test]
+    fn test_file_format_and_reference() {
+        let dict = PadaDict::from_entries(vec![vec![0x1B, 0x40, 0x33], vec![0x00, 0x40, 0x00]]);
+        let parsed = PadaDict::from_bytes(&dict.to_bytes()).unwrap();
+        assert_eq!(parsed.entries(), dict.entries());
+        assert_eq!(parsed.hash(), dict.hash());
+
+        let bytes = dict.to_bytes();
+        for cut in 5..bytes.len() {
+            assert!(PadaDict::from_bytes(&bytes[..cut]).is_err());
+        }
+        // An entry count near u32::MAX in a ten-byte file
+        let huge = [
+            b'S',
+            b'L',
+            b'B',
+            b'D',
+            DICT_VERSION,
+            0xFF,
+            0xFF,
+            0xFF,
+            0xFF,
+            0x0F,
+        ];
+        let e = PadaDict::from_bytes(&huge).unwrap_err();
+        assert!(e.to_string().starts_with("entry count 4294967295 exceeds"));
+        assert!(matches!(
+            e,
+            SlbcError::Truncated {
+                offset: Some(10),
+                ..
+            }
+        ));
+
+        let r = DictReference::to(&dict, 7, "common.dict");
+        let chunk = r.to_chunk();
+        let back = DictReference::find(&[chunk]).unwrap().unwrap();
+        assert_eq!(back, r);
+        assert!(back.verify(&dict).is_ok());
+        assert!(back.verify(&PadaDict::default()).is_err());
+    }
+
+    #[test]
+    fn test_open_resolves_header_reference() {
+        let corpus = corpus();
+        let dict = train(corpus.iter().map(|p| p.as_slice()), 16).unwrap();
+        let r = DictReference::to(&dict, 3, "stotra.dict");
+        let packed = compress(&corpus[0], &dict).unwrap();
+        let slbc = container::build_slbc_ext(&[r.to_ext_record()], &packed, &[]);
+
+        let resolver = |want: &DictReference| -> Result<Option<PadaDict>, SlbcError> {
+            Ok((want.id == 3).then(|| dict.clone()))
+        };
+        let (_, chunks, found) = open(&slbc, &resolver).unwrap();
+        assert_eq!(found, Some(r));
+        assert_eq!(chunks[0].payload, corpus[0]);
+
+        let err = open(&slbc, &NoDicts).unwrap_err().to_string();
+        assert!(
+            err.contains("'stotra.dict'") && err.contains("not found"),
+            "{}",
+            err
+        );
+
+        let wrong = |_: &DictReference| -> Result<Option<PadaDict>, SlbcError> {
+            Ok(Some(PadaDict::default()))
+        };
+        assert!(open(&slbc, &wrong)
+            .unwrap_err()
+            .to_string()
+            .contains("hash mismatch"));
+
+        // References with no named dictionary are an error, not garbage
+        let orphan = container::build_slbc(&packed);
+        assert!(open(&orphan, &NoDicts).is_err());
+    }
+}
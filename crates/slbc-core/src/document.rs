@@ -0,0 +1,372 @@
+//! A payload as a tree: sentences of padas, numerals, and punctuation, and
+//! padas of phonemes, each with its byte offsets.
+//!
+//! `decoder::decode_document` builds one. Sentences are the outermost PHON
+//! frames where the payload has any (`PhonFrames::Sentences`), and
+//! otherwise run to each daṇḍa, a double daṇḍa taking the verse number
+//! after it (`|| 1 ||`). Text is IAST. Spacing, annotations, and the
+//! manuscript's emendation blocks are left out of the tree, and offsets
+//! are into the payload as given, so a tool can go from a node back to its
+//! bytes.
+
+use std::ops::Range;
+
+use crate::codepage::{self, EXT_LONG};
+use crate::decoder::{self, Script};
+use crate::error::SlbcError;
+use crate::numeral;
+use crate::types::*;
+
+/// A decoded payload.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Document {
+    pub sentences: Vec<Sentence>,
+}
+
+impl Document {
+    /// Every pada, in order.
+    pub fn padas(&self) -> impl Iterator<Item = &Pada> {
+        self.sentences
+            .iter()
+            .flat_map(|s| &s.items)
+            .filter_map(|item| match item {
+                Item::Pada(pada) => Some(pada),
+                _ => None,
+            })
+    }
+}
+
+/// One sentence (or verse, or other outermost PHON frame).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sentence {
+    /// From the first item's first byte to the last item's last.
+    pub span: Range<usize>,
+    pub items: Vec<Item>,
+}
+
+/// What a sentence is made of.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Item {
+    Pada(Pada),
+    Numeral(Numeral),
+    Punctuation(Punctuation),
+}
+
+impl Item {
+    pub fn span(&self) -> &Range<usize> {
+        match self {
+            Item::Pada(p) => &p.span,
+            Item::Numeral(n) => &n.span,
+            Item::Punctuation(p) => &p.span,
+        }
+    }
+}
+
+/// A word.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pada {
+    /// PADA_START through PADA_END.
+    pub span: Range<usize>,
+    pub text: String,
+    /// Morpheme seams, compound boundaries, case marks, and the avagraha
+    /// are not phonemes and are left out.
+    pub phonemes: Vec<Phoneme>,
+}
+
+/// A phoneme: one byte, or a two-byte extension phoneme.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Phoneme {
+    pub span: Range<usize>,
+    pub text: String,
+}
+
+/// A number: its SAṄKHYĀ span and the NUM span after it, if any.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Numeral {
+    pub span: Range<usize>,
+    /// ASCII digits, most significant first.
+    pub digits: String,
+}
+
+/// A daṇḍa, a double daṇḍa, or a punctuation mark (§6.4).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Punctuation {
+    pub span: Range<usize>,
+    pub text: String,
+}
+
+/// Build the document tree of a PHON payload.
+pub fn build(data: &[u8]) -> Result<Document, SlbcError> {
+    let units = units(data)?;
+    let framed = units.iter().any(|u| u.byte == PHON_START);
+
+    let mut sentences = Vec::new();
+    let mut items = Vec::new();
+    let mut depth = 0;
+    let mut k = 0;
+    while k < units.len() {
+        let unit = &units[k];
+        k += 1;
+        match unit.byte {
+            PHON_START => {
+                if depth == 0 {
+                    close(&mut items, &mut sentences);
+                }
+                depth += 1;
+            }
+            PHON_END if depth > 0 => {
+                depth -= 1;
+                if depth == 0 {
+                    close(&mut items, &mut sentences);
+                }
+            }
+            PADA_START => {
+                let end = units[k..]
+                    .iter()
+                    .position(|u| u.byte == PADA_END)
+                    .map(|p| k + p)
+                    .ok_or_else(|| SlbcError::malformed(unit.span.start, "unclosed PADA_START"))?;
+                items.push(Item::Pada(pada(data, &units[k - 1..=end])?));
+                k = end + 1;
+            }
+            SANKHYA_START => {
+                let (digits, _) = numeral::decode_sankhya(data, unit.span.start)?;
+                let mut span = unit.span.clone();
+                if units.get(k).is_some_and(|u| u.byte == NUM) {
+                    span.end = units[k].span.end;
+                    k += 1;
+                }
+                items.push(Item::Numeral(Numeral {
+                    span,
+                    digits: digit_text(&digits),
+                }));
+            }
+            NUM => {
+                let (digits, _) = numeral::decode_num(data, unit.span.start)?;
+                items.push(Item::Numeral(Numeral {
+                    span: unit.span.clone(),
+                    digits: digit_text(&digits),
+                }));
+            }
+            DANDA | DOUBLE_DANDA => {
+                items.push(Item::Punctuation(Punctuation {
+                    span: unit.span.clone(),
+                    text: if unit.byte == DANDA { "|" } else { "||" }.into(),
+                }));
+                if framed {
+                    continue;
+                }
+                if unit.byte == DOUBLE_DANDA {
+                    k += verse_number(data, &units[k..], &mut items)?;
+                }
+                close(&mut items, &mut sentences);
+            }
+            META_EXT => {
+                if let Some(mark) = data
+                    .get(unit.span.start + 1)
+                    .and_then(|&c| codepage::punctuation(c))
+                {
+                    items.push(Item::Punctuation(Punctuation {
+                        span: unit.span.clone(),
+                        text: mark.to_string(),
+                    }));
+                }
+            }
+            DICT_REF => {
+                return Err(SlbcError::DictRef {
+                    offset: unit.span.start,
+                })
+            }
+            _ => {}
+        }
+    }
+    close(&mut items, &mut sentences);
+    Ok(Document { sentences })
+}
+
+/// One unit of a payload: a byte, or a multi-byte span named by its first
+/// byte.
+struct Unit {
+    byte: u8,
+    span: Range<usize>,
+}
+
+fn units(data: &[u8]) -> Result<Vec<Unit>, SlbcError> {
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < data.len() {
+        let len = match data[i] {
+            SANKHYA_START => numeral::decode_sankhya(data, i)?.1,
+            NUM => numeral::decode_num(data, i)?.1,
+            // Reported when the tree is built
+            DICT_REF => 1,
+            META_EXT => codepage::ext_len(data, i)?,
+            META_START => data[i..]
+                .iter()
+                .position(|&b| b == META_END)
+                .map_or(data.len() - i, |p| p + 1),
+            _ => 1,
+        };
+        out.push(Unit {
+            byte: data[i],
+            span: i..i + len,
+        });
+        i += len;
+    }
+    Ok(out)
+}
+
+/// The pada whose units run from its PADA_START to its PADA_END.
+fn pada(data: &[u8], units: &[Unit]) -> Result<Pada, SlbcError> {
+    let span = units[0].span.start..units[units.len() - 1].span.end;
+    let mut phonemes = Vec::new();
+    for unit in units {
+        let b = unit.byte;
+        let phoneme = match b {
+            META_EXT => {
+                !codepage::is_samasa(data, unit.span.start)
+                    && data.get(unit.span.start + 1) != Some(&EXT_LONG)
+            }
+            DICT_REF => {
+                return Err(SlbcError::DictRef {
+                    offset: unit.span.start,
+                })
+            }
+            _ => is_svara(b) || is_vyanjana(b),
+        };
+        if phoneme {
+            let mut bytes = vec![PADA_START];
+            bytes.extend_from_slice(&data[unit.span.clone()]);
+            bytes.push(PADA_END);
+            phonemes.push(Phoneme {
+                span: unit.span.clone(),
+                text: decoder::decode_phon(&bytes, Script::Iast)?,
+            });
+        }
+    }
+    Ok(Pada {
+        text: decoder::decode_phon(&data[span.clone()], Script::Iast)?,
+        span,
+        phonemes,
+    })
+}
+
+/// Take the verse number after a double daṇḍa (`1 ||`) into `items`, and
+/// return how many units it spans, or 0 if none follows.
+fn verse_number(data: &[u8], rest: &[Unit], items: &mut Vec<Item>) -> Result<usize, SlbcError> {
+    let skip_space = |k: usize| k + usize::from(rest.get(k).is_some_and(|u| u.byte == SPACE));
+    let number = skip_space(0);
+    let Some(first) = rest.get(number).filter(|u| u.byte == SANKHYA_START) else {
+        return Ok(0);
+    };
+    let glyphs = number + 1 + usize::from(rest.get(number + 1).is_some_and(|u| u.byte == NUM));
+    let close = skip_space(glyphs);
+    match rest.get(close) {
+        Some(danda) if danda.byte == DOUBLE_DANDA => {
+            let (digits, _) = numeral::decode_sankhya(data, first.span.start)?;
+            items.push(Item::Numeral(Numeral {
+                span: first.span.start..rest[glyphs - 1].span.end,
+                digits: digit_text(&digits),
+            }));
+            items.push(Item::Punctuation(Punctuation {
+                span: danda.span.clone(),
+                text: "||".into(),
+            }));
+            Ok(close + 1)
+        }
+        _ => Ok(0),
+    }
+}
+
+fn digit_text(digits: &[u8]) -> String {
+    digits.iter().map(|d| char::from(b'0' + d)).collect()
+}
+
+/// End the sentence so far, if it has anything in it.
+fn close(items: &mut Vec<Item>, sentences: &mut Vec<Sentence>) {
+    if let (Some(first), Some(last)) = (items.first(), items.last()) {
+        sentences.push(Sentence {
+            span: first.span().start..last.span().end,
+            items: std::mem::take(items),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoder::{self, EncodeOptions, PhonFrames, SamasaMarker};
+
+    fn texts(sentence: &Sentence) -> Vec<&str> {
+        sentence
+            .items
+            .iter()
+            .map(|item| match item {
+                Item::Pada(p) => p.text.as_str(),
+                Item::Numeral(n) => n.digits.as_str(),
+                Item::Punctuation(p) => p.text.as_str(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_sentences_at_dandas() {
+        let data = encoder::encode_iast("rāmo vanaṃ gacchati | sītā, ca || 12 || ātmā").unwrap();
+        let doc = decoder::decode_document(&data).unwrap();
+        assert_eq!(doc.sentences.len(), 3);
+        assert_eq!(texts(&doc.sentences[0]), ["rāmo", "vanaṃ", "gacchati", "|"]);
+        assert_eq!(
+            texts(&doc.sentences[1]),
+            ["sītā", ",", "ca", "||", "12", "||"]
+        );
+        assert_eq!(texts(&doc.sentences[2]), ["ātmā"]);
+        assert_eq!(doc.sentences[0].span.start, 0);
+        assert_eq!(doc.sentences[2].span.end, data.len());
+
+        let rama = doc.padas().next().unwrap();
+        assert_eq!(rama.span, 0..6);
+        let phonemes: Vec<&str> = rama.phonemes.iter().map(|p| p.text.as_str()).collect();
+        assert_eq!(phonemes, ["r", "ā", "m", "o"]);
+        assert_eq!(rama.phonemes[1].span, 2..3);
+        assert_eq!(data[rama.phonemes[1].span.clone()], [0x80]);
+    }
+
+    #[test]
+    fn test_framed_sentences() {
+        let opts = EncodeOptions {
+            phon_frames: PhonFrames::Verses,
+            samasa: SamasaMarker::Hyphen,
+            ..Default::default()
+        };
+        let input = "dharma-kṣetre kuru-kṣetre | ḻa ca || 1 || ātmā";
+        let data = encoder::encode_iast_with(input, &opts).unwrap();
+        let doc = decoder::decode_document(&data).unwrap();
+        assert_eq!(doc.sentences.len(), 2);
+        assert_eq!(
+            texts(&doc.sentences[0]),
+            [
+                "dharmakṣetre",
+                "kurukṣetre",
+                "|",
+                "ḻa",
+                "ca",
+                "||",
+                "1",
+                "||"
+            ]
+        );
+        // A compound boundary is no phoneme; an extension phoneme is one of
+        // two bytes
+        let dharma = doc.padas().next().unwrap();
+        assert_eq!(dharma.phonemes.len(), 11);
+        let la = doc.padas().nth(2).unwrap();
+        assert_eq!(la.phonemes[0].text, "ḻ");
+        assert_eq!(la.phonemes[0].span.len(), 2);
+
+        assert_eq!(decoder::decode_document(&[]).unwrap(), Document::default());
+        assert_eq!(
+            decoder::decode_document(&[PADA_START, DICT_REF, 0, PADA_END]),
+            Err(SlbcError::DictRef { offset: 1 })
+        );
+    }
+}
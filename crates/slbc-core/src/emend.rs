@@ -0,0 +1,264 @@
+//! Editorial emendations (§8.3, sub-tag 0xFB).
+//!
+//! An emended span keeps the manuscript reading in the stream, with the
+//! editor's reading in a META block ahead of it and a closing block after:
+//! ```text
+//! META_START 0xFB 0x01 <edited bytes> META_END
+//! <manuscript bytes>
+//! META_START 0xFB 0x02 META_END
+//! ```
+//! Text decoders that skip META render the diplomatic reading;
+//! `Reading::Edited` swaps each span for the editor's bytes. Spans cover
+//! whole padas and do not nest.
+
+use std::borrow::Cow;
+use std::ops::Range;
+
+use crate::decoder::Script;
+use crate::encoder::{self, Token};
+use crate::error::SlbcError;
+use crate::frame;
+use crate::types::*;
+
+/// Which reading of the emended spans to decode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Reading {
+    /// The manuscript as transcribed.
+    #[default]
+    Diplomatic,
+    /// The manuscript with the editor's emendations applied.
+    Edited,
+}
+
+impl Reading {
+    /// Parse a reading name as given on the command line.
+    pub fn from_name(name: &str) -> Option<Reading> {
+        match name {
+            "diplomatic" => Some(Reading::Diplomatic),
+            "edited" => Some(Reading::Edited),
+            _ => None,
+        }
+    }
+}
+
+/// The META block opening an emended span with the editor's reading.
+///
+/// The reading may not hold META blocks or META_END (e.g. a numeral of
+/// fourteen digits, whose count byte is 0x0E).
+pub fn open_block(edited: &[u8]) -> Result<Vec<u8>, SlbcError> {
+    if edited.contains(&META_END) || !frame::meta_spans(edited)?.is_empty() {
+        return Err(SlbcError::invalid(
+            "an edited reading may not contain META bytes",
+        ));
+    }
+    let mut out = vec![META_START, SUBTAG_EMEND, EMEND_OPEN];
+    out.extend_from_slice(edited);
+    out.push(META_END);
+    Ok(out)
+}
+
+/// The META block closing an emended span.
+pub const CLOSE_BLOCK: [u8; 4] = [META_START, SUBTAG_EMEND, EMEND_CLOSE, META_END];
+
+/// One emended span, by payload offsets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Emendation {
+    /// From the opening META_START through the closing META_END.
+    pub span: Range<usize>,
+    /// The editor's reading, inside the opening block.
+    pub edited: Range<usize>,
+    /// The manuscript reading, between the blocks.
+    pub original: Range<usize>,
+}
+
+/// Every emended span in a payload, in order.
+pub fn emendations(data: &[u8]) -> Result<Vec<Emendation>, SlbcError> {
+    let mut out = Vec::new();
+    let mut open: Option<(usize, Range<usize>)> = None;
+
+    for block in frame::meta_spans(data)? {
+        if block.bytes.first() != Some(&SUBTAG_EMEND) {
+            continue;
+        }
+        if !block.closed {
+            return Err(SlbcError::malformed(
+                block.offset,
+                "unclosed emendation block",
+            ));
+        }
+        let end = block.offset + block.bytes.len() + 2;
+        match (block.bytes.get(1), open.take()) {
+            (Some(&EMEND_OPEN), None) => {
+                open = Some((block.offset, block.offset + 3..end - 1));
+            }
+            (Some(&EMEND_CLOSE), Some((start, edited))) if block.bytes.len() == 2 => {
+                out.push(Emendation {
+                    span: start..end,
+                    original: edited.end + 1..block.offset,
+                    edited,
+                });
+            }
+            (Some(&EMEND_OPEN), Some(_)) => {
+                return Err(SlbcError::malformed(block.offset, "nested emendation"));
+            }
+            (Some(&EMEND_CLOSE), None) => {
+                return Err(SlbcError::malformed(
+                    block.offset,
+                    "emendation close without an open",
+                ));
+            }
+            _ => {
+                return Err(SlbcError::malformed(
+                    block.offset,
+                    "malformed emendation block",
+                ))
+            }
+        }
+    }
+    if let Some((start, _)) = open {
+        return Err(SlbcError::truncated(start, "emendation never closed"));
+    }
+    Ok(out)
+}
+
+/// The payload in one reading, with the emendation blocks removed so that
+/// framing around an empty reading can be normalized. Borrowed when there
+/// are no emendations.
+pub fn select(data: &[u8], reading: Reading) -> Result<Cow<'_, [u8]>, SlbcError> {
+    let spans = emendations(data)?;
+    if spans.is_empty() {
+        return Ok(Cow::Borrowed(data));
+    }
+    let mut out = Vec::with_capacity(data.len());
+    let mut pos = 0;
+    for e in spans {
+        out.extend_from_slice(&data[pos..e.span.start]);
+        out.extend_from_slice(match reading {
+            Reading::Diplomatic => &data[e.original],
+            Reading::Edited => &data[e.edited],
+        });
+        pos = e.span.end;
+    }
+    out.extend_from_slice(&data[pos..]);
+    Ok(Cow::Owned(out))
+}
+
+// ═══════════════════════════════════════════════
+//  Emendation markup
+// ═══════════════════════════════════════════════
+
+/// Encode text with inline emendation markup: `{dha[?]ma=dharma}` records
+/// the manuscript reading before `=` and the editor's after. Spans should
+/// sit at word boundaries; either side may be empty (an omission or an
+/// addition). A numeral may not end either reading or touch the opening
+/// brace, since its glyph span would run on into the META block.
+pub fn encode_emended(input: &str, script: Script) -> Result<Vec<u8>, SlbcError> {
+    let mut out = Vec::new();
+    let mut rest = input;
+    // An error at byte `at` of `rest`, by char position in the input
+    let error = |rest: &str, at: usize, what: String| {
+        let byte = input.len() - rest.len() + at;
+        SlbcError::encode(input, input[..byte].chars().count(), what)
+    };
+
+    while let Some(open) = rest.find('{') {
+        out.extend(encode_reading(&rest[..open], script, true)?);
+        let close = rest[open..]
+            .find('}')
+            .map(|p| open + p)
+            .ok_or_else(|| error(rest, open, "unclosed emendation".into()))?;
+        let body = &rest[open + 1..close];
+        let (original, edited) = body
+            .split_once('=')
+            .ok_or_else(|| error(rest, open, format!("emendation '{{{}}}' has no '='", body)))?;
+        if body.contains('{') || edited.contains('=') {
+            let what = format!("malformed emendation '{{{}}}'", body);
+            return Err(error(rest, open, what));
+        }
+        out.extend(open_block(&encode_reading(edited, script, true)?)?);
+        out.extend(encode_reading(original, script, true)?);
+        out.extend_from_slice(&CLOSE_BLOCK);
+        rest = &rest[close + 1..];
+    }
+    if let Some(at) = rest.find('}') {
+        return Err(error(
+            rest,
+            at,
+            "unexpected '}' outside an emendation".into(),
+        ));
+    }
+    out.extend(encode_reading(rest, script, false)?);
+    Ok(out)
+}
+
+/// Encode one stretch of markup. `before_block`: a META block follows, so
+/// a numeral at the very end would run on into it.
+fn encode_reading(text: &str, script: Script, before_block: bool) -> Result<Vec<u8>, SlbcError> {
+    let tokens = encoder::tokenize_script(text, script)?;
+    if let (true, Some(Token::Numeral(digits))) = (before_block, tokens.last()) {
+        return Err(SlbcError::invalid(format_args!(
+            "numeral '{}' directly before an emendation block",
+            digits
+        )));
+    }
+    Ok(encoder::tokens_to_bytes(&tokens))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoder::{self, DecodeOptions};
+
+    fn decode(data: &[u8], reading: Reading) -> String {
+        let opts = DecodeOptions {
+            reading,
+            ..Default::default()
+        };
+        decoder::decode_phon_with(data, Script::Iast, &opts).unwrap()
+    }
+
+    #[test]
+    fn test_both_readings() {
+        let text = "{dha[?]makṣetre=dharmakṣetre} {ku[...]=kurukṣetre} |";
+        let data = encode_emended(text, Script::Iast).unwrap();
+        let spans = emendations(&data).unwrap();
+        assert_eq!(spans.len(), 2);
+        assert_eq!(
+            decode(&data, Reading::Diplomatic),
+            "dha[?]makṣetre ku[...] |"
+        );
+        assert_eq!(decode(&data, Reading::Edited), "dharmakṣetre kurukṣetre |");
+
+        let deva = encode_emended("{धम=धर्म} क्षेत्रे", Script::Devanagari).unwrap();
+        assert_eq!(decode(&deva, Reading::Edited), "dharma kṣetre");
+
+        // Omission and addition
+        let data = encode_emended("na {ca=} {=api} tat", Script::Iast).unwrap();
+        assert_eq!(decode(&data, Reading::Diplomatic), "na ca tat");
+        assert_eq!(decode(&data, Reading::Edited), "na api tat");
+    }
+
+    #[test]
+    fn test_markup_errors() {
+        for bad in [
+            "{ca",
+            "{ca}",
+            "ca}",
+            "{a={b=c}}",
+            "{108=109} ca",
+            "108{ca=na}",
+        ] {
+            assert!(encode_emended(bad, Script::Iast).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_malformed_blocks() {
+        let mut data = open_block(&encoder::encode_iast("ca").unwrap()).unwrap();
+        assert!(emendations(&data).is_err());
+        data.extend_from_slice(&CLOSE_BLOCK);
+        data.extend_from_slice(&CLOSE_BLOCK);
+        assert!(emendations(&data).is_err());
+        assert!(open_block(&[PADA_START, META_START, 0x00, META_END, PADA_END]).is_err());
+    }
+}
@@ -1,9 +1,20 @@
-//! IAST → SLBC encoder.
+//! IAST / SLP1 / Harvard-Kyoto / ITRANS / Velthuis / WX / ISO 15919 /
+//! Devanāgarī → SLBC encoder.
 //!
-//! Parses IAST text into phoneme tokens, then emits SLBC byte stream
-//! wrapped in PADA/SPACE/DANDA boundaries.
+//! Parses romanized text into phoneme tokens (via the `scheme` tables), or
+//! Devanāgarī text via its own akṣara-aware tokenizer, then emits SLBC byte
+//! stream wrapped in PADA/SPACE/DANDA boundaries. `encode_auto` does the
+//! same for documents that switch scripts.
 
+use std::fmt;
+use std::sync::Arc;
+
+use crate::codepage::{self, ExtKind};
+use crate::codespace::{self, Accent};
+use crate::decoder::{self, Script};
+use crate::error::SlbcError;
 use crate::numeral;
+use crate::scheme::{self, SchemeIndex};
 use crate::types::*;
 
 /// A token produced by the IAST tokenizer.
@@ -16,197 +27,164 @@ pub enum Token {
     DoubleDanda,
     Avagraha,
     Numeral(String), // string of digit chars, e.g. "108"
+    /// Extension phoneme code (emitted as META_EXT + code).
+    Extension(u8),
+    /// One illegible akṣara.
+    Illegible,
+    /// A lacuna of unknown length.
+    Lacuna,
+    /// Morpheme seam inside a pada ("+").
+    Morph,
+    /// Compound boundary inside a pada (emitted as META_EXT SAMASA).
+    Samasa,
+    /// Anusvāra written as candrabindu (emitted as ṃ + CANDRABINDU).
+    Candrabindu,
+    /// A standalone oṃ kept as the sacred symbol (emitted as o ṃ + PRANAVA).
+    Pranava,
+    /// The yama after a stop before a nasal (emitted as YAMA).
+    Yama,
+    /// The nāsikya after h before a nasal (emitted as NASIKYA).
+    Nasikya,
+    /// Opens a recitation unit or quotation (emitted as PHON_START).
+    PhonStart,
+    /// Closes one (emitted as PHON_END).
+    PhonEnd,
+    /// The next word began with a capital (emitted as CAPITAL).
+    Capital,
+    /// The next word was all capitals (emitted as UPPERCASE).
+    Uppercase,
+    /// A line break of the source (emitted as LINE_BREAK).
+    LineBreak,
+    /// A punctuation mark between words (emitted as META_EXT + its code,
+    /// see `codepage::PUNCTUATION`).
+    Punctuation(u8),
 }
 
-/// Tokenize an IAST string into a sequence of tokens.
-pub fn tokenize_iast(input: &str) -> Result<Vec<Token>, String> {
-    let mut tokens = Vec::new();
-    let chars: Vec<char> = input.chars().collect();
-    let len = chars.len();
-    let mut i = 0;
-
-    while i < len {
-        let ch = chars[i];
-        let next = if i + 1 < len {
-            Some(chars[i + 1])
-        } else {
-            None
-        };
-
-        // Skip carriage returns
-        if ch == '\r' {
-            i += 1;
-            continue;
-        }
-
-        // Whitespace → SPACE token
-        if ch == ' ' || ch == '\t' || ch == '\n' {
-            // Collapse consecutive whitespace
-            if tokens.last() != Some(&Token::Space) {
-                tokens.push(Token::Space);
-            }
-            i += 1;
-            continue;
-        }
-
-        // Double daṇḍa: ||
-        if ch == '|' && next == Some('|') {
-            tokens.push(Token::DoubleDanda);
-            i += 2;
-            continue;
-        }
-
-        // Single daṇḍa: |
-        if ch == '|' {
-            tokens.push(Token::Danda);
-            i += 1;
-            continue;
-        }
-
-        // Avagraha
-        if ch == '\'' || ch == 'ऽ' {
-            tokens.push(Token::Avagraha);
-            i += 1;
-            continue;
-        }
-
-        // Numerals: consecutive ASCII digits
-        if ch.is_ascii_digit() {
-            let start = i;
-            while i < len && chars[i].is_ascii_digit() {
-                i += 1;
-            }
-            let num_str: String = chars[start..i].iter().collect();
-            tokens.push(Token::Numeral(num_str));
-            continue;
-        }
-
-        // ── Diphthongs (must check before single vowels) ──
-        if ch == 'a' && next == Some('i') {
-            tokens.push(Token::Svara(0x86)); // ai
-            i += 2;
-            continue;
-        }
-        if ch == 'a' && next == Some('u') {
-            tokens.push(Token::Svara(0x8A)); // au
-            i += 2;
-            continue;
-        }
-
-        // ── Aspirated consonants (base + h) ──
-        if let Some('h') = next {
-            let aspirated = match ch {
-                'k' => Some(0x01u8), // kha
-                'g' => Some(0x03),   // gha
-                'c' => Some(0x09),   // cha
-                'j' => Some(0x0B),   // jha
-                'ṭ' => Some(0x11),   // ṭha
-                'ḍ' => Some(0x13),   // ḍha
-                't' => Some(0x19),   // tha
-                'd' => Some(0x1B),   // dha
-                'p' => Some(0x21),   // pha
-                'b' => Some(0x23),   // bha
-                _ => None,
-            };
-            if let Some(byte) = aspirated {
-                tokens.push(Token::Vyanjana(byte));
-                i += 2;
-                continue;
-            }
-        }
-
-        // ── Single-character mappings ──
-        match match_single(ch) {
-            Some(tok) => {
-                tokens.push(tok);
-                i += 1;
-            }
-            None => {
-                return Err(format!(
-                    "unrecognized IAST character '{}' (U+{:04X}) at position {}",
-                    ch, ch as u32, i
-                ));
-            }
-        }
-    }
+/// Written form of ILLEGIBLE, in every script.
+pub const ILLEGIBLE_MARK: &str = "[?]";
+/// Written form of LACUNA, in every script.
+pub const LACUNA_MARK: &str = "[...]";
+/// Written forms of PHON_START and PHON_END, in every script.
+pub const PHON_OPEN: char = '⟨';
+pub const PHON_CLOSE: char = '⟩';
 
-    Ok(tokens)
+/// The gap marker `rest` starts with, and its length in bytes. "[…]" is
+/// accepted for a lacuna.
+pub fn gap_marker(rest: &str) -> Option<(Token, usize)> {
+    [
+        (ILLEGIBLE_MARK, Token::Illegible),
+        (LACUNA_MARK, Token::Lacuna),
+        ("[…]", Token::Lacuna),
+    ]
+    .into_iter()
+    .find(|(mark, _)| rest.starts_with(mark))
+    .map(|(mark, token)| (token, mark.len()))
 }
 
-/// Match a single IAST character to a token.
-fn match_single(ch: char) -> Option<Token> {
-    let tok = match ch {
-        // ── Svaras ──
-        'a' => Token::Svara(0x40),
-        'ā' => Token::Svara(0x80),
-        'i' => Token::Svara(0x44),
-        'ī' => Token::Svara(0x84),
-        'u' => Token::Svara(0x48),
-        'ū' => Token::Svara(0x88),
-        'ṛ' => Token::Svara(0x4C),
-        'ṝ' => Token::Svara(0x8C),
-        'ḷ' => Token::Svara(0x4F),
-        'ḹ' => Token::Svara(0x8F),
-        'e' => Token::Svara(0x85),
-        'o' => Token::Svara(0x89),
-
-        // ── Varga vyañjanas (unaspirated only — aspirated handled above) ──
-        'k' => Token::Vyanjana(0x00),
-        'g' => Token::Vyanjana(0x02),
-        'ṅ' => Token::Vyanjana(0x04),
-        'c' => Token::Vyanjana(0x08),
-        'j' => Token::Vyanjana(0x0A),
-        'ñ' => Token::Vyanjana(0x0C),
-        'ṭ' => Token::Vyanjana(0x10),
-        'ḍ' => Token::Vyanjana(0x12),
-        'ṇ' => Token::Vyanjana(0x14),
-        't' => Token::Vyanjana(0x18),
-        'd' => Token::Vyanjana(0x1A),
-        'n' => Token::Vyanjana(0x1C),
-        'p' => Token::Vyanjana(0x20),
-        'b' => Token::Vyanjana(0x22),
-        'm' => Token::Vyanjana(0x24),
-
-        // ── Sibilants ──
-        'ś' => Token::Vyanjana(0x29),
-        'ṣ' => Token::Vyanjana(0x2A),
-        's' => Token::Vyanjana(0x2B),
-
-        // ── Sonorants ──
-        'y' => Token::Vyanjana(0x31),
-        'v' => Token::Vyanjana(0x32),
-        'r' => Token::Vyanjana(0x33),
-        'l' => Token::Vyanjana(0x34),
-
-        // ── Glottal / special ──
-        'h' => Token::Vyanjana(0x38),
-        'ḥ' => Token::Vyanjana(0x39), // visarga
-        'ṃ' => Token::Vyanjana(0x3A), // anusvāra
-        'ẖ' => Token::Vyanjana(0x3B), // jihvāmūlīya
-        'ḫ' => Token::Vyanjana(0x3C), // upadhmānīya
-
-        _ => return None,
-    };
-    Some(tok)
+/// Tokenize an IAST string into a sequence of tokens.
+///
+/// Vedic accents may follow a vowel as combining marks (see
+/// `IAST_UDATTA`); precomposed accented vowels (á, è, î, …) are split
+/// first, so "aí" is accented ai. Letters spelled with combining marks, as
+/// text copied from PDFs often is, read as their precomposed forms (see
+/// `normalize_roman`).
+pub fn tokenize_iast(input: &str) -> Result<Vec<Token>, SlbcError> {
+    scheme::IAST.tokenize(input)
 }
 
 /// Encode a token stream into an SLBC byte stream (PHON chunk payload).
 ///
 /// Inserts PADA_START/PADA_END around word segments.
-/// Handles SPACE, DANDA, DOUBLE_DANDA, AVAGRAHA, and numeral spans.
+/// Handles SPACE, DANDA, DOUBLE_DANDA, AVAGRAHA, gap markers, morpheme
+/// seams, and numeral spans. A seam at either edge of a pada is dropped.
 pub fn tokens_to_bytes(tokens: &[Token]) -> Vec<u8> {
+    tokens_to_bytes_with(tokens, &[])
+}
+
+/// Encode a token stream, writing `pada_prefix` (e.g. a META block) right
+/// after every PADA_START.
+pub fn tokens_to_bytes_with(tokens: &[Token], pada_prefix: &[u8]) -> Vec<u8> {
     let mut out = Vec::new();
+    push_tokens(tokens, pada_prefix, &mut out);
+    out
+}
+
+/// `tokens_to_bytes_with`, appending to `out`.
+fn push_tokens(tokens: &[Token], pada_prefix: &[u8], out: &mut Vec<u8>) {
     let mut in_pada = false;
+    // A seam or compound boundary is only written between two phonemes of
+    // one pada
+    let mut seam: Option<&[u8]> = None;
 
     for token in tokens {
+        let phoneme = matches!(
+            token,
+            Token::Svara(_)
+                | Token::Vyanjana(_)
+                | Token::Avagraha
+                | Token::Extension(_)
+                | Token::Candrabindu
+                | Token::Pranava
+        );
+        if let Some(seam) = seam.take().filter(|_| in_pada && phoneme) {
+            out.extend_from_slice(seam);
+        }
         match token {
             Token::Svara(b) | Token::Vyanjana(b) => {
                 if !in_pada {
                     out.push(PADA_START);
+                    out.extend_from_slice(pada_prefix);
                     in_pada = true;
                 }
                 out.push(*b);
             }
+            Token::Candrabindu => {
+                if !in_pada {
+                    out.push(PADA_START);
+                    out.extend_from_slice(pada_prefix);
+                    in_pada = true;
+                }
+                out.extend_from_slice(&[0x3A, CANDRABINDU]);
+            }
+            Token::Pranava => {
+                if !in_pada {
+                    out.push(PADA_START);
+                    out.extend_from_slice(pada_prefix);
+                    in_pada = true;
+                }
+                out.extend_from_slice(&[0x89, 0x3A, PRANAVA]);
+            }
+            // Only ever follows a consonant of the open pada
+            Token::Yama if in_pada => out.push(YAMA),
+            Token::Nasikya if in_pada => out.push(NASIKYA),
+            Token::Yama | Token::Nasikya => {}
+            // Before the word's first phoneme
+            Token::Capital | Token::Uppercase => {
+                if !in_pada {
+                    out.push(PADA_START);
+                    out.extend_from_slice(pada_prefix);
+                    in_pada = true;
+                }
+                out.push(match token {
+                    Token::Capital => CAPITAL,
+                    _ => UPPERCASE,
+                });
+            }
+            // A compound boundary outweighs a seam beside it
+            Token::Morph if seam.is_none() => seam = Some(&[MORPH]),
+            Token::Morph => {}
+            Token::Samasa => seam = Some(&[META_EXT, codepage::SAMASA]),
+            // Frames hold whole padas
+            Token::PhonStart | Token::PhonEnd => {
+                if in_pada {
+                    out.push(PADA_END);
+                    in_pada = false;
+                }
+                out.push(match token {
+                    Token::PhonStart => PHON_START,
+                    _ => PHON_END,
+                });
+            }
             Token::Space => {
                 if in_pada {
                     out.push(PADA_END);
@@ -214,6 +192,20 @@ pub fn tokens_to_bytes(tokens: &[Token]) -> Vec<u8> {
                 }
                 out.push(SPACE);
             }
+            Token::LineBreak => {
+                if in_pada {
+                    out.push(PADA_END);
+                    in_pada = false;
+                }
+                out.push(LINE_BREAK);
+            }
+            Token::Punctuation(code) => {
+                if in_pada {
+                    out.push(PADA_END);
+                    in_pada = false;
+                }
+                out.extend_from_slice(&[META_EXT, *code]);
+            }
             Token::Danda => {
                 if in_pada {
                     out.push(PADA_END);
@@ -232,16 +224,30 @@ pub fn tokens_to_bytes(tokens: &[Token]) -> Vec<u8> {
                 // Avagraha is lipi-layer, but appears inline
                 if !in_pada {
                     out.push(PADA_START);
+                    out.extend_from_slice(pada_prefix);
                     in_pada = true;
                 }
                 out.push(AVAGRAHA);
             }
+            Token::Extension(code) => {
+                if !in_pada {
+                    out.push(PADA_START);
+                    out.extend_from_slice(pada_prefix);
+                    in_pada = true;
+                }
+                out.push(META_EXT);
+                out.push(*code);
+            }
+            // A gap may fall inside a word or between words, so it leaves
+            // the pada state alone
+            Token::Illegible => out.push(ILLEGIBLE),
+            Token::Lacuna => out.push(LACUNA),
             Token::Numeral(digits) => {
                 if in_pada {
                     out.push(PADA_END);
                     in_pada = false;
                 }
-                numeral::encode_numeral(digits, &mut out);
+                numeral::encode_numeral(digits, out);
             }
         }
     }
@@ -250,64 +256,1652 @@ pub fn tokens_to_bytes(tokens: &[Token]) -> Vec<u8> {
     if in_pada {
         out.push(PADA_END);
     }
+}
 
-    out
+// ── Vedic svara marks ──
+//
+// Devanāgarī sources mark accent with combining signs after the vowel and
+// pluta with a following digit three; `tokenize_devanagari` routes these
+// through `apply_svara_mark`.
+
+pub const DEVA_UDATTA: char = '\u{0951}';
+pub const DEVA_ANUDATTA: char = '\u{0952}';
+/// The double svarita ᳚, which Devanāgarī output writes for svarita.
+pub const DEVA_SVARITA: char = '\u{1CDA}';
+pub const DEVA_PLUTA: char = '३';
+
+// Romanized sources mark accent with a combining acute (udātta), grave
+// (anudātta), or circumflex (independent svarita) after the vowel, and
+// pluta with a digit three.
+
+pub const IAST_UDATTA: char = '\u{0301}';
+pub const IAST_ANUDATTA: char = '\u{0300}';
+pub const IAST_SVARITA: char = '\u{0302}';
+/// Pluta in romanized sources: "3" right after the vowel (ā3).
+pub const IAST_PLUTA: char = '3';
+
+/// Letters the romanizations write with a diacritic: base, combining mark,
+/// precomposed letter. ṝ and ḹ compose from ṛ and ḷ.
+const COMPOSED: &[(char, char, char)] = &[
+    // Macron
+    ('a', '\u{0304}', 'ā'),
+    ('i', '\u{0304}', 'ī'),
+    ('u', '\u{0304}', 'ū'),
+    ('e', '\u{0304}', 'ē'),
+    ('o', '\u{0304}', 'ō'),
+    ('ṛ', '\u{0304}', 'ṝ'),
+    ('ḷ', '\u{0304}', 'ḹ'),
+    ('A', '\u{0304}', 'Ā'),
+    ('I', '\u{0304}', 'Ī'),
+    ('U', '\u{0304}', 'Ū'),
+    ('E', '\u{0304}', 'Ē'),
+    ('O', '\u{0304}', 'Ō'),
+    ('Ṛ', '\u{0304}', 'Ṝ'),
+    ('Ḷ', '\u{0304}', 'Ḹ'),
+    // Dot below
+    ('r', '\u{0323}', 'ṛ'),
+    ('l', '\u{0323}', 'ḷ'),
+    ('t', '\u{0323}', 'ṭ'),
+    ('d', '\u{0323}', 'ḍ'),
+    ('n', '\u{0323}', 'ṇ'),
+    ('s', '\u{0323}', 'ṣ'),
+    ('h', '\u{0323}', 'ḥ'),
+    ('m', '\u{0323}', 'ṃ'),
+    ('R', '\u{0323}', 'Ṛ'),
+    ('L', '\u{0323}', 'Ḷ'),
+    ('T', '\u{0323}', 'Ṭ'),
+    ('D', '\u{0323}', 'Ḍ'),
+    ('N', '\u{0323}', 'Ṇ'),
+    ('S', '\u{0323}', 'Ṣ'),
+    ('H', '\u{0323}', 'Ḥ'),
+    ('M', '\u{0323}', 'Ṃ'),
+    // Acute on a consonant (on a vowel it is the udātta)
+    ('s', '\u{0301}', 'ś'),
+    ('S', '\u{0301}', 'Ś'),
+    // Dot above
+    ('n', '\u{0307}', 'ṅ'),
+    ('m', '\u{0307}', 'ṁ'),
+    ('g', '\u{0307}', 'ġ'),
+    ('N', '\u{0307}', 'Ṅ'),
+    ('M', '\u{0307}', 'Ṁ'),
+    ('G', '\u{0307}', 'Ġ'),
+    // Tilde
+    ('n', '\u{0303}', 'ñ'),
+    ('N', '\u{0303}', 'Ñ'),
+    // Macron below
+    ('l', '\u{0331}', 'ḻ'),
+    ('r', '\u{0331}', 'ṟ'),
+    ('n', '\u{0331}', 'ṉ'),
+    ('k', '\u{0331}', 'ḵ'),
+    ('L', '\u{0331}', 'Ḻ'),
+    ('R', '\u{0331}', 'Ṟ'),
+    ('N', '\u{0331}', 'Ṉ'),
+    ('K', '\u{0331}', 'Ḵ'),
+    // Breve
+    ('e', '\u{0306}', 'ĕ'),
+    ('o', '\u{0306}', 'ŏ'),
+    ('E', '\u{0306}', 'Ĕ'),
+    ('O', '\u{0306}', 'Ŏ'),
+];
+
+/// Canonical combining class of the marks in `COMPOSED` (and the accents
+/// and candrabindu, which keep their place after them).
+fn combining_class(c: char) -> Option<u8> {
+    match c {
+        '\u{0323}' | '\u{0325}' | '\u{0331}' => Some(220),
+        '\u{0300}'..='\u{0310}' => Some(230),
+        _ => None,
+    }
+}
+
+/// Bring romanized text to the form the scheme tables use, whatever its
+/// Unicode normalization: letters written as base + combining mark (NFD,
+/// or marks in either order) are composed, and accented vowels written
+/// precomposed (á, è, î, …) are split into vowel + accent mark.
+pub fn normalize_roman(input: &str) -> std::borrow::Cow<'_, str> {
+    if input.is_ascii() {
+        return input.into();
+    }
+    let mut out = String::with_capacity(input.len());
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        // The marks after this character, in canonical order
+        let mut marks = Vec::new();
+        while let Some(&m) = chars.peek() {
+            let Some(class) = combining_class(m) else {
+                break;
+            };
+            marks.push((class, m));
+            chars.next();
+        }
+        marks.sort_by_key(|&(class, _)| class);
+
+        let mut base = c;
+        let mut rest = Vec::new();
+        for (_, m) in marks {
+            match COMPOSED
+                .iter()
+                .find(|&&(b, mark, _)| b == base && mark == m)
+            {
+                Some(&(_, _, composed)) if rest.is_empty() => base = composed,
+                _ => rest.push(m),
+            }
+        }
+        push_decomposing_accent(base, &mut out);
+        out.extend(rest);
+    }
+    out.into()
+}
+
+/// Push `c`, split into vowel + combining accent if it is a precomposed
+/// accented vowel.
+fn push_decomposing_accent(c: char, out: &mut String) {
+    let base = match c {
+        'á' | 'à' | 'â' => 'a',
+        'í' | 'ì' | 'î' => 'i',
+        'ú' | 'ù' | 'û' => 'u',
+        'é' | 'è' | 'ê' => 'e',
+        'ó' | 'ò' | 'ô' => 'o',
+        _ => {
+            out.push(c);
+            return;
+        }
+    };
+    out.push(base);
+    out.push(match c {
+        'á' | 'í' | 'ú' | 'é' | 'ó' => IAST_UDATTA,
+        'à' | 'ì' | 'ù' | 'è' | 'ò' => IAST_ANUDATTA,
+        _ => IAST_SVARITA,
+    });
+}
+
+/// Apply a Vedic mark that follows a vowel to its svara byte.
+///
+/// U+0951 sets A=udātta, U+0952 sets A=anudātta, U+1CDA sets A=svarita,
+/// and "३" sets Q=pluta; the IAST combining acute, grave, and circumflex
+/// set udātta, anudātta, and svarita, and "3" sets pluta. The other fields
+/// are kept. Returns `None` if `mark` is not a svara mark or `svara` is not
+/// a svara byte.
+pub fn apply_svara_mark(svara: u8, mark: char) -> Option<u8> {
+    if !is_svara(svara) {
+        return None;
+    }
+    match mark {
+        DEVA_UDATTA | IAST_UDATTA => Some((svara & !0x30) | (0b01 << 4)),
+        DEVA_ANUDATTA | IAST_ANUDATTA => Some((svara & !0x30) | (0b10 << 4)),
+        DEVA_SVARITA | IAST_SVARITA => Some((svara & !0x30) | (0b11 << 4)),
+        DEVA_PLUTA | IAST_PLUTA => Some((svara & !0xC0) | (0b11 << 6)),
+        _ => None,
+    }
 }
 
 /// Top-level encode: IAST string → SLBC byte stream (PHON payload).
-pub fn encode_iast(input: &str) -> Result<Vec<u8>, String> {
+pub fn encode_iast(input: &str) -> Result<Vec<u8>, SlbcError> {
     let tokens = tokenize_iast(input)?;
     Ok(tokens_to_bytes(&tokens))
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Encode IAST leniently: a character that cannot be read is skipped or
+/// substituted (see `recover`) and reported, instead of failing the whole
+/// text.
+pub fn encode_iast_lossy(input: &str) -> (Vec<u8>, Vec<EncodeWarning>) {
+    let (tokens, warnings) = scheme::IAST.tokenize_lossy(input);
+    (tokens_to_bytes(&tokens), warnings)
+}
 
-    #[test]
-    fn test_tokenize_simple() {
-        let tokens = tokenize_iast("ka").unwrap();
-        assert_eq!(tokens, vec![Token::Vyanjana(0x00), Token::Svara(0x40)]);
+/// A character lenient encoding could not read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncodeWarning {
+    /// Char offset in the input, after `normalize_roman`.
+    pub position: usize,
+    pub found: char,
+    /// What it was read as instead; `None` when it was skipped.
+    pub replacement: Option<char>,
+}
+
+impl fmt::Display for EncodeWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (found, code) = (self.found, self.found as u32);
+        match self.replacement {
+            Some(r) => write!(
+                f,
+                "read '{}' (U+{:04X}) at position {} as '{}'",
+                found, code, self.position, r
+            ),
+            None => write!(
+                f,
+                "skipped '{}' (U+{:04X}) at position {}",
+                found, code, self.position
+            ),
+        }
     }
+}
 
-    #[test]
-    fn test_tokenize_aspirate() {
-        let tokens = tokenize_iast("kha").unwrap();
-        assert_eq!(tokens, vec![Token::Vyanjana(0x01), Token::Svara(0x40)]);
+/// Recover from a character no grapheme matches: a digit of an Indic
+/// script joins the numeral as its ASCII digit, sentence punctuation
+/// (. ! ? ;) becomes a daṇḍa unless one precedes it, and anything else —
+/// other punctuation, zero-width joiners, foreign letters — is dropped.
+/// Returns what the character was read as.
+pub(crate) fn recover(ch: char, tokens: &mut Vec<Token>) -> Option<char> {
+    // The Indic digit blocks all start at xx66 or xxE6
+    let code = ch as u32;
+    if (0x0966..=0x0DEF).contains(&code) && (0x66..=0x6F).contains(&(code & 0x7F)) {
+        let digit = char::from_digit((code & 0x7F) - 0x66, 10)?;
+        match tokens.last_mut() {
+            Some(Token::Numeral(digits)) => digits.push(digit),
+            _ => tokens.push(Token::Numeral(digit.to_string())),
+        }
+        return Some(digit);
+    }
+    if matches!(ch, '.' | '!' | '?' | ';') {
+        end_sentence(tokens);
+        return Some('|');
     }
+    None
+}
 
-    #[test]
-    fn test_tokenize_diphthong() {
-        let tokens = tokenize_iast("ai").unwrap();
-        assert_eq!(tokens, vec![Token::Svara(0x86)]);
+/// A daṇḍa ending the sentence so far, unless one already does.
+fn end_sentence(tokens: &mut Vec<Token>) {
+    if !matches!(tokens.last(), Some(Token::Danda | Token::DoubleDanda)) {
+        tokens.push(Token::Danda);
     }
+}
 
-    #[test]
-    fn test_tokenize_au() {
-        let tokens = tokenize_iast("au").unwrap();
-        assert_eq!(tokens, vec![Token::Svara(0x8A)]);
+/// Read a whitespace character (space, tab, line break) as `opts` say: a
+/// line break kept as itself, taking the place of spaces before it, or
+/// else a SPACE, collapsed with the one before and dropped at the start of
+/// a line. Under `Newlines::Danda` a line break is a SPACE, with a daṇḍa
+/// first unless the line ended in one.
+pub(crate) fn push_whitespace(ch: char, tokens: &mut Vec<Token>, opts: &EncodeOptions) {
+    if ch == '\n' && opts.newlines == Newlines::Keep {
+        if tokens.last() == Some(&Token::Space) {
+            tokens.pop();
+        }
+        tokens.push(Token::LineBreak);
+        return;
+    }
+    let text = tokens.iter().rev().find(|t| **t != Token::Space);
+    if ch == '\n'
+        && opts.newlines == Newlines::Danda
+        && !matches!(text, None | Some(Token::Danda | Token::DoubleDanda))
+    {
+        if tokens.last() != Some(&Token::Space) {
+            tokens.push(Token::Space);
+        }
+        tokens.push(Token::Danda);
+    }
+    if !matches!(tokens.last(), Some(Token::Space | Token::LineBreak)) {
+        tokens.push(Token::Space);
     }
+}
 
-    #[test]
-    fn test_encode_dharma() {
-        let bytes = encode_iast("dharma").unwrap();
-        // PADA_START dh a r m a PADA_END
-        assert_eq!(bytes, vec![0x26, 0x1B, 0x40, 0x33, 0x24, 0x40, 0x2E]);
+/// Read punctuation no grapheme matches as `policy` says: false if it is
+/// rejected, or is not punctuation at all.
+pub(crate) fn punctuate(ch: char, tokens: &mut Vec<Token>, policy: Punctuation) -> bool {
+    if policy == Punctuation::Keep {
+        let code = codepage::punctuation_code(ch);
+        tokens.extend(code.map(Token::Punctuation));
+        return code.is_some();
     }
+    let punctuation =
+        ch.is_ascii_punctuation() || matches!(ch, '‘' | '’' | '“' | '”' | '–' | '—' | '…');
+    if policy == Punctuation::Reject || !punctuation {
+        return false;
+    }
+    if policy == Punctuation::Danda && matches!(ch, '.' | '!' | '?' | ';' | '…') {
+        end_sentence(tokens);
+    }
+    true
+}
 
-    #[test]
-    fn test_encode_two_words() {
-        let bytes = encode_iast("na ca").unwrap();
-        // PADA_START n a PADA_END SPACE PADA_START c a PADA_END
-        assert_eq!(
-            bytes,
-            vec![0x26, 0x1C, 0x40, 0x2E, 0x1F, 0x26, 0x08, 0x40, 0x2E]
-        );
+/// Top-level encode: SLP1 string → SLBC byte stream (PHON payload).
+pub fn encode_slp1(input: &str) -> Result<Vec<u8>, SlbcError> {
+    let tokens = scheme::SLP1.tokenize(input)?;
+    Ok(tokens_to_bytes(&tokens))
+}
+
+/// Top-level encode: Harvard-Kyoto string → SLBC byte stream (PHON payload).
+pub fn encode_hk(input: &str) -> Result<Vec<u8>, SlbcError> {
+    let tokens = scheme::HK.tokenize(input)?;
+    Ok(tokens_to_bytes(&tokens))
+}
+
+/// Top-level encode: ITRANS string → SLBC byte stream (PHON payload).
+pub fn encode_itrans(input: &str) -> Result<Vec<u8>, SlbcError> {
+    let tokens = scheme::ITRANS.tokenize(input)?;
+    Ok(tokens_to_bytes(&tokens))
+}
+
+/// Top-level encode: WX string → SLBC byte stream (PHON payload).
+pub fn encode_wx(input: &str) -> Result<Vec<u8>, SlbcError> {
+    let tokens = scheme::WX.tokenize(input)?;
+    Ok(tokens_to_bytes(&tokens))
+}
+
+/// Encode IAST with options (see `EncodeOptions`).
+pub fn encode_iast_with(input: &str, opts: &EncodeOptions) -> Result<Vec<u8>, SlbcError> {
+    encode_script_with(input, Script::Iast, opts)
+}
+
+/// Tokenize text in any input script (see `Script::INPUT`).
+pub fn tokenize_script(input: &str, script: Script) -> Result<Vec<Token>, SlbcError> {
+    match (script.scheme(), script) {
+        (Some(roman), _) => roman.tokenize(input),
+        (None, Script::Devanagari) => tokenize_devanagari(input),
+        (None, _) => Err(SlbcError::OutputOnlyScript {
+            script: script.name(),
+        }),
     }
+}
 
-    #[test]
-    fn test_ka_is_null_byte() {
-        let tokens = tokenize_iast("ka").unwrap();
-        assert_eq!(tokens[0], Token::Vyanjana(0x00));
+/// Encode text in any input script (see `Script::INPUT`).
+pub fn encode_script(input: &str, script: Script) -> Result<Vec<u8>, SlbcError> {
+    Ok(tokens_to_bytes(&tokenize_script(input, script)?))
+}
+
+/// Choices about how the source is read and what of its spelling is kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct EncodeOptions {
+    /// Read the text as Vedic (see `tokenize_vedic`).
+    pub vedic: bool,
+    /// Keep a standalone oṃ (`oṃ`, `om̐`, ॐ) as the sacred symbol, so
+    /// abugida output writes ॐ; otherwise it is the phonemes o ṃ.
+    pub pranava: bool,
+    /// Record the yamas and nāsikyas of Prātiśākhya recitation (see
+    /// `mark_yamas`).
+    pub yama: bool,
+    /// How Devanāgarī tone marks are read. Romanized accents are always
+    /// explicit.
+    pub tone_marks: ToneMarks,
+    /// Frame the clause before each standalone iti as a quotation (see
+    /// `mark_iti`).
+    pub iti: bool,
+    /// Whether capitals in IAST and ISO 15919 input are read as lower
+    /// case instead of rejected.
+    pub fold_case: CaseFolding,
+    /// Whether whitespace and line breaks at the start and end of the text
+    /// are kept.
+    pub whitespace: Whitespace,
+    /// What a line break is read as.
+    pub newlines: Newlines,
+    /// What becomes of punctuation the script does not spell.
+    pub punctuation: Punctuation,
+    /// Skip or substitute any other character that cannot be read (see
+    /// `recover`) instead of failing. `encode_iast_lossy` reports what was
+    /// skipped.
+    pub lenient: bool,
+    /// Which stretches of the text the encoder frames as PHON frames.
+    pub phon_frames: PhonFrames,
+    /// Which mark in the input divides the members of a compound.
+    pub samasa: SamasaMarker,
+    /// Read `.` and `..` in IAST and ISO 15919 as daṇḍa and double daṇḍa,
+    /// as some editions type them; otherwise a full stop is punctuation.
+    pub dot_dandas: bool,
+}
+
+/// The mark a pre-segmented text divides compound members with, as the
+/// Digital Corpus of Sanskrit does (`dharma-kṣetre`). It is read as a
+/// compound boundary inside the pada (§6.4) instead of as a hyphen between
+/// two padas or a morpheme seam.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SamasaMarker {
+    /// Compound boundaries are not marked.
+    #[default]
+    Off,
+    /// `-`
+    Hyphen,
+    /// `+`
+    Plus,
+}
+
+impl SamasaMarker {
+    /// The mark itself.
+    pub fn mark(&self) -> Option<char> {
+        match self {
+            SamasaMarker::Off => None,
+            SamasaMarker::Hyphen => Some('-'),
+            SamasaMarker::Plus => Some('+'),
+        }
+    }
+
+    /// Short name, as accepted by the CLI `--samasa` flag.
+    pub fn name(&self) -> &'static str {
+        match self {
+            SamasaMarker::Off => "off",
+            SamasaMarker::Hyphen => "hyphen",
+            SamasaMarker::Plus => "plus",
+        }
+    }
+
+    /// Parse a mark from its short name.
+    pub fn from_name(name: &str) -> Option<SamasaMarker> {
+        match name {
+            "off" => Some(SamasaMarker::Off),
+            "hyphen" => Some(SamasaMarker::Hyphen),
+            "plus" => Some(SamasaMarker::Plus),
+            _ => None,
+        }
+    }
+}
+
+/// What becomes of whitespace. A run of it on one line is always one
+/// SPACE.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Whitespace {
+    /// Keep it at the start and end of the text.
+    #[default]
+    Collapse,
+    /// Drop it at the start and end of the text.
+    Trim,
+}
+
+/// What a line break in the input is read as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Newlines {
+    /// A LINE_BREAK (§6.10), so decoding restores the lines of a verse.
+    /// Spaces at either end of a line are not kept.
+    #[default]
+    Keep,
+    /// Whitespace like any other.
+    Space,
+    /// The end of a half verse: a line that does not end in a daṇḍa gets
+    /// one, as in editions that set each pāda on its own line.
+    Danda,
+}
+
+impl Newlines {
+    /// Short name, as accepted by the CLI `--newlines` flag.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Newlines::Keep => "keep",
+            Newlines::Space => "space",
+            Newlines::Danda => "danda",
+        }
+    }
+
+    /// Parse a mode from its short name.
+    pub fn from_name(name: &str) -> Option<Newlines> {
+        match name {
+            "keep" => Some(Newlines::Keep),
+            "space" => Some(Newlines::Space),
+            "danda" => Some(Newlines::Danda),
+            _ => None,
+        }
+    }
+}
+
+/// What becomes of Latin punctuation (`, . ; : ! ? ( ) " “ ” – —` …) that
+/// no grapheme of the script matches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Punctuation {
+    /// Keep the marks of critical editions — comma, semicolon, hyphen and
+    /// dashes, parentheses, quotation marks (see `codepage::PUNCTUATION`)
+    /// — so decoding restores them; reject the rest.
+    #[default]
+    Keep,
+    /// It cannot be read and fails the text, unless `lenient`.
+    Reject,
+    /// Drop it.
+    Drop,
+    /// Read sentence punctuation (. ! ? ; …) as a daṇḍa, unless one
+    /// precedes it, and drop the rest.
+    Danda,
+}
+
+impl Punctuation {
+    /// Short name, as accepted by the CLI `--punctuation` flag.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Punctuation::Keep => "keep",
+            Punctuation::Reject => "reject",
+            Punctuation::Drop => "drop",
+            Punctuation::Danda => "danda",
+        }
+    }
+
+    /// Parse a policy from its short name.
+    pub fn from_name(name: &str) -> Option<Punctuation> {
+        match name {
+            "keep" => Some(Punctuation::Keep),
+            "reject" => Some(Punctuation::Reject),
+            "drop" => Some(Punctuation::Drop),
+            "danda" => Some(Punctuation::Danda),
+            _ => None,
+        }
+    }
+}
+
+/// The stretches of text the encoder frames as PHON frames (§6.1), on top
+/// of any the input brackets with `⟨ … ⟩`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PhonFrames {
+    /// None.
+    #[default]
+    None,
+    /// Each verse: the text up to and including a double daṇḍa, with the
+    /// verse number after it (`|| 1 ||`).
+    Verses,
+    /// Each sentence: the text up to and including a daṇḍa or double
+    /// daṇḍa, with a verse number after a double daṇḍa.
+    Sentences,
+    /// The whole text as one frame.
+    Text,
+}
+
+impl PhonFrames {
+    /// Short name, as accepted by the CLI `--phon-frames` flag.
+    pub fn name(&self) -> &'static str {
+        match self {
+            PhonFrames::None => "none",
+            PhonFrames::Verses => "verses",
+            PhonFrames::Sentences => "sentences",
+            PhonFrames::Text => "text",
+        }
+    }
+
+    /// Parse a mode from its short name.
+    pub fn from_name(name: &str) -> Option<PhonFrames> {
+        match name {
+            "none" => Some(PhonFrames::None),
+            "verses" => Some(PhonFrames::Verses),
+            "sentences" => Some(PhonFrames::Sentences),
+            "text" => Some(PhonFrames::Text),
+            _ => None,
+        }
+    }
+}
+
+/// What becomes of capitals in IAST and ISO 15919 input, whose phonemes
+/// are all lower case.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CaseFolding {
+    /// Capitals are not phonemes and are rejected.
+    #[default]
+    Off,
+    /// Read capitals as lower case and forget them.
+    Fold,
+    /// Read capitals as lower case and record a capitalized word (`Dharma`)
+    /// or a word in capitals (`ŚRĪ`) as a CAPITAL or UPPERCASE annotation,
+    /// so romanized output restores it.
+    Preserve,
+}
+
+impl CaseFolding {
+    /// Short name, as accepted by the CLI `--fold-case` flag.
+    pub fn name(&self) -> &'static str {
+        match self {
+            CaseFolding::Off => "off",
+            CaseFolding::Fold => "fold",
+            CaseFolding::Preserve => "preserve",
+        }
+    }
+
+    /// Parse a mode from its short name.
+    pub fn from_name(name: &str) -> Option<CaseFolding> {
+        match name {
+            "off" => Some(CaseFolding::Off),
+            "fold" => Some(CaseFolding::Fold),
+            "preserve" => Some(CaseFolding::Preserve),
+            _ => None,
+        }
+    }
+}
+
+/// The convention a Devanāgarī source marks Vedic accent by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ToneMarks {
+    /// U+0951 is udātta and U+0952 anudātta; unmarked vowels carry no
+    /// accent.
+    #[default]
+    Direct,
+    /// The Ṛgveda saṃhitā convention: U+0952 is anudātta, U+0951 svarita,
+    /// and the udātta is unmarked. An unmarked vowel after a svarita is
+    /// pracaya and so anudātta, up to the next marked vowel.
+    Samhita,
+}
+
+impl ToneMarks {
+    /// Short name, as accepted by the CLI `--tone-marks` flag.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ToneMarks::Direct => "direct",
+            ToneMarks::Samhita => "samhita",
+        }
+    }
+
+    /// Parse a convention from its short name.
+    pub fn from_name(name: &str) -> Option<ToneMarks> {
+        match name {
+            "direct" => Some(ToneMarks::Direct),
+            "samhita" => Some(ToneMarks::Samhita),
+            _ => None,
+        }
+    }
+}
+
+/// Tokenize text in any input script with options.
+pub fn tokenize_script_with(
+    input: &str,
+    script: Script,
+    opts: &EncodeOptions,
+) -> Result<Vec<Token>, SlbcError> {
+    let mut tokens = match script.scheme() {
+        Some(roman) => roman.tokenize_with_options(input, opts)?,
+        None if script == Script::Devanagari => tokenize_devanagari_with(input, opts)?,
+        None => tokenize_script(input, script)?,
+    };
+    if opts.pranava {
+        mark_pranava(&mut tokens);
+    }
+    if opts.yama {
+        mark_yamas(&mut tokens);
+    }
+    if opts.tone_marks == ToneMarks::Samhita && script == Script::Devanagari {
+        infer_samhita_accents(&mut tokens);
+    }
+    if opts.iti {
+        mark_iti(&mut tokens);
+    }
+    if opts.whitespace == Whitespace::Trim {
+        let blank = |t: &Token| matches!(t, Token::Space | Token::LineBreak);
+        let end = tokens.iter().rposition(|t| !blank(t)).map_or(0, |p| p + 1);
+        tokens.truncate(end);
+        let start = tokens.iter().position(|t| !blank(t)).unwrap_or(0);
+        tokens.drain(..start);
+    }
+    if opts.phon_frames != PhonFrames::None {
+        mark_phon_frames(&mut tokens, opts.phon_frames);
+    }
+    Ok(tokens)
+}
+
+/// Frame each verse, or the whole text, as a PHON frame (see
+/// `PhonFrames`). SPACE and line breaks at either end of a stretch stay
+/// outside its frame.
+fn mark_phon_frames(tokens: &mut Vec<Token>, frames: PhonFrames) {
+    let mut spans = Vec::new();
+    let mut start = 0;
+    let mut i = 0;
+    while i < tokens.len() {
+        let ends = match frames {
+            PhonFrames::Verses => tokens[i] == Token::DoubleDanda,
+            PhonFrames::Sentences => matches!(tokens[i], Token::Danda | Token::DoubleDanda),
+            _ => false,
+        };
+        if ends {
+            if tokens[i] == Token::DoubleDanda {
+                i += verse_number_len(&tokens[i + 1..]);
+            }
+            spans.push(start..i + 1);
+            start = i + 1;
+        }
+        i += 1;
+    }
+    spans.push(start..tokens.len());
+
+    let text = |t: &Token| !matches!(t, Token::Space | Token::LineBreak);
+    for span in spans.into_iter().rev() {
+        let stretch = &tokens[span.clone()];
+        let Some(first) = stretch.iter().position(text) else {
+            continue;
+        };
+        let last = stretch.iter().rposition(text).unwrap_or(first);
+        tokens.insert(span.start + last + 1, Token::PhonEnd);
+        tokens.insert(span.start + first, Token::PhonStart);
+    }
+}
+
+/// How many tokens after a double daṇḍa number the verse it closes
+/// (`1 ||`), or 0.
+fn verse_number_len(rest: &[Token]) -> usize {
+    let skip_space = |i: usize| i + usize::from(rest.get(i) == Some(&Token::Space));
+    let number = skip_space(0);
+    if !matches!(rest.get(number), Some(Token::Numeral(_))) {
+        return 0;
+    }
+    let close = skip_space(number + 1);
+    match rest.get(close) {
+        Some(Token::DoubleDanda) => close + 1,
+        _ => 0,
+    }
+}
+
+/// Whether `tokens` at `i` is the word iti (ity before a vowel), with or
+/// without accents.
+fn is_iti_at(tokens: &[Token], i: usize) -> bool {
+    let plain = |t: Option<&Token>| match t {
+        Some(Token::Svara(b)) => Some(b & !0x30),
+        Some(Token::Vyanjana(b)) => Some(*b),
+        _ => None,
+    };
+    let word: Vec<Option<u8>> = (i..i + 4).map(|j| plain(tokens.get(j))).collect();
+    matches!(word[..], [Some(0x44), Some(0x18), Some(0x44 | 0x31), None])
+}
+
+/// Frame the clause before each standalone iti that is not framed already
+/// as a PHON frame: the words since the previous daṇḍa, frame, or iti, or
+/// since the start of the text. Where a quotation begins is a guess; mark
+/// it with ⟨ … ⟩ in the input to be exact.
+fn mark_iti(tokens: &mut Vec<Token>) {
+    let mut start = 0;
+    let mut i = 0;
+    while i < tokens.len() {
+        match tokens[i] {
+            Token::Danda | Token::DoubleDanda | Token::PhonStart | Token::PhonEnd => {
+                start = i + 1;
+            }
+            Token::Space | Token::LineBreak if is_iti_at(tokens, i + 1) => {
+                let first = tokens[start..i]
+                    .iter()
+                    .position(|t| !matches!(t, Token::Space | Token::LineBreak))
+                    .map(|p| start + p);
+                if let Some(first) = first {
+                    tokens.insert(i, Token::PhonEnd);
+                    tokens.insert(first, Token::PhonStart);
+                    i += 2;
+                }
+                // Past the iti
+                i += 4;
+                start = i;
+                continue;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+}
+
+/// Read accents marked in the saṃhitā convention (see `ToneMarks`), half
+/// verse by half verse. A half verse with no marks at all is left
+/// unaccented.
+fn infer_samhita_accents(tokens: &mut [Token]) {
+    let set = |b: u8, accent: Accent| (b & !0x30) | (accent.bits() << 4);
+    for half in tokens.split_mut(|t| matches!(t, Token::Danda | Token::DoubleDanda)) {
+        let marked = half
+            .iter()
+            .any(|t| matches!(t, Token::Svara(b) if Accent::of(*b) != Accent::Neutral));
+        if !marked {
+            continue;
+        }
+        let mut pracaya = false;
+        for token in half.iter_mut() {
+            let Token::Svara(b) = token else { continue };
+            *b = match Accent::of(*b) {
+                // U+0951 was read as udātta
+                Accent::Udatta | Accent::Svarita => {
+                    pracaya = true;
+                    set(*b, Accent::Svarita)
+                }
+                Accent::Anudatta => {
+                    pracaya = false;
+                    *b
+                }
+                Accent::Neutral if pracaya => set(*b, Accent::Anudatta),
+                Accent::Neutral => set(*b, Accent::Udatta),
+            };
+        }
+    }
+}
+
+/// Encode text in any input script with options.
+pub fn encode_script_with(
+    input: &str,
+    script: Script,
+    opts: &EncodeOptions,
+) -> Result<Vec<u8>, SlbcError> {
+    Ok(tokens_to_bytes(&tokenize_script_with(input, script, opts)?))
+}
+
+/// Replace every word that is just oṃ (or om̐) with `Token::Pranava`.
+fn mark_pranava(tokens: &mut Vec<Token>) {
+    let is_word = |t: Option<&Token>| {
+        !matches!(
+            t,
+            None | Some(
+                Token::Space
+                    | Token::LineBreak
+                    | Token::Punctuation(_)
+                    | Token::Danda
+                    | Token::DoubleDanda
+                    | Token::Numeral(_)
+            )
+        )
+    };
+    let mut i = 0;
+    while i + 1 < tokens.len() {
+        if tokens[i] == Token::Svara(0x89)
+            && matches!(tokens[i + 1], Token::Vyanjana(0x3A) | Token::Candrabindu)
+            && !is_word(i.checked_sub(1).and_then(|p| tokens.get(p)))
+            && !is_word(tokens.get(i + 2))
+        {
+            tokens.splice(i..i + 2, [Token::Pranava]);
+        }
+        i += 1;
+    }
+}
+
+/// Insert the segments the Prātiśākhyas hear between a consonant and a
+/// following nasal of the same word: a yama after a non-nasal stop
+/// (`Token::Yama`, e.g. paliknī, yajña) and a nāsikya after h
+/// (`Token::Nasikya`, e.g. brahma, ahnā). A morpheme seam between the two
+/// does not prevent it.
+fn mark_yamas(tokens: &mut Vec<Token>) {
+    let is_stop = |b: u8| b <= 0x23 && b & 0x07 < 0x04;
+    let is_nasal =
+        |t: Option<&Token>| matches!(t, Some(Token::Vyanjana(b)) if b & 0x07 == 0x04 && *b <= 0x24);
+    let mut i = 0;
+    while i < tokens.len() {
+        let next = tokens[i + 1..]
+            .iter()
+            .find(|t| !matches!(t, Token::Morph | Token::Samasa));
+        let segment = match tokens[i] {
+            Token::Vyanjana(b) if is_stop(b) && is_nasal(next) => Some(Token::Yama),
+            Token::Vyanjana(0x38) if is_nasal(next) => Some(Token::Nasikya),
+            _ => None,
+        };
+        if let Some(segment) = segment {
+            tokens.insert(i + 1, segment);
+            i += 1;
+        }
+        i += 1;
+    }
+}
+
+/// Tokenize Vedic text, where IAST ḻ and ḻh are the Ṛgvedic laterals ळ
+/// and ळ्ह (see `Scheme::vedic`). Devanāgarī spells them apart anyway.
+pub fn tokenize_vedic(input: &str, script: Script) -> Result<Vec<Token>, SlbcError> {
+    match script.scheme() {
+        Some(roman) => roman.tokenize_vedic(input),
+        None => tokenize_script(input, script),
+    }
+}
+
+/// Encode Vedic text (see `tokenize_vedic`). Containers holding it set
+/// the VEDIC header flag.
+pub fn encode_vedic(input: &str, script: Script) -> Result<Vec<u8>, SlbcError> {
+    Ok(tokens_to_bytes(&tokenize_vedic(input, script)?))
+}
+
+/// A reusable encoding context for one input script, with the scheme's
+/// graphemes indexed up front. Cheap to clone and `Send + Sync`, so one
+/// instance can serve many threads.
+#[derive(Debug, Clone)]
+pub struct Encoder {
+    script: Script,
+    index: Option<Arc<SchemeIndex>>,
+}
+
+impl Encoder {
+    pub fn new(script: Script) -> Self {
+        let index = script
+            .scheme()
+            .map(|roman| Arc::new(SchemeIndex::new(roman)));
+        Encoder { script, index }
+    }
+
+    /// An encoder for Vedic text (see `tokenize_vedic`).
+    pub fn vedic(script: Script) -> Self {
+        let index = script
+            .scheme()
+            .map(|roman| Arc::new(SchemeIndex::vedic(roman)));
+        Encoder { script, index }
+    }
+
+    pub fn script(&self) -> Script {
+        self.script
+    }
+
+    pub fn tokenize(&self, input: &str) -> Result<Vec<Token>, SlbcError> {
+        match &self.index {
+            Some(index) => index.tokenize(input),
+            None => tokenize_script(input, self.script),
+        }
+    }
+
+    /// Encode text to a PHON chunk payload.
+    pub fn encode(&self, input: &str) -> Result<Vec<u8>, SlbcError> {
+        let mut out = Vec::new();
+        self.encode_into(input, &mut out)?;
+        Ok(out)
+    }
+
+    /// Encode text, appending the PHON bytes to `out`.
+    pub fn encode_into(&self, input: &str, out: &mut Vec<u8>) -> Result<(), SlbcError> {
+        push_tokens(&self.tokenize(input)?, &[], out);
+        Ok(())
+    }
+}
+
+// ── Devanāgarī input ──
+//
+// A consonant letter carries an inherent 'a' unless a vowel sign or virāma
+// follows it, so the tokenizer holds each consonant until the next
+// character settles its vowel. Nukta letters are accepted precomposed or as
+// base + U+093C.
+
+const VIRAMA: char = '्';
+const NUKTA: char = '\u{093C}';
+const INHERENT_A: u8 = 0x40;
+const VEDIC_LLA: u8 = 0x04;
+const VEDIC_LLHA: u8 = 0x05;
+
+/// Split a precomposed nukta letter into its base consonant.
+fn split_nukta(c: char) -> Option<char> {
+    Some(match c {
+        'ऩ' => 'न',
+        'ऱ' => 'र',
+        'ऴ' => 'ळ',
+        '\u{0958}' => 'क',
+        '\u{0959}' => 'ख',
+        '\u{095A}' => 'ग',
+        '\u{095B}' => 'ज',
+        '\u{095C}' => 'ड',
+        '\u{095D}' => 'ढ',
+        '\u{095E}' => 'फ',
+        '\u{095F}' => 'य',
+        _ => return None,
+    })
+}
+
+/// Base consonant and nukta flag of a (possibly precomposed) letter string.
+fn nukta_form(s: &str) -> Option<(char, bool)> {
+    let mut chars = s.chars();
+    let first = chars.next()?;
+    match (split_nukta(first), chars.next(), chars.next()) {
+        (Some(base), None, _) => Some((base, true)),
+        (None, Some(NUKTA), None) => Some((first, true)),
+        (None, None, _) => Some((first, false)),
+        _ => None,
+    }
+}
+
+fn svara_bytes() -> impl Iterator<Item = u8> {
+    codespace::svaras().with_accent(Accent::Neutral)
+}
+
+/// Independent vowel letter.
+fn deva_vowel(c: char) -> Option<Token> {
+    let mut buf = [0; 4];
+    let s = c.encode_utf8(&mut buf);
+    if let Some(b) = svara_bytes().find(|&b| decoder::byte_to_devanagari_independent(b) == s) {
+        return Some(Token::Svara(b));
+    }
+    codepage::CODE_PAGE
+        .iter()
+        .find(|p| p.kind == ExtKind::Vowel && p.devanagari == s)
+        .map(|p| Token::Extension(p.code))
+}
+
+/// Dependent vowel sign.
+fn deva_matra(c: char) -> Option<Token> {
+    let mut buf = [0; 4];
+    let s = c.encode_utf8(&mut buf);
+    if let Some(b) = svara_bytes().find(|&b| decoder::byte_to_devanagari_matra(b) == Some(s)) {
+        return Some(Token::Svara(b));
+    }
+    codepage::CODE_PAGE
+        .iter()
+        .find(|p| p.matra == Some(s))
+        .map(|p| Token::Extension(p.code))
+}
+
+/// Consonant letter, with or without nukta.
+fn deva_consonant(base: char, nukta: bool) -> Option<Token> {
+    if !nukta {
+        let mut buf = [0; 4];
+        let s = base.encode_utf8(&mut buf);
+        let core = (0..0x40u8)
+            .filter(|&b| is_vyanjana(b))
+            .find(|&b| decoder::byte_to_devanagari_consonant(b) == s);
+        if let Some(b) = core {
+            return Some(Token::Vyanjana(b));
+        }
+    }
+    codepage::CODE_PAGE
+        .iter()
+        .find(|p| p.kind == ExtKind::Consonant && nukta_form(p.devanagari) == Some((base, nukta)))
+        .map(|p| Token::Extension(p.code))
+}
+
+fn deva_digit(c: char) -> Option<char> {
+    if c.is_ascii_digit() {
+        return Some(c);
+    }
+    let d = decoder::DEVANAGARI_DIGITS.iter().position(|&g| g == c)?;
+    char::from_digit(d as u32, 10)
+}
+
+/// Apply a svara mark to the last vowel, looking past anusvāra and visarga.
+pub(crate) fn mark_last_svara(tokens: &mut [Token], mark: char) -> Option<()> {
+    let last = tokens
+        .iter_mut()
+        .rev()
+        .find(|t| !matches!(t, Token::Vyanjana(0x39 | 0x3A) | Token::Candrabindu))?;
+    match last {
+        Token::Svara(b) => {
+            *b = apply_svara_mark(*b, mark)?;
+            Some(())
+        }
+        _ => None,
+    }
+}
+
+/// Tokenize Devanāgarī text into the same token stream as the equivalent
+/// IAST.
+///
+/// Handles mātrās, virāma, anusvāra, candrabindu, visarga, avagraha,
+/// daṇḍas (also as ASCII `|`), Devanāgarī and ASCII digits, nukta letters
+/// and the Vedic laterals ळ, ळ्ह from the extension code page, the Vedic
+/// marks U+0951/U+0952/U+1CDA and jihvāmūlīya/upadhmānīya (U+1CF5/U+1CF6),
+/// ॐ (as o ṃ), the gap markers "[?]" and "[...]", and "+" for a morpheme
+/// seam. A "३" directly after a vowel is pluta; elsewhere it is a digit.
+pub fn tokenize_devanagari(input: &str) -> Result<Vec<Token>, SlbcError> {
+    tokenize_devanagari_with(input, &EncodeOptions::default())
+}
+
+/// Tokenize Devanāgarī text, reading whitespace, punctuation, and
+/// characters it cannot read as `opts` say.
+fn tokenize_devanagari_with(input: &str, opts: &EncodeOptions) -> Result<Vec<Token>, SlbcError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    // A consonant was just emitted and its vowel is not settled yet
+    let mut pending = false;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let err = |what: &str| {
+            SlbcError::encode(input, i, format!("{} '{}' (U+{:04X})", what, c, c as u32))
+        };
+
+        if let Some(token) = deva_matra(c) {
+            if !pending {
+                return Err(err("vowel sign without a consonant"));
+            }
+            tokens.push(token);
+            pending = false;
+            i += 1;
+            continue;
+        }
+        if c == VIRAMA {
+            if !pending {
+                return Err(err("virāma without a consonant"));
+            }
+            pending = false;
+            i += 1;
+            // A joiner after it only asks the font for one form of the
+            // conjunct or another
+            if matches!(chars.get(i), Some('\u{200C}' | '\u{200D}')) {
+                i += 1;
+            }
+            continue;
+        }
+        if pending {
+            tokens.push(Token::Svara(INHERENT_A));
+            pending = false;
+        }
+
+        match c {
+            '\r' => {}
+            ' ' | '\t' | '\n' => push_whitespace(c, &mut tokens, opts),
+            c if Some(c) == opts.samasa.mark() => tokens.push(Token::Samasa),
+            '+' => tokens.push(Token::Morph),
+            PHON_OPEN => tokens.push(Token::PhonStart),
+            PHON_CLOSE => tokens.push(Token::PhonEnd),
+            '[' => {
+                let rest: String = chars[i..].iter().collect();
+                let (token, len) =
+                    gap_marker(&rest).ok_or_else(|| err("unrecognized gap marker"))?;
+                tokens.push(token);
+                i += rest[..len].chars().count();
+                continue;
+            }
+            DEVA_UDATTA | DEVA_ANUDATTA | DEVA_SVARITA => {
+                mark_last_svara(&mut tokens, c)
+                    .ok_or_else(|| err("accent mark without a vowel"))?;
+            }
+            DEVA_PLUTA if matches!(tokens.last(), Some(Token::Svara(_))) => {
+                mark_last_svara(&mut tokens, c).ok_or_else(|| err("pluta mark without a vowel"))?;
+            }
+            '।' | '|' if matches!(chars.get(i + 1), Some('।' | '|')) => {
+                tokens.push(Token::DoubleDanda);
+                i += 1;
+            }
+            '।' | '|' => tokens.push(Token::Danda),
+            '॥' => tokens.push(Token::DoubleDanda),
+            'ऽ' | '\'' => tokens.push(Token::Avagraha),
+            'ं' => tokens.push(Token::Vyanjana(0x3A)),
+            'ँ' => tokens.push(Token::Candrabindu),
+            'ः' => tokens.push(Token::Vyanjana(0x39)),
+            'ᳵ' => tokens.push(Token::Vyanjana(0x3B)),
+            'ᳶ' => tokens.push(Token::Vyanjana(0x3C)),
+            'ॐ' => tokens.extend([Token::Svara(0x89), Token::Vyanjana(0x3A)]),
+            c if deva_digit(c).is_some() => {
+                let digits: String = chars[i..].iter().map_while(|&c| deva_digit(c)).collect();
+                i += digits.len();
+                tokens.push(Token::Numeral(digits));
+                continue;
+            }
+            c => {
+                if let Some(token) = deva_vowel(c) {
+                    tokens.push(token);
+                } else {
+                    let (base, nukta) = match (split_nukta(c), chars.get(i + 1)) {
+                        (Some(base), _) => (base, true),
+                        (None, Some(&NUKTA)) => (c, true),
+                        (None, _) => (c, false),
+                    };
+                    let Some(token) = deva_consonant(base, nukta) else {
+                        if !punctuate(c, &mut tokens, opts.punctuation) {
+                            if !opts.lenient {
+                                return Err(err("unrecognized Devanāgarī character"));
+                            }
+                            recover(c, &mut tokens);
+                        }
+                        i += 1;
+                        continue;
+                    };
+                    if nukta && split_nukta(c).is_none() {
+                        i += 1;
+                    }
+                    // ळ्ह is one letter, the aspirated Vedic lateral
+                    let token = match (token, chars.get(i + 1..i + 3)) {
+                        (Token::Extension(VEDIC_LLA), Some(&[VIRAMA, 'ह'])) => {
+                            i += 2;
+                            Token::Extension(VEDIC_LLHA)
+                        }
+                        (token, _) => token,
+                    };
+                    tokens.push(token);
+                    pending = true;
+                }
+            }
+        }
+        i += 1;
+    }
+
+    if pending {
+        tokens.push(Token::Svara(INHERENT_A));
+    }
+    Ok(tokens)
+}
+
+/// Top-level encode: Devanāgarī string → SLBC byte stream (PHON payload).
+pub fn encode_devanagari(input: &str) -> Result<Vec<u8>, SlbcError> {
+    let tokens = tokenize_devanagari(input)?;
+    Ok(tokens_to_bytes(&tokens))
+}
+
+// ── Mixed-script input ──
+//
+// A document may switch between Devanāgarī and romanized text, or between
+// romanizations from one line to the next. `encode_auto` splits it into
+// runs of words in one writing system, breaking romanized runs at line
+// ends, and reads each run in the script `pipeline::detect_script` picks.
+
+/// A mixed-script document encoded by `encode_auto`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AutoEncoded {
+    /// PHON chunk payload.
+    pub payload: Vec<u8>,
+    /// The script each pada was read in, by PADA_START offset.
+    pub scripts: Vec<(usize, Script)>,
+}
+
+/// Writing system of a word; `None` for words without letters (numerals,
+/// daṇḍas, gap markers), which join the run around them.
+fn word_class(word: &str) -> Option<bool> {
+    let is_deva = |c: &char| ('\u{0900}'..='\u{097F}').contains(c);
+    let deva = word
+        .chars()
+        .filter(|c| c.is_alphabetic() && is_deva(c))
+        .count();
+    let latin = word
+        .chars()
+        .filter(|c| c.is_alphabetic() && !is_deva(c))
+        .count();
+    match (deva, latin) {
+        (0, 0) => None,
+        _ => Some(deva > latin),
+    }
+}
+
+/// Split text into single-script runs: (run, is Devanāgarī).
+fn script_runs(input: &str) -> Vec<(&str, bool)> {
+    let mut runs = Vec::new();
+    let (mut start, mut class) = (0, None);
+    let mut newline = false;
+    let mut pos = 0;
+
+    while pos < input.len() {
+        let rest = &input[pos..];
+        let gap = rest.len() - rest.trim_start().len();
+        if gap > 0 {
+            newline |= rest[..gap].contains('\n');
+            pos += gap;
+            continue;
+        }
+        let len = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        if let Some(deva) = word_class(&rest[..len]) {
+            match class {
+                None => class = Some(deva),
+                Some(current) if current != deva || (newline && !deva) => {
+                    runs.push((&input[start..pos], current));
+                    (start, class) = (pos, Some(deva));
+                }
+                Some(_) => {}
+            }
+        }
+        newline = false;
+        pos += len;
+    }
+    if start < input.len() {
+        runs.push((&input[start..], class.unwrap_or(false)));
+    }
+    runs
+}
+
+/// Padas a token stream opens.
+fn count_padas(tokens: &[Token]) -> usize {
+    let mut count = 0;
+    let mut in_pada = false;
+    for token in tokens {
+        match token {
+            Token::Svara(_) | Token::Vyanjana(_) | Token::Avagraha | Token::Extension(_) => {
+                count += usize::from(!in_pada);
+                in_pada = true;
+            }
+            Token::Illegible | Token::Lacuna | Token::Morph => {}
+            _ => in_pada = false,
+        }
+    }
+    count
+}
+
+/// Encode a document mixing Devanāgarī with romanized text, detecting the
+/// script of each run (see `pipeline::detect_script`). Runs break where the
+/// writing system changes and, for romanized text, at line ends, so lines
+/// in different romanizations are told apart.
+pub fn encode_auto(input: &str) -> Result<AutoEncoded, SlbcError> {
+    let mut tokens = Vec::new();
+    let mut pada_scripts = Vec::new();
+    for (run, deva) in script_runs(input) {
+        let script = match deva {
+            true => Script::Devanagari,
+            false => crate::pipeline::detect_script(run),
+        };
+        let run_tokens = tokenize_script(run, script)?;
+        pada_scripts.extend(std::iter::repeat_n(script, count_padas(&run_tokens)));
+        tokens.extend(run_tokens);
+    }
+    let payload = tokens_to_bytes(&tokens);
+    let scripts = crate::lang::padas(&payload)?
+        .into_iter()
+        .map(|(offset, _)| offset)
+        .zip(pada_scripts)
+        .collect();
+    Ok(AutoEncoded { payload, scripts })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize_simple() {
+        let tokens = tokenize_iast("ka").unwrap();
+        assert_eq!(tokens, vec![Token::Vyanjana(0x00), Token::Svara(0x40)]);
+    }
+
+    #[test]
+    fn test_tokenize_aspirate() {
+        let tokens = tokenize_iast("kha").unwrap();
+        assert_eq!(tokens, vec![Token::Vyanjana(0x01), Token::Svara(0x40)]);
+    }
+
+    #[test]
+    fn test_tokenize_diphthong() {
+        let tokens = tokenize_iast("ai").unwrap();
+        assert_eq!(tokens, vec![Token::Svara(0x86)]);
+    }
+
+    #[test]
+    fn test_tokenize_au() {
+        let tokens = tokenize_iast("au").unwrap();
+        assert_eq!(tokens, vec![Token::Svara(0x8A)]);
+    }
+
+    #[test]
+    fn test_encode_dharma() {
+        let bytes = encode_iast("dharma").unwrap();
+        // PADA_START dh a r m a PADA_END
+        assert_eq!(bytes, vec![0x26, 0x1B, 0x40, 0x33, 0x24, 0x40, 0x2E]);
+    }
+
+    #[test]
+    fn test_encode_two_words() {
+        let bytes = encode_iast("na ca").unwrap();
+        // PADA_START n a PADA_END SPACE PADA_START c a PADA_END
+        assert_eq!(
+            bytes,
+            vec![0x26, 0x1C, 0x40, 0x2E, 0x1F, 0x26, 0x08, 0x40, 0x2E]
+        );
+    }
+
+    #[test]
+    fn test_svara_marks() {
+        assert_eq!(apply_svara_mark(0x40, DEVA_UDATTA), Some(0x50)); // a → á
+        assert_eq!(apply_svara_mark(0x84, DEVA_ANUDATTA), Some(0xA4)); // ī → ī̀
+        assert_eq!(apply_svara_mark(0x50, DEVA_PLUTA), Some(0xD0)); // á → á3
+        assert_eq!(apply_svara_mark(0x00, DEVA_UDATTA), None);
+        assert_eq!(apply_svara_mark(0x40, 'x'), None);
+        assert_eq!(apply_svara_mark(0x85, IAST_SVARITA), Some(0xB5)); // e → ê
+    }
+
+    #[test]
+    fn test_iast_accents() {
+        // Precomposed and combining marks agree with Devanāgarī
+        assert_eq!(encode_iast("àgnimī́ḍe"), encode_devanagari("अ॒ग्निमी॑डे"));
+        assert_eq!(
+            encode_iast("a\u{0300}gnimī\u{0301}ḍe"),
+            encode_devanagari("अ॒ग्निमी॑डे")
+        );
+        let tokens = tokenize_iast("svâr daívyam").unwrap();
+        assert_eq!(tokens[2], Token::Svara(0x70)); // svarita a
+        assert_eq!(tokens[6], Token::Svara(0x96)); // udātta ai
+        assert!(tokenize_iast("\u{0301}a").is_err());
+        assert!(tokenize_iast("k\u{0300}a").is_err());
+    }
+
+    #[test]
+    fn test_devanagari_matches_iast() {
+        let pairs = [
+            ("धर्मक्षेत्रे कुरुक्षेत्रे ।", "dharmakṣetre kurukṣetre |"),
+            ("सो ऽहम् ॥", "so 'ham ||"),
+            ("दुःखं पितॄन् कॢप्त", "duḥkhaṃ pitṝn kḷpta"),
+            ("ऐश्वर्यम् औषधम् १०८", "aiśvaryam auṣadham 108"),
+            ("क़लम ज़रा", "qalama zarā"),
+        ];
+        for (deva, iast) in pairs {
+            assert_eq!(encode_devanagari(deva), encode_iast(iast), "{}", deva);
+        }
+        // Precomposed and decomposed nukta are the same phoneme
+        assert_eq!(
+            encode_devanagari("\u{0958}"),
+            encode_devanagari("\u{0915}\u{093C}")
+        );
+    }
+
+    #[test]
+    fn test_devanagari_svara_marks() {
+        let tokens = tokenize_devanagari("अ॒ग्निमी॑ळे").unwrap();
+        assert_eq!(tokens[0], Token::Svara(0x60)); // anudātta a
+        assert_eq!(tokens[5], Token::Svara(0x94)); // udātta ī
+        assert_eq!(tokens[6], Token::Extension(VEDIC_LLA));
+        let tokens = tokenize_devanagari("अग्ना३इ ३").unwrap();
+        assert_eq!(tokens[3], Token::Svara(0xC0)); // pluta ā
+        assert_eq!(tokens.last(), Some(&Token::Numeral("3".into())));
+        assert!(tokenize_devanagari("ि").is_err());
+        assert!(tokenize_devanagari("॑").is_err());
+    }
+
+    #[test]
+    fn test_normalize_roman() {
+        let nfc = "śāntiḥ kṛṣṇaṃ pitṝn";
+        let nfd =
+            "s\u{301}a\u{304}ntih\u{323} kr\u{323}s\u{323}n\u{323}am\u{323} pitr\u{304}\u{323}n";
+        assert_eq!(normalize_roman(nfd), nfc);
+        assert_eq!(encode_iast(nfd), encode_iast(nfc));
+        // A combining acute on a vowel stays its accent, after a macron too
+        assert_eq!(
+            encode_iast("a\u{304}\u{301}gni\u{301}"),
+            encode_iast("ā\u{301}gní")
+        );
+        assert_eq!(
+            encode_script("n\u{303}a\u{304}na l\u{325}", Script::Iso15919),
+            encode_script("ñāna l̥", Script::Iso15919)
+        );
+        assert!(matches!(
+            normalize_roman("rāma"),
+            std::borrow::Cow::Owned(_)
+        ));
+        assert!(matches!(
+            normalize_roman("rama"),
+            std::borrow::Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn test_encode_options() {
+        let with = |input: &str, opts: EncodeOptions| encode_iast_with(input, &opts).unwrap();
+        let iast = |input: &str| encode_iast(input).unwrap();
+
+        let trim = EncodeOptions {
+            whitespace: Whitespace::Trim,
+            ..Default::default()
+        };
+        assert_eq!(with(" rāma\n", trim), iast("rāma"));
+        assert_eq!(with(" rāma \n", EncodeOptions::default()), iast(" rāma\n"));
+
+        let lines = EncodeOptions {
+            newlines: Newlines::Danda,
+            ..Default::default()
+        };
+        assert_eq!(
+            with(
+                "dharmakṣetre kurukṣetre\nsamavetā yuyutsavaḥ ||\n\nmāmakāḥ",
+                lines
+            ),
+            iast("dharmakṣetre kurukṣetre | samavetā yuyutsavaḥ || māmakāḥ")
+        );
+
+        let input = "rāma, gaccha!";
+        assert!(encode_iast_with(input, &EncodeOptions::default()).is_err());
+        let punctuation = |punctuation| EncodeOptions {
+            punctuation,
+            ..Default::default()
+        };
+        assert_eq!(
+            with(input, punctuation(Punctuation::Drop)),
+            iast("rāma gaccha")
+        );
+        assert_eq!(
+            with(input, punctuation(Punctuation::Danda)),
+            iast("rāma gaccha|")
+        );
+        assert_eq!(
+            encode_script_with(
+                "राम, गच्छ।",
+                Script::Devanagari,
+                &punctuation(Punctuation::Drop)
+            )
+            .unwrap(),
+            encode_devanagari("राम गच्छ।").unwrap()
+        );
+
+        let lenient = EncodeOptions {
+            lenient: true,
+            ..Default::default()
+        };
+        assert!(encode_iast("rā\u{200D}ma").is_err());
+        assert_eq!(with("rā\u{200D}ma", lenient), iast("rāma"));
+        assert_eq!(
+            encode_script_with("रा\u{200D}म", Script::Devanagari, &lenient).unwrap(),
+            encode_devanagari("राम").unwrap()
+        );
+
+        let frames = |phon_frames| EncodeOptions {
+            phon_frames,
+            ..Default::default()
+        };
+        assert_eq!(
+            with("rāma || 1 || kṛṣṇa ||", frames(PhonFrames::Verses)),
+            iast("⟨rāma || 1 ||⟩ ⟨kṛṣṇa ||⟩")
+        );
+        assert_eq!(
+            with("rāma || kṛṣṇa ", frames(PhonFrames::Verses)),
+            iast("⟨rāma ||⟩ ⟨kṛṣṇa⟩ ")
+        );
+        assert_eq!(
+            with("rāma || 1 || kṛṣṇa", frames(PhonFrames::Text)),
+            iast("⟨rāma || 1 || kṛṣṇa⟩")
+        );
+    }
+
+    #[test]
+    fn test_unicode_dandas() {
+        let iast = |input: &str| encode_iast(input).unwrap();
+        let expected = iast("dharmakṣetre kurukṣetre | samavetā yuyutsavaḥ ||");
+        assert_eq!(
+            iast("dharmakṣetre kurukṣetre । samavetā yuyutsavaḥ ॥"),
+            expected
+        );
+        assert_eq!(
+            iast("samavetā yuyutsavaḥ ।।"),
+            iast("samavetā yuyutsavaḥ ||")
+        );
+        assert_eq!(
+            encode_script("rāma । sītā ॥", Script::Iso15919).unwrap(),
+            iast("rāma | sītā ||")
+        );
+        assert_eq!(
+            decoder::decode_phon(&expected, Script::Iast).unwrap(),
+            "dharmakṣetre kurukṣetre | samavetā yuyutsavaḥ ||"
+        );
+
+        let input = "dharmakṣetre kurukṣetre. samavetā yuyutsavaḥ..";
+        assert!(encode_iast(input).is_err());
+        let dots = EncodeOptions {
+            dot_dandas: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            encode_iast_with(input, &dots).unwrap(),
+            iast("dharmakṣetre kurukṣetre| samavetā yuyutsavaḥ||")
+        );
+        // A dot is part of a letter in Velthuis
+        assert_eq!(
+            encode_script_with("raama.h", Script::Velthuis, &dots).unwrap(),
+            iast("rāmaḥ")
+        );
+    }
+
+    #[test]
+    fn test_encode_iast_lossy() {
+        let input = "“rāma,” vana\u{200D}m gacchati. ॥ १०८";
+        assert!(encode_iast(input).is_err());
+        let (bytes, warnings) = encode_iast_lossy(input);
+        assert_eq!(
+            bytes,
+            encode_iast("“rāma,” vanam gacchati| || 108").unwrap()
+        );
+        let found: Vec<(usize, char, Option<char>)> = warnings
+            .iter()
+            .map(|w| (w.position, w.found, w.replacement))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (12, '\u{200D}', None),
+                (23, '.', Some('|')),
+                (27, '१', Some('1')),
+                (28, '०', Some('0')),
+                (29, '८', Some('8')),
+            ]
+        );
+        assert_eq!(
+            warnings[1].to_string(),
+            "read '.' (U+002E) at position 23 as '|'"
+        );
+        assert_eq!(
+            encode_iast_lossy("rāmaḥ"),
+            (encode_iast("rāmaḥ").unwrap(), vec![])
+        );
+    }
+
+    #[test]
+    fn test_samhita_tone_marks() {
+        let opts = EncodeOptions {
+            vedic: true,
+            tone_marks: ToneMarks::Samhita,
+            ..Default::default()
+        };
+        let text = "अ॒ग्निमी॑ळे पु॒रोहि॑तं । देवम्";
+        let expected = "àgnímī\u{302}ḻè pùróhîtàṃ | devam";
+        let bytes = encode_script_with(text, Script::Devanagari, &opts).unwrap();
+        assert_eq!(
+            bytes,
+            encode_script_with(expected, Script::Iast, &opts).unwrap()
+        );
+        // Romanized marks are explicit whatever the convention
+        assert_eq!(
+            encode_script_with("agni\u{301}m", Script::Iast, &opts).unwrap(),
+            encode_iast("agním").unwrap()
+        );
+        assert_eq!(ToneMarks::from_name("samhita"), Some(ToneMarks::Samhita));
+    }
+
+    #[test]
+    fn test_encode_auto() {
+        let text = "dharmakṣetre kurukṣetre धर्मक्षेत्रे कुरुक्षेत्रे ।\nsamavetA yuyutsavaH ||";
+        let auto = encode_auto(text).unwrap();
+        let expected = encode_iast(
+            "dharmakṣetre kurukṣetre dharmakṣetre kurukṣetre |\nsamavetā yuyutsavaḥ ||",
+        );
+        assert_eq!(Ok(auto.payload), expected);
+        let scripts: Vec<Script> = auto.scripts.iter().map(|&(_, s)| s).collect();
+        use Script::*;
+        assert_eq!(
+            scripts,
+            vec![
+                Iast,
+                Iast,
+                Devanagari,
+                Devanagari,
+                HarvardKyoto,
+                HarvardKyoto
+            ]
+        );
+
+        // Words without letters join the run around them
+        let auto = encode_auto("१०८ नमः 108 ||").unwrap();
+        assert_eq!(auto.scripts, vec![(auto.scripts[0].0, Devanagari)]);
+        assert_eq!(Ok(auto.payload), encode_iast("108 namaḥ 108 ||"));
+    }
+
+    #[test]
+    fn test_ka_is_null_byte() {
+        let tokens = tokenize_iast("ka").unwrap();
+        assert_eq!(tokens[0], Token::Vyanjana(0x00));
+    }
+
+    #[test]
+    fn test_encoder_context() {
+        let text = include_str!("../testdata/bhagavadgita_2_11-25.txt").trim();
+        let data = encode_iast(text).unwrap();
+        for &script in Script::INPUT {
+            let rendered = decoder::decode_phon(&data, script).unwrap();
+            let encoder = Encoder::new(script);
+            assert_eq!(
+                encoder.encode(&rendered).unwrap(),
+                encode_script(&rendered, script).unwrap(),
+                "{}",
+                script.name()
+            );
+        }
+
+        let encoder = Arc::new(Encoder::new(Script::HarvardKyoto));
+        let rendered = decoder::decode_phon(&data, Script::HarvardKyoto).unwrap();
+        std::thread::scope(|s| {
+            for _ in 0..4 {
+                let encoder = Arc::clone(&encoder);
+                let (rendered, data) = (&rendered, &data);
+                s.spawn(move || {
+                    let mut out = Vec::new();
+                    encoder.encode_into(rendered, &mut out).unwrap();
+                    assert_eq!(&out, data);
+                });
+            }
+        });
     }
 }
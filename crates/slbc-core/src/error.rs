@@ -0,0 +1,213 @@
+//! The error type of the library.
+//!
+//! An `SlbcError` says what went wrong by its variant and where: a byte
+//! offset into a stream or container, or for input text a char position and
+//! a snippet of the text around it, so tools can react to the kind and put
+//! a caret in the right place. Its message ends with the place ("… at
+//! offset 12", "… at position 4"). Each module builds the variant where the
+//! failure is found; a failure read through another keeps its kind (see
+//! `context`).
+
+use std::fmt;
+
+/// Chars of context on either side of an encode error's position.
+const SNIPPET_CONTEXT: usize = 8;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SlbcError {
+    /// Input text that cannot be read: an unrecognized character, or a
+    /// mark with no letter to carry it.
+    Encode {
+        message: String,
+        /// Char index into the input.
+        position: usize,
+        /// The input around `position`.
+        snippet: String,
+    },
+    /// Text in a script that can be written but not read.
+    OutputOnlyScript { script: &'static str },
+    /// Bytes that are not valid SLBC.
+    Malformed {
+        message: String,
+        offset: Option<usize>,
+    },
+    /// A stream, chunk, or field that ends too early.
+    Truncated {
+        message: String,
+        offset: Option<usize>,
+    },
+    /// A pada-dictionary reference in a payload that must be expanded
+    /// first (see `dict::expand`).
+    DictRef { offset: usize },
+    /// A reader or seek failed, or a file could not be read.
+    Io { message: String },
+    /// A request that cannot be carried out, or data wrong in a way no
+    /// single offset shows: a name already registered, an edit on the
+    /// wrong kind of phoneme, chunks that contradict each other.
+    Invalid { message: String },
+}
+
+impl SlbcError {
+    /// An encode error for the char at `position` of `input`; `what` names
+    /// the problem.
+    pub fn encode(input: &str, position: usize, what: impl fmt::Display) -> Self {
+        let snippet = input
+            .chars()
+            .skip(position.saturating_sub(SNIPPET_CONTEXT))
+            .take(position.min(SNIPPET_CONTEXT) + SNIPPET_CONTEXT + 1)
+            .collect();
+        SlbcError::Encode {
+            message: format!("{} at position {}", what, position),
+            position,
+            snippet,
+        }
+    }
+
+    /// Invalid bytes at `offset`.
+    pub fn malformed(offset: usize, what: impl fmt::Display) -> Self {
+        SlbcError::Malformed {
+            message: format!("{} at offset {}", what, offset),
+            offset: Some(offset),
+        }
+    }
+
+    /// Bytes cut off at `offset`.
+    pub fn truncated(offset: usize, what: impl fmt::Display) -> Self {
+        SlbcError::Truncated {
+            message: format!("{} at offset {}", what, offset),
+            offset: Some(offset),
+        }
+    }
+
+    /// A failure with no place in a stream or text; `what` names it.
+    pub fn invalid(what: impl fmt::Display) -> Self {
+        SlbcError::Invalid {
+            message: what.to_string(),
+        }
+    }
+
+    /// This error as the cause of a failure reading `what` at `offset`,
+    /// keeping its kind: "`what` at offset N: cause".
+    pub fn context(self, offset: usize, what: impl fmt::Display) -> Self {
+        let message = format!("{} at offset {}: {}", what, offset, self);
+        match self {
+            SlbcError::Truncated { .. } => SlbcError::Truncated {
+                message,
+                offset: Some(offset),
+            },
+            _ => SlbcError::Malformed {
+                message,
+                offset: Some(offset),
+            },
+        }
+    }
+
+    /// Byte offset into the stream or container, where known.
+    pub fn offset(&self) -> Option<usize> {
+        match self {
+            SlbcError::Malformed { offset, .. } | SlbcError::Truncated { offset, .. } => *offset,
+            SlbcError::DictRef { offset } => Some(*offset),
+            _ => None,
+        }
+    }
+
+    /// Char index into input text, for encode errors.
+    pub fn position(&self) -> Option<usize> {
+        match self {
+            SlbcError::Encode { position, .. } => Some(*position),
+            _ => None,
+        }
+    }
+
+    /// The input text around the error, for encode errors.
+    pub fn snippet(&self) -> Option<&str> {
+        match self {
+            SlbcError::Encode { snippet, .. } => Some(snippet),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for SlbcError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SlbcError::Encode { message, .. }
+            | SlbcError::Malformed { message, .. }
+            | SlbcError::Truncated { message, .. }
+            | SlbcError::Io { message }
+            | SlbcError::Invalid { message } => f.write_str(message),
+            SlbcError::OutputOnlyScript { script } => {
+                write!(f, "{} is an output-only script", script)
+            }
+            SlbcError::DictRef { offset } => write!(
+                f,
+                "dictionary reference at offset {} — expand the payload with its pada dictionary first",
+                offset
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SlbcError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locations() {
+        let e = SlbcError::encode("dharmakṣetre kurukṣetre", 14, "unrecognized 'x'");
+        assert_eq!(e.to_string(), "unrecognized 'x' at position 14");
+        assert_eq!(e.position(), Some(14));
+        assert_eq!(e.snippet(), Some("kṣetre kurukṣetre"));
+        assert_eq!(SlbcError::encode("ab", 0, "bad").snippet(), Some("ab"));
+
+        let e = SlbcError::malformed(7, "invalid digit-word");
+        assert_eq!(e.to_string(), "invalid digit-word at offset 7");
+        assert_eq!(e.offset(), Some(7));
+        let e = SlbcError::invalid("script 'iast' is already registered");
+        assert_eq!(e.to_string(), "script 'iast' is already registered");
+        assert_eq!((e.offset(), e.position()), (None, None));
+        let e = SlbcError::Truncated {
+            message: "truncated ULEB128".into(),
+            offset: None,
+        };
+        let e = e.context(3, "chunk length ULEB128 error");
+        assert_eq!(
+            e.to_string(),
+            "chunk length ULEB128 error at offset 3: truncated ULEB128"
+        );
+        assert!(matches!(
+            e,
+            SlbcError::Truncated {
+                offset: Some(3),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_module_errors() {
+        use crate::decoder::{self, Script};
+        use crate::{container, encoder, types::*};
+
+        let e = encoder::encode_iast("rāmo vanaṃ gacchati!").unwrap_err();
+        assert_eq!(e.position(), Some(19));
+        assert_eq!(e.snippet(), Some("gacchati!"));
+        let e = encoder::encode_devanagari("राम ा").unwrap_err();
+        assert!(matches!(e, SlbcError::Encode { position: 4, .. }));
+        #[cfg(feature = "scripts")]
+        assert!(matches!(
+            encoder::encode_script("rama", Script::Tamil),
+            Err(SlbcError::OutputOnlyScript { script: "tamil" })
+        ));
+
+        let e = decoder::decode_phon(&[PADA_START, 0x40, DICT_REF, 0], Script::Iast);
+        assert_eq!(e, Err(SlbcError::DictRef { offset: 2 }));
+        let e = container::parse_slbc(&container::build_slbc(&[PADA_START])[..16]).unwrap_err();
+        assert!(matches!(e, SlbcError::Truncated { .. }));
+        let mut bad = container::build_slbc(&[]);
+        bad[0] = b'X';
+        assert_eq!(container::parse_slbc(&bad).unwrap_err().offset(), Some(0));
+    }
+}
@@ -0,0 +1,248 @@
+//! Application-specific data (EXT chunk, 0x10).
+//!
+//! An EXT chunk carries data of an outside tool, named by the tool's vendor
+//! ID and a sub-type of the vendor's choosing:
+//! ```text
+//! vendor ID (ULEB128) | sub-type (ULEB128) | data (rest of the payload)
+//! ```
+//! Vendor ID 0 is reserved. Readers keep EXT chunks they do not understand,
+//! and tools that rewrite a container carry them over. A tool `register`s
+//! its vendor ID with a name (and optionally names for its sub-types), after
+//! which `describe` — and `inspect` — name its chunks.
+
+use std::sync::RwLock;
+
+use crate::container::{self, Chunk};
+use crate::error::SlbcError;
+use crate::types::*;
+
+/// A vendor's data in one EXT chunk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtChunk {
+    pub vendor: u32,
+    pub subtype: u32,
+    pub data: Vec<u8>,
+}
+
+fn read_field(data: &[u8], pos: &mut usize, what: &str) -> Result<u32, SlbcError> {
+    let (v, consumed) = container::read_uleb128(&data[(*pos).min(data.len())..])
+        .map_err(|e| e.context(*pos, format_args!("EXT chunk {} error", what)))?;
+    *pos += consumed;
+    Ok(v as u32)
+}
+
+impl ExtChunk {
+    pub fn new(vendor: u32, subtype: u32, data: impl Into<Vec<u8>>) -> Self {
+        ExtChunk {
+            vendor,
+            subtype,
+            data: data.into(),
+        }
+    }
+
+    /// Build the EXT chunk.
+    pub fn to_chunk(&self) -> Result<Chunk, SlbcError> {
+        if self.vendor == 0 {
+            return Err(SlbcError::invalid("EXT vendor ID 0 is reserved"));
+        }
+        let mut payload = Vec::with_capacity(self.data.len() + 4);
+        container::write_uleb128(&mut payload, self.vendor as u64);
+        container::write_uleb128(&mut payload, self.subtype as u64);
+        payload.extend_from_slice(&self.data);
+        Ok(Chunk {
+            chunk_type: CHUNK_EXT,
+            payload,
+        })
+    }
+
+    /// Parse an EXT chunk payload.
+    pub fn from_chunk_payload(payload: &[u8]) -> Result<Self, SlbcError> {
+        let mut pos = 0;
+        let vendor = read_field(payload, &mut pos, "vendor ID")?;
+        if vendor == 0 {
+            return Err(SlbcError::malformed(
+                0,
+                "EXT chunk has the reserved vendor ID 0",
+            ));
+        }
+        let subtype = read_field(payload, &mut pos, "sub-type")?;
+        Ok(ExtChunk {
+            vendor,
+            subtype,
+            data: payload[pos..].to_vec(),
+        })
+    }
+
+    /// Every EXT chunk among a container's chunks.
+    pub fn all(chunks: &[Chunk]) -> Result<Vec<Self>, SlbcError> {
+        chunks
+            .iter()
+            .filter(|c| c.chunk_type == CHUNK_EXT)
+            .map(|c| ExtChunk::from_chunk_payload(&c.payload))
+            .collect()
+    }
+
+    /// Every EXT chunk of one vendor.
+    pub fn of_vendor(chunks: &[Chunk], vendor: u32) -> Result<Vec<Self>, SlbcError> {
+        Ok(ExtChunk::all(chunks)?
+            .into_iter()
+            .filter(|e| e.vendor == vendor)
+            .collect())
+    }
+}
+
+/// A tool that stores EXT chunks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Vendor {
+    pub id: u32,
+    /// Name shown by `inspect` (e.g. "org.example.scanner").
+    pub name: String,
+    /// (sub-type, name) for the sub-types the tool names.
+    pub subtypes: Vec<(u32, String)>,
+}
+
+impl Vendor {
+    pub fn new(id: u32, name: impl Into<String>) -> Self {
+        Vendor {
+            id,
+            name: name.into(),
+            subtypes: Vec::new(),
+        }
+    }
+
+    pub fn subtype(mut self, subtype: u32, name: impl Into<String>) -> Self {
+        self.subtypes.push((subtype, name.into()));
+        self
+    }
+
+    pub fn subtype_name(&self, subtype: u32) -> Option<&str> {
+        self.subtypes
+            .iter()
+            .find(|(n, _)| *n == subtype)
+            .map(|(_, name)| name.as_str())
+    }
+}
+
+static REGISTRY: RwLock<Vec<Vendor>> = RwLock::new(Vec::new());
+
+/// Make a vendor known to `lookup` and `describe`. Neither the ID nor the
+/// name may collide with an earlier registration, and ID 0 is reserved.
+pub fn register(vendor: Vendor) -> Result<(), SlbcError> {
+    if vendor.id == 0 {
+        return Err(SlbcError::invalid("EXT vendor ID 0 is reserved"));
+    }
+    let mut registry = REGISTRY.write().unwrap_or_else(|e| e.into_inner());
+    if let Some(v) = registry
+        .iter()
+        .find(|v| v.id == vendor.id || v.name == vendor.name)
+    {
+        return Err(SlbcError::invalid(format_args!(
+            "EXT vendor {} ('{}') is already registered",
+            v.id, v.name
+        )));
+    }
+    registry.push(vendor);
+    Ok(())
+}
+
+/// A registered vendor, by ID.
+pub fn lookup(id: u32) -> Option<Vendor> {
+    let registry = REGISTRY.read().unwrap_or_else(|e| e.into_inner());
+    registry.iter().find(|v| v.id == id).cloned()
+}
+
+/// Every registered vendor, in registration order.
+pub fn vendors() -> Vec<Vendor> {
+    REGISTRY.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// One line naming an EXT chunk's vendor and sub-type, by name where the
+/// vendor is registered.
+pub fn describe(ext: &ExtChunk) -> String {
+    let Some(vendor) = lookup(ext.vendor) else {
+        return format!(
+            "vendor {} (unregistered), sub-type {}, {} bytes",
+            ext.vendor,
+            ext.subtype,
+            ext.data.len()
+        );
+    };
+    let subtype = match vendor.subtype_name(ext.subtype) {
+        Some(name) => format!("{} ({})", name, ext.subtype),
+        None => ext.subtype.to_string(),
+    };
+    format!(
+        "{} (vendor {}), sub-type {}, {} bytes",
+        vendor.name,
+        ext.vendor,
+        subtype,
+        ext.data.len()
+    )
+}
+
+/// A container with `ext` added before EOF. Earlier EXT chunks, of this
+/// vendor or another, are kept.
+pub fn attach(data: &[u8], ext: &ExtChunk) -> Result<Vec<u8>, SlbcError> {
+    let (header, chunks) = container::parse_slbc(data)?;
+    let new = ext.to_chunk()?;
+    let mut out = header.to_bytes_with(&header.ext_records()?)?;
+    for chunk in &chunks {
+        if chunk.chunk_type == CHUNK_EOF {
+            container::write_chunk(&mut out, new.chunk_type, &new.payload);
+        }
+        container::write_chunk(&mut out, chunk.chunk_type, &chunk.payload);
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoder::{self, Script};
+    use crate::dict::{self, DictDirs};
+    use crate::encoder;
+
+    #[test]
+    fn test_attach_and_read() {
+        let data = encoder::encode_iast("oṃ namaḥ śivāya").unwrap();
+        let file = container::build_slbc(&data);
+        let scan = ExtChunk::new(300, 1, b"page 12".to_vec());
+        let notes = ExtChunk::new(301, 0, Vec::new());
+        let out = attach(&attach(&file, &scan).unwrap(), &notes).unwrap();
+
+        let (_, chunks) = container::parse_slbc(&out).unwrap();
+        assert_eq!(ExtChunk::all(&chunks).unwrap(), [scan.clone(), notes]);
+        assert_eq!(ExtChunk::of_vendor(&chunks, 300).unwrap(), [scan]);
+        assert_eq!(chunks.last().unwrap().chunk_type, CHUNK_EOF);
+
+        // Readers step over EXT chunks
+        let (_, opened, _) = dict::open(&out, &DictDirs(Vec::new())).unwrap();
+        let phon = opened.iter().find(|c| c.chunk_type == CHUNK_PHON).unwrap();
+        assert_eq!(
+            decoder::decode_phon(&phon.payload, Script::Iast).unwrap(),
+            "oṃ namaḥ śivāya"
+        );
+
+        assert!(ExtChunk::new(0, 1, Vec::new()).to_chunk().is_err());
+        assert!(ExtChunk::from_chunk_payload(&[0x00, 0x01]).is_err());
+        assert!(ExtChunk::from_chunk_payload(&[0xAC]).is_err());
+    }
+
+    #[test]
+    fn test_registry() {
+        let ext = ExtChunk::new(4242, 2, vec![1, 2, 3]);
+        assert_eq!(
+            describe(&ext),
+            "vendor 4242 (unregistered), sub-type 2, 3 bytes"
+        );
+        register(Vendor::new(4242, "org.example.scanner").subtype(2, "page-images")).unwrap();
+        assert_eq!(
+            describe(&ext),
+            "org.example.scanner (vendor 4242), sub-type page-images (2), 3 bytes"
+        );
+        assert!(register(Vendor::new(4242, "other")).is_err());
+        assert!(register(Vendor::new(4243, "org.example.scanner")).is_err());
+        assert!(register(Vendor::new(0, "zero")).is_err());
+        assert!(vendors().iter().any(|v| v.id == 4242));
+    }
+}
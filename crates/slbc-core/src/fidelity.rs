@@ -0,0 +1,218 @@
+//! How faithfully each output script can carry a payload.
+//!
+//! Every distinct sound in the payload (core phoneme, extension phoneme, or
+//! accent) is rendered on its own in the target script. A sound the script
+//! has no letter for comes out as "?"; a sound written the same as another
+//! sound of the inventory cannot be told apart from it; an accent written
+//! the same as the unaccented vowel is dropped. Anything else survives
+//! export.
+
+use std::fmt;
+
+use crate::codepage;
+use crate::codespace::{self, Accent};
+use crate::decoder::{self, DecodeOptions, Script};
+use crate::error::SlbcError;
+use crate::lang;
+use crate::scheme;
+use crate::types::*;
+
+/// A sound a payload may contain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    /// Svara (accent bits cleared) or vyañjana byte.
+    Phoneme(u8),
+    /// Extension code.
+    Ext(u8),
+    Accent(Accent),
+}
+
+impl Unit {
+    /// IAST spelling, or the accent's name.
+    pub fn label(self) -> String {
+        match self {
+            Unit::Phoneme(b) => decoder::byte_to_iast(b).to_string(),
+            Unit::Ext(code) => scheme::IAST.ext_grapheme(code).unwrap_or("?").to_string(),
+            Unit::Accent(a) => match a {
+                Accent::Neutral => "unaccented",
+                Accent::Udatta => "udātta",
+                Accent::Anudatta => "anudātta",
+                Accent::Svarita => "svarita",
+            }
+            .to_string(),
+        }
+    }
+
+    /// A one-pada payload holding the unit (an accent on a).
+    fn payload(self) -> Vec<u8> {
+        match self {
+            Unit::Phoneme(b) => vec![PADA_START, b, PADA_END],
+            Unit::Ext(code) => vec![PADA_START, META_EXT, code, PADA_END],
+            Unit::Accent(a) => vec![PADA_START, 0x40 | (a.bits() << 4), PADA_END],
+        }
+    }
+}
+
+/// How a script degrades a unit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Degradation {
+    /// No letter for it ("?").
+    Missing,
+    /// Written the same as these other units.
+    Merged(Vec<Unit>),
+    /// The accent is not written.
+    Unmarked,
+}
+
+impl fmt::Display for Degradation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Degradation::Missing => write!(f, "cannot be written"),
+            Degradation::Merged(others) => {
+                let labels: Vec<String> = others.iter().map(|u| u.label()).collect();
+                write!(f, "written the same as {}", labels.join(", "))
+            }
+            Degradation::Unmarked => write!(f, "not marked"),
+        }
+    }
+}
+
+/// One unit of the payload a script degrades.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Loss {
+    pub unit: Unit,
+    /// Occurrences in the payload.
+    pub count: usize,
+    pub degradation: Degradation,
+}
+
+/// Every distinct unit in a payload's padas, with its count, in order of
+/// first occurrence. Numeral digit-words and META blocks are skipped.
+pub fn units(data: &[u8]) -> Result<Vec<(Unit, usize)>, SlbcError> {
+    let mut out: Vec<(Unit, usize)> = Vec::new();
+    let mut add = |unit: Unit| match out.iter_mut().find(|(u, _)| *u == unit) {
+        Some((_, n)) => *n += 1,
+        None => out.push((unit, 1)),
+    };
+    for (_, pada) in lang::padas(data)? {
+        let mut j = 0;
+        while j < pada.len() {
+            let b = pada[j];
+            j += 1;
+            match b {
+                META_EXT if pada.get(j) == Some(&codepage::EXT_LONG) => {
+                    j += codepage::ext_len(pada, j - 1)? - 1;
+                }
+                META_EXT if codepage::is_samasa(pada, j - 1) => j += 1,
+                META_EXT => {
+                    let code = codepage::read_ext(pada, j - 1)?.code;
+                    add(Unit::Ext(code));
+                    j += 1;
+                }
+                META_START => {
+                    while j < pada.len() && pada[j - 1] != META_END {
+                        j += 1;
+                    }
+                }
+                b if is_svara(b) => {
+                    add(Unit::Phoneme(b & 0b11_00_11_11));
+                    if Accent::of(b) != Accent::Neutral {
+                        add(Unit::Accent(Accent::of(b)));
+                    }
+                }
+                b if is_vyanjana(b) => add(Unit::Phoneme(b)),
+                _ => {}
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// Units of the same kind as `unit` that a reader could confuse it with.
+fn inventory(unit: Unit) -> Vec<Unit> {
+    match unit {
+        Unit::Accent(_) => Accent::ALL.into_iter().map(Unit::Accent).collect(),
+        _ => codespace::svaras()
+            .with_accent(Accent::Neutral)
+            .chain(codespace::vyanjanas())
+            .map(Unit::Phoneme)
+            .chain(codepage::CODE_PAGE.iter().map(|p| Unit::Ext(p.code)))
+            .collect(),
+    }
+}
+
+fn render(unit: Unit, script: Script, opts: &DecodeOptions) -> Option<String> {
+    decoder::decode_phon_with(&unit.payload(), script, opts)
+        .ok()
+        .filter(|text| !text.contains('?'))
+}
+
+/// The units of `data` that `script` cannot carry faithfully.
+pub fn losses(data: &[u8], script: Script, opts: &DecodeOptions) -> Result<Vec<Loss>, SlbcError> {
+    let mut out = Vec::new();
+    for (unit, count) in units(data)? {
+        let Some(text) = render(unit, script, opts) else {
+            out.push(Loss {
+                unit,
+                count,
+                degradation: Degradation::Missing,
+            });
+            continue;
+        };
+        let twins: Vec<Unit> = inventory(unit)
+            .into_iter()
+            .filter(|&other| other != unit && render(other, script, opts).as_ref() == Some(&text))
+            .collect();
+        let degradation = match (unit, twins.first()) {
+            (_, None) => continue,
+            (Unit::Accent(_), Some(Unit::Accent(Accent::Neutral))) => Degradation::Unmarked,
+            _ => Degradation::Merged(twins),
+        };
+        out.push(Loss {
+            unit,
+            count,
+            degradation,
+        });
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoder;
+
+    #[test]
+    fn test_units() {
+        let data = encoder::encode_devanagari("अ॒ग्निमी॑डे १०८ तमिऴ्").unwrap();
+        let found = units(&data).unwrap();
+        assert_eq!(found[0], (Unit::Phoneme(0x40), 2));
+        assert_eq!(found[1], (Unit::Accent(Accent::Anudatta), 1));
+        assert!(found.contains(&(Unit::Ext(0x01), 1)));
+        assert!(found.contains(&(Unit::Phoneme(0x44), 2)));
+        // Digit-words are not counted
+        assert!(!found.contains(&(Unit::Phoneme(0x29), 1)));
+    }
+
+    #[test]
+    fn test_losses() {
+        let data = encoder::encode_devanagari("अ॒ग्निमी॑डे तमिऴ् दुःखं").unwrap();
+        let opts = DecodeOptions::default();
+        assert!(losses(&data, Script::Devanagari, &opts).unwrap().is_empty());
+
+        let hk = losses(&data, Script::HarvardKyoto, &opts).unwrap();
+        assert_eq!(
+            hk.iter()
+                .filter(|l| matches!(l.unit, Unit::Accent(_)))
+                .count(),
+            2
+        );
+        assert!(hk
+            .iter()
+            .filter(|l| matches!(l.unit, Unit::Accent(_)))
+            .all(|l| l.degradation == Degradation::Unmarked));
+        assert!(hk
+            .iter()
+            .any(|l| l.unit == Unit::Ext(0x01) && l.degradation == Degradation::Missing));
+    }
+}
@@ -0,0 +1,681 @@
+//! Checked construction of control framing (§6.1, §8.1).
+//!
+//! The raw control constants in `types` make it easy to emit a PADA_END with
+//! no PADA_START, or a META block that never closes. `FrameBuilder` keeps a
+//! frame stack and rejects anything that would produce unbalanced framing.
+//!
+//! Payloads from other encoders may still carry harmless degenerate framing —
+//! empty padas, doubled SPACEs, unbalanced PHON frames. `check_framing` flags
+//! it and `normalize_framing` cleans it up; decoders normalize before reading.
+//!
+//! `verses` splits a payload at DOUBLE_DANDA for verse-by-verse tools,
+//! `meta_spans` lists the META blocks the text decoders skip, and
+//! `phon_spans` lists the (possibly nested) PHON frames.
+
+use std::ops::Range;
+
+use crate::codepage::{self, LongExt, EXT_LONG};
+use crate::container;
+use crate::error::SlbcError;
+use crate::numeral;
+use crate::types::*;
+
+/// A bracketed span in the bhāṣā lane.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frame {
+    /// PHON_START … PHON_END — recitation unit or quotation; may nest.
+    Phon,
+    /// PADA_START … PADA_END — word.
+    Pada,
+    /// META_START … META_END — grammar/meta block.
+    Meta,
+}
+
+impl Frame {
+    /// The byte that opens this frame.
+    pub fn open_byte(self) -> u8 {
+        match self {
+            Frame::Phon => PHON_START,
+            Frame::Pada => PADA_START,
+            Frame::Meta => META_START,
+        }
+    }
+
+    /// The byte that closes this frame.
+    pub fn close_byte(self) -> u8 {
+        match self {
+            Frame::Phon => PHON_END,
+            Frame::Pada => PADA_END,
+            Frame::Meta => META_END,
+        }
+    }
+
+    /// The frame opened by `b`, if it is an opening control byte.
+    pub fn from_open_byte(b: u8) -> Option<Frame> {
+        match b {
+            PHON_START => Some(Frame::Phon),
+            PADA_START => Some(Frame::Pada),
+            META_START => Some(Frame::Meta),
+            _ => None,
+        }
+    }
+
+    /// The frame closed by `b`, if it is a closing control byte.
+    pub fn from_close_byte(b: u8) -> Option<Frame> {
+        match b {
+            PHON_END => Some(Frame::Phon),
+            PADA_END => Some(Frame::Pada),
+            META_END => Some(Frame::Meta),
+            _ => None,
+        }
+    }
+
+    /// Control-byte name, as used by inspection.
+    pub fn name(self) -> &'static str {
+        match self {
+            Frame::Phon => "PHON",
+            Frame::Pada => "PADA",
+            Frame::Meta => "META",
+        }
+    }
+}
+
+/// Builds a PHON payload with checked framing.
+#[derive(Debug, Default)]
+pub struct FrameBuilder {
+    out: Vec<u8>,
+    stack: Vec<Frame>,
+}
+
+impl FrameBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The innermost open frame, if any.
+    pub fn current(&self) -> Option<Frame> {
+        self.stack.last().copied()
+    }
+
+    /// Number of open frames.
+    pub fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Open a frame.
+    ///
+    /// PHON may not nest inside PADA, PADA may not nest inside PADA, and
+    /// nothing may open inside META.
+    pub fn open(&mut self, frame: Frame) -> Result<(), SlbcError> {
+        match (self.current(), frame) {
+            (Some(Frame::Meta), _) => {
+                return Err(SlbcError::invalid(format_args!(
+                    "cannot open {} inside META",
+                    frame.name()
+                )));
+            }
+            (Some(Frame::Pada), Frame::Phon | Frame::Pada) => {
+                return Err(SlbcError::invalid(format_args!(
+                    "cannot open {} inside PADA",
+                    frame.name()
+                )));
+            }
+            _ => {}
+        }
+        self.out.push(frame.open_byte());
+        self.stack.push(frame);
+        Ok(())
+    }
+
+    /// Close the innermost frame, which must be `frame`.
+    pub fn close(&mut self, frame: Frame) -> Result<(), SlbcError> {
+        match self.current() {
+            Some(open) if open == frame => {
+                self.stack.pop();
+                self.out.push(frame.close_byte());
+                Ok(())
+            }
+            Some(open) => Err(SlbcError::invalid(format_args!(
+                "cannot close {} while {} is open",
+                frame.name(),
+                open.name()
+            ))),
+            None => Err(SlbcError::invalid(format_args!(
+                "cannot close {}: no frame is open",
+                frame.name()
+            ))),
+        }
+    }
+
+    /// Append a svara or vyañjana byte. Must be inside a PADA.
+    pub fn phoneme(&mut self, b: u8) -> Result<(), SlbcError> {
+        if !is_svara(b) && !is_vyanjana(b) {
+            return Err(SlbcError::invalid(format_args!(
+                "0x{:02X} is not a phoneme byte",
+                b
+            )));
+        }
+        if self.current() != Some(Frame::Pada) {
+            return Err(SlbcError::invalid(format_args!(
+                "phoneme 0x{:02X} outside PADA",
+                b
+            )));
+        }
+        self.out.push(b);
+        Ok(())
+    }
+
+    /// Append a lipi control: SPACE, DANDA, DOUBLE_DANDA, AVAGRAHA, or a gap
+    /// marker (ILLEGIBLE, LACUNA), or a LINE_BREAK.
+    ///
+    /// AVAGRAHA belongs inside a PADA; gap markers may go inside or between
+    /// padas; the others separate padas.
+    pub fn lipi(&mut self, b: u8) -> Result<(), SlbcError> {
+        match b {
+            ILLEGIBLE | LACUNA => {
+                if self.current() == Some(Frame::Meta) {
+                    return Err(SlbcError::invalid(format_args!(
+                        "lipi control 0x{:02X} inside META",
+                        b
+                    )));
+                }
+            }
+            AVAGRAHA => {
+                if self.current() != Some(Frame::Pada) {
+                    return Err(SlbcError::invalid("AVAGRAHA outside PADA"));
+                }
+            }
+            SPACE | DANDA | DOUBLE_DANDA | LINE_BREAK => {
+                if matches!(self.current(), Some(Frame::Pada | Frame::Meta)) {
+                    return Err(SlbcError::invalid(format_args!(
+                        "lipi control 0x{:02X} inside {}",
+                        b,
+                        self.stack_top_name()
+                    )));
+                }
+            }
+            _ => {
+                return Err(SlbcError::invalid(format_args!(
+                    "0x{:02X} is not a framing-safe lipi control",
+                    b
+                )))
+            }
+        }
+        self.out.push(b);
+        Ok(())
+    }
+
+    /// Append a numeral (SAṄKHYĀ + NUM spans). Must be outside any PADA.
+    pub fn numeral(&mut self, digits: &str) -> Result<(), SlbcError> {
+        if !digits.chars().all(|c| c.is_ascii_digit()) || digits.is_empty() {
+            return Err(SlbcError::invalid(format_args!(
+                "invalid numeral '{}'",
+                digits
+            )));
+        }
+        if matches!(self.current(), Some(Frame::Pada | Frame::Meta)) {
+            return Err(SlbcError::invalid(format_args!(
+                "numeral inside {}",
+                self.stack_top_name()
+            )));
+        }
+        numeral::encode_numeral(digits, &mut self.out);
+        Ok(())
+    }
+
+    /// Append raw bytes to the open META block. META_END is rejected —
+    /// close the block with `close(Frame::Meta)`.
+    pub fn meta_bytes(&mut self, bytes: &[u8]) -> Result<(), SlbcError> {
+        if self.current() != Some(Frame::Meta) {
+            return Err(SlbcError::invalid("META bytes outside META block"));
+        }
+        if bytes.contains(&META_END) {
+            return Err(SlbcError::invalid("META payload may not contain META_END"));
+        }
+        self.out.extend_from_slice(bytes);
+        Ok(())
+    }
+
+    /// Finish, returning the payload. Fails if any frame is still open.
+    pub fn finish(self) -> Result<Vec<u8>, SlbcError> {
+        if let Some(open) = self.current() {
+            return Err(SlbcError::invalid(format_args!(
+                "unclosed {} frame",
+                open.name()
+            )));
+        }
+        Ok(self.out)
+    }
+
+    fn stack_top_name(&self) -> &'static str {
+        self.current().map(Frame::name).unwrap_or("payload")
+    }
+}
+
+// ═══════════════════════════════════════════════
+//  Degenerate framing
+// ═══════════════════════════════════════════════
+
+/// Framing that decodes unambiguously but that no conforming encoder emits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Degeneracy {
+    /// PADA_START immediately followed by PADA_END.
+    EmptyPada,
+    /// SPACE directly after another SPACE (once empty padas are dropped).
+    DoubledSpace,
+    /// PHON_END with no open PHON frame.
+    StrayPhonEnd,
+    /// PHON frame still open at the end of the payload.
+    UnclosedPhon,
+}
+
+impl Degeneracy {
+    pub fn describe(self) -> &'static str {
+        match self {
+            Degeneracy::EmptyPada => "empty pada",
+            Degeneracy::DoubledSpace => "doubled SPACE",
+            Degeneracy::StrayPhonEnd => "PHON_END without PHON_START",
+            Degeneracy::UnclosedPhon => "unclosed PHON frame",
+        }
+    }
+}
+
+/// A degenerate framing site, by payload offset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FramingIssue {
+    pub offset: usize,
+    pub kind: Degeneracy,
+}
+
+/// Find degenerate framing in a PHON payload.
+pub fn check_framing(data: &[u8]) -> Result<Vec<FramingIssue>, SlbcError> {
+    Ok(scan_framing(data)?.1)
+}
+
+/// Rewrite a PHON payload without degenerate framing: empty padas and
+/// repeated SPACEs are dropped, stray PHON_ENDs removed, and unclosed PHON
+/// frames closed. Nested PHON frames are kept. Returns the payload and the
+/// number of sites fixed.
+pub fn normalize_framing(data: &[u8]) -> Result<(Vec<u8>, usize), SlbcError> {
+    let (out, issues) = scan_framing(data)?;
+    Ok((out, issues.len()))
+}
+
+fn scan_framing(data: &[u8]) -> Result<(Vec<u8>, Vec<FramingIssue>), SlbcError> {
+    let mut out = Vec::with_capacity(data.len());
+    let mut issues = Vec::new();
+    let mut phon_depth = 0usize;
+    let mut i = 0;
+
+    while i < data.len() {
+        let b = data[i];
+        let mut flag = |kind| issues.push(FramingIssue { offset: i, kind });
+        let span = match b {
+            SANKHYA_START => numeral::decode_sankhya(data, i)?.1,
+            NUM => numeral::decode_num(data, i)?.1,
+            // The index that follows may encode as any control byte
+            DICT_REF => {
+                let (_, consumed) = container::read_uleb128(&data[i + 1..])
+                    .map_err(|e| e.context(i + 1, "DICT_REF index error"))?;
+                1 + consumed
+            }
+            META_EXT => codepage::ext_len(data, i)?,
+            META_START => data[i..]
+                .iter()
+                .position(|&b| b == META_END)
+                .map_or(data.len() - i, |p| p + 1),
+            PADA_START if data.get(i + 1) == Some(&PADA_END) => {
+                flag(Degeneracy::EmptyPada);
+                i += 2;
+                continue;
+            }
+            SPACE if out.last() == Some(&SPACE) => {
+                flag(Degeneracy::DoubledSpace);
+                i += 1;
+                continue;
+            }
+            PHON_START => {
+                phon_depth += 1;
+                1
+            }
+            PHON_END if phon_depth == 0 => {
+                flag(Degeneracy::StrayPhonEnd);
+                i += 1;
+                continue;
+            }
+            PHON_END => {
+                phon_depth -= 1;
+                1
+            }
+            _ => 1,
+        };
+        out.extend_from_slice(&data[i..i + span]);
+        i += span;
+    }
+
+    if phon_depth > 0 {
+        issues.push(FramingIssue {
+            offset: data.len(),
+            kind: Degeneracy::UnclosedPhon,
+        });
+        out.extend(std::iter::repeat_n(PHON_END, phon_depth));
+    }
+    Ok((out, issues))
+}
+
+// ═══════════════════════════════════════════════
+//  Verses
+// ═══════════════════════════════════════════════
+
+/// Split a payload into verses: spans ending at each DOUBLE_DANDA (kept with
+/// its verse), plus any trailing text after the last one. SPACEs and line
+/// breaks before a verse's first byte are left out, and spans of nothing
+/// else are dropped.
+pub fn verses(data: &[u8]) -> Result<Vec<Range<usize>>, SlbcError> {
+    let mut out = Vec::new();
+    let mut start = 0;
+    let mut i = 0;
+    let mut push = |start: usize, end: usize| {
+        let first = data[start..end]
+            .iter()
+            .position(|&b| b != SPACE && b != LINE_BREAK);
+        if let Some(first) = first {
+            out.push(start + first..end);
+        }
+    };
+
+    while i < data.len() {
+        i += match data[i] {
+            SANKHYA_START => numeral::decode_sankhya(data, i)?.1,
+            NUM => numeral::decode_num(data, i)?.1,
+            DICT_REF => {
+                let (_, consumed) = container::read_uleb128(&data[i + 1..])
+                    .map_err(|e| e.context(i + 1, "DICT_REF index error"))?;
+                1 + consumed
+            }
+            META_EXT => codepage::ext_len(data, i)?,
+            META_START => data[i..]
+                .iter()
+                .position(|&b| b == META_END)
+                .map_or(data.len() - i, |p| p + 1),
+            DOUBLE_DANDA => {
+                push(start, i + 1);
+                start = i + 1;
+                1
+            }
+            _ => 1,
+        };
+    }
+    push(start, data.len());
+    Ok(out)
+}
+
+// ═══════════════════════════════════════════════
+//  PHON spans
+// ═══════════════════════════════════════════════
+
+/// A PHON_START … PHON_END span, by payload offset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PhonSpan {
+    /// The bytes between the delimiters.
+    pub body: Range<usize>,
+    /// Number of PHON frames around this one.
+    pub depth: usize,
+}
+
+/// Every PHON frame in a payload, outer frames before the frames they hold.
+/// Unlike the decoders this is strict: a PHON_END without a PHON_START, a
+/// frame left open, or a PHON boundary inside a pada is an error.
+pub fn phon_spans(data: &[u8]) -> Result<Vec<PhonSpan>, SlbcError> {
+    let mut out = Vec::new();
+    // Indices into `out` of the open frames
+    let mut open: Vec<usize> = Vec::new();
+    let mut in_pada = false;
+    let mut i = 0;
+
+    while i < data.len() {
+        let b = data[i];
+        if matches!(b, PHON_START | PHON_END) && in_pada {
+            return Err(SlbcError::malformed(i, "PHON boundary inside a pada"));
+        }
+        i += match b {
+            SANKHYA_START => numeral::decode_sankhya(data, i)?.1,
+            NUM => numeral::decode_num(data, i)?.1,
+            DICT_REF => {
+                let (_, consumed) = container::read_uleb128(&data[i + 1..])
+                    .map_err(|e| e.context(i + 1, "DICT_REF index error"))?;
+                1 + consumed
+            }
+            META_EXT => codepage::ext_len(data, i)?,
+            META_START => data[i..]
+                .iter()
+                .position(|&b| b == META_END)
+                .map_or(data.len() - i, |p| p + 1),
+            PADA_START | PADA_END => {
+                in_pada = b == PADA_START;
+                1
+            }
+            PHON_START => {
+                open.push(out.len());
+                out.push(PhonSpan {
+                    body: i + 1..i + 1,
+                    depth: open.len() - 1,
+                });
+                1
+            }
+            PHON_END => {
+                let n = open
+                    .pop()
+                    .ok_or_else(|| SlbcError::malformed(i, "PHON_END without PHON_START"))?;
+                out[n].body.end = i;
+                1
+            }
+            _ => 1,
+        };
+    }
+    match open.first() {
+        Some(&n) => Err(SlbcError::truncated(
+            out[n].body.start - 1,
+            "unclosed PHON frame",
+        )),
+        None => Ok(out),
+    }
+}
+
+// ═══════════════════════════════════════════════
+//  META blocks
+// ═══════════════════════════════════════════════
+
+/// A META_START … META_END block, by payload offset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetaSpan {
+    /// Offset of META_START.
+    pub offset: usize,
+    /// The bytes between the delimiters.
+    pub bytes: Vec<u8>,
+    /// False when the payload ends before META_END.
+    pub closed: bool,
+}
+
+/// Every META block in a payload, in order. Numeral spans, DICT_REF
+/// indices, and long extensions are stepped over, so bytes inside them are
+/// never mistaken for META_START.
+pub fn meta_spans(data: &[u8]) -> Result<Vec<MetaSpan>, SlbcError> {
+    let mut out = Vec::new();
+    let mut i = 0;
+
+    while i < data.len() {
+        i += match data[i] {
+            SANKHYA_START => numeral::decode_sankhya(data, i)?.1,
+            NUM => numeral::decode_num(data, i)?.1,
+            DICT_REF => {
+                let (_, consumed) = container::read_uleb128(&data[i + 1..])
+                    .map_err(|e| e.context(i + 1, "DICT_REF index error"))?;
+                1 + consumed
+            }
+            META_EXT => codepage::ext_len(data, i)?,
+            META_START => {
+                let end = data[i + 1..].iter().position(|&b| b == META_END);
+                let body = &data[i + 1..end.map_or(data.len(), |p| i + 1 + p)];
+                out.push(MetaSpan {
+                    offset: i,
+                    bytes: body.to_vec(),
+                    closed: end.is_some(),
+                });
+                body.len() + 1 + end.map_or(0, |_| 1)
+            }
+            _ => 1,
+        };
+    }
+    Ok(out)
+}
+
+/// Every long extension in a payload, in order (see `codepage::EXT_LONG`).
+pub fn long_exts(data: &[u8]) -> Result<Vec<LongExt>, SlbcError> {
+    let mut out = Vec::new();
+    let mut i = 0;
+
+    while i < data.len() {
+        i += match data[i] {
+            SANKHYA_START => numeral::decode_sankhya(data, i)?.1,
+            NUM => numeral::decode_num(data, i)?.1,
+            DICT_REF => {
+                let (_, consumed) = container::read_uleb128(&data[i + 1..])
+                    .map_err(|e| e.context(i + 1, "DICT_REF index error"))?;
+                1 + consumed
+            }
+            META_START => data[i..]
+                .iter()
+                .position(|&b| b == META_END)
+                .map_or(data.len() - i, |p| p + 1),
+            META_EXT if data.get(i + 1) == Some(&EXT_LONG) => {
+                let (payload, len) = codepage::read_long_ext(data, i)?;
+                out.push(LongExt {
+                    offset: i,
+                    payload: payload.to_vec(),
+                });
+                len
+            }
+            META_EXT => codepage::ext_len(data, i)?,
+            _ => 1,
+        };
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoder;
+
+    #[test]
+    fn test_builder_matches_encoder() {
+        let mut b = FrameBuilder::new();
+        b.open(Frame::Pada).unwrap();
+        b.phoneme(0x1C).unwrap();
+        b.phoneme(0x40).unwrap();
+        b.close(Frame::Pada).unwrap();
+        b.lipi(SPACE).unwrap();
+        b.open(Frame::Pada).unwrap();
+        b.phoneme(0x08).unwrap();
+        b.phoneme(0x40).unwrap();
+        b.close(Frame::Pada).unwrap();
+        assert_eq!(b.finish().unwrap(), encoder::encode_iast("na ca").unwrap());
+    }
+
+    #[test]
+    fn test_rejects_unbalanced() {
+        let mut b = FrameBuilder::new();
+        assert!(b.close(Frame::Pada).is_err());
+        b.open(Frame::Phon).unwrap();
+        b.open(Frame::Pada).unwrap();
+        assert!(b.close(Frame::Phon).is_err());
+        assert!(b.open(Frame::Pada).is_err());
+        assert!(b.lipi(SPACE).is_err());
+        b.lipi(ILLEGIBLE).unwrap();
+        b.close(Frame::Pada).unwrap();
+        b.lipi(LACUNA).unwrap();
+        assert!(b.finish().is_err()); // PHON still open
+    }
+
+    #[test]
+    fn test_meta_block() {
+        let mut b = FrameBuilder::new();
+        b.open(Frame::Pada).unwrap();
+        b.open(Frame::Meta).unwrap();
+        assert!(b.phoneme(0x00).is_err());
+        assert!(b.meta_bytes(&[0x01, META_END]).is_err());
+        b.meta_bytes(&[0x01, 0x02]).unwrap();
+        b.close(Frame::Meta).unwrap();
+        b.phoneme(0x00).unwrap();
+        b.close(Frame::Pada).unwrap();
+        assert_eq!(
+            b.finish().unwrap(),
+            vec![PADA_START, META_START, 0x01, 0x02, META_END, 0x00, PADA_END]
+        );
+    }
+
+    #[test]
+    fn test_normalize_degenerate_framing() {
+        let clean = encoder::encode_iast("na ca").unwrap();
+        let mut messy = vec![PHON_START, PHON_START, PADA_START, PADA_END, SPACE];
+        messy.extend_from_slice(&clean[..4]); // "na" pada + SPACE
+        messy.push(SPACE);
+        messy.extend_from_slice(&clean[4..]);
+        messy.extend_from_slice(&[PHON_END, PHON_END, PHON_END, PHON_START]);
+
+        let kinds: Vec<Degeneracy> = check_framing(&messy)
+            .unwrap()
+            .iter()
+            .map(|i| i.kind)
+            .collect();
+        use Degeneracy::*;
+        assert_eq!(kinds, [EmptyPada, DoubledSpace, StrayPhonEnd, UnclosedPhon]);
+
+        let (fixed, n) = normalize_framing(&messy).unwrap();
+        assert_eq!(n, 4);
+        // Nesting is kept
+        let mut expected = vec![PHON_START, PHON_START, SPACE];
+        expected.extend_from_slice(&clean);
+        expected.extend_from_slice(&[PHON_END, PHON_END, PHON_START, PHON_END]);
+        assert_eq!(fixed, expected);
+        assert!(check_framing(&clean).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_phon_spans() {
+        let data = encoder::encode_iast("āha ⟨ rāmaḥ ⟨ na ⟩ ⟩ iti").unwrap();
+        let spans = phon_spans(&data).unwrap();
+        assert_eq!(spans.len(), 2);
+        assert_eq!(spans.iter().map(|s| s.depth).collect::<Vec<_>>(), [0, 1]);
+        assert!(spans[0].body.start < spans[1].body.start && spans[1].body.end < spans[0].body.end);
+        let inner =
+            crate::decoder::decode_phon(&data[spans[1].body.clone()], crate::decoder::Script::Iast);
+        assert_eq!(inner.unwrap().trim(), "na");
+
+        assert!(phon_spans(&[PHON_END]).is_err());
+        assert!(phon_spans(&[PHON_START, PHON_START, PHON_END]).is_err());
+        assert!(phon_spans(&[PADA_START, 0x00, PHON_START, 0x40, PADA_END]).is_err());
+    }
+
+    #[test]
+    fn test_verses() {
+        let data = encoder::encode_iast("a b || c 17 || d").unwrap();
+        let text: Vec<String> = verses(&data)
+            .unwrap()
+            .into_iter()
+            .map(|r| crate::decoder::decode_phon(&data[r], crate::decoder::Script::Iast).unwrap())
+            .collect();
+        assert_eq!(text, ["a b ||", "c 17 ||", "d"]);
+        let data = encoder::encode_iast("a ||").unwrap();
+        assert_eq!(verses(&data).unwrap(), vec![0..data.len()]);
+    }
+
+    #[test]
+    fn test_frame_bytes_roundtrip() {
+        for f in [Frame::Phon, Frame::Pada, Frame::Meta] {
+            assert_eq!(Frame::from_open_byte(f.open_byte()), Some(f));
+            assert_eq!(Frame::from_close_byte(f.close_byte()), Some(f));
+        }
+    }
+}
@@ -0,0 +1,145 @@
+//! Differential checks against a corpus of verified IAST ↔ Devanāgarī pairs.
+//!
+//! Each pair is run through the codec (IAST → SLBC → Devanāgarī,
+//! IAST → SLBC → IAST, and Devanāgarī → SLBC → IAST) and any output that
+//! differs from the reference is reported. The bundled corpus holds lines from standard editions; users
+//! migrating an archive can check their own pairs the same way.
+//!
+//! Corpus format: one pair per line, `source<TAB>iast<TAB>devanagari`.
+//! Blank lines and lines starting with `#` are ignored.
+
+use crate::decoder::{self, Script};
+use crate::encoder;
+use crate::error::SlbcError;
+
+/// The bundled reference corpus.
+pub const BUNDLED: &str = include_str!("../testdata/golden_pairs.tsv");
+
+/// One verified pair.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pair {
+    /// 1-based line number in the corpus.
+    pub line: usize,
+    pub source: String,
+    pub iast: String,
+    pub devanagari: String,
+}
+
+/// Which conversion diverged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    IastToDevanagari,
+    IastRoundtrip,
+    DevanagariToIast,
+}
+
+impl Direction {
+    pub fn name(self) -> &'static str {
+        match self {
+            Direction::IastToDevanagari => "iast→devanagari",
+            Direction::IastRoundtrip => "iast→iast",
+            Direction::DevanagariToIast => "devanagari→iast",
+        }
+    }
+}
+
+/// A pair whose codec output differs from the reference.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Divergence {
+    pub line: usize,
+    pub source: String,
+    pub direction: Direction,
+    pub expected: String,
+    /// Codec output, or the codec error.
+    pub actual: Result<String, SlbcError>,
+}
+
+/// Parse a corpus.
+pub fn parse_pairs(tsv: &str) -> Result<Vec<Pair>, SlbcError> {
+    let mut pairs = Vec::new();
+    for (n, line) in tsv.lines().enumerate() {
+        let line = line.trim_end_matches('\r');
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line.split('\t').collect();
+        let [source, iast, devanagari] = fields[..] else {
+            return Err(SlbcError::invalid(format_args!(
+                "line {}: expected 3 tab-separated fields, found {}",
+                n + 1,
+                fields.len()
+            )));
+        };
+        pairs.push(Pair {
+            line: n + 1,
+            source: source.to_string(),
+            iast: iast.to_string(),
+            devanagari: devanagari.to_string(),
+        });
+    }
+    Ok(pairs)
+}
+
+/// Run every pair through the codec and collect divergences.
+pub fn check(pairs: &[Pair]) -> Vec<Divergence> {
+    let mut out = Vec::new();
+    for pair in pairs {
+        let encoded = encoder::encode_iast(&pair.iast);
+        let from_deva = encoder::encode_devanagari(&pair.devanagari);
+        let runs = [
+            (
+                Direction::IastToDevanagari,
+                &encoded,
+                Script::Devanagari,
+                &pair.devanagari,
+            ),
+            (Direction::IastRoundtrip, &encoded, Script::Iast, &pair.iast),
+            (
+                Direction::DevanagariToIast,
+                &from_deva,
+                Script::Iast,
+                &pair.iast,
+            ),
+        ];
+        for (direction, input, script, expected) in runs {
+            let actual = input
+                .clone()
+                .and_then(|data| decoder::decode_phon(&data, script));
+            if actual.as_ref() != Ok(expected) {
+                out.push(Divergence {
+                    line: pair.line,
+                    source: pair.source.clone(),
+                    direction,
+                    expected: expected.clone(),
+                    actual,
+                });
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_corpus_agrees() {
+        let pairs = parse_pairs(BUNDLED).unwrap();
+        assert!(pairs.len() >= 20);
+        let divergences = check(&pairs);
+        assert!(divergences.is_empty(), "{:#?}", divergences);
+    }
+
+    #[test]
+    fn test_divergence_reported() {
+        let pairs = parse_pairs("x\tkṛṣṇa\tकृष्न\n# comment\n").unwrap();
+        let d = check(&pairs);
+        assert_eq!(d.len(), 2);
+        assert_eq!(d[0].direction, Direction::IastToDevanagari);
+        assert_eq!(d[0].actual.as_deref(), Ok("कृष्ण"));
+        assert_eq!(d[1].direction, Direction::DevanagariToIast);
+        assert_eq!(d[1].actual.as_deref(), Ok("kṛṣna"));
+        assert!(parse_pairs("only\ttwo").is_err());
+    }
+}
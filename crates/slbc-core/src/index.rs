@@ -0,0 +1,254 @@
+//! Pada and verse offsets (IDX chunk, kind 0x01).
+//!
+//! The offsets of every top-level pada (see `lang::padas`) and every verse
+//! (see `frame::verses`) of one PHON chunk, so a reader can seek to one word
+//! or verse without reading the rest (`container::seek_pada`,
+//! `container::seek_verse`). As for the verse checksums, offsets are into
+//! the PHON payload as stored: a span may hold packed codes or dictionary
+//! references, which `pack::unpack` and `dict::expand` resolve.
+//!
+//! IDX payload (pada-offset kind):
+//! ```text
+//! IDX_PADA | PHON chunk ordinal (ULEB128) | pada count (ULEB128)
+//! per pada: gap (ULEB128) | length (ULEB128)
+//! verse count (ULEB128)
+//! per verse: gap (ULEB128) | length (ULEB128)
+//! ```
+//! A pada runs from its PADA_START through its PADA_END. `gap` is the
+//! distance from the end of the previous span of its list (or the start of
+//! the payload) to the start of this one.
+
+use std::ops::Range;
+
+use crate::container::{self, Chunk};
+use crate::error::SlbcError;
+use crate::frame;
+use crate::lang;
+use crate::types::*;
+
+/// Pada and verse spans of one PHON chunk.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PadaIndex {
+    /// Which PHON chunk (0-based, among PHON chunks) the offsets refer to.
+    pub phon_chunk: u32,
+    pub padas: Vec<Range<u32>>,
+    pub verses: Vec<Range<u32>>,
+}
+
+fn read_field(data: &[u8], pos: &mut usize, what: &str) -> Result<u64, SlbcError> {
+    let (v, consumed) = container::read_uleb128(&data[(*pos).min(data.len())..])
+        .map_err(|e| e.context(*pos, format_args!("pada index {} error", what)))?;
+    *pos += consumed;
+    Ok(v)
+}
+
+fn write_spans(out: &mut Vec<u8>, spans: &[Range<u32>]) {
+    container::write_uleb128(out, spans.len() as u64);
+    let mut end = 0;
+    for span in spans {
+        container::write_uleb128(out, (span.start - end) as u64);
+        container::write_uleb128(out, span.len() as u64);
+        end = span.end;
+    }
+}
+
+fn read_spans(data: &[u8], pos: &mut usize, what: &str) -> Result<Vec<Range<u32>>, SlbcError> {
+    let count = read_field(data, pos, &format!("{} count", what))?;
+    let mut spans = Vec::with_capacity(count.min(1 << 16) as usize);
+    let mut end = 0u32;
+    for _ in 0..count {
+        let field = *pos;
+        let gap = read_field(data, pos, "gap")?;
+        let len = read_field(data, pos, "length")?;
+        let out_of_range = |_| {
+            SlbcError::malformed(
+                field,
+                format_args!("pada index {} offset out of range", what),
+            )
+        };
+        let start = u32::try_from(end as u64 + gap).map_err(out_of_range)?;
+        end = u32::try_from(start as u64 + len).map_err(out_of_range)?;
+        spans.push(start..end);
+    }
+    Ok(spans)
+}
+
+/// Replace the spans inside `start..end` with `fresh`, or stretch the one
+/// span around it, and move the spans after it to end where `end` now
+/// ends, at `new_end`.
+fn respan(
+    spans: &mut Vec<Range<u32>>,
+    start: u32,
+    end: u32,
+    new_end: u32,
+    fresh: Vec<Range<u32>>,
+    what: &str,
+) -> Result<(), SlbcError> {
+    let moved = |o: u32| o - end + new_end;
+    let first = spans.partition_point(|s| s.end <= start);
+    let last = spans.partition_point(|s| s.start < end);
+    let after = match &mut spans[first..last] {
+        [s] if s.start <= start && end <= s.end && (s.start, s.end) != (start, end) => {
+            s.end = moved(s.end);
+            last
+        }
+        touched if touched.iter().all(|s| start <= s.start && s.end <= end) => {
+            let count = fresh.len();
+            spans.splice(first..last, fresh);
+            first + count
+        }
+        _ => {
+            return Err(SlbcError::invalid(format_args!(
+                "replaced bytes cut across a {}",
+                what
+            )))
+        }
+    };
+    for s in &mut spans[after..] {
+        *s = moved(s.start)..moved(s.end);
+    }
+    Ok(())
+}
+
+impl PadaIndex {
+    /// Index the padas and verses of a stored PHON payload.
+    pub fn build(data: &[u8], phon_chunk: u32) -> Result<Self, SlbcError> {
+        let padas = lang::padas(data)?
+            .into_iter()
+            .map(|(start, body)| start as u32..(start + body.len() + 2) as u32)
+            .collect();
+        let verses = frame::verses(data)?
+            .into_iter()
+            .map(|r| r.start as u32..r.end as u32)
+            .collect();
+        Ok(PadaIndex {
+            phon_chunk,
+            padas,
+            verses,
+        })
+    }
+
+    /// Update the index after the payload bytes `start..start + old_len` were
+    /// replaced by `replacement`, which must hold whole padas, and whole
+    /// verses or part of one (e.g. a pada). Spans inside the replaced bytes
+    /// are indexed afresh, a verse around them grows or shrinks, and later
+    /// spans move.
+    pub fn splice(
+        &mut self,
+        start: u32,
+        old_len: u32,
+        replacement: &[u8],
+    ) -> Result<(), SlbcError> {
+        let end = start + old_len;
+        let new_end = start + replacement.len() as u32;
+        let fresh = PadaIndex::build(replacement, self.phon_chunk)?;
+        let shift = |spans: Vec<Range<u32>>| {
+            spans
+                .into_iter()
+                .map(|s| start + s.start..start + s.end)
+                .collect()
+        };
+        respan(
+            &mut self.padas,
+            start,
+            end,
+            new_end,
+            shift(fresh.padas),
+            "pada",
+        )?;
+        respan(
+            &mut self.verses,
+            start,
+            end,
+            new_end,
+            shift(fresh.verses),
+            "verse",
+        )
+    }
+
+    /// The padas of verse `n`, as indices into `padas`.
+    pub fn verse_padas(&self, n: usize) -> Option<Range<usize>> {
+        let verse = self.verses.get(n)?;
+        let first = self.padas.partition_point(|p| p.start < verse.start);
+        let end = self.padas.partition_point(|p| p.end <= verse.end);
+        Some(first..end.max(first))
+    }
+
+    /// Build the IDX chunk holding this index.
+    pub fn to_chunk(&self) -> Chunk {
+        let mut payload = vec![IDX_PADA];
+        container::write_uleb128(&mut payload, self.phon_chunk as u64);
+        write_spans(&mut payload, &self.padas);
+        write_spans(&mut payload, &self.verses);
+        Chunk {
+            chunk_type: CHUNK_IDX,
+            payload,
+        }
+    }
+
+    /// Parse an IDX chunk payload. Returns `None` for other index kinds.
+    pub fn from_chunk_payload(payload: &[u8]) -> Result<Option<Self>, SlbcError> {
+        if payload.first() != Some(&IDX_PADA) {
+            return Ok(None);
+        }
+        let mut pos = 1;
+        let phon_chunk = read_field(payload, &mut pos, "chunk ordinal")? as u32;
+        let padas = read_spans(payload, &mut pos, "pada")?;
+        let verses = read_spans(payload, &mut pos, "verse")?;
+        if pos != payload.len() {
+            return Err(SlbcError::malformed(pos, "trailing bytes in pada index"));
+        }
+        Ok(Some(PadaIndex {
+            phon_chunk,
+            padas,
+            verses,
+        }))
+    }
+
+    /// Find the pada index among a container's chunks.
+    pub fn find(chunks: &[Chunk]) -> Result<Option<Self>, SlbcError> {
+        for chunk in chunks.iter().filter(|c| c.chunk_type == CHUNK_IDX) {
+            if let Some(index) = PadaIndex::from_chunk_payload(&chunk.payload)? {
+                return Ok(Some(index));
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoder::{self, Script};
+    use crate::encoder;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_pada_index_and_seek() {
+        let text = "tat savitur vareṇyaṃ || bhargo 12 devasya || dhiyo yo naḥ pracodayāt ||";
+        let data = encoder::encode_iast(text).unwrap();
+        let index = PadaIndex::build(&data, 0).unwrap();
+        assert_eq!(index.padas.len(), 9);
+        assert_eq!(index.verses.len(), 3);
+        assert_eq!(index.verse_padas(1), Some(3..5));
+        assert_eq!(index.verse_padas(3), None);
+        let chunk = index.to_chunk();
+        assert_eq!(PadaIndex::find(&[chunk]).unwrap(), Some(index.clone()));
+
+        let file = container::build_slbc_with(&data, &[index.to_chunk()]);
+        let pada = container::seek_pada(&mut Cursor::new(&file), 4).unwrap();
+        assert_eq!(
+            decoder::decode_phon(&pada, Script::Iast).unwrap(),
+            "devasya"
+        );
+        let verse = container::seek_verse(&mut Cursor::new(&file), 1).unwrap();
+        assert_eq!(
+            decoder::decode_phon(&verse, Script::Iast).unwrap(),
+            "bhargo 12 devasya ||"
+        );
+        assert!(container::seek_verse(&mut Cursor::new(&file), 3).is_err());
+
+        let bare = container::build_slbc(&data);
+        assert!(container::seek_pada(&mut Cursor::new(&bare), 0).is_err());
+    }
+}
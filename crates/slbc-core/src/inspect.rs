@@ -1,8 +1,22 @@
 //! Byte introspection — explains what any SLBC byte represents.
 
+use crate::codepage;
 use crate::decoder;
+use crate::error::SlbcError;
+use crate::json;
 use crate::types::*;
 
+/// One field of an inspected byte.
+#[derive(Debug)]
+pub struct ByteField {
+    pub name: String,
+    pub value: String,
+    /// Spec section defining the field (e.g. "§4").
+    pub spec: &'static str,
+    /// Pāṇinian term for the field (e.g. "kāla").
+    pub term: &'static str,
+}
+
 /// Detailed description of an SLBC byte.
 #[derive(Debug)]
 pub struct ByteInfo {
@@ -11,7 +25,20 @@ pub struct ByteInfo {
     pub binary: String,
     pub class: String,
     pub description: String,
-    pub fields: Vec<(String, String)>,
+    /// Spec section defining the byte (e.g. "§3.3").
+    pub spec: &'static str,
+    /// Pāṇinian categories: varṇa class and pratyāhāra memberships.
+    pub categories: Vec<String>,
+    pub fields: Vec<ByteField>,
+}
+
+fn field(name: &str, value: String, spec: &'static str, term: &'static str) -> ByteField {
+    ByteField {
+        name: name.into(),
+        value,
+        spec,
+        term,
+    }
 }
 
 /// Inspect a single SLBC byte and return its full description.
@@ -35,6 +62,92 @@ pub fn inspect_byte(b: u8) -> ByteInfo {
         return inspect_lipi_control(b, hex, binary);
     }
 
+    if b == MORPH {
+        return ByteInfo {
+            byte: b,
+            hex,
+            binary,
+            class: "Annotation".into(),
+            description: "MORPH (morpheme seam, rendered +)".into(),
+            spec: "§6.5",
+            categories: vec!["morpheme".into()],
+            fields: vec![],
+        };
+    }
+
+    if b == CANDRABINDU {
+        return ByteInfo {
+            byte: b,
+            hex,
+            binary,
+            class: "Annotation".into(),
+            description: "CANDRABINDU (preceding anusvāra written m̐ / ँ)".into(),
+            spec: "§6.6",
+            categories: vec!["lipi".into()],
+            fields: vec![],
+        };
+    }
+
+    if b == PRANAVA {
+        return ByteInfo {
+            byte: b,
+            hex,
+            binary,
+            class: "Annotation".into(),
+            description: "PRANAVA (preceding oṃ written as ॐ)".into(),
+            spec: "§6.7",
+            categories: vec!["lipi".into()],
+            fields: vec![],
+        };
+    }
+
+    if b == YAMA || b == NASIKYA {
+        let description = match b {
+            YAMA => "YAMA (nasal twin of the preceding stop, before a nasal)",
+            _ => "NASIKYA (nasal glide after the preceding h, before a nasal)",
+        };
+        return ByteInfo {
+            byte: b,
+            hex,
+            binary,
+            class: "Annotation".into(),
+            description: description.into(),
+            spec: "§6.8",
+            categories: vec!["bhāṣā".into(), "prātiśākhya".into()],
+            fields: vec![],
+        };
+    }
+
+    if b == CAPITAL || b == UPPERCASE {
+        let description = match b {
+            CAPITAL => "CAPITAL (pada written with an initial capital)",
+            _ => "UPPERCASE (pada written in capitals)",
+        };
+        return ByteInfo {
+            byte: b,
+            hex,
+            binary,
+            class: "Annotation".into(),
+            description: description.into(),
+            spec: "§6.9",
+            categories: vec!["lipi".into()],
+            fields: vec![],
+        };
+    }
+
+    if b == LINE_BREAK {
+        return ByteInfo {
+            byte: b,
+            hex,
+            binary,
+            class: "Annotation".into(),
+            description: "LINE_BREAK (line break of the source)".into(),
+            spec: "§6.10",
+            categories: vec!["lipi".into()],
+            fields: vec![],
+        };
+    }
+
     // Reserved column (COLUMN = 101)
     ByteInfo {
         byte: b,
@@ -42,6 +155,8 @@ pub fn inspect_byte(b: u8) -> ByteInfo {
         binary,
         class: "Reserved".into(),
         description: format!("reserved byte (PLACE={}, COLUMN=101)", place(b)),
+        spec: "§2",
+        categories: vec![],
         fields: vec![],
     }
 }
@@ -80,7 +195,13 @@ fn inspect_svara(b: u8, hex: String, binary: String) -> ByteInfo {
         _ => "?",
     };
 
-    let iast = decoder::byte_to_iast(b);
+    let iast = decoder::phoneme_to_iast(b);
+
+    let mut categories = vec!["svara".to_string(), q_str.to_string()];
+    if a != 0 {
+        categories.push(a_str.to_string());
+    }
+    categories.extend(pratyaharas(b).into_iter().map(String::from));
 
     ByteInfo {
         byte: b,
@@ -91,12 +212,34 @@ fn inspect_svara(b: u8, hex: String, binary: String) -> ByteInfo {
             "svara '{}' ({}, {}, {}-series, {})",
             iast, q_str, a_str, s_str, g_str
         ),
+        spec: "§4.1",
+        categories,
         fields: vec![
-            ("Q (quantity)".into(), format!("{:02b} = {}", q, q_str)),
-            ("A (accent)".into(), format!("{:02b} = {}", a, a_str)),
-            ("S (series)".into(), format!("{:02b} = {}", s, s_str)),
-            ("G (grade)".into(), format!("{:02b} = {}", g, g_str)),
-            ("IAST".into(), iast.to_string()),
+            field(
+                "Q (quantity)",
+                format!("{:02b} = {}", q, q_str),
+                "§4",
+                "kāla",
+            ),
+            field(
+                "A (accent)",
+                format!("{:02b} = {}", a, a_str),
+                "§4",
+                "svara",
+            ),
+            field(
+                "S (series)",
+                format!("{:02b} = {}", s, s_str),
+                "§4",
+                "savarṇa",
+            ),
+            field(
+                "G (grade)",
+                format!("{:02b} = {}", g, g_str),
+                "§5.1",
+                "guṇa/vṛddhi",
+            ),
+            field("IAST", iast.to_string(), "§4.1", "varṇa"),
         ],
     }
 }
@@ -131,7 +274,24 @@ fn inspect_vyanjana(b: u8, hex: String, binary: String) -> ByteInfo {
     };
 
     let iast = decoder::byte_to_iast(b);
-    let is_varga_str = if p <= 4 { "yes" } else { "no" };
+    let varga = p <= 4;
+    let is_varga_str = if varga { "yes" } else { "no" };
+
+    let group = match p {
+        0..=4 => "sparśa",
+        5 => "ūṣman",
+        6 => "antastha",
+        _ => match b {
+            0x38 => "ūṣman",
+            0x39 => "visarga",
+            0x3A => "anusvāra",
+            0x3B => "jihvāmūlīya",
+            0x3C => "upadhmānīya",
+            _ => "?",
+        },
+    };
+    let mut categories = vec!["vyañjana".to_string(), group.to_string()];
+    categories.extend(pratyaharas(b).into_iter().map(String::from));
 
     ByteInfo {
         byte: b,
@@ -139,11 +299,28 @@ fn inspect_vyanjana(b: u8, hex: String, binary: String) -> ByteInfo {
         binary,
         class: "Vyañjana".into(),
         description: format!("vyañjana '{}' ({}, {})", iast, place_str, manner_str),
+        spec: if varga { "§3.3" } else { "§3.4" },
+        categories,
         fields: vec![
-            ("PLACE".into(), format!("{:03b} = {}", p, place_str)),
-            ("COLUMN".into(), format!("{:03b} = {}", c, manner_str)),
-            ("Varga".into(), is_varga_str.into()),
-            ("IAST".into(), iast.to_string()),
+            field(
+                "PLACE",
+                format!("{:03b} = {}", p, place_str),
+                "§3.1",
+                "sthāna",
+            ),
+            field(
+                "COLUMN",
+                format!("{:03b} = {}", c, manner_str),
+                if varga { "§3.2" } else { "§3.5" },
+                "prayatna",
+            ),
+            field("Varga", is_varga_str.into(), "§3.5", "varga"),
+            field(
+                "IAST",
+                iast.to_string(),
+                if varga { "§3.3" } else { "§3.4" },
+                "varṇa",
+            ),
         ],
     }
 }
@@ -152,14 +329,21 @@ fn inspect_bhasha_control(b: u8, hex: String, binary: String) -> ByteInfo {
     let name = match b {
         0x06 => "META_START",
         0x0E => "META_END",
-        0x16 => "PHON_START",
-        0x1E => "PHON_END",
+        0x16 => "PHON_START (⟨)",
+        0x1E => "PHON_END (⟩)",
         0x26 => "PADA_START",
         0x2E => "PADA_END",
-        0x36 => "reserved",
+        0x36 => "DICT_REF",
         0x3E => "SAṄKHYĀ_START",
         _ => "unknown",
     };
+    let (spec, category) = match b {
+        0x06 | 0x0E => ("§8.1", "vyākaraṇa"),
+        0x26 | 0x2E => ("§6.1", "pada"),
+        0x3E => ("§6.3.2", "saṅkhyā"),
+        0x36 => ("§9.6", "kośa"),
+        _ => ("§6.1", "bhāṣā"),
+    };
 
     ByteInfo {
         byte: b,
@@ -167,25 +351,34 @@ fn inspect_bhasha_control(b: u8, hex: String, binary: String) -> ByteInfo {
         binary,
         class: "Bhāṣā Control".into(),
         description: format!("{} — bhāṣā lane (COLUMN=110)", name),
+        spec,
+        categories: vec!["bhāṣā".into(), category.into()],
         fields: vec![
-            ("PLACE".into(), format!("{:03b}", place(b))),
-            ("Name".into(), name.into()),
+            field("PLACE", format!("{:03b}", place(b)), "§6.1", "sthāna"),
+            field("Name", name.into(), spec, category),
         ],
     }
 }
 
 fn inspect_lipi_control(b: u8, hex: String, binary: String) -> ByteInfo {
     let name = match b {
-        0x07 => "reserved",
+        0x07 => "ILLEGIBLE ([?])",
         0x0F => "DANDA (।)",
         0x17 => "DOUBLE_DANDA (॥)",
         0x1F => "SPACE",
         0x27 => "AVAGRAHA (ऽ)",
         0x2F => "NUM",
         0x37 => "META_EXT",
-        0x3F => "reserved",
+        0x3F => "LACUNA ([...])",
         _ => "unknown",
     };
+    let (spec, category) = match b {
+        0x0F | 0x17 => ("§6.2", "virāma-cihna"),
+        0x27 => ("§6.2", "avagraha"),
+        0x2F => ("§6.3.3", "aṅka"),
+        0x07 | 0x3F => ("§6.2", "lacuna"),
+        _ => ("§6.2", "lipi"),
+    };
 
     ByteInfo {
         byte: b,
@@ -193,23 +386,219 @@ fn inspect_lipi_control(b: u8, hex: String, binary: String) -> ByteInfo {
         binary,
         class: "Lipi Control".into(),
         description: format!("{} — lipi lane (COLUMN=111)", name),
+        spec,
+        categories: vec!["lipi".into(), category.into()],
         fields: vec![
-            ("PLACE".into(), format!("{:03b}", place(b))),
-            ("Name".into(), name.into()),
+            field("PLACE", format!("{:03b}", place(b)), "§6.2", "sthāna"),
+            field("Name", name.into(), spec, category),
         ],
     }
 }
 
+// ═══════════════════════════════════════════════
+//  Pratyāhāras (Māheśvara-sūtrāṇi)
+// ═══════════════════════════════════════════════
+
+/// The fourteen Māheśvara-sūtras: phoneme bytes followed by the anubandha.
+/// Vowels are stored as their hrasva (or guṇa/vṛddhi) śuddha forms.
+const MAHESVARA_SUTRAS: [(&[u8], &str); 14] = [
+    (&[0x40, 0x44, 0x48], "ṇ"),                               // a i u ṇ
+    (&[0x4C, 0x4F], "k"),                                     // ṛ ḷ k
+    (&[0x85, 0x89], "ṅ"),                                     // e o ṅ
+    (&[0x86, 0x8A], "c"),                                     // ai au c
+    (&[0x38, 0x31, 0x32, 0x33], "ṭ"),                         // ha ya va ra ṭ
+    (&[0x34], "ṇ"),                                           // la ṇ
+    (&[0x0C, 0x24, 0x04, 0x14, 0x1C], "m"),                   // ña ma ṅa ṇa na m
+    (&[0x0B, 0x23], "ñ"),                                     // jha bha ñ
+    (&[0x03, 0x13, 0x1B], "ṣ"),                               // gha ḍha dha ṣ
+    (&[0x0A, 0x22, 0x02, 0x12, 0x1A], "ś"),                   // ja ba ga ḍa da ś
+    (&[0x01, 0x21, 0x09, 0x11, 0x19, 0x08, 0x10, 0x18], "v"), // kha pha cha ṭha tha ca ṭa ta v
+    (&[0x00, 0x20], "y"),                                     // ka pa y
+    (&[0x29, 0x2A, 0x2B], "r"),                               // śa ṣa sa r
+    (&[0x38], "l"),                                           // ha l
+];
+
+/// Pratyāhāras reported by inspection: (name, first phoneme, anubandha sūtra index).
+const PRATYAHARAS: [(&str, u8, usize); 19] = [
+    ("ac", 0x40, 3),
+    ("ak", 0x40, 1),
+    ("ik", 0x44, 1),
+    ("ec", 0x85, 3),
+    ("eṅ", 0x85, 2),
+    ("aic", 0x86, 3),
+    ("hal", 0x38, 13),
+    ("yaṇ", 0x31, 5),
+    ("yar", 0x31, 12),
+    ("yay", 0x31, 11),
+    ("may", 0x24, 11),
+    ("ṅam", 0x04, 6),
+    ("jhal", 0x0B, 13),
+    ("jhay", 0x0B, 11),
+    ("jhaś", 0x0B, 9),
+    ("jaś", 0x0A, 9),
+    ("khay", 0x01, 11),
+    ("khar", 0x01, 12),
+    ("śal", 0x29, 13),
+];
+
+/// Reduce a svara to the form listed in the Māheśvara-sūtras: accent dropped
+/// and quantity normalized, so dīrgha and pluta vowels are taken with their
+/// listed form (savarṇa-grahaṇa, 1.1.69).
+fn sutra_form(b: u8) -> u8 {
+    if !is_svara(b) {
+        return b;
+    }
+    let base = b & 0b00_00_11_11;
+    let g = svara_g(base);
+    if g == 0b00 || g == 0b11 {
+        base | (0b01 << 6)
+    } else {
+        base | (0b10 << 6)
+    }
+}
+
+/// Check whether a phoneme byte belongs to a pratyāhāra spanning from `first`
+/// to the anubandha of sūtra `end`.
+fn in_pratyahara(b: u8, first: u8, end: usize) -> bool {
+    let target = sutra_form(b);
+    let mut started = false;
+    for (i, (phonemes, _)) in MAHESVARA_SUTRAS.iter().enumerate() {
+        for &p in phonemes.iter() {
+            if p == first {
+                started = true;
+            }
+            if started && p == target {
+                return true;
+            }
+        }
+        if i == end {
+            break;
+        }
+    }
+    false
+}
+
+/// Names of the common pratyāhāras that contain this phoneme byte.
+pub fn pratyaharas(b: u8) -> Vec<&'static str> {
+    if !is_svara(b) && !is_vyanjana(b) {
+        return vec![];
+    }
+    PRATYAHARAS
+        .iter()
+        .filter(|(_, first, end)| in_pratyahara(b, *first, *end))
+        .map(|(name, _, _)| *name)
+        .collect()
+}
+
+// ═══════════════════════════════════════════════
+//  Output
+// ═══════════════════════════════════════════════
+
 /// Inspect a hex stream (e.g. "1B 40 33 24 40") and return info for each byte.
-pub fn inspect_hex_stream(hex_str: &str) -> Result<Vec<ByteInfo>, String> {
+pub fn inspect_hex_stream(hex_str: &str) -> Result<Vec<ByteInfo>, SlbcError> {
     let mut bytes = Vec::new();
     for token in hex_str.split_whitespace() {
         let token = token.trim_start_matches("0x").trim_start_matches("0X");
-        let b =
-            u8::from_str_radix(token, 16).map_err(|_| format!("invalid hex byte: '{}'", token))?;
+        let b = u8::from_str_radix(token, 16)
+            .map_err(|_| SlbcError::invalid(format_args!("invalid hex byte: '{}'", token)))?;
         bytes.push(b);
     }
-    Ok(bytes.iter().map(|&b| inspect_byte(b)).collect())
+    Ok(inspect_stream(&bytes))
+}
+
+/// Inspect a PHON byte stream. Bytes are described one by one, except that
+/// a long extension's code, length, and payload bytes are described as such
+/// rather than as the phonemes or controls they would otherwise be, and
+/// PHON frames are numbered so a sentence's boundaries can be paired up.
+pub fn inspect_stream(bytes: &[u8]) -> Vec<ByteInfo> {
+    let mut infos = Vec::with_capacity(bytes.len());
+    // Numbers of the open frames
+    let mut frames: Vec<usize> = Vec::new();
+    let mut opened = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        let frame = match bytes[i] {
+            PHON_START => {
+                opened += 1;
+                frames.push(opened);
+                Some(("opens", opened, frames.len() - 1))
+            }
+            PHON_END => frames.pop().map(|n| ("closes", n, frames.len())),
+            _ => None,
+        };
+        if let Some((verb, n, depth)) = frame {
+            let mut info = inspect_byte(bytes[i]);
+            info.description
+                .push_str(&format!("; {} frame {} at depth {}", verb, n, depth));
+            infos.push(info);
+            i += 1;
+            continue;
+        }
+        let mark = match bytes.get(i + 1) {
+            Some(&codepage::SAMASA) => Some(("Compound Boundary", "samāsa boundary".into())),
+            Some(&c) => codepage::punctuation(c)
+                .map(|mark| ("Punctuation", format!("punctuation mark '{}'", mark))),
+            None => None,
+        };
+        if let (META_EXT, Some((class, description))) = (bytes[i], mark) {
+            infos.push(inspect_byte(META_EXT));
+            infos.push(ByteInfo {
+                byte: bytes[i + 1],
+                hex: format!("0x{:02X}", bytes[i + 1]),
+                binary: format!("{:08b}", bytes[i + 1]),
+                class: class.into(),
+                description,
+                spec: "§6.4",
+                categories: vec!["lipi".into(), "extension".into()],
+                fields: Vec::new(),
+            });
+            i += 2;
+            continue;
+        }
+        let whole = match bytes[i] {
+            META_EXT if bytes.get(i + 1) == Some(&codepage::EXT_LONG) => {
+                codepage::read_long_ext(bytes, i).ok()
+            }
+            _ => None,
+        };
+        let Some((payload, len)) = whole else {
+            infos.push(inspect_byte(bytes[i]));
+            i += 1;
+            continue;
+        };
+        let body = i + len - payload.len();
+        infos.push(inspect_byte(META_EXT));
+        for (j, &b) in bytes[i + 1..i + len].iter().enumerate() {
+            let at = i + 1 + j;
+            let description = if at == i + 1 {
+                "EXT_LONG — opens a long extension".to_string()
+            } else if at < body {
+                format!("long extension length ({} payload bytes)", payload.len())
+            } else {
+                format!(
+                    "long extension payload byte {}/{}",
+                    at - body + 1,
+                    payload.len()
+                )
+            };
+            infos.push(long_ext_byte(b, description));
+        }
+        i += len;
+    }
+    infos
+}
+
+fn long_ext_byte(b: u8, description: String) -> ByteInfo {
+    ByteInfo {
+        byte: b,
+        hex: format!("0x{:02X}", b),
+        binary: format!("{:08b}", b),
+        class: "Long Extension".into(),
+        description,
+        spec: "§6.4",
+        categories: vec!["lipi".into(), "extension".into()],
+        fields: Vec::new(),
+    }
 }
 
 /// Format a ByteInfo for display.
@@ -218,11 +607,103 @@ pub fn format_byte_info(info: &ByteInfo) -> String {
         "  {} ({}) [{}]\n  Class: {}\n  {}",
         info.hex, info.binary, info.class, info.class, info.description
     );
+    out.push_str(&format!("\n  Spec: {}", info.spec));
+    if !info.categories.is_empty() {
+        out.push_str(&format!("\n  Categories: {}", info.categories.join(", ")));
+    }
     if !info.fields.is_empty() {
         out.push('\n');
-        for (name, value) in &info.fields {
-            out.push_str(&format!("    {}: {}\n", name, value));
+        for f in &info.fields {
+            out.push_str(&format!("    {}: {}\n", f.name, f.value));
         }
     }
     out
 }
+
+/// Format a ByteInfo as a JSON object.
+pub fn byte_info_json(info: &ByteInfo) -> String {
+    let fields: Vec<String> = info
+        .fields
+        .iter()
+        .map(|f| {
+            format!(
+                "{{\"name\":{},\"value\":{},\"spec\":{},\"term\":{}}}",
+                json::quote(&f.name),
+                json::quote(&f.value),
+                json::quote(f.spec),
+                json::quote(f.term)
+            )
+        })
+        .collect();
+    format!(
+        "{{\"byte\":{},\"hex\":{},\"binary\":{},\"class\":{},\"description\":{},\"spec\":{},\"categories\":{},\"fields\":[{}]}}",
+        info.byte,
+        json::quote(&info.hex),
+        json::quote(&info.binary),
+        json::quote(&info.class),
+        json::quote(&info.description),
+        json::quote(info.spec),
+        json::string_array(&info.categories),
+        fields.join(",")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pratyaharas_vowels() {
+        assert_eq!(pratyaharas(0x44), vec!["ac", "ak", "ik"]); // i
+        assert_eq!(pratyaharas(0x84), vec!["ac", "ak", "ik"]); // ī via savarṇa-grahaṇa
+        assert_eq!(pratyaharas(0x85), vec!["ac", "ec", "eṅ"]); // e
+        assert_eq!(pratyaharas(0x86), vec!["ac", "ec", "aic"]); // ai
+    }
+
+    #[test]
+    fn test_pratyaharas_consonants() {
+        let ka = pratyaharas(0x00);
+        assert!(ka.contains(&"jhal") && ka.contains(&"khar") && !ka.contains(&"jaś"));
+        let ga = pratyaharas(0x02);
+        assert!(ga.contains(&"jaś") && ga.contains(&"jhaś") && !ga.contains(&"khar"));
+        let ha = pratyaharas(0x38);
+        assert!(ha.contains(&"hal") && ha.contains(&"śal") && !ha.contains(&"yaṇ"));
+        assert!(pratyaharas(0x31).contains(&"yaṇ"));
+        assert!(pratyaharas(SPACE).is_empty());
+    }
+
+    #[test]
+    fn test_inspect_spec_refs() {
+        assert_eq!(inspect_byte(0x00).spec, "§3.3");
+        assert_eq!(inspect_byte(0x29).spec, "§3.4");
+        assert_eq!(inspect_byte(SANKHYA_START).spec, "§6.3.2");
+        assert_eq!(inspect_byte(0x44).fields[0].term, "kāla");
+    }
+
+    #[test]
+    fn test_inspect_stream_long_ext() {
+        let infos = inspect_stream(&[META_EXT, codepage::EXT_LONG, 1, PADA_END, 0x00]);
+        assert_eq!(infos.len(), 5);
+        assert_eq!(infos[0].class, "Lipi Control");
+        assert!(infos[2].description.contains("1 payload bytes"));
+        assert_eq!(infos[3].description, "long extension payload byte 1/1");
+        assert_eq!(infos[4].class, "Vyañjana");
+    }
+
+    #[test]
+    fn test_inspect_stream_frames() {
+        let infos = inspect_stream(&[PHON_START, PHON_START, PHON_END, PHON_END, PHON_START]);
+        assert!(infos[0].description.ends_with("opens frame 1 at depth 0"));
+        assert!(infos[1].description.ends_with("opens frame 2 at depth 1"));
+        assert!(infos[2].description.ends_with("closes frame 2 at depth 1"));
+        assert!(infos[3].description.ends_with("closes frame 1 at depth 0"));
+        assert!(infos[4].description.starts_with("PHON_START (⟨)"));
+    }
+
+    #[test]
+    fn test_byte_info_json() {
+        let j = byte_info_json(&inspect_byte(0x44));
+        assert!(j.starts_with("{\"byte\":68,\"hex\":\"0x44\""));
+        assert!(j.contains("\"categories\":[\"svara\",\"hrasva\",\"ac\",\"ak\",\"ik\"]"));
+    }
+}
@@ -0,0 +1,339 @@
+//! Per-pada language/dialect tags (§8.3, sub-tag 0xFC).
+//!
+//! A tagged pada opens with a language META block:
+//! ```text
+//! PADA_START META_START 0xFC <code> META_END … PADA_END
+//! ```
+//! Untagged padas are Sanskrit. Phonotactic checks that only hold for
+//! Sanskrit are skipped for padas tagged with another language, so dramas
+//! and Buddhist sūtras can mix both in one file.
+
+use crate::codepage;
+use crate::encoder;
+use crate::error::SlbcError;
+use crate::numeral;
+use crate::types::*;
+
+/// Language (or dialect) of a pada.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Language {
+    #[default]
+    Sanskrit,
+    /// Prakrit, dialect unspecified.
+    Prakrit,
+    Sauraseni,
+    Maharastri,
+    Magadhi,
+    Pali,
+    /// Buddhist Hybrid Sanskrit.
+    HybridSanskrit,
+    Apabhramsa,
+}
+
+/// (language, code byte, markup tag, display name)
+const LANGUAGES: [(Language, u8, &str, &str); 8] = [
+    (Language::Sanskrit, 0x00, "sa", "Sanskrit"),
+    (Language::Prakrit, 0x01, "pra", "Prakrit"),
+    (Language::Sauraseni, 0x02, "sau", "Śaurasenī"),
+    (Language::Maharastri, 0x03, "mah", "Māhārāṣṭrī"),
+    (Language::Magadhi, 0x04, "mag", "Māgadhī"),
+    (Language::Pali, 0x05, "pi", "Pāli"),
+    (
+        Language::HybridSanskrit,
+        0x06,
+        "bhs",
+        "Buddhist Hybrid Sanskrit",
+    ),
+    (Language::Apabhramsa, 0x07, "apa", "Apabhraṃśa"),
+];
+
+impl Language {
+    fn row(self) -> &'static (Language, u8, &'static str, &'static str) {
+        LANGUAGES.iter().find(|r| r.0 == self).unwrap()
+    }
+
+    /// Code byte written after the 0xFC sub-tag.
+    pub fn code(self) -> u8 {
+        self.row().1
+    }
+
+    pub fn from_code(code: u8) -> Option<Language> {
+        LANGUAGES.iter().find(|r| r.1 == code).map(|r| r.0)
+    }
+
+    /// Short tag used in input markup (e.g. "pra").
+    pub fn tag(self) -> &'static str {
+        self.row().2
+    }
+
+    pub fn from_tag(tag: &str) -> Option<Language> {
+        LANGUAGES.iter().find(|r| r.2 == tag).map(|r| r.0)
+    }
+
+    pub fn name(self) -> &'static str {
+        self.row().3
+    }
+
+    /// The META block that tags a pada with this language.
+    pub fn tag_block(self) -> [u8; 4] {
+        [META_START, SUBTAG_LANG, self.code(), META_END]
+    }
+}
+
+/// Language of a pada, given its contents (without PADA_START/PADA_END).
+pub fn pada_language(pada: &[u8]) -> Result<Language, SlbcError> {
+    Ok(split_tag(pada)?.0)
+}
+
+/// Split a pada into its language and the bytes after any language block.
+pub fn split_tag(pada: &[u8]) -> Result<(Language, usize), SlbcError> {
+    if pada.len() < 2 || pada[0] != META_START || pada[1] != SUBTAG_LANG {
+        return Ok((Language::Sanskrit, 0));
+    }
+    if pada.get(3) != Some(&META_END) {
+        return Err(SlbcError::malformed(
+            0,
+            "language META block must hold exactly one code byte",
+        ));
+    }
+    let lang = Language::from_code(pada[2]).ok_or_else(|| {
+        SlbcError::malformed(2, format_args!("unknown language code 0x{:02X}", pada[2]))
+    })?;
+    Ok((lang, 4))
+}
+
+/// Every top-level pada in a payload: (offset of PADA_START, contents).
+/// Numeral spans (whose digit-words are padas too) and META blocks are skipped.
+pub fn padas(data: &[u8]) -> Result<Vec<(usize, &[u8])>, SlbcError> {
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < data.len() {
+        match data[i] {
+            SANKHYA_START => i += numeral::decode_sankhya(data, i)?.1,
+            NUM => i += numeral::decode_num(data, i)?.1,
+            META_START => {
+                while i < data.len() && data[i] != META_END {
+                    i += 1;
+                }
+                i += 1;
+            }
+            PADA_START => {
+                // A long extension may hold a PADA_END byte
+                let mut end = i + 1;
+                while data.get(end) != Some(&PADA_END) {
+                    end += match data.get(end) {
+                        Some(&META_EXT) => codepage::ext_len(data, end)?,
+                        Some(_) => 1,
+                        None => return Err(SlbcError::truncated(i, "unterminated pada")),
+                    };
+                }
+                out.push((i, &data[i + 1..end]));
+                i = end + 1;
+            }
+            _ => i += 1,
+        }
+    }
+    Ok(out)
+}
+
+/// The language of every pada in a payload, by PADA_START offset.
+pub fn pada_languages(data: &[u8]) -> Result<Vec<(usize, Language)>, SlbcError> {
+    padas(data)?
+        .into_iter()
+        .map(|(offset, pada)| Ok((offset, pada_language(pada)?)))
+        .collect()
+}
+
+// ═══════════════════════════════════════════════
+//  Mixed-language input
+// ═══════════════════════════════════════════════
+
+/// Encode IAST with inline language markup: `<pra>…</pra>` tags every pada
+/// inside the span. Tags should sit at word boundaries and may not nest.
+pub fn encode_mixed(input: &str) -> Result<Vec<u8>, SlbcError> {
+    let mut out = Vec::new();
+    let mut rest = input;
+    let mut current: Option<Language> = None;
+    // An error at byte `at` of `rest`, by char position in the input
+    let error = |rest: &str, at: usize, what: String| {
+        let byte = input.len() - rest.len() + at;
+        SlbcError::encode(input, input[..byte].chars().count(), what)
+    };
+
+    loop {
+        let Some(open) = rest.find('<') else {
+            if let Some(lang) = current {
+                return Err(error(
+                    rest,
+                    rest.len(),
+                    format!("unclosed <{}> span", lang.tag()),
+                ));
+            }
+            out.extend(encoder::encode_iast(rest)?);
+            return Ok(out);
+        };
+        let close = rest[open..]
+            .find('>')
+            .map(|p| open + p)
+            .ok_or_else(|| error(rest, open, "unterminated markup tag".into()))?;
+
+        let prefix = current.map(|l| l.tag_block().to_vec()).unwrap_or_default();
+        let tokens = encoder::tokenize_iast(&rest[..open])?;
+        out.extend(encoder::tokens_to_bytes_with(&tokens, &prefix));
+
+        let tag = &rest[open + 1..close];
+        match (tag.strip_prefix('/'), current) {
+            (Some(name), Some(lang)) if name == lang.tag() => current = None,
+            (Some(name), _) => {
+                let what = format!("unexpected closing tag </{}>", name);
+                return Err(error(rest, open, what));
+            }
+            (None, Some(lang)) => {
+                let what = format!("<{}> may not nest inside <{}>", tag, lang.tag());
+                return Err(error(rest, open, what));
+            }
+            (None, None) => {
+                let lang = Language::from_tag(tag)
+                    .ok_or_else(|| error(rest, open, format!("unknown language tag <{}>", tag)))?;
+                current = Some(lang);
+            }
+        }
+        rest = &rest[close + 1..];
+    }
+}
+
+// ═══════════════════════════════════════════════
+//  Phonotactics
+// ═══════════════════════════════════════════════
+
+/// A phonotactic violation inside a pada.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PhonotacticIssue {
+    /// Offset of the offending byte in the payload.
+    pub offset: usize,
+    pub language: Language,
+    pub message: String,
+}
+
+/// Check pada-internal phonotactics.
+///
+/// Every pada: visarga and anusvāra follow a vowel; jihvāmūlīya precedes
+/// k/kh and upadhmānīya precedes p/ph. Sanskrit padas only: no vowel hiatus,
+/// which sandhi always resolves — Prakrit, Pāli, and BHS keep it.
+pub fn check_phonotactics(data: &[u8]) -> Result<Vec<PhonotacticIssue>, SlbcError> {
+    let mut issues = Vec::new();
+    for (start, pada) in padas(data)? {
+        let (language, skip) = split_tag(pada)?;
+        let body = &pada[skip..];
+        let mut prev: Option<u8> = None;
+        // The code byte after META_EXT, or a long extension, is not a
+        // core phoneme
+        let mut skip_to = 0;
+        for (j, &b) in body.iter().enumerate() {
+            if j < skip_to {
+                continue;
+            }
+            if !is_svara(b) && !is_vyanjana(b) {
+                if b == META_EXT {
+                    skip_to = j + codepage::ext_len(body, j)?;
+                }
+                prev = None;
+                continue;
+            }
+            let offset = start + 1 + skip + j;
+            let next = body.get(j + 1).copied();
+            let mut issue = |message: String| {
+                issues.push(PhonotacticIssue {
+                    offset,
+                    language,
+                    message,
+                })
+            };
+            let after_vowel = prev.is_some_and(is_svara);
+            match b {
+                0x39 if !after_vowel => issue("visarga does not follow a vowel".into()),
+                0x3A if !after_vowel => issue("anusvāra does not follow a vowel".into()),
+                0x3B if !matches!(next, Some(0x00 | 0x01)) => {
+                    issue("jihvāmūlīya not before k/kh".into());
+                }
+                0x3C if !matches!(next, Some(0x20 | 0x21)) => {
+                    issue("upadhmānīya not before p/ph".into());
+                }
+                _ if is_svara(b)
+                    && prev.is_some_and(is_svara)
+                    && language == Language::Sanskrit =>
+                {
+                    issue("vowel hiatus inside a Sanskrit pada".into());
+                }
+                _ => {}
+            }
+            prev = Some(b);
+        }
+    }
+    Ok(issues)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_codes() {
+        for (lang, code, tag, _) in LANGUAGES {
+            assert_eq!(Language::from_code(code), Some(lang));
+            assert_eq!(Language::from_tag(tag), Some(lang));
+            assert_ne!(code, META_END);
+        }
+    }
+
+    #[test]
+    fn test_encode_mixed_tags_padas() {
+        let data = encode_mixed("rājā uvāca <pra>ajja gaaṇaṃ</pra> iti").unwrap();
+        let langs: Vec<Language> = pada_languages(&data)
+            .unwrap()
+            .into_iter()
+            .map(|(_, l)| l)
+            .collect();
+        use Language::*;
+        assert_eq!(langs, vec![Sanskrit, Sanskrit, Prakrit, Prakrit, Sanskrit]);
+
+        // Decoders skip the META block
+        let text = crate::decoder::decode_phon(&data, crate::decoder::Script::Iast).unwrap();
+        assert_eq!(text, "rājā uvāca ajja gaaṇaṃ iti");
+    }
+
+    #[test]
+    fn test_encode_mixed_errors() {
+        assert!(encode_mixed("<pra>ajja").is_err());
+        assert!(encode_mixed("<xx>ajja</xx>").is_err());
+        assert!(encode_mixed("<pra><pi>a</pi></pra>").is_err());
+        assert!(encode_mixed("ajja</pra>").is_err());
+        let e = encode_mixed("rājā <pra>ajja <xx>").unwrap_err();
+        assert_eq!(
+            (e.position(), e.snippet()),
+            (Some(15), Some("ra>ajja <xx>"))
+        );
+    }
+
+    #[test]
+    fn test_phonotactics_relaxed_for_tagged_padas() {
+        let sanskrit = encoder::encode_iast("gaaṇaṃ").unwrap();
+        let issues = check_phonotactics(&sanskrit).unwrap();
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].message.contains("hiatus"));
+
+        let prakrit = encode_mixed("<pra>gaaṇaṃ</pra>").unwrap();
+        assert!(check_phonotactics(&prakrit).unwrap().is_empty());
+
+        // Rules common to every language still apply
+        let bad = encode_mixed("<pra>ṃa</pra>").unwrap();
+        assert_eq!(
+            check_phonotactics(&bad).unwrap()[0].language,
+            Language::Prakrit
+        );
+
+        // Numeral digit-words are not checked as padas
+        let num = encoder::encode_iast("108").unwrap();
+        assert!(check_phonotactics(&num).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,256 @@
+//! Separated layers: BHA and LIPI chunks (§7.3).
+//!
+//! A PHON chunk interleaves the bhāṣā stream with the lipi bytes that say
+//! how it was written. A separated container (INTERLEAVED flag clear)
+//! stores each PHON chunk as a BHA chunk, the bhāṣā bytes alone, followed
+//! by a LIPI chunk, an overlay of the lipi runs with their offsets into the
+//! bhāṣā stream:
+//! ```text
+//! run count (ULEB128)
+//! per run: offset (ULEB128, gap from the previous run's offset)
+//!          length (ULEB128) | lipi bytes
+//! ```
+//! A run goes in just before the bhāṣā byte at its offset (or at the end).
+//! The lipi side holds what bhāṣā-only extraction strips (§7.5): lipi
+//! controls with their NUM spans, punctuation, compound boundaries and long
+//! extensions, and the MORPH, CANDRABINDU, PRANAVA, CAPITAL, UPPERCASE and
+//! LINE_BREAK marks. Phonemes — extension phonemes and packed codes
+//! included — bhāṣā controls with their META blocks, SAṄKHYĀ spans and
+//! DICT_REF indices, and YAMA and NĀSIKYA, which carry sound, stay in the
+//! bhāṣā stream. Merging the two gives back the PHON payload byte for byte.
+
+use crate::codepage::{self, EXT_LONG};
+use crate::container::{self, Chunk};
+use crate::error::SlbcError;
+use crate::numeral;
+use crate::types::*;
+
+/// The unit at `i`: its length, and whether it belongs to the lipi layer.
+fn unit(data: &[u8], i: usize) -> Result<(usize, bool), SlbcError> {
+    Ok(match data[i] {
+        META_START => {
+            let end = data[i + 1..].iter().position(|&b| b == META_END);
+            (end.map_or(data.len() - i, |p| p + 2), false)
+        }
+        SANKHYA_START => (numeral::decode_sankhya(data, i)?.1, false),
+        NUM => (numeral::decode_num(data, i)?.1, true),
+        DICT_REF => {
+            let (_, len) = container::read_uleb128(&data[i + 1..])
+                .map_err(|e| e.context(i + 1, "DICT_REF index error"))?;
+            (1 + len, false)
+        }
+        META_EXT => {
+            let code = data.get(i + 1).copied();
+            let lipi = code == Some(EXT_LONG)
+                || code == Some(codepage::SAMASA)
+                || code.and_then(codepage::punctuation).is_some();
+            (codepage::ext_len(data, i)?, lipi)
+        }
+        MORPH | CANDRABINDU | PRANAVA | CAPITAL | UPPERCASE | LINE_BREAK => (1, true),
+        b => (1, is_lipi_control(b)),
+    })
+}
+
+/// Split a PHON payload into its bhāṣā stream and lipi overlay.
+pub fn split(payload: &[u8]) -> Result<(Vec<u8>, Vec<u8>), SlbcError> {
+    let mut bha = Vec::with_capacity(payload.len());
+    let mut runs: Vec<(usize, Vec<u8>)> = Vec::new();
+    let mut i = 0;
+    while i < payload.len() {
+        let (len, lipi) = unit(payload, i)?;
+        let bytes = payload
+            .get(i..i + len)
+            .ok_or_else(|| SlbcError::truncated(i, "truncated unit"))?;
+        match runs.last_mut() {
+            Some((at, run)) if lipi && *at == bha.len() => run.extend_from_slice(bytes),
+            _ if lipi => runs.push((bha.len(), bytes.to_vec())),
+            _ => bha.extend_from_slice(bytes),
+        }
+        i += len;
+    }
+
+    let mut lipi = Vec::new();
+    container::write_uleb128(&mut lipi, runs.len() as u64);
+    let mut prev = 0;
+    for (at, run) in &runs {
+        container::write_uleb128(&mut lipi, (at - prev) as u64);
+        container::write_uleb128(&mut lipi, run.len() as u64);
+        lipi.extend_from_slice(run);
+        prev = *at;
+    }
+    Ok((bha, lipi))
+}
+
+fn read_field(data: &[u8], pos: &mut usize, what: &str) -> Result<usize, SlbcError> {
+    let (v, consumed) = container::read_uleb128(&data[(*pos).min(data.len())..])
+        .map_err(|e| e.context(*pos, format_args!("lipi overlay {} error", what)))?;
+    *pos += consumed;
+    Ok(v as usize)
+}
+
+/// The runs of a lipi overlay: (offset into the bhāṣā stream, bytes).
+pub fn runs(lipi: &[u8]) -> Result<Vec<(usize, &[u8])>, SlbcError> {
+    let mut pos = 0;
+    let count = read_field(lipi, &mut pos, "run count")?;
+    let mut out = Vec::with_capacity(count.min(1 << 16));
+    let mut at = 0;
+    for _ in 0..count {
+        at += read_field(lipi, &mut pos, "offset")?;
+        let len = read_field(lipi, &mut pos, "length")?;
+        let run = lipi
+            .get(pos..pos + len)
+            .ok_or_else(|| SlbcError::truncated(pos, "truncated lipi run"))?;
+        out.push((at, run));
+        pos += len;
+    }
+    if pos != lipi.len() {
+        return Err(SlbcError::malformed(pos, "trailing bytes in lipi overlay"));
+    }
+    Ok(out)
+}
+
+/// Merge a bhāṣā stream and its lipi overlay back into a PHON payload.
+pub fn merge(bha: &[u8], lipi: &[u8]) -> Result<Vec<u8>, SlbcError> {
+    let runs = runs(lipi)?;
+    let mut out = Vec::with_capacity(bha.len() + lipi.len());
+    let mut done = 0;
+    for (at, run) in runs {
+        let before = bha.get(done..at).ok_or_else(|| {
+            SlbcError::malformed(
+                at,
+                format_args!(
+                    "lipi run past the end of the bhāṣā stream ({} bytes)",
+                    bha.len()
+                ),
+            )
+        })?;
+        out.extend_from_slice(before);
+        out.extend_from_slice(run);
+        done = at;
+    }
+    out.extend_from_slice(&bha[done..]);
+    Ok(out)
+}
+
+/// Chunks with every BHA chunk, and the LIPI chunk after it if any,
+/// merged back into a PHON chunk; other chunks are kept as they are.
+pub fn merge_chunks(chunks: Vec<Chunk>) -> Result<Vec<Chunk>, SlbcError> {
+    let mut out: Vec<Chunk> = Vec::with_capacity(chunks.len());
+    for chunk in chunks {
+        match chunk.chunk_type {
+            CHUNK_BHA => out.push(Chunk {
+                chunk_type: CHUNK_PHON,
+                payload: chunk.payload,
+            }),
+            CHUNK_LIPI => {
+                let bha = match out.last_mut() {
+                    Some(c) if c.chunk_type == CHUNK_PHON => c,
+                    _ => {
+                        return Err(SlbcError::invalid(
+                            "LIPI chunk without a BHA chunk before it",
+                        ))
+                    }
+                };
+                bha.payload = merge(&bha.payload, &chunk.payload)?;
+            }
+            _ => out.push(chunk),
+        }
+    }
+    Ok(out)
+}
+
+/// A container in separated layout: each PHON chunk written as a BHA and a
+/// LIPI chunk, with the INTERLEAVED flag cleared.
+pub fn separate(data: &[u8]) -> Result<Vec<u8>, SlbcError> {
+    let (mut header, chunks) = container::parse_slbc(data)?;
+    header.flags &= !FLAG_INTERLEAVED;
+    let mut out = header.to_bytes_with(&header.ext_records()?)?;
+    for chunk in &chunks {
+        if chunk.chunk_type == CHUNK_PHON {
+            let (bha, lipi) = split(&chunk.payload)?;
+            container::write_chunk(&mut out, CHUNK_BHA, &bha);
+            container::write_chunk(&mut out, CHUNK_LIPI, &lipi);
+        } else {
+            container::write_chunk(&mut out, chunk.chunk_type, &chunk.payload);
+        }
+    }
+    Ok(out)
+}
+
+/// A container in interleaved layout: BHA and LIPI chunks merged into PHON
+/// chunks, with the INTERLEAVED flag set.
+pub fn interleave(data: &[u8]) -> Result<Vec<u8>, SlbcError> {
+    let (mut header, chunks) = container::parse_slbc(data)?;
+    header.flags |= FLAG_INTERLEAVED;
+    let mut out = header.to_bytes_with(&header.ext_records()?)?;
+    for chunk in merge_chunks(chunks)? {
+        container::write_chunk(&mut out, chunk.chunk_type, &chunk.payload);
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoder::{self, Script};
+    use crate::dict::{self, DictDirs};
+    use crate::encoder::{self, EncodeOptions, Newlines, SamasaMarker};
+
+    #[test]
+    fn test_split_merge() {
+        let opts = EncodeOptions {
+            samasa: SamasaMarker::Hyphen,
+            newlines: Newlines::Keep,
+            ..Default::default()
+        };
+        let text = "dharma-kṣetre kuru+kṣetre, 12 ||\nsamavetā [?] ḻa ||";
+        let data = encoder::encode_iast_with(text, &opts).unwrap();
+        let (bha, lipi) = split(&data).unwrap();
+        assert_eq!(merge(&bha, &lipi).unwrap(), data);
+
+        // The bhāṣā stream keeps the SAṄKHYĀ span and the ḻ, and nothing of
+        // the lipi layer
+        assert!(bha.contains(&SANKHYA_START));
+        assert!(!bha
+            .iter()
+            .any(|&b| matches!(b, SPACE | NUM | DOUBLE_DANDA | MORPH)));
+        assert_eq!(
+            decoder::decode_phon(&bha, Script::Iast).unwrap(),
+            decoder::decode_phon(
+                &encoder::encode_iast("dharmakṣetre kurukṣetre 12 samavetā ḻa").unwrap(),
+                Script::Iast
+            )
+            .unwrap()
+            .replace(' ', "")
+        );
+        let overlay = runs(&lipi).unwrap();
+        assert!(overlay.windows(2).all(|w| w[0].0 < w[1].0));
+        assert!(overlay.iter().all(|(at, _)| *at <= bha.len()));
+
+        assert!(merge(&bha[..4], &lipi).is_err());
+        assert!(runs(&lipi[..lipi.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_separate_interleave() {
+        let data = encoder::encode_iast("rāmaḥ vanaṃ gacchati ||").unwrap();
+        let file = container::build_slbc(&data);
+        let separated = separate(&file).unwrap();
+        let (header, chunks) = container::parse_slbc(&separated).unwrap();
+        assert!(!header.is_interleaved());
+        let types: Vec<u8> = chunks.iter().map(|c| c.chunk_type).collect();
+        assert_eq!(types, [CHUNK_BHA, CHUNK_LIPI, CHUNK_EOF]);
+        assert_eq!(interleave(&separated).unwrap(), file);
+        let (_, opened, _) = dict::open(&separated, &DictDirs(Vec::new())).unwrap();
+        assert_eq!(opened[0].payload, data);
+
+        let merged = merge_chunks(chunks).unwrap();
+        assert_eq!(merged[0].chunk_type, CHUNK_PHON);
+        assert_eq!(merged[0].payload, data);
+        assert!(merge_chunks(vec![Chunk {
+            chunk_type: CHUNK_LIPI,
+            payload: vec![0],
+        }])
+        .is_err());
+    }
+}
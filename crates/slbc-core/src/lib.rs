@@ -3,10 +3,42 @@
 //! A binary encoding of Sanskrit that preserves Pāṇinian phonological structure.
 //! Encodes from IAST, decodes to IAST or Devanāgarī.
 
+pub mod abugida;
+pub mod akshara;
+pub mod annotation;
+pub mod anvaya;
+pub mod audit;
+pub mod checksum;
+pub mod codec;
+pub mod codepage;
+pub mod codespace;
 pub mod container;
 pub mod decoder;
+pub mod dict;
+pub mod document;
+pub mod emend;
 pub mod encoder;
+pub mod error;
+pub mod extension;
+pub mod fidelity;
+pub mod frame;
+pub mod golden;
+pub mod index;
 pub mod inspect;
+pub mod json;
+pub mod lang;
+pub mod layers;
 pub mod numeral;
+pub mod pack;
+pub mod pada;
+pub mod phoneme;
+pub mod pipeline;
+pub mod proof;
+pub mod provenance;
+pub mod push;
+pub mod scheme;
+pub mod search;
+pub mod segment;
 pub mod transform;
+pub mod translation;
 pub mod types;
@@ -4,6 +4,7 @@
 //! Lipi layer: NUM + L→R digit glyphs.
 
 use crate::container;
+use crate::error::SlbcError;
 use crate::types::*;
 
 /// The closed digit-word vocabulary (§6.3.2).
@@ -52,23 +53,23 @@ pub fn encode_numeral(digits: &str, out: &mut Vec<u8>) {
 
 /// Decode a SAṄKHYĀ span from a byte slice starting at `pos`.
 /// Returns (digit_vector_L2R, bytes_consumed).
-pub fn decode_sankhya(data: &[u8], pos: usize) -> Result<(Vec<u8>, usize), String> {
+pub fn decode_sankhya(data: &[u8], pos: usize) -> Result<(Vec<u8>, usize), SlbcError> {
     let mut i = pos;
 
-    if data[i] != SANKHYA_START {
-        return Err(format!("expected SAṄKHYĀ_START at offset {}", i));
+    if data.get(i) != Some(&SANKHYA_START) {
+        return Err(SlbcError::malformed(i, "expected SAṄKHYĀ_START"));
     }
     i += 1;
 
-    let (count, consumed) = container::read_uleb128(&data[i..])
-        .map_err(|e| format!("ULEB128 error at offset {}: {}", i, e))?;
+    let (count, consumed) =
+        container::read_uleb128(&data[i..]).map_err(|e| e.context(i, "ULEB128 error"))?;
     i += consumed;
 
     let mut digits = Vec::with_capacity(count as usize);
 
     for _ in 0..count {
-        if data[i] != PADA_START {
-            return Err(format!("expected PADA_START at offset {}", i));
+        if data.get(i) != Some(&PADA_START) {
+            return Err(SlbcError::malformed(i, "expected PADA_START"));
         }
         i += 1;
 
@@ -78,13 +79,16 @@ pub fn decode_sankhya(data: &[u8], pos: usize) -> Result<(Vec<u8>, usize), Strin
             i += 1;
         }
         if i >= data.len() {
-            return Err("unterminated digit-pada".into());
+            return Err(SlbcError::Truncated {
+                message: "unterminated digit-pada".into(),
+                offset: Some(pada_start),
+            });
         }
         let pada_bytes = &data[pada_start..i];
         i += 1; // skip PADA_END
 
         let digit = lookup_digit_word(pada_bytes)
-            .ok_or_else(|| format!("invalid digit-word at offset {}", pada_start))?;
+            .ok_or_else(|| SlbcError::malformed(pada_start, "invalid digit-word"))?;
         digits.push(digit);
     }
 
@@ -96,11 +100,11 @@ pub fn decode_sankhya(data: &[u8], pos: usize) -> Result<(Vec<u8>, usize), Strin
 
 /// Decode a NUM (digit-glyph) span from a byte slice starting at `pos`.
 /// Returns (digit_vector_L2R, bytes_consumed).
-pub fn decode_num(data: &[u8], pos: usize) -> Result<(Vec<u8>, usize), String> {
+pub fn decode_num(data: &[u8], pos: usize) -> Result<(Vec<u8>, usize), SlbcError> {
     let mut i = pos;
 
-    if data[i] != NUM {
-        return Err(format!("expected NUM at offset {}", i));
+    if data.get(i) != Some(&NUM) {
+        return Err(SlbcError::malformed(i, "expected NUM"));
     }
     i += 1;
 
@@ -113,6 +117,11 @@ pub fn decode_num(data: &[u8], pos: usize) -> Result<(Vec<u8>, usize), String> {
     Ok((digits, i - pos))
 }
 
+/// The bhāṣā bytes of the digit-word for `digit` (0–9).
+pub fn digit_word(digit: u8) -> &'static [u8] {
+    DIGIT_WORDS[digit as usize]
+}
+
 /// Look up a pada's byte content against the digit-word vocabulary.
 /// Returns the digit value (0–9) or None.
 fn lookup_digit_word(pada_bytes: &[u8]) -> Option<u8> {
@@ -124,11 +133,268 @@ fn lookup_digit_word(pada_bytes: &[u8]) -> Option<u8> {
     None
 }
 
+// ── Integrity ──
+
+/// A SAṄKHYĀ span whose digit-words disagree with its paired NUM span.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NumeralMismatch {
+    /// Payload offset of SAṄKHYĀ_START.
+    pub sankhya_offset: usize,
+    /// Payload offset of the paired NUM byte.
+    pub num_offset: usize,
+    /// Digits per the bhāṣā layer (L→R).
+    pub words: Vec<u8>,
+    /// Glyph bytes per the lipi layer (L→R).
+    pub glyphs: Vec<u8>,
+}
+
+/// Which layer is authoritative when repairing a mismatched numeral.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RepairSource {
+    /// Regenerate the NUM glyphs from the SAṄKHYĀ digit-words.
+    Words,
+    /// Regenerate the SAṄKHYĀ digit-words from the NUM glyphs.
+    Glyphs,
+}
+
+/// A numeral span located in a payload.
+struct NumeralSpan {
+    sankhya_offset: usize,
+    num_offset: Option<usize>,
+    end: usize,
+    words: Vec<u8>,
+    glyphs: Vec<u8>,
+}
+
+/// Walk a PHON payload and collect every SAṄKHYĀ span with its NUM pairing.
+fn numeral_spans(data: &[u8]) -> Result<Vec<NumeralSpan>, SlbcError> {
+    let mut spans = Vec::new();
+    let mut i = 0;
+
+    while i < data.len() {
+        match data[i] {
+            META_START => {
+                while i < data.len() && data[i] != META_END {
+                    i += 1;
+                }
+                i += 1;
+            }
+            SANKHYA_START => {
+                let sankhya_offset = i;
+                let (words, consumed) = decode_sankhya(data, i)?;
+                i += consumed;
+                let mut num_offset = None;
+                let mut glyphs = Vec::new();
+                if i < data.len() && data[i] == NUM {
+                    num_offset = Some(i);
+                    let (g, num_consumed) = decode_num(data, i)?;
+                    glyphs = g;
+                    i += num_consumed;
+                }
+                spans.push(NumeralSpan {
+                    sankhya_offset,
+                    num_offset,
+                    end: i,
+                    words,
+                    glyphs,
+                });
+            }
+            NUM => {
+                let (_, consumed) = decode_num(data, i)?;
+                i += consumed;
+            }
+            _ => i += 1,
+        }
+    }
+
+    Ok(spans)
+}
+
+/// Check that every SAṄKHYĀ span agrees with its paired NUM span.
+///
+/// A SAṄKHYĀ span without a NUM span is not a mismatch — bhāṣā-only streams
+/// strip the lipi layer legitimately (§6.3.5).
+pub fn validate_numerals(data: &[u8]) -> Result<Vec<NumeralMismatch>, SlbcError> {
+    Ok(numeral_spans(data)?
+        .into_iter()
+        .filter_map(|span| {
+            let num_offset = span.num_offset?;
+            if span.words == span.glyphs {
+                return None;
+            }
+            Some(NumeralMismatch {
+                sankhya_offset: span.sankhya_offset,
+                num_offset,
+                words: span.words,
+                glyphs: span.glyphs,
+            })
+        })
+        .collect())
+}
+
+/// Rewrite every mismatched numeral so both layers agree, taking the digits
+/// from `source`. Returns the repaired payload and the number of spans fixed.
+pub fn repair_numerals(data: &[u8], source: RepairSource) -> Result<(Vec<u8>, usize), SlbcError> {
+    let mut out = Vec::with_capacity(data.len());
+    let mut copied = 0;
+    let mut fixed = 0;
+
+    for span in numeral_spans(data)? {
+        if span.num_offset.is_none() || span.words == span.glyphs {
+            continue;
+        }
+        let digits = match source {
+            RepairSource::Words => &span.words,
+            RepairSource::Glyphs => &span.glyphs,
+        };
+        if let Some(&bad) = digits.iter().find(|&&d| d > 9) {
+            return Err(SlbcError::malformed(
+                span.sankhya_offset,
+                format_args!(
+                    "cannot regenerate digit-words from glyph byte 0x{:02X}",
+                    bad
+                ),
+            ));
+        }
+        let digit_str: String = digits.iter().map(|&d| (b'0' + d) as char).collect();
+
+        out.extend_from_slice(&data[copied..span.sankhya_offset]);
+        encode_numeral(&digit_str, &mut out);
+        copied = span.end;
+        fixed += 1;
+    }
+    out.extend_from_slice(&data[copied..]);
+
+    Ok((out, fixed))
+}
+
 /// IAST names for digits (for inspection / decode display).
 pub const DIGIT_IAST: [&str; 10] = [
     "śūnya", "eka", "dvi", "tri", "catur", "pañca", "ṣaṣ", "sapta", "aṣṭa", "nava",
 ];
 
+// ── Composed cardinals ──
+
+/// Numbers 11–19, which do not follow the pattern of the higher decades.
+const TEENS: [&str; 9] = [
+    "ekādaśa",
+    "dvādaśa",
+    "trayodaśa",
+    "caturdaśa",
+    "pañcadaśa",
+    "ṣoḍaśa",
+    "saptadaśa",
+    "aṣṭādaśa",
+    "navadaśa",
+];
+
+/// The decades, indexed by their tens digit.
+const DECADES: [&str; 10] = [
+    "",
+    "daśa",
+    "viṃśati",
+    "triṃśat",
+    "catvāriṃśat",
+    "pañcāśat",
+    "ṣaṣṭi",
+    "saptati",
+    "aśīti",
+    "navati",
+];
+
+/// The composed cardinal of a numeral, in IAST: "aṣṭottaraśatam" for 108,
+/// "trayoviṃśati" for 23. Numbers below 100 are given as stems, hundreds as
+/// the neuter "śatam", with the rest joined by *uttara* ("exceeding").
+/// Returns None above 999 or where a leading zero would be lost, which the
+/// digit-words still record.
+pub fn cardinal(digits: &[u8]) -> Option<String> {
+    if digits.is_empty() || digits.len() > 3 || (digits.len() > 1 && digits[0] == 0) {
+        return None;
+    }
+    let n = digits.iter().fold(0usize, |n, &d| n * 10 + d as usize);
+    if n == 0 {
+        return Some(DIGIT_IAST[0].into());
+    }
+    let (hundreds, rest) = (n / 100, n % 100);
+    if hundreds == 0 {
+        return Some(below_hundred(rest));
+    }
+    let head = match hundreds {
+        1 => "śata".to_string(),
+        h => join(DIGIT_IAST[h], "śata"),
+    };
+    if rest == 0 {
+        return Some(head + "m");
+    }
+    Some(join(&join(&below_hundred(rest), "uttara"), &head) + "m")
+}
+
+/// 1–99 as a stem. Before a decade dvi, tri and aṣṭa take the forms dvā,
+/// trayas and aṣṭā (6.3.47–48), optional from forty on (6.3.49) and given
+/// here throughout, except before aśīti.
+fn below_hundred(n: usize) -> String {
+    let (tens, units) = (n / 10, n % 10);
+    match (tens, units) {
+        (0, u) => DIGIT_IAST[u].into(),
+        (1, 0) => DECADES[1].into(),
+        (1, u) => TEENS[u - 1].into(),
+        (t, 0) => DECADES[t].into(),
+        (t, u) => {
+            let unit = match u {
+                2 if t != 8 => "dvā",
+                3 if t != 8 => "trayas",
+                8 if t != 8 => "aṣṭā",
+                u => DIGIT_IAST[u],
+            };
+            join(unit, DECADES[t])
+        }
+    }
+}
+
+/// Join two members of a numeral compound with the sandhi their seam takes.
+fn join(left: &str, right: &str) -> String {
+    let next = right.chars().next().unwrap_or(' ');
+    let vowel = matches!(next, 'a' | 'u');
+    let voiced = vowel || matches!(next, 'v' | 'n' | 'd');
+    let mut stem: Vec<char> = left.chars().collect();
+    let last = stem.pop().unwrap_or(' ');
+    let mut right = right.to_string();
+    let seam = match last {
+        'a' if next == 'a' => {
+            right.remove(0);
+            "ā"
+        }
+        'a' if next == 'u' => {
+            right.remove(0);
+            "o"
+        }
+        'i' if vowel => "y",
+        't' if voiced => "d",
+        's' if voiced && stem.last() == Some(&'a') => {
+            stem.pop();
+            "o"
+        }
+        's' | 'r' if voiced => "r",
+        's' | 'r' if next == 't' => "s",
+        's' | 'r' if next == 'c' => "ś",
+        's' | 'r' => "ḥ",
+        'ṣ' if next == 'n' => {
+            right.replace_range(..1, "ṇ");
+            "ṇ"
+        }
+        'ṣ' if voiced => "ḍ",
+        'ṣ' => "ṭ",
+        _ => {
+            stem.push(last);
+            ""
+        }
+    };
+    let mut out: String = stem.into_iter().collect();
+    out.push_str(seam);
+    out.push_str(&right);
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -149,6 +415,34 @@ mod tests {
         assert!(out.contains(&NUM));
     }
 
+    #[test]
+    fn test_cardinal() {
+        let cases = [
+            ("0", "śūnya"),
+            ("6", "ṣaṣ"),
+            ("12", "dvādaśa"),
+            ("16", "ṣoḍaśa"),
+            ("23", "trayoviṃśati"),
+            ("33", "trayastriṃśat"),
+            ("44", "catuścatvāriṃśat"),
+            ("64", "catuḥṣaṣṭi"),
+            ("82", "dvyaśīti"),
+            ("86", "ṣaḍaśīti"),
+            ("96", "ṣaṇṇavati"),
+            ("100", "śatam"),
+            ("108", "aṣṭottaraśatam"),
+            ("120", "viṃśatyuttaraśatam"),
+            ("430", "triṃśaduttaracatuḥśatam"),
+            ("600", "ṣaṭśatam"),
+        ];
+        for (number, word) in cases {
+            let digits: Vec<u8> = number.bytes().map(|b| b - b'0').collect();
+            assert_eq!(cardinal(&digits).as_deref(), Some(word), "{}", number);
+        }
+        assert_eq!(cardinal(&[1, 0, 0, 8]), None);
+        assert_eq!(cardinal(&[0, 7]), None);
+    }
+
     #[test]
     fn test_roundtrip_sankhya() {
         let mut out = Vec::new();
@@ -157,4 +451,29 @@ mod tests {
         let (digits, _) = decode_sankhya(&out, 0).unwrap();
         assert_eq!(digits, vec![1, 0, 8]);
     }
+
+    #[test]
+    fn test_validate_and_repair_numerals() {
+        let mut out = vec![SPACE];
+        encode_numeral("108", &mut out);
+        assert!(validate_numerals(&out).unwrap().is_empty());
+
+        // Corrupt the last glyph: 108 → 109
+        let last = out.len() - 1;
+        out[last] = 0x09;
+        let mismatches = validate_numerals(&out).unwrap();
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].sankhya_offset, 1);
+        assert_eq!(mismatches[0].words, vec![1, 0, 8]);
+        assert_eq!(mismatches[0].glyphs, vec![1, 0, 9]);
+
+        let (fixed, n) = repair_numerals(&out, RepairSource::Words).unwrap();
+        assert_eq!(n, 1);
+        assert!(validate_numerals(&fixed).unwrap().is_empty());
+        assert_eq!(*fixed.last().unwrap(), 0x08);
+
+        let (fixed, _) = repair_numerals(&out, RepairSource::Glyphs).unwrap();
+        let (digits, _) = decode_sankhya(&fixed, 1).unwrap();
+        assert_eq!(digits, vec![1, 0, 9]);
+    }
 }
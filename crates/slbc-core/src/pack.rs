@@ -0,0 +1,389 @@
+//! Packed cluster mode (§9.6, registry type 0x05).
+//!
+//! The PHON stream spends one byte per phoneme, so a conjunct akṣara such as
+//! `kṣa` or `tra` costs three bytes and a common pair of akṣaras four. A pack
+//! table lists the sequences that are frequent in one container; the packed
+//! payload stores each occurrence inside a pada as the two-byte extension
+//! `META_EXT <code>`, with codes 0x80–0xFF (the code page proper uses codes
+//! below 0x80). The table travels in an embedded DICT chunk, and `dict::open`
+//! unpacks before any other processing, so decoders see ordinary bytes.
+//!
+//! DICT chunk payload:
+//! ```text
+//! REGISTRY_CLUSTER | DICT_MODE_EMBEDDED | entry count (ULEB128)
+//! per entry: length (ULEB128) + phoneme bytes      (code = 0x80 + entry number)
+//! ```
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::akshara;
+use crate::codepage;
+use crate::container::{self, Chunk};
+use crate::dict;
+use crate::error::SlbcError;
+use crate::types::*;
+
+/// Extension code of the first pack table entry.
+pub const FIRST_CODE: u8 = 0x80;
+
+/// Most entries a pack table can hold.
+pub const MAX_ENTRIES: usize = 0x100 - FIRST_CODE as usize;
+
+/// Frequent phoneme sequences and their extension codes.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PackTable {
+    entries: Vec<Vec<u8>>,
+    index: HashMap<Vec<u8>, u8>,
+}
+
+impl PackTable {
+    /// Build a table from phoneme sequences; the first takes `FIRST_CODE`.
+    pub fn from_entries(entries: Vec<Vec<u8>>) -> Result<Self, SlbcError> {
+        if entries.len() > MAX_ENTRIES {
+            return Err(SlbcError::invalid(format_args!(
+                "pack table has {} entries (at most {})",
+                entries.len(),
+                MAX_ENTRIES
+            )));
+        }
+        if let Some(e) = entries.iter().find(|e| e.len() < 2 || !is_plain(e)) {
+            return Err(SlbcError::invalid(format_args!(
+                "invalid pack table entry {:02X?}",
+                e
+            )));
+        }
+        let index = entries
+            .iter()
+            .enumerate()
+            .map(|(i, e)| (e.clone(), FIRST_CODE + i as u8))
+            .collect();
+        Ok(PackTable { entries, index })
+    }
+
+    pub fn entries(&self) -> &[Vec<u8>] {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The sequence packed as `code`.
+    pub fn get(&self, code: u8) -> Option<&[u8]> {
+        let n = code.checked_sub(FIRST_CODE)? as usize;
+        self.entries.get(n).map(|e| e.as_slice())
+    }
+
+    /// The code of a sequence, if it is in the table.
+    pub fn lookup(&self, seq: &[u8]) -> Option<u8> {
+        self.index.get(seq).copied()
+    }
+
+    /// Build the embedded DICT chunk holding this table.
+    pub fn to_chunk(&self) -> Chunk {
+        let mut payload = vec![REGISTRY_CLUSTER, DICT_MODE_EMBEDDED];
+        container::write_uleb128(&mut payload, self.entries.len() as u64);
+        for entry in &self.entries {
+            container::write_uleb128(&mut payload, entry.len() as u64);
+            payload.extend_from_slice(entry);
+        }
+        Chunk {
+            chunk_type: CHUNK_DICT,
+            payload,
+        }
+    }
+
+    /// Parse a DICT chunk payload. Returns `None` for other registry types.
+    pub fn from_chunk_payload(payload: &[u8]) -> Result<Option<Self>, SlbcError> {
+        if payload.first() != Some(&REGISTRY_CLUSTER) {
+            return Ok(None);
+        }
+        if payload.get(1) != Some(&DICT_MODE_EMBEDDED) {
+            return Err(SlbcError::malformed(
+                1,
+                "pack table DICT chunk must use embedded mode",
+            ));
+        }
+        let mut pos = 2;
+        let read = |pos: &mut usize| -> Result<usize, SlbcError> {
+            let (v, consumed) = container::read_uleb128(&payload[(*pos).min(payload.len())..])
+                .map_err(|e| e.context(*pos, "pack table ULEB128 error"))?;
+            *pos += consumed;
+            Ok(v as usize)
+        };
+        let count = read(&mut pos)?;
+        let mut entries = Vec::with_capacity(count.min(MAX_ENTRIES));
+        for _ in 0..count {
+            let len = read(&mut pos)?;
+            let entry = payload.get(pos..pos + len).ok_or_else(|| {
+                SlbcError::truncated(pos, "pack table entry extends beyond chunk")
+            })?;
+            entries.push(entry.to_vec());
+            pos += len;
+        }
+        PackTable::from_entries(entries).map(Some)
+    }
+
+    /// Find the pack table among a container's chunks.
+    pub fn find(chunks: &[Chunk]) -> Result<Option<Self>, SlbcError> {
+        for chunk in chunks.iter().filter(|c| c.chunk_type == CHUNK_DICT) {
+            if let Some(table) = PackTable::from_chunk_payload(&chunk.payload)? {
+                return Ok(Some(table));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Only svara and vyañjana bytes (no extensions, META, or lipi bytes).
+fn is_plain(seq: &[u8]) -> bool {
+    seq.iter().all(|&b| is_svara(b) || is_vyanjana(b))
+}
+
+/// Longest run of adjacent akṣaras a table entry may cover.
+const MAX_RUN: usize = 3;
+
+/// The akṣaras of one pada's contents, as ranges into `content`.
+fn pada_aksharas(content: &[u8]) -> Result<Vec<Range<usize>>, SlbcError> {
+    let wrapped = [&[PADA_START], content, &[PADA_END]].concat();
+    Ok(akshara::aksharas(&wrapped)?
+        .into_iter()
+        .map(|a| a.offset - 1..a.offset - 1 + a.len)
+        .collect())
+}
+
+/// The span of akṣaras `n..n + k`, if they exist and are adjacent.
+fn run(spans: &[Range<usize>], n: usize, k: usize) -> Option<Range<usize>> {
+    let run = spans.get(n..n + k)?;
+    run.windows(2)
+        .all(|w| w[0].end == w[1].start)
+        .then(|| run[0].start..run[k - 1].end)
+}
+
+// ═══════════════════════════════════════════════
+//  Training
+// ═══════════════════════════════════════════════
+
+/// Runs `n..n + k` of a pada's akṣaras that no earlier entry has taken.
+fn free_runs<'s>(
+    spans: &'s [Range<usize>],
+    taken: &'s [bool],
+) -> impl Iterator<Item = (usize, usize, Range<usize>)> + 's {
+    (0..spans.len()).flat_map(move |n| {
+        (1..=MAX_RUN).filter_map(move |k| {
+            let span = run(spans, n, k)?;
+            (!taken[n..n + k].contains(&true)).then_some((n, k, span))
+        })
+    })
+}
+
+/// A pada's contents, its akṣaras, and which akṣaras entries have taken.
+struct TrainingPada {
+    content: Vec<u8>,
+    spans: Vec<Range<usize>>,
+    taken: Vec<bool>,
+}
+
+/// Build a pack table for a set of PHON payloads.
+///
+/// Candidates are single akṣaras of three or more bytes (conjunct clusters)
+/// and runs of two or three adjacent akṣaras (CV pairs and the like), made
+/// only of phoneme bytes. Entries are chosen greedily: each round takes the
+/// candidate with the largest saving — uses × (length − 2), less the
+/// length + 1 bytes of its table entry — over the akṣaras earlier entries
+/// left free, and training stops when no candidate pays for itself.
+pub fn train<'a, I>(payloads: I, max_entries: usize) -> Result<PackTable, SlbcError>
+where
+    I: IntoIterator<Item = &'a [u8]>,
+{
+    let mut padas: Vec<TrainingPada> = Vec::new();
+    for payload in payloads {
+        dict::rewrite_padas(payload, |content, _| {
+            if content.first() != Some(&DICT_REF) {
+                let spans = pada_aksharas(content)?;
+                padas.push(TrainingPada {
+                    content: content.to_vec(),
+                    taken: vec![false; spans.len()],
+                    spans,
+                });
+            }
+            Ok(())
+        })?;
+    }
+
+    let mut entries = Vec::new();
+    while entries.len() < max_entries.min(MAX_ENTRIES) {
+        let mut counts: HashMap<&[u8], usize> = HashMap::new();
+        for p in &padas {
+            for (_, _, span) in free_runs(&p.spans, &p.taken) {
+                let seq = &p.content[span];
+                if seq.len() >= 3 && is_plain(seq) {
+                    *counts.entry(seq).or_insert(0) += 1;
+                }
+            }
+        }
+        let best = counts
+            .into_iter()
+            .map(|(seq, count)| (count * (seq.len() - 2), seq))
+            .filter(|(saving, seq)| *saving > seq.len() + 1)
+            .max_by(|a, b| a.0.cmp(&b.0).then_with(|| b.1.cmp(a.1)));
+        let Some((_, seq)) = best else {
+            break;
+        };
+        let seq = seq.to_vec();
+        for p in &mut padas {
+            let hits: Vec<(usize, usize)> = free_runs(&p.spans, &p.taken)
+                .filter(|(_, _, span)| p.content[span.clone()] == seq[..])
+                .map(|(n, k, _)| (n, k))
+                .collect();
+            for (n, k) in hits {
+                if !p.taken[n..n + k].contains(&true) {
+                    p.taken[n..n + k].fill(true);
+                }
+            }
+        }
+        entries.push(seq);
+    }
+    PackTable::from_entries(entries)
+}
+
+// ═══════════════════════════════════════════════
+//  Packing
+// ═══════════════════════════════════════════════
+
+/// Replace table sequences inside padas with their extension codes. Entries
+/// take precedence in table order, each claiming its occurrences left to
+/// right among the akṣaras still free — the order `train` chose them in.
+pub fn pack(data: &[u8], table: &PackTable) -> Result<Vec<u8>, SlbcError> {
+    dict::rewrite_padas(data, |content, out| {
+        if content.first() == Some(&DICT_REF) || table.is_empty() {
+            out.extend_from_slice(content);
+            return Ok(());
+        }
+        let spans = pada_aksharas(content)?;
+        let mut taken = vec![false; spans.len()];
+        let mut hits: Vec<(u8, usize, usize)> = free_runs(&spans, &taken)
+            .filter_map(|(n, k, span)| table.lookup(&content[span]).map(|c| (c, n, k)))
+            .collect();
+        hits.sort();
+        let mut chosen: Vec<(Range<usize>, u8)> = Vec::new();
+        for (code, n, k) in hits {
+            if !taken[n..n + k].contains(&true) {
+                taken[n..n + k].fill(true);
+                chosen.push((spans[n].start..spans[n + k - 1].end, code));
+            }
+        }
+        chosen.sort_by_key(|(span, _)| span.start);
+        let mut copied = 0;
+        for (span, code) in chosen {
+            out.extend_from_slice(&content[copied..span.start]);
+            out.extend_from_slice(&[META_EXT, code]);
+            copied = span.end;
+        }
+        out.extend_from_slice(&content[copied..]);
+        Ok(())
+    })
+}
+
+/// Copy one pada's contents, expanding packed codes through `table` (or
+/// copying them when `None`). Returns the number of codes seen.
+fn unpack_pada(
+    content: &[u8],
+    table: Option<&PackTable>,
+    out: &mut Vec<u8>,
+) -> Result<usize, SlbcError> {
+    if content.first() == Some(&DICT_REF) {
+        out.extend_from_slice(content);
+        return Ok(0);
+    }
+    let mut codes = 0;
+    let mut i = 0;
+    while i < content.len() {
+        let width = match content[i] {
+            META_START => content[i..]
+                .iter()
+                .position(|&b| b == META_END)
+                .map_or(content.len() - i, |p| p + 1),
+            META_EXT if content.get(i + 1).is_some_and(|&c| c >= FIRST_CODE) => {
+                codes += 1;
+                if let Some(table) = table {
+                    let code = content[i + 1];
+                    let seq = table.get(code).ok_or_else(|| {
+                        SlbcError::invalid(format_args!(
+                            "packed code 0x{:02X} not in pack table",
+                            code
+                        ))
+                    })?;
+                    out.extend_from_slice(seq);
+                    i += 2;
+                    continue;
+                }
+                2
+            }
+            META_EXT => codepage::ext_len(content, i).unwrap_or(content.len() - i),
+            _ => 1,
+        };
+        out.extend_from_slice(&content[i..i + width]);
+        i += width;
+    }
+    Ok(codes)
+}
+
+/// Expand every packed code back to its phoneme bytes.
+pub fn unpack(data: &[u8], table: &PackTable) -> Result<Vec<u8>, SlbcError> {
+    dict::rewrite_padas(data, |content, out| {
+        unpack_pada(content, Some(table), out).map(|_| ())
+    })
+}
+
+/// Returns true if a payload holds any packed code.
+pub fn has_packed(data: &[u8]) -> Result<bool, SlbcError> {
+    let mut found = false;
+    dict::rewrite_padas(data, |content, out| {
+        found |= unpack_pada(content, None, out)? > 0;
+        Ok(())
+    })?;
+    Ok(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoder;
+
+    #[test]
+    fn test_pack_roundtrip_and_saving() {
+        // Bhagavad Gītā 2.11–25
+        let text = include_str!("../testdata/bhagavadgita_2_11-25.txt");
+        let data = encoder::encode_iast(text).unwrap();
+        let table = train([data.as_slice()], MAX_ENTRIES).unwrap();
+        assert!(!table.is_empty());
+        let packed = pack(&data, &table).unwrap();
+        assert!(has_packed(&packed).unwrap());
+        assert_eq!(unpack(&packed, &table).unwrap(), data);
+
+        let chunk = table.to_chunk();
+        // The payload shrinks by over 5%, and the table more than pays for itself
+        assert!(packed.len() * 100 < data.len() * 95);
+        assert!(packed.len() + chunk.payload.len() + 2 < data.len());
+        assert_eq!(PackTable::find(&[chunk]).unwrap(), Some(table));
+    }
+
+    #[test]
+    fn test_pack_preserves_other_bytes() {
+        let data = encoder::encode_iast("kṣatriyaḥ 8 kṣatriyāḥ qatra ||").unwrap();
+        let kṣa = encoder::encode_iast("kṣa").unwrap();
+        let table = PackTable::from_entries(vec![kṣa[1..kṣa.len() - 1].to_vec()]).unwrap();
+        let packed = pack(&data, &table).unwrap();
+        assert_eq!(packed.len(), data.len() - 2);
+        assert_eq!(unpack(&packed, &table).unwrap(), data);
+        assert!(unpack(&packed, &PackTable::default()).is_err());
+        assert!(!has_packed(&data).unwrap());
+        assert!(PackTable::from_entries(vec![vec![META_EXT, 0x08]]).is_err());
+    }
+}
@@ -0,0 +1,404 @@
+//! Padas as values.
+//!
+//! A `Pada` owns one word — the bytes between PADA_START and PADA_END,
+//! including any language tag — together with its akṣaras, so sandhi, meter,
+//! and search code can ask about a word without reparsing the payload.
+
+use crate::akshara::{self, Akshara};
+use crate::codepage;
+use crate::decoder::{self, Script};
+use crate::encoder;
+use crate::error::SlbcError;
+use crate::lang::{self, Language};
+use crate::transform::{self, SandhiOptions};
+use crate::types::*;
+
+/// One pada and its cached syllabification.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pada {
+    bytes: Vec<u8>,
+    /// Offsets are into `bytes`.
+    aksharas: Vec<Akshara>,
+}
+
+impl Pada {
+    /// Wrap pada contents (without PADA_START/PADA_END).
+    pub fn new(bytes: Vec<u8>) -> Result<Pada, SlbcError> {
+        if let Some(i) = bytes.iter().position(|&b| b == PADA_START || b == PADA_END) {
+            return Err(SlbcError::malformed(
+                i,
+                "pada contents must not contain pada boundaries",
+            ));
+        }
+        if let Some(i) = bytes.iter().position(|&b| b == DICT_REF) {
+            return Err(SlbcError::malformed(
+                i,
+                "pada contents must not hold dictionary references",
+            ));
+        }
+        lang::split_tag(&bytes)?;
+        let aksharas = akshara::aksharas(&framed(&bytes))?
+            .into_iter()
+            .map(|a| Akshara {
+                offset: a.offset - 1,
+                len: a.len,
+            })
+            .collect();
+        Ok(Pada { bytes, aksharas })
+    }
+
+    /// Encode one IAST word.
+    pub fn from_iast(word: &str) -> Result<Pada, SlbcError> {
+        let data = encoder::encode_iast(word)?;
+        match padas(&data)?.as_slice() {
+            [pada] if data.len() == pada.bytes.len() + 2 => Ok(pada.clone()),
+            _ => Err(SlbcError::invalid(format_args!(
+                "'{}' is not a single pada",
+                word
+            ))),
+        }
+    }
+
+    /// The pada contents.
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// The contents between PADA_START and PADA_END.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        framed(&self.bytes)
+    }
+
+    pub fn language(&self) -> Language {
+        lang::pada_language(&self.bytes).unwrap_or_default()
+    }
+
+    /// Akṣaras, with offsets into `bytes()`.
+    pub fn aksharas(&self) -> &[Akshara] {
+        &self.aksharas
+    }
+
+    /// The bytes of the first phoneme (two for an extension code).
+    pub fn initial(&self) -> Option<&[u8]> {
+        self.phonemes()
+            .next()
+            .map(|(i, len)| &self.bytes[i..i + len])
+    }
+
+    /// The bytes of the last phoneme (two for an extension code).
+    pub fn final_phoneme(&self) -> Option<&[u8]> {
+        self.phonemes()
+            .last()
+            .map(|(i, len)| &self.bytes[i..i + len])
+    }
+
+    /// Join this pada to the next one in saṃhitā, returning both as they
+    /// then stand. Two junctions are applied:
+    ///
+    /// - savarṇa vowels merge into their dīrgha (6.1.101), which ends this
+    ///   pada while `next` loses its initial vowel;
+    /// - a final nasal or anusvāra takes the place of a following varga
+    ///   consonant (8.4.58).
+    ///
+    /// Pluta, pragṛhya padas (the built-in particles only; see
+    /// `apply_final_sandhi_with`), extension phonemes, and every other
+    /// junction are left as they are.
+    pub fn apply_final_sandhi(&self, next: &Pada) -> Result<(Pada, Pada), SlbcError> {
+        let (left, right, _) = self.apply_final_sandhi_with(next, &Pragrhya::default())?;
+        Ok((left, right))
+    }
+
+    /// `apply_final_sandhi`, with the caller's pragṛhya list, reporting
+    /// which rule decided the junction.
+    pub fn apply_final_sandhi_with(
+        &self,
+        next: &Pada,
+        pragrhya: &Pragrhya,
+    ) -> Result<(Pada, Pada, Junction), SlbcError> {
+        let unchanged = |junction| Ok((self.clone(), next.clone(), junction));
+        let (Some((fi, 1)), Some((ni, 1))) = (self.phonemes().last(), next.phonemes().next())
+        else {
+            return unchanged(Junction::Unchanged);
+        };
+        let (last, first) = (self.bytes[fi], next.bytes[ni]);
+
+        // 6.1.125 plutapragṛhyā aci nityam: no vowel sandhi after a
+        // pragṛhya
+        if is_svara(first) {
+            if let Some(rule) = pragrhya.rule(self) {
+                return unchanged(Junction::Pragrhya(rule));
+            }
+        }
+
+        let strict = SandhiOptions {
+            strict: true,
+            ..SandhiOptions::default()
+        };
+        if let Ok(r) = transform::savarna_dirgha_with(last, first, &strict) {
+            let mut left = self.bytes.clone();
+            left[fi] = r.transform.output_byte;
+            let mut right = next.bytes.clone();
+            right.remove(ni);
+            return Ok((
+                Pada::new(left)?,
+                Pada::new(right)?,
+                Junction::Applied(r.rule),
+            ));
+        }
+        if let Ok(r) = transform::assimilate_nasal(last, first) {
+            let mut left = self.bytes.clone();
+            left[fi] = r.output_byte;
+            return Ok((
+                Pada::new(left)?,
+                next.clone(),
+                Junction::Applied(ASSIMILATION_RULE),
+            ));
+        }
+        unchanged(Junction::Unchanged)
+    }
+
+    pub fn to_iast(&self) -> String {
+        decoder::decode_phon(&self.to_bytes(), Script::Iast)
+            .expect("pada bytes are validated on construction")
+    }
+
+    /// (offset, width) of each phoneme after any language tag.
+    fn phonemes(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let mut i = lang::split_tag(&self.bytes).map_or(0, |(_, n)| n);
+        std::iter::from_fn(move || {
+            while i < self.bytes.len() {
+                let at = i;
+                let b = self.bytes[i];
+                if b == META_EXT && codepage::read_ext(&self.bytes, i).is_ok() {
+                    i += 2;
+                    return Some((at, 2));
+                }
+                if b == META_EXT {
+                    i += codepage::ext_len(&self.bytes, i).unwrap_or(1);
+                    continue;
+                }
+                i += 1;
+                if is_svara(b) || is_vyanjana(b) {
+                    return Some((at, 1));
+                }
+            }
+            None
+        })
+    }
+}
+
+fn framed(bytes: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(bytes.len() + 2);
+    out.push(PADA_START);
+    out.extend_from_slice(bytes);
+    out.push(PADA_END);
+    out
+}
+
+/// Every top-level pada of a PHON payload, in order.
+pub fn padas(data: &[u8]) -> Result<Vec<Pada>, SlbcError> {
+    lang::padas(data)?
+        .into_iter()
+        .map(|(_, contents)| Pada::new(contents.to_vec()))
+        .collect()
+}
+
+/// The pada without language tag, accents cleared, for comparing word
+/// forms. Seams, compound boundaries, yamas, and case marks are dropped.
+fn plain_form(bytes: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = lang::split_tag(bytes).map_or(0, |(_, n)| n);
+    while i < bytes.len() {
+        match bytes[i] {
+            META_EXT if codepage::is_samasa(bytes, i) => i += 1,
+            MORPH | YAMA | NASIKYA | CAPITAL | UPPERCASE => {}
+            b if is_svara(b) => out.push(b & 0b11_00_11_11),
+            b => out.push(b),
+        }
+        i += 1;
+    }
+    out
+}
+
+const ASSIMILATION_RULE: &str = "8.4.58 anusvārasya yayi parasavarṇaḥ";
+
+// ═══════════════════════════════════════════════
+//  Pragṛhya (1.1.11–1.1.15)
+// ═══════════════════════════════════════════════
+
+/// How `apply_final_sandhi_with` decided a junction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Junction {
+    /// A sandhi rule applied (its sūtra).
+    Applied(&'static str),
+    /// The left pada is pragṛhya, so the vowels stay apart (the sūtra
+    /// making it so).
+    Pragrhya(&'static str),
+    /// No rule applies.
+    Unchanged,
+}
+
+/// Single-vowel particles other than āṅ (1.1.14 nipāta ekāj anāṅ).
+const EKAC_PARTICLES: &[&str] = &["a", "ā", "i", "ī", "u", "ū"];
+/// Particles ending in o (1.1.15 ot).
+const O_PARTICLES: &[&str] = &["aho", "atho", "utāho", "ho"];
+/// Plural of adas after m (1.1.12 adaso māt).
+const ADAS_FORMS: &[&str] = &["amī", "amū"];
+
+const DUAL_RULE: &str = "1.1.11 īdūded dvivacanaṃ pragṛhyam";
+const LISTED_RULE: &str = "pragṛhya by the caller's list";
+
+/// Which padas are pragṛhya: their final vowel stays uncombined before a
+/// vowel.
+///
+/// The particles of 1.1.12–1.1.15 are built in. Dual forms cannot be told
+/// from their spelling (phale is dual or locative), so the caller lists
+/// the duals of the text; one is pragṛhya if it ends in ī, ū, or e
+/// (1.1.11). Words may also be added outright, or excluded, overriding the
+/// built-in lists. Forms are compared without accents or morpheme seams.
+#[derive(Debug, Clone, Default)]
+pub struct Pragrhya {
+    /// Dual forms (pada contents).
+    pub duals: Vec<Vec<u8>>,
+    /// Padas always pragṛhya.
+    pub include: Vec<Vec<u8>>,
+    /// Padas never pragṛhya.
+    pub exclude: Vec<Vec<u8>>,
+}
+
+impl Pragrhya {
+    /// Build the lists from IAST words.
+    pub fn from_iast(
+        duals: &[&str],
+        include: &[&str],
+        exclude: &[&str],
+    ) -> Result<Self, SlbcError> {
+        let forms = |words: &[&str]| -> Result<Vec<Vec<u8>>, SlbcError> {
+            words
+                .iter()
+                .map(|w| Ok(plain_form(Pada::from_iast(w)?.bytes())))
+                .collect()
+        };
+        Ok(Pragrhya {
+            duals: forms(duals)?,
+            include: forms(include)?,
+            exclude: forms(exclude)?,
+        })
+    }
+
+    /// The sūtra making `pada` pragṛhya, if it is.
+    pub fn rule(&self, pada: &Pada) -> Option<&'static str> {
+        let form = plain_form(pada.bytes());
+        let listed = |list: &[Vec<u8>]| list.iter().any(|w| plain_form(w) == form);
+        let builtin = |words: &[&str]| {
+            words
+                .iter()
+                .any(|w| Pada::from_iast(w).is_ok_and(|p| p.bytes() == form))
+        };
+        if listed(&self.exclude) {
+            return None;
+        }
+        if listed(&self.include) {
+            return Some(LISTED_RULE);
+        }
+        if listed(&self.duals) && matches!(form.last(), Some(0x84 | 0x88 | 0x85)) {
+            return Some(DUAL_RULE);
+        }
+        if builtin(ADAS_FORMS) {
+            return Some("1.1.12 adaso māt");
+        }
+        if builtin(EKAC_PARTICLES) {
+            return Some("1.1.14 nipāta ekāj anāṅ");
+        }
+        if builtin(O_PARTICLES) {
+            return Some("1.1.15 ot");
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pada() {
+        let p = Pada::from_iast("kṛṣṇaḥ").unwrap();
+        assert_eq!(p.aksharas().len(), 2);
+        assert_eq!(p.initial(), Some(&[0x00][..]));
+        assert_eq!(p.final_phoneme(), Some(&[0x39][..]));
+        assert_eq!(p.to_iast(), "kṛṣṇaḥ");
+        assert_eq!(p.language(), Language::Sanskrit);
+        assert!(Pada::from_iast("rāma kṛṣṇa").is_err());
+        assert!(Pada::new(vec![0x00, PADA_END]).is_err());
+
+        let all = padas(&encoder::encode_iast("rāma iti | vana").unwrap()).unwrap();
+        assert_eq!(
+            all.iter().map(Pada::to_iast).collect::<Vec<_>>(),
+            ["rāma", "iti", "vana"]
+        );
+    }
+
+    #[test]
+    fn test_apply_final_sandhi() {
+        let join = |a: &str, b: &str| {
+            let (l, r) = Pada::from_iast(a)
+                .unwrap()
+                .apply_final_sandhi(&Pada::from_iast(b).unwrap())
+                .unwrap();
+            format!("{} {}", l.to_iast(), r.to_iast())
+        };
+        assert_eq!(join("rāma", "avatāra"), "rāmā vatāra");
+        assert_eq!(join("dadhi", "idam"), "dadhī dam");
+        assert_eq!(join("tam", "kṛṣṇam"), "taṅ kṛṣṇam");
+        assert_eq!(join("saṃ", "tāpa"), "san tāpa");
+        assert_eq!(join("rāma", "iti"), "rāma iti");
+    }
+
+    #[test]
+    fn test_pragrhya() {
+        let join = |a: &str, b: &str, pragrhya: &Pragrhya| {
+            let (l, r, junction) = Pada::from_iast(a)
+                .unwrap()
+                .apply_final_sandhi_with(&Pada::from_iast(b).unwrap(), pragrhya)
+                .unwrap();
+            (format!("{} {}", l.to_iast(), r.to_iast()), junction)
+        };
+        let none = Pragrhya::default();
+        assert_eq!(
+            join("kavī", "iha", &none),
+            (
+                "kavī ha".into(),
+                Junction::Applied("6.1.101 akaḥ savarṇe dīrghaḥ")
+            )
+        );
+        let duals = Pragrhya::from_iast(&["kavī", "rāmau"], &[], &[]).unwrap();
+        assert_eq!(
+            join("kavī", "iha", &duals),
+            ("kavī iha".into(), Junction::Pragrhya(DUAL_RULE))
+        );
+        assert_eq!(
+            join("amī", "īśāḥ", &none).1,
+            Junction::Pragrhya("1.1.12 adaso māt")
+        );
+        assert_eq!(
+            join("i", "indram", &none).1,
+            Junction::Pragrhya("1.1.14 nipāta ekāj anāṅ")
+        );
+        // Only before a vowel
+        assert_eq!(join("i", "ma", &none).1, Junction::Unchanged);
+        // A dual in au is not pragṛhya
+        assert_eq!(duals.rule(&Pada::from_iast("rāmau").unwrap()), None);
+
+        let overrides = Pragrhya::from_iast(&[], &["dadhi"], &["i"]).unwrap();
+        assert_eq!(
+            join("dadhi", "idam", &overrides).1,
+            Junction::Pragrhya(LISTED_RULE)
+        );
+        assert_eq!(join("i", "indram", &overrides).0, "ī ndram");
+        assert_eq!(
+            join("tam", "kṛṣṇam", &none).1,
+            Junction::Applied(ASSIMILATION_RULE)
+        );
+    }
+}
@@ -0,0 +1,232 @@
+//! A pull iterator over a PHON payload.
+//!
+//! `decoder::phonemes` walks a payload as typed events — frame and pada
+//! boundaries, phonemes, numerals, punctuation, META blocks — so a library
+//! caller can follow the stream without its own control-byte state
+//! machine. The payload is read as given: emendation blocks and annotations
+//! are reported as META blocks, and degenerate framing is not normalized.
+//! A byte the decoders would reject ends the walk with that error.
+
+use crate::codepage::{self, EXT_LONG};
+use crate::container;
+use crate::error::SlbcError;
+use crate::numeral;
+use crate::types::*;
+
+/// One step through a payload.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event<'a> {
+    PhonStart,
+    PhonEnd,
+    PadaStart,
+    PadaEnd,
+    /// A svara byte, accent and quantity bits included.
+    Svara(u8),
+    Vyanjana(u8),
+    /// An extension phoneme (§6.4), by its code.
+    Ext(u8),
+    /// A long extension's payload (§6.4), which has no written form.
+    LongExt(&'a [u8]),
+    /// A morpheme seam (§6.5).
+    Morph,
+    /// A compound boundary.
+    Samasa,
+    /// A mark on the phoneme or pada next to it: CANDRABINDU, PRANAVA,
+    /// YAMA, NASIKYA, CAPITAL, or UPPERCASE.
+    Mark(u8),
+    Space,
+    LineBreak,
+    Danda,
+    DoubleDanda,
+    Avagraha,
+    /// A punctuation mark from the extension code page.
+    Punctuation(char),
+    Illegible,
+    Lacuna,
+    /// A number: digits, most significant first, from the SAṄKHYĀ span,
+    /// which takes the NUM span after it along; a NUM span on its own
+    /// gives its glyphs.
+    Numeral(Vec<u8>),
+    /// The bytes between META_START and META_END; an unclosed block runs
+    /// to the end of the payload, as the decoders read it.
+    Meta(&'a [u8]),
+    /// A pada-dictionary reference, by index.
+    DictRef(u64),
+}
+
+/// The events of a payload, in order (see `decoder::phonemes`).
+#[derive(Debug, Clone)]
+pub struct PhonemeIter<'a> {
+    data: &'a [u8],
+    pos: usize,
+    offset: usize,
+    failed: bool,
+}
+
+impl<'a> PhonemeIter<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        PhonemeIter {
+            data,
+            pos: 0,
+            offset: 0,
+            failed: false,
+        }
+    }
+
+    /// Offset of the first byte of the event last returned.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// The event at `i` and its length in bytes.
+    fn event(&self, i: usize) -> Result<(Event<'a>, usize), SlbcError> {
+        let data = self.data;
+        let b = data[i];
+        let event = match b {
+            PHON_START => Event::PhonStart,
+            PHON_END => Event::PhonEnd,
+            PADA_START => Event::PadaStart,
+            PADA_END => Event::PadaEnd,
+            META_START => {
+                let end = data[i + 1..].iter().position(|&b| b == META_END);
+                let body = &data[i + 1..end.map_or(data.len(), |p| i + 1 + p)];
+                return Ok((
+                    Event::Meta(body),
+                    body.len() + 1 + usize::from(end.is_some()),
+                ));
+            }
+            SANKHYA_START => {
+                let (digits, mut len) = numeral::decode_sankhya(data, i)?;
+                if data.get(i + len) == Some(&NUM) {
+                    len += numeral::decode_num(data, i + len)?.1;
+                }
+                return Ok((Event::Numeral(digits), len));
+            }
+            NUM => {
+                let (digits, len) = numeral::decode_num(data, i)?;
+                return Ok((Event::Numeral(digits), len));
+            }
+            DICT_REF => {
+                let (index, len) = container::read_uleb128(&data[i + 1..])
+                    .map_err(|e| e.context(i + 1, "DICT_REF index"))?;
+                return Ok((Event::DictRef(index), 1 + len));
+            }
+            META_EXT => {
+                let len = codepage::ext_len(data, i)?;
+                let event = match data[i + 1] {
+                    EXT_LONG => Event::LongExt(codepage::read_long_ext(data, i)?.0),
+                    codepage::SAMASA => Event::Samasa,
+                    code => match codepage::punctuation(code) {
+                        Some(mark) => Event::Punctuation(mark),
+                        None => Event::Ext(codepage::read_ext(data, i)?.code),
+                    },
+                };
+                return Ok((event, len));
+            }
+            MORPH => Event::Morph,
+            CANDRABINDU | PRANAVA | YAMA | NASIKYA | CAPITAL | UPPERCASE => Event::Mark(b),
+            SPACE => Event::Space,
+            LINE_BREAK => Event::LineBreak,
+            DANDA => Event::Danda,
+            DOUBLE_DANDA => Event::DoubleDanda,
+            AVAGRAHA => Event::Avagraha,
+            ILLEGIBLE => Event::Illegible,
+            LACUNA => Event::Lacuna,
+            b if is_svara(b) => Event::Svara(b),
+            b if is_vyanjana(b) => Event::Vyanjana(b),
+            _ => {
+                return Err(SlbcError::malformed(
+                    i,
+                    format_args!("unexpected byte 0x{:02X}", b),
+                ))
+            }
+        };
+        Ok((event, 1))
+    }
+}
+
+impl<'a> Iterator for PhonemeIter<'a> {
+    type Item = Result<Event<'a>, SlbcError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.pos >= self.data.len() {
+            return None;
+        }
+        self.offset = self.pos;
+        match self.event(self.pos) {
+            Ok((event, len)) => {
+                self.pos += len;
+                Some(Ok(event))
+            }
+            Err(e) => {
+                self.failed = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::annotation::{self, Annotation};
+    use crate::decoder;
+    use crate::encoder::{self, EncodeOptions, SamasaMarker};
+
+    #[test]
+    fn test_events() {
+        let opts = EncodeOptions {
+            samasa: SamasaMarker::Hyphen,
+            ..Default::default()
+        };
+        let data = encoder::encode_iast_with("rāma-ḻa, 12 ||", &opts).unwrap();
+        let events: Vec<Event> = decoder::phonemes(&data).map(Result::unwrap).collect();
+        assert_eq!(
+            events,
+            [
+                Event::PadaStart,
+                Event::Vyanjana(0x33),
+                Event::Svara(0x80),
+                Event::Vyanjana(0x24),
+                Event::Svara(0x40),
+                Event::Samasa,
+                Event::Ext(0x01),
+                Event::Svara(0x40),
+                Event::PadaEnd,
+                Event::Punctuation(','),
+                Event::Space,
+                Event::Numeral(vec![1, 2]),
+                Event::Space,
+                Event::DoubleDanda,
+            ]
+        );
+
+        let note = Annotation::new(annotation::KEY_VERSE, "1");
+        let noted = annotation::insert(&data, 0, &note).unwrap();
+        let mut iter = decoder::phonemes(&noted);
+        let Some(Ok(Event::Meta(body))) = iter.next() else {
+            panic!("expected a META block");
+        };
+        assert_eq!(Annotation::parse(body), Some(note));
+        assert_eq!(iter.next(), Some(Ok(Event::PadaStart)));
+        assert_eq!(iter.offset(), body.len() + 2);
+    }
+
+    #[test]
+    fn test_errors_end_the_walk() {
+        // 0x7E is no extension phoneme
+        let data = [
+            PADA_START, DICT_REF, 0x81, 0x01, PADA_END, META_EXT, 0x7E, SPACE,
+        ];
+        let mut iter = decoder::phonemes(&data);
+        assert_eq!(iter.next(), Some(Ok(Event::PadaStart)));
+        assert_eq!(iter.next(), Some(Ok(Event::DictRef(129))));
+        assert_eq!(iter.next(), Some(Ok(Event::PadaEnd)));
+        assert!(matches!(
+            iter.next(),
+            Some(Err(SlbcError::Malformed { .. }))
+        ));
+        assert_eq!(iter.offset(), 5);
+        assert_eq!(iter.next(), None);
+    }
+}
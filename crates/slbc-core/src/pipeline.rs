@@ -0,0 +1,470 @@
+//! End-to-end ingest: script detection → encode → validate → canonicalize →
+//! index → stats, in one configurable call.
+//!
+//! Each stage is the same function the CLI subcommands use (`encoder`,
+//! `numeral::validate_numerals`, `frame::normalize_framing`,
+//! `AksharaIndex`, `VerseSums`); the pipeline
+//! only fixes their order and collects what each one reports. `check` runs
+//! the matching checks on a stored container, for integrity sweeps over an
+//! archive.
+
+use std::collections::HashSet;
+
+use crate::akshara::{self, AksharaIndex};
+use crate::checksum::VerseSums;
+use crate::container;
+use crate::decoder::{self, DecodeOptions, Script};
+use crate::dict::{self, DictResolver};
+use crate::encoder::{self, CaseFolding, EncodeOptions};
+use crate::error::SlbcError;
+use crate::frame::{self, FramingIssue};
+use crate::lang::{self, PhonotacticIssue};
+use crate::layers;
+use crate::numeral::{self, NumeralMismatch, RepairSource};
+use crate::provenance::{self, Operation};
+use crate::types::*;
+
+// ═══════════════════════════════════════════════
+//  Script detection
+// ═══════════════════════════════════════════════
+
+/// Romanizations tried on ASCII input, in tie-breaking order.
+const ASCII_CANDIDATES: [Script; 6] = [
+    Script::Iast,
+    Script::HarvardKyoto,
+    Script::Itrans,
+    Script::Slp1,
+    Script::Velthuis,
+    Script::Wx,
+];
+
+/// How implausible a payload is as Sanskrit: phonotactic violations, stop
+/// or sibilant + h (an unread digraph), a nasal before a stop of another
+/// place, a retroflex stop outside ṇ/ṣ/retroflex company or a dental after
+/// ṣ (a misread dental/retroflex pair), and the rare ḷ/ḹ/ṝ and extension
+/// sounds.
+fn implausibility(data: &[u8]) -> Result<usize, SlbcError> {
+    let is_stop = |b: u8| b <= 0x23 && b & 0x07 < 0x04;
+    let is_retroflex = |b: u8| (0x10..=0x14).contains(&b) || b == 0x2A;
+    let mut score = lang::check_phonotactics(data)?.len();
+    for (_, pada) in lang::padas(data)? {
+        let mut prev = None;
+        for (j, &b) in pada.iter().enumerate() {
+            let next = pada.get(j + 1).copied();
+            if (is_stop(b) || (0x29..=0x2B).contains(&b)) && next == Some(0x38) {
+                score += 1;
+            }
+            if b <= 0x1C && b & 0x07 == 0x04 && next.is_some_and(|n| is_stop(n) && n >> 3 != b >> 3)
+            {
+                score += 1;
+            }
+            if (0x10..=0x13).contains(&b)
+                && !prev.is_some_and(is_retroflex)
+                && !next.is_some_and(is_retroflex)
+            {
+                score += 1;
+            }
+            if prev == Some(0x2A) && (0x18..=0x1B).contains(&b) {
+                score += 1;
+            }
+            if is_svara(b) && matches!(b & 0b11_00_11_11, 0x4F | 0x8F | 0x8C) {
+                score += 2;
+            }
+            if b == META_EXT {
+                score += 2;
+            }
+            prev = Some(b);
+        }
+    }
+    Ok(score)
+}
+
+/// Guess the script of `text`. Devanāgarī is recognised by its characters;
+/// other text is read in every romanization that could have produced it
+/// (IAST or ISO 15919 when it has diacritics, the ASCII schemes otherwise)
+/// and the most plausible reading wins, IAST on ties.
+pub fn detect_script(text: &str) -> Script {
+    let is_deva = |c: &char| ('\u{0900}'..='\u{097F}').contains(c);
+    let deva = text.chars().filter(is_deva).count();
+    let latin = text
+        .chars()
+        .filter(|c| c.is_alphabetic() && !is_deva(c))
+        .count();
+    if deva > latin {
+        return Script::Devanagari;
+    }
+    let candidates: &[Script] = if text.chars().any(|c| c.is_alphabetic() && !c.is_ascii()) {
+        &[Script::Iast, Script::Iso15919]
+    } else {
+        &ASCII_CANDIDATES
+    };
+    candidates
+        .iter()
+        .filter_map(|&script| {
+            let data = encoder::encode_script(text, script).ok()?;
+            Some((implausibility(&data).ok()?, script))
+        })
+        .min_by_key(|&(score, _)| score)
+        .map_or(Script::Iast, |(_, script)| script)
+}
+
+// ═══════════════════════════════════════════════
+//  Ingest
+// ═══════════════════════════════════════════════
+
+/// Which stages run, and how.
+#[derive(Debug, Clone)]
+pub struct IngestOptions {
+    /// Input script; detected when `None`.
+    pub script: Option<Script>,
+    /// Rewrite degenerate framing (empty padas, doubled SPACEs, nested PHON).
+    pub normalize_framing: bool,
+    /// Repair mismatched numerals from this layer.
+    pub repair_numerals: Option<RepairSource>,
+    /// Also check pada phonotactics.
+    pub phonotactics: bool,
+    pub akshara_index: bool,
+    pub verse_sums: bool,
+}
+
+impl Default for IngestOptions {
+    fn default() -> Self {
+        IngestOptions {
+            script: None,
+            normalize_framing: true,
+            repair_numerals: None,
+            phonotactics: false,
+            akshara_index: true,
+            verse_sums: true,
+        }
+    }
+}
+
+/// Counts over one or more payloads.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Stats {
+    pub documents: usize,
+    pub verses: usize,
+    pub padas: usize,
+    pub distinct_padas: usize,
+    pub aksharas: usize,
+    /// PHON payload bytes.
+    pub bytes: usize,
+}
+
+impl Stats {
+    /// Stats over a corpus, one payload per document.
+    pub fn collect<'a, I>(documents: I) -> Result<Self, SlbcError>
+    where
+        I: IntoIterator<Item = &'a [u8]>,
+    {
+        let documents: Vec<&[u8]> = documents.into_iter().collect();
+        let mut distinct: HashSet<&[u8]> = HashSet::new();
+        let mut stats = Stats {
+            documents: documents.len(),
+            ..Default::default()
+        };
+        for data in documents {
+            // Padas are counted by contents, ignoring any language tag;
+            // numeral digit-words are not padas here
+            for (_, pada) in lang::padas(data)? {
+                let body = &pada[lang::split_tag(pada)?.1..];
+                if !body.is_empty() {
+                    stats.padas += 1;
+                    distinct.insert(body);
+                }
+            }
+            stats.verses += frame::verses(data)?.len();
+            stats.aksharas += akshara::aksharas(data)?.len();
+            stats.bytes += data.len();
+        }
+        stats.distinct_padas = distinct.len();
+        Ok(stats)
+    }
+}
+
+/// One ingested text.
+#[derive(Debug, Clone)]
+pub struct Ingested {
+    pub script: Script,
+    /// Canonical PHON payload.
+    pub payload: Vec<u8>,
+    /// The complete container (header, index chunks, PHON, EOF).
+    pub container: Vec<u8>,
+    /// Found before repair.
+    pub numeral_mismatches: Vec<NumeralMismatch>,
+    /// Found before normalization.
+    pub framing_issues: Vec<FramingIssue>,
+    pub phonotactic_issues: Vec<PhonotacticIssue>,
+    /// Rewrites applied, as recorded in the provenance log.
+    pub operations: Vec<Operation>,
+    pub stats: Stats,
+}
+
+impl Ingested {
+    /// Whether validation left anything unresolved.
+    pub fn has_issues(&self) -> bool {
+        let applied = |name: &str| self.operations.iter().any(|op| op.name == name);
+        !self.numeral_mismatches.is_empty() && !applied("repair-numerals")
+            || !self.framing_issues.is_empty() && !applied("normalize-framing")
+            || !self.phonotactic_issues.is_empty()
+    }
+}
+
+/// Run every stage on one text.
+pub fn ingest(text: &str, opts: &IngestOptions) -> Result<Ingested, SlbcError> {
+    let text = text.trim();
+    let script = opts.script.unwrap_or_else(|| detect_script(text));
+    let mut payload = encoder::encode_script(text, script)?;
+
+    let numeral_mismatches = numeral::validate_numerals(&payload)?;
+    let framing_issues = frame::check_framing(&payload)?;
+    let phonotactic_issues = if opts.phonotactics {
+        lang::check_phonotactics(&payload)?
+    } else {
+        Vec::new()
+    };
+
+    let mut operations = Vec::new();
+    if opts.normalize_framing && !framing_issues.is_empty() {
+        let (clean, sites) = frame::normalize_framing(&payload)?;
+        payload = clean;
+        operations.push(Operation::new("normalize-framing").param("sites", sites));
+    }
+    if let Some(source) = opts
+        .repair_numerals
+        .filter(|_| !numeral_mismatches.is_empty())
+    {
+        let (repaired, n) = numeral::repair_numerals(&payload, source)?;
+        payload = repaired;
+        let name = match source {
+            RepairSource::Words => "words",
+            RepairSource::Glyphs => "glyphs",
+        };
+        operations.push(
+            Operation::new("repair-numerals")
+                .param("source", name)
+                .param("repaired", n),
+        );
+    }
+
+    let mut extra = Vec::new();
+    if opts.akshara_index {
+        extra.push(AksharaIndex::build(&payload, 0)?.to_chunk());
+    }
+    if opts.verse_sums {
+        extra.push(VerseSums::build(&payload, 0)?.to_chunk());
+    }
+    let mut records = Vec::new();
+    if !operations.is_empty() {
+        provenance::append(&mut records, &operations)?;
+    }
+    let container = container::build_slbc_ext(&records, &payload, &extra);
+    let stats = Stats::collect([payload.as_slice()])?;
+
+    Ok(Ingested {
+        script,
+        payload,
+        container,
+        numeral_mismatches,
+        framing_issues,
+        phonotactic_issues,
+        operations,
+        stats,
+    })
+}
+
+// ═══════════════════════════════════════════════
+//  Integrity check
+// ═══════════════════════════════════════════════
+
+/// What checking one stored container found. Counts are over all its PHON
+/// chunks; chunk numbers are 0-based among PHON chunks.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Checked {
+    /// Verses whose checksum does not match; `None` when the container has
+    /// no verse checksums.
+    pub checksum_mismatches: Option<Vec<usize>>,
+    pub numeral_mismatches: Vec<NumeralMismatch>,
+    pub framing_issues: Vec<FramingIssue>,
+    /// PHON frames that do not nest around whole padas, by chunk.
+    pub frame_errors: Vec<(usize, SlbcError)>,
+    /// Chunks whose IAST text does not read back as itself, and why.
+    pub roundtrip_failures: Vec<(usize, SlbcError)>,
+    pub stats: Stats,
+}
+
+impl Checked {
+    /// Whether any check failed.
+    pub fn has_issues(&self) -> bool {
+        self.checksum_mismatches
+            .as_ref()
+            .is_some_and(|m| !m.is_empty())
+            || !self.numeral_mismatches.is_empty()
+            || !self.framing_issues.is_empty()
+            || !self.frame_errors.is_empty()
+            || !self.roundtrip_failures.is_empty()
+    }
+}
+
+/// Check a stored container: verse checksums (on the stored bytes), then,
+/// with dictionary references expanded, numeral agreement, framing, PHON
+/// frame nesting, and an IAST roundtrip — decoded text, encoded again,
+/// must decode to the same text. An error means the container could not
+/// be parsed.
+pub fn check(data: &[u8], resolver: &dyn DictResolver) -> Result<Checked, SlbcError> {
+    let (_, stored) = container::parse_slbc(data)?;
+    let stored = layers::merge_chunks(stored)?;
+    let checksum_mismatches = match VerseSums::find(&stored)? {
+        Some(sums) => {
+            let payload = stored
+                .iter()
+                .filter(|c| c.chunk_type == CHUNK_PHON)
+                .nth(sums.phon_chunk as usize)
+                .ok_or_else(|| {
+                    SlbcError::invalid(format_args!("missing PHON chunk {}", sums.phon_chunk))
+                })?;
+            Some(sums.mismatches(&payload.payload))
+        }
+        None => None,
+    };
+
+    let (header, chunks, _) = dict::open(data, resolver)?;
+    let decode = DecodeOptions {
+        morph_boundaries: true,
+        phon_boundaries: true,
+        ..Default::default()
+    };
+    let encode = EncodeOptions {
+        vedic: header.is_vedic(),
+        fold_case: CaseFolding::Preserve,
+        ..Default::default()
+    };
+    let mut checked = Checked {
+        checksum_mismatches,
+        ..Default::default()
+    };
+    let payloads: Vec<&[u8]> = chunks
+        .iter()
+        .filter(|c| c.chunk_type == CHUNK_PHON)
+        .map(|c| c.payload.as_slice())
+        .collect();
+    for (n, &payload) in payloads.iter().enumerate() {
+        checked
+            .numeral_mismatches
+            .extend(numeral::validate_numerals(payload)?);
+        let issues = frame::check_framing(payload)?;
+        if issues.is_empty() {
+            if let Err(e) = frame::phon_spans(payload) {
+                checked.frame_errors.push((n, e));
+            }
+        }
+        checked.framing_issues.extend(issues);
+
+        let roundtrip =
+            decoder::decode_phon_with(payload, Script::Iast, &decode).and_then(|text| {
+                let again = encoder::encode_script_with(&text, Script::Iast, &encode)?;
+                match decoder::decode_phon_with(&again, Script::Iast, &decode)? {
+                    t if t == text => Ok(()),
+                    _ => Err(SlbcError::invalid("decoded text reads back differently")),
+                }
+            });
+        if let Err(e) = roundtrip {
+            checked.roundtrip_failures.push((n, e));
+        }
+    }
+    checked.stats = Stats::collect(payloads)?;
+    Ok(checked)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoder;
+    use crate::dict;
+
+    const GITA: &str = include_str!("../testdata/bhagavadgita_2_11-25.txt");
+
+    #[test]
+    fn test_detect_script() {
+        let data = encoder::encode_iast(GITA.trim()).unwrap();
+        for &script in Script::INPUT {
+            let text = decoder::decode_phon(&data, script).unwrap();
+            assert_eq!(detect_script(&text), script, "{}", script.name());
+        }
+        for &script in Script::INPUT {
+            let verse = decoder::decode_phon(&data[..frame::verses(&data).unwrap()[0].end], script)
+                .unwrap();
+            assert_eq!(detect_script(&verse), script, "{}", script.name());
+        }
+        assert_eq!(detect_script("tat savitur varenyam"), Script::Iast);
+    }
+
+    #[test]
+    fn test_ingest() {
+        let verse = "tato mAmakAH pANDavAz ca 18 ||";
+        let out = ingest(verse, &IngestOptions::default()).unwrap();
+        assert_eq!(out.script, Script::HarvardKyoto);
+        assert!(!out.has_issues());
+        assert!(out.operations.is_empty());
+        assert_eq!(
+            out.stats,
+            Stats {
+                documents: 1,
+                verses: 1,
+                padas: 4,
+                distinct_padas: 4,
+                aksharas: 9,
+                bytes: out.payload.len(),
+            }
+        );
+
+        let (header, chunks, _) = dict::open(&out.container, &dict::NoDicts).unwrap();
+        assert!(provenance::read(&header).unwrap().is_empty());
+        assert!(AksharaIndex::find(&chunks).unwrap().is_some());
+        let sums = VerseSums::find(&chunks).unwrap().unwrap();
+        assert!(sums.mismatches(&out.payload).is_empty());
+
+        let bare = IngestOptions {
+            script: Some(Script::Iast),
+            akshara_index: false,
+            verse_sums: false,
+            phonotactics: true,
+            ..Default::default()
+        };
+        let out = ingest("gaaṇaṃ", &bare).unwrap();
+        assert_eq!(out.phonotactic_issues.len(), 1);
+        assert!(out.has_issues());
+        assert_eq!(
+            out.container,
+            container::build_slbc(&encoder::encode_iast("gaaṇaṃ").unwrap())
+        );
+    }
+
+    #[test]
+    fn test_check() {
+        let out = ingest(GITA, &IngestOptions::default()).unwrap();
+        let checked = check(&out.container, &dict::NoDicts).unwrap();
+        assert!(!checked.has_issues(), "{:?}", checked);
+        assert_eq!(checked.checksum_mismatches, Some(vec![]));
+        assert_eq!(checked.stats, out.stats);
+
+        // a → ā in the first verse: still a valid stream
+        let mut damaged = out.container.clone();
+        let at = damaged.len() - out.payload.len() - 2;
+        let first = at + out.payload.iter().position(|&b| b == 0x40).unwrap();
+        damaged[first] = 0x80;
+        let checked = check(&damaged, &dict::NoDicts).unwrap();
+        assert!(checked.has_issues());
+        assert_eq!(checked.checksum_mismatches, Some(vec![0]));
+        assert!(checked.roundtrip_failures.is_empty());
+
+        let bare = container::build_slbc(&[
+            PADA_START, 0x40, PADA_END, SPACE, SPACE, PADA_START, 0x40, PADA_END,
+        ]);
+        let checked = check(&bare, &dict::NoDicts).unwrap();
+        assert_eq!(checked.checksum_mismatches, None);
+        assert_eq!(checked.framing_issues.len(), 1);
+        assert!(check(&out.container[..20], &dict::NoDicts).is_err());
+    }
+}
@@ -0,0 +1,224 @@
+//! Proofreading a decoded archive against its source text.
+//!
+//! `align` lines the decoded text up with the original character by
+//! character (a shortest edit script, Myers 1986) and reports each run of
+//! differing characters with its position in both texts. A run of whitespace
+//! matches any other run of whitespace, since the codec keeps word boundaries
+//! but not line breaks, and leading and trailing whitespace is ignored.
+
+/// A place in a text: 0-based char offset, 1-based line and column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pub offset: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// One run of characters where the decoded text departs from the original.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Divergence {
+    pub original: Position,
+    pub decoded: Position,
+    /// The original's characters in the run (empty for an insertion).
+    pub expected: String,
+    /// The decoded characters in the run (empty for a deletion).
+    pub found: String,
+}
+
+/// Every divergence of `decoded` from `original`, in text order.
+pub fn align(original: &str, decoded: &str) -> Vec<Divergence> {
+    let (a, a_end) = units(original);
+    let (b, b_end) = units(decoded);
+    let keys_a: Vec<char> = a.iter().map(|u| u.key).collect();
+    let keys_b: Vec<char> = b.iter().map(|u| u.key).collect();
+
+    let mut out: Vec<Divergence> = Vec::new();
+    let mut open = false;
+    let (mut i, mut j) = (0, 0);
+    for op in edit_script(&keys_a, &keys_b) {
+        if op == Op::Equal {
+            i += 1;
+            j += 1;
+            open = false;
+            continue;
+        }
+        if !open {
+            out.push(Divergence {
+                original: a.get(i).map_or(a_end, |u| u.pos),
+                decoded: b.get(j).map_or(b_end, |u| u.pos),
+                expected: String::new(),
+                found: String::new(),
+            });
+            open = true;
+        }
+        let d = out.last_mut().expect("a divergence is open");
+        if op == Op::Delete {
+            d.expected.push_str(&a[i].text);
+            i += 1;
+        } else {
+            d.found.push_str(&b[j].text);
+            j += 1;
+        }
+    }
+    out
+}
+
+/// One comparable character, or one whole run of whitespace.
+struct Unit {
+    key: char,
+    text: String,
+    pos: Position,
+}
+
+/// The units of a text, with the position just past its last unit.
+fn units(text: &str) -> (Vec<Unit>, Position) {
+    let mut out: Vec<Unit> = Vec::new();
+    let mut pos = Position {
+        offset: 0,
+        line: 1,
+        column: 1,
+    };
+    let mut end = pos;
+    for c in text.chars() {
+        if c.is_whitespace() {
+            match out.last_mut() {
+                Some(u) if u.key == ' ' => u.text.push(c),
+                Some(_) => out.push(Unit {
+                    key: ' ',
+                    text: c.to_string(),
+                    pos,
+                }),
+                None => {}
+            }
+        } else {
+            out.push(Unit {
+                key: c,
+                text: c.to_string(),
+                pos,
+            });
+        }
+        pos.offset += 1;
+        if c == '\n' {
+            pos.line += 1;
+            pos.column = 1;
+        } else {
+            pos.column += 1;
+        }
+        if !c.is_whitespace() {
+            end = pos;
+        }
+    }
+    if out.last().is_some_and(|u| u.key == ' ') {
+        out.pop();
+    }
+    (out, end)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Equal,
+    /// A character of the original is missing.
+    Delete,
+    /// A character not in the original was decoded.
+    Insert,
+}
+
+/// A shortest edit script turning `a` into `b`. Time O((N+M)·D), and the trace
+/// kept for backtracking is O(D²).
+fn edit_script(a: &[char], b: &[char]) -> Vec<Op> {
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let max = n + m;
+    let off = max as usize + 1;
+    let mut v = vec![0isize; 2 * off + 1];
+    let at = |k: isize| (k + off as isize) as usize;
+    let mut trace: Vec<Vec<isize>> = Vec::new();
+
+    'search: for d in 0..=max {
+        for k in (-d..=d).step_by(2) {
+            let mut x = if k == -d || (k != d && v[at(k - 1)] < v[at(k + 1)]) {
+                v[at(k + 1)]
+            } else {
+                v[at(k - 1)] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[at(k)] = x;
+            if x >= n && y >= m {
+                trace.push(v[at(-d)..=at(d)].to_vec());
+                break 'search;
+            }
+        }
+        trace.push(v[at(-d)..=at(d)].to_vec());
+    }
+
+    let mut ops = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for d in (1..trace.len() as isize).rev() {
+        let prev = &trace[d as usize - 1];
+        let get = |k: isize| prev[(k + d - 1) as usize];
+        let k = x - y;
+        let prev_k = if k == -d || (k != d && get(k - 1) < get(k + 1)) {
+            k + 1
+        } else {
+            k - 1
+        };
+        let prev_x = get(prev_k);
+        let prev_y = prev_x - prev_k;
+        while x > prev_x && y > prev_y {
+            ops.push(Op::Equal);
+            x -= 1;
+            y -= 1;
+        }
+        ops.push(if prev_k == k + 1 {
+            Op::Insert
+        } else {
+            Op::Delete
+        });
+        x = prev_x;
+        y = prev_y;
+    }
+    ops.extend((0..x).map(|_| Op::Equal));
+    ops.reverse();
+    ops
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_align() {
+        assert!(align("rāmo rājā", "rāmo rājā").is_empty());
+        assert!(align("dharmakṣetre\nkurukṣetre\n", "dharmakṣetre kurukṣetre").is_empty());
+
+        let d = align("dharmakṣetre\nkurukṣetre", "dharmakṣetre kurukśetre");
+        assert_eq!(d.len(), 1);
+        assert_eq!((d[0].expected.as_str(), d[0].found.as_str()), ("ṣ", "ś"));
+        assert_eq!(
+            d[0].original,
+            Position {
+                offset: 18,
+                line: 2,
+                column: 6
+            }
+        );
+        assert_eq!(d[0].decoded.offset, 18);
+        assert_eq!(d[0].decoded.line, 1);
+
+        let d = align("rāma vanam", "rāma vana");
+        assert_eq!(d.len(), 1);
+        assert_eq!((d[0].expected.as_str(), d[0].found.as_str()), ("m", ""));
+        assert_eq!(d[0].original.column, 10);
+        assert_eq!(d[0].decoded.column, 10);
+
+        let d = align("ab", "xaby");
+        assert_eq!(d.len(), 2);
+        assert_eq!(d[0].found, "x");
+        assert_eq!(d[1].found, "y");
+        assert_eq!(align("", "a")[0].found, "a");
+        assert_eq!(align("a", "")[0].expected, "a");
+    }
+}
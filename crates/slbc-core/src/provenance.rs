@@ -0,0 +1,194 @@
+//! Transform provenance log.
+//!
+//! When a sequence-level transform (sandhi, framing normalization, numeral
+//! repair) rewrites a container, it appends an `Operation` to the extended
+//! header record `EXT_HDR_PROVENANCE`, so the derivation of any generated
+//! file can be replayed. Operations accumulate in the order applied.
+//!
+//! Record payload:
+//! ```text
+//! count (ULEB128) | count × operation
+//! operation := name | param count (ULEB128) | param count × (key | value)
+//! name, key, value := ULEB128 len + UTF-8
+//! ```
+
+use crate::container::{self, ExtRecord, SlbcHeader};
+use crate::error::SlbcError;
+use crate::types::*;
+
+/// One applied transform and its parameters.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Operation {
+    pub name: String,
+    pub params: Vec<(String, String)>,
+}
+
+impl Operation {
+    pub fn new(name: &str) -> Self {
+        Operation {
+            name: name.into(),
+            params: Vec::new(),
+        }
+    }
+
+    /// Add a parameter (builder style).
+    pub fn param(mut self, key: &str, value: impl ToString) -> Self {
+        self.params.push((key.into(), value.to_string()));
+        self
+    }
+}
+
+impl std::fmt::Display for Operation {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.name)?;
+        for (key, value) in &self.params {
+            write!(f, " {}={}", key, value)?;
+        }
+        Ok(())
+    }
+}
+
+fn write_str(out: &mut Vec<u8>, s: &str) {
+    container::write_uleb128(out, s.len() as u64);
+    out.extend_from_slice(s.as_bytes());
+}
+
+fn read_uleb(data: &[u8], pos: &mut usize) -> Result<usize, SlbcError> {
+    let (value, consumed) =
+        container::read_uleb128(&data[*pos..]).map_err(|e| e.context(*pos, "provenance record"))?;
+    *pos += consumed;
+    Ok(value as usize)
+}
+
+fn read_str(data: &[u8], pos: &mut usize) -> Result<String, SlbcError> {
+    let len = read_uleb(data, pos)?;
+    let end = *pos + len;
+    if end > data.len() {
+        return Err(SlbcError::truncated(
+            *pos,
+            "provenance string extends beyond record",
+        ));
+    }
+    let s = String::from_utf8(data[*pos..end].to_vec())
+        .map_err(|_| SlbcError::malformed(*pos, "provenance string is not valid UTF-8"))?;
+    *pos = end;
+    Ok(s)
+}
+
+/// Encode an operation list as a record payload.
+pub fn write_log(ops: &[Operation]) -> Vec<u8> {
+    let mut out = Vec::new();
+    container::write_uleb128(&mut out, ops.len() as u64);
+    for op in ops {
+        write_str(&mut out, &op.name);
+        container::write_uleb128(&mut out, op.params.len() as u64);
+        for (key, value) in &op.params {
+            write_str(&mut out, key);
+            write_str(&mut out, value);
+        }
+    }
+    out
+}
+
+/// Decode a record payload.
+pub fn parse_log(data: &[u8]) -> Result<Vec<Operation>, SlbcError> {
+    let mut pos = 0;
+    let count = read_uleb(data, &mut pos)?;
+    let mut ops = Vec::new();
+    for _ in 0..count {
+        let name = read_str(data, &mut pos)?;
+        let n = read_uleb(data, &mut pos)?;
+        let mut params = Vec::new();
+        for _ in 0..n {
+            let key = read_str(data, &mut pos)?;
+            params.push((key, read_str(data, &mut pos)?));
+        }
+        ops.push(Operation { name, params });
+    }
+    if pos != data.len() {
+        return Err(SlbcError::malformed(
+            pos,
+            "trailing bytes in provenance record",
+        ));
+    }
+    Ok(ops)
+}
+
+/// The operations recorded in a container's extended header.
+pub fn read(header: &SlbcHeader) -> Result<Vec<Operation>, SlbcError> {
+    match header
+        .ext_records()?
+        .into_iter()
+        .find(|r| r.tag == EXT_HDR_PROVENANCE)
+    {
+        Some(record) => parse_log(&record.payload),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Append operations to the provenance record in `records`, creating it if
+/// absent. Other records are kept in place.
+pub fn append(records: &mut Vec<ExtRecord>, ops: &[Operation]) -> Result<(), SlbcError> {
+    let existing = records.iter().position(|r| r.tag == EXT_HDR_PROVENANCE);
+    let mut log = match existing {
+        Some(i) => parse_log(&records[i].payload)?,
+        None => Vec::new(),
+    };
+    log.extend_from_slice(ops);
+    let record = ExtRecord {
+        tag: EXT_HDR_PROVENANCE,
+        payload: write_log(&log),
+    };
+    match existing {
+        Some(i) => records[i] = record,
+        None => records.push(record),
+    }
+    Ok(())
+}
+
+/// Header bytes for a rewritten container: `header` with `ops` appended to
+/// its provenance log.
+pub fn rewrite_header(header: &SlbcHeader, ops: &[Operation]) -> Result<Vec<u8>, SlbcError> {
+    let mut records = header.ext_records()?;
+    append(&mut records, ops)?;
+    header.to_bytes_with(&records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_accumulates_across_rewrites() {
+        let dict = ExtRecord {
+            tag: EXT_HDR_DICT,
+            payload: vec![1, 2, 3],
+        };
+        let file = container::build_slbc_ext(std::slice::from_ref(&dict), &[0x26, 0x00, 0x2E], &[]);
+        let (header, chunks) = container::parse_slbc(&file).unwrap();
+        assert!(read(&header).unwrap().is_empty());
+
+        let first = Operation::new("normalize-framing").param("sites", 2);
+        let mut out = rewrite_header(&header, std::slice::from_ref(&first)).unwrap();
+        for chunk in &chunks {
+            container::write_chunk(&mut out, chunk.chunk_type, &chunk.payload);
+        }
+        let (header, _) = container::parse_slbc(&out).unwrap();
+        assert_eq!(header.flags, file[11]);
+        assert_eq!(read(&header).unwrap()[0], first);
+
+        let second = Operation::new("repair-numerals")
+            .param("source", "words")
+            .param("repaired", 1);
+        let bytes = rewrite_header(&header, std::slice::from_ref(&second)).unwrap();
+        let (header, _) = container::parse_slbc(&[bytes, vec![CHUNK_EOF, 0]].concat()).unwrap();
+        let records = header.ext_records().unwrap();
+        assert_eq!(records[0], dict);
+        assert_eq!(read(&header).unwrap(), [first, second]);
+        assert_eq!(
+            read(&header).unwrap()[1].to_string(),
+            "repair-numerals source=words repaired=1"
+        );
+        assert!(parse_log(&[1, 5, b'x']).is_err());
+    }
+}
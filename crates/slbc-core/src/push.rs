@@ -0,0 +1,445 @@
+//! Push parser for .slbc containers.
+//!
+//! Bytes are fed in arbitrary slices and events come back through a callback,
+//! so a reader never holds more than the current byte. The parser state is a
+//! few dozen bytes and nothing allocates, which suits devices decoding
+//! straight from flash.
+//!
+//! PHON chunks are reported phoneme by phoneme; other chunks only as
+//! `ChunkStart`/`ChunkEnd`. Pada-dictionary references are reported as
+//! `DictRef` for the host to resolve.
+
+use std::fmt;
+
+use crate::codepage::EXT_LONG;
+use crate::types::*;
+
+/// One parser event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    /// The 14-byte header was read.
+    Header { version: [u8; 4], flags: u8 },
+    /// A chunk begins; `len` is its payload length.
+    ChunkStart { chunk_type: u8, len: u32 },
+    /// A svara or vyañjana byte.
+    Phoneme(u8),
+    /// An extension phoneme (`META_EXT <code>`).
+    Ext(u8),
+    /// A long extension (`META_EXT EXT_LONG <len>`) begins; its `len`
+    /// payload bytes follow as `ExtData`.
+    LongExt(u32),
+    /// A byte of long-extension payload.
+    ExtData(u8),
+    /// A bhāṣā or lipi control byte.
+    Control(u8),
+    /// A byte inside a META block.
+    Meta(u8),
+    /// A digit glyph (0–9) inside a NUM span.
+    Digit(u8),
+    /// The number of digit-word padas following SAṄKHYĀ_START.
+    SankhyaCount(u32),
+    /// A pada-dictionary reference (`DICT_REF` + index).
+    DictRef(u32),
+    /// A chunk ends.
+    ChunkEnd { chunk_type: u8 },
+    /// The EOF chunk was read.
+    End,
+}
+
+/// Why the parser stopped. Carries the absolute stream offset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PushError {
+    BadMagic,
+    /// A ULEB128 field ran past 5 bytes or u32 range.
+    BadLength {
+        offset: u64,
+    },
+    /// A META_EXT (or long extension), SAṄKHYĀ count, or DICT_REF index was
+    /// cut off by the end of its chunk.
+    TruncatedSpan {
+        offset: u64,
+    },
+    /// Bytes after the EOF chunk.
+    TrailingData {
+        offset: u64,
+    },
+    /// `finish` was called before the EOF chunk.
+    UnexpectedEnd {
+        offset: u64,
+    },
+}
+
+impl fmt::Display for PushError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PushError::BadMagic => write!(f, "bad magic: expected 'SLBC'"),
+            PushError::BadLength { offset } => {
+                write!(f, "malformed ULEB128 length at offset {}", offset)
+            }
+            PushError::TruncatedSpan { offset } => {
+                write!(f, "span truncated by end of chunk at offset {}", offset)
+            }
+            PushError::TrailingData { offset } => {
+                write!(f, "data after EOF chunk at offset {}", offset)
+            }
+            PushError::UnexpectedEnd { offset } => {
+                write!(f, "stream ended before EOF chunk at offset {}", offset)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Stage {
+    Header,
+    ExtHeader { remaining: u16 },
+    ChunkType,
+    ChunkLen { chunk_type: u8 },
+    Payload { chunk_type: u8, remaining: u32 },
+    Done,
+}
+
+/// What the next PHON byte means.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Lane {
+    Stream,
+    ExtCode,
+    ExtLen,
+    ExtData { remaining: u32 },
+    Meta,
+    Num,
+    SankhyaCount,
+    DictRef,
+}
+
+/// Streaming container parser. See the module docs.
+#[derive(Debug, Clone)]
+pub struct PushParser {
+    stage: Stage,
+    lane: Lane,
+    /// Header bytes seen so far (only the first 14 bytes are buffered).
+    header: [u8; 14],
+    /// ULEB128 accumulator: value, bytes read.
+    uleb: (u32, u8),
+    offset: u64,
+}
+
+impl Default for PushParser {
+    fn default() -> Self {
+        PushParser::new()
+    }
+}
+
+impl PushParser {
+    pub const fn new() -> Self {
+        PushParser {
+            stage: Stage::Header,
+            lane: Lane::Stream,
+            header: [0; 14],
+            uleb: (0, 0),
+            offset: 0,
+        }
+    }
+
+    /// Bytes consumed so far.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Whether the EOF chunk has been read.
+    pub fn is_done(&self) -> bool {
+        self.stage == Stage::Done
+    }
+
+    /// Feed the next slice of the stream.
+    pub fn feed<F: FnMut(Event)>(&mut self, data: &[u8], mut sink: F) -> Result<(), PushError> {
+        for &b in data {
+            self.step(b, &mut sink)?;
+            self.offset += 1;
+        }
+        Ok(())
+    }
+
+    /// Check that the stream ended cleanly.
+    pub fn finish(&self) -> Result<(), PushError> {
+        match self.stage {
+            Stage::Done => Ok(()),
+            _ => Err(PushError::UnexpectedEnd {
+                offset: self.offset,
+            }),
+        }
+    }
+
+    /// Accumulate one ULEB128 byte; `Some(value)` once complete.
+    fn uleb_step(&mut self, b: u8) -> Result<Option<u32>, PushError> {
+        let (value, n) = self.uleb;
+        let err = PushError::BadLength {
+            offset: self.offset,
+        };
+        if n >= 5 {
+            return Err(err);
+        }
+        let low = ((b & 0x7F) as u64) << (7 * n);
+        let value = value as u64 | low;
+        if value > u32::MAX as u64 {
+            return Err(err);
+        }
+        if b & 0x80 != 0 {
+            self.uleb = (value as u32, n + 1);
+            return Ok(None);
+        }
+        self.uleb = (0, 0);
+        Ok(Some(value as u32))
+    }
+
+    fn step<F: FnMut(Event)>(&mut self, b: u8, sink: &mut F) -> Result<(), PushError> {
+        match self.stage {
+            Stage::Header => {
+                let i = self.offset as usize;
+                self.header[i] = b;
+                if i < 4 && b != MAGIC[i] {
+                    return Err(PushError::BadMagic);
+                }
+                if i == 13 {
+                    let mut version = [0; 4];
+                    version.copy_from_slice(&self.header[4..8]);
+                    sink(Event::Header {
+                        version,
+                        flags: self.header[11],
+                    });
+                    let remaining = u16::from_le_bytes([self.header[12], self.header[13]]);
+                    self.stage = match remaining {
+                        0 => Stage::ChunkType,
+                        _ => Stage::ExtHeader { remaining },
+                    };
+                }
+            }
+            Stage::ExtHeader { remaining } => {
+                self.stage = match remaining - 1 {
+                    0 => Stage::ChunkType,
+                    remaining => Stage::ExtHeader { remaining },
+                };
+            }
+            Stage::ChunkType => self.stage = Stage::ChunkLen { chunk_type: b },
+            Stage::ChunkLen { chunk_type } => {
+                if let Some(len) = self.uleb_step(b)? {
+                    if chunk_type == CHUNK_EOF {
+                        self.stage = Stage::Done;
+                        sink(Event::End);
+                        return Ok(());
+                    }
+                    sink(Event::ChunkStart { chunk_type, len });
+                    self.lane = Lane::Stream;
+                    self.stage = Stage::Payload {
+                        chunk_type,
+                        remaining: len,
+                    };
+                    if len == 0 {
+                        self.end_chunk(chunk_type, sink)?;
+                    }
+                }
+            }
+            Stage::Payload {
+                chunk_type,
+                remaining,
+            } => {
+                if chunk_type == CHUNK_PHON {
+                    self.phon_byte(b, sink)?;
+                }
+                self.stage = Stage::Payload {
+                    chunk_type,
+                    remaining: remaining - 1,
+                };
+                if remaining == 1 {
+                    self.end_chunk(chunk_type, sink)?;
+                }
+            }
+            Stage::Done => {
+                return Err(PushError::TrailingData {
+                    offset: self.offset,
+                })
+            }
+        }
+        Ok(())
+    }
+
+    fn end_chunk<F: FnMut(Event)>(
+        &mut self,
+        chunk_type: u8,
+        sink: &mut F,
+    ) -> Result<(), PushError> {
+        if !matches!(self.lane, Lane::Stream | Lane::Num | Lane::Meta) {
+            return Err(PushError::TruncatedSpan {
+                offset: self.offset,
+            });
+        }
+        self.lane = Lane::Stream;
+        self.stage = Stage::ChunkType;
+        sink(Event::ChunkEnd { chunk_type });
+        Ok(())
+    }
+
+    fn phon_byte<F: FnMut(Event)>(&mut self, b: u8, sink: &mut F) -> Result<(), PushError> {
+        match self.lane {
+            Lane::ExtCode => {
+                self.lane = match b {
+                    EXT_LONG => Lane::ExtLen,
+                    _ => {
+                        sink(Event::Ext(b));
+                        Lane::Stream
+                    }
+                };
+                return Ok(());
+            }
+            Lane::ExtLen => {
+                if let Some(len) = self.uleb_step(b)? {
+                    sink(Event::LongExt(len));
+                    self.lane = match len {
+                        0 => Lane::Stream,
+                        remaining => Lane::ExtData { remaining },
+                    };
+                }
+                return Ok(());
+            }
+            // Payload bytes are opaque, even a PADA_END or META_START
+            Lane::ExtData { remaining } => {
+                sink(Event::ExtData(b));
+                self.lane = match remaining - 1 {
+                    0 => Lane::Stream,
+                    remaining => Lane::ExtData { remaining },
+                };
+                return Ok(());
+            }
+            Lane::SankhyaCount => {
+                if let Some(count) = self.uleb_step(b)? {
+                    self.lane = Lane::Stream;
+                    sink(Event::SankhyaCount(count));
+                }
+                return Ok(());
+            }
+            Lane::DictRef => {
+                if let Some(index) = self.uleb_step(b)? {
+                    self.lane = Lane::Stream;
+                    sink(Event::DictRef(index));
+                }
+                return Ok(());
+            }
+            Lane::Meta if b != META_END => {
+                sink(Event::Meta(b));
+                return Ok(());
+            }
+            // Digit glyphs run until the first byte ≥ 0x10
+            Lane::Num if b < 0x10 => {
+                sink(Event::Digit(b));
+                return Ok(());
+            }
+            _ => self.lane = Lane::Stream,
+        }
+
+        match b {
+            META_EXT => self.lane = Lane::ExtCode,
+            SANKHYA_START => {
+                self.lane = Lane::SankhyaCount;
+                sink(Event::Control(b));
+            }
+            DICT_REF => self.lane = Lane::DictRef,
+            META_START => {
+                self.lane = Lane::Meta;
+                sink(Event::Control(b));
+            }
+            NUM => {
+                self.lane = Lane::Num;
+                sink(Event::Control(b));
+            }
+            b if is_svara(b) || is_vyanjana(b) => sink(Event::Phoneme(b)),
+            b => sink(Event::Control(b)),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::container;
+    use crate::encoder;
+
+    fn events(file: &[u8], slice: usize) -> Vec<Event> {
+        let mut parser = PushParser::new();
+        let mut out = Vec::new();
+        for part in file.chunks(slice) {
+            parser.feed(part, |e| out.push(e)).unwrap();
+        }
+        parser.finish().unwrap();
+        out
+    }
+
+    #[test]
+    fn test_events_match_payload() {
+        let phon = encoder::encode_iast("rāmaḥ 12 qalam |").unwrap();
+        let file = container::build_slbc(&phon);
+        let all = events(&file, file.len());
+        // Feeding one byte at a time gives the same events
+        assert_eq!(events(&file, 1), all);
+
+        assert!(matches!(all[0], Event::Header { .. }));
+        assert_eq!(all.last(), Some(&Event::End));
+        let phonemes = all
+            .iter()
+            .filter(|e| matches!(e, Event::Phoneme(_)))
+            .count();
+        let expected = phon
+            .iter()
+            .filter(|&&b| is_svara(b) || is_vyanjana(b))
+            .count();
+        // The SAṄKHYĀ count, NUM glyphs, and ext code are not phonemes
+        assert_eq!(phonemes, expected - 4);
+        assert!(all.contains(&Event::SankhyaCount(2)));
+        assert!(all.contains(&Event::Digit(1)) && all.contains(&Event::Digit(2)));
+        assert!(all.contains(&Event::Ext(0x08)));
+        assert!(all.contains(&Event::Control(DANDA)));
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut parser = PushParser::new();
+        assert_eq!(parser.feed(b"SLBX", |_| {}), Err(PushError::BadMagic));
+
+        let file = container::build_slbc(&encoder::encode_iast("a").unwrap());
+        let mut parser = PushParser::new();
+        parser.feed(&file[..file.len() - 1], |_| {}).unwrap();
+        assert!(parser.finish().is_err());
+
+        // META_EXT as the last byte of a chunk
+        let file = container::build_slbc(&[PADA_START, META_EXT]);
+        let mut parser = PushParser::new();
+        let err = parser.feed(&file, |_| {}).unwrap_err();
+        assert!(matches!(err, PushError::TruncatedSpan { .. }));
+        assert!(std::mem::size_of::<PushParser>() <= 64);
+
+        // A long extension cut off mid-payload
+        let file = container::build_slbc(&[PADA_START, META_EXT, EXT_LONG, 4, 0x01]);
+        let mut parser = PushParser::new();
+        let err = parser.feed(&file, |_| {}).unwrap_err();
+        assert!(matches!(err, PushError::TruncatedSpan { .. }));
+    }
+
+    #[test]
+    fn test_long_ext() {
+        let phon = [
+            PADA_START, 0x00, META_EXT, EXT_LONG, 2, PADA_END, META_START, 0x3A,
+        ];
+        let file = container::build_slbc(&[&phon[..], &[PADA_END]].concat());
+        let all = events(&file, 1);
+        let at = all.iter().position(|e| *e == Event::LongExt(2)).unwrap();
+        assert_eq!(
+            all[at..at + 4],
+            [
+                Event::LongExt(2),
+                Event::ExtData(PADA_END),
+                Event::ExtData(META_START),
+                Event::Phoneme(0x3A)
+            ]
+        );
+    }
+}
@@ -0,0 +1,1191 @@
+//! Romanization schemes as data.
+//!
+//! Each scheme is a grapheme ↔ byte table consumed by one generic
+//! longest-match tokenizer, so a new scheme is a table, not a tokenizer.
+//! Whitespace, ASCII digits, the editorial gap markers, and the morpheme
+//! seam "+" are scheme-independent and handled by the matcher itself.
+
+use std::collections::HashMap;
+
+use crate::encoder::{self, CaseFolding, EncodeOptions, EncodeWarning, Token};
+use crate::error::SlbcError;
+use crate::json;
+use crate::types::*;
+
+/// A grapheme ↔ byte table.
+///
+/// The first entry for a byte is its canonical output form; later entries
+/// for the same byte are accepted on input only.
+#[derive(Debug)]
+pub struct Scheme {
+    /// Identifier used on the command line (e.g. "iast").
+    pub name: &'static str,
+    /// Display name used in messages (e.g. "IAST").
+    pub label: &'static str,
+    pub table: &'static [(&'static str, u8)],
+    /// Grapheme → extension code (`META_EXT <code>`, see `codepage`).
+    pub extensions: &'static [(&'static str, u8)],
+    /// Grapheme → extension code in Vedic text (`tokenize_vedic`), read
+    /// ahead of `extensions`; also the written form of those codes.
+    pub vedic: &'static [(&'static str, u8)],
+    /// Graphemes standing for several phonemes (ITRANS `x` = kṣ), accepted
+    /// on input only.
+    pub clusters: &'static [(&'static str, &'static [u8])],
+}
+
+pub const IAST: Scheme = Scheme {
+    name: "iast",
+    label: "IAST",
+    table: &[
+        // ── Svaras ──
+        ("a", 0x40),
+        ("ā", 0x80),
+        ("i", 0x44),
+        ("ī", 0x84),
+        ("u", 0x48),
+        ("ū", 0x88),
+        ("ṛ", 0x4C),
+        ("ṝ", 0x8C),
+        ("ḷ", 0x4F),
+        ("ḹ", 0x8F),
+        ("e", 0x85),
+        ("ai", 0x86),
+        ("o", 0x89),
+        ("au", 0x8A),
+        // ── Varga vyañjanas ──
+        ("k", 0x00),
+        ("kh", 0x01),
+        ("g", 0x02),
+        ("gh", 0x03),
+        ("ṅ", 0x04),
+        ("c", 0x08),
+        ("ch", 0x09),
+        ("j", 0x0A),
+        ("jh", 0x0B),
+        ("ñ", 0x0C),
+        ("ṭ", 0x10),
+        ("ṭh", 0x11),
+        ("ḍ", 0x12),
+        ("ḍh", 0x13),
+        ("ṇ", 0x14),
+        ("t", 0x18),
+        ("th", 0x19),
+        ("d", 0x1A),
+        ("dh", 0x1B),
+        ("n", 0x1C),
+        ("p", 0x20),
+        ("ph", 0x21),
+        ("b", 0x22),
+        ("bh", 0x23),
+        ("m", 0x24),
+        // ── Sibilants ──
+        ("ś", 0x29),
+        ("ṣ", 0x2A),
+        ("s", 0x2B),
+        // ── Sonorants ──
+        ("y", 0x31),
+        ("v", 0x32),
+        ("r", 0x33),
+        ("l", 0x34),
+        // ── Glottal / special ──
+        ("h", 0x38),
+        ("ḥ", 0x39),
+        ("ṃ", 0x3A),
+        ("ẖ", 0x3B),
+        ("ḫ", 0x3C),
+        // ── Lipi ──
+        ("|", DANDA),
+        ("||", DOUBLE_DANDA),
+        ("।", DANDA),
+        ("।।", DOUBLE_DANDA),
+        ("॥", DOUBLE_DANDA),
+        ("'", AVAGRAHA),
+        ("ऽ", AVAGRAHA),
+        ("m̐", CANDRABINDU),
+    ],
+    extensions: &[
+        ("ḻ", 0x01),
+        ("ṟ", 0x02),
+        ("ṉ", 0x03),
+        ("q", 0x08),
+        ("ḵh", 0x09),
+        ("ġ", 0x0A),
+        ("z", 0x0B),
+        ("f", 0x0C),
+        ("ĕ", 0x10),
+        ("ŏ", 0x11),
+    ],
+    vedic: &[("ḻ", 0x04), ("ḻh", 0x05)],
+    clusters: &[],
+};
+
+/// SLP1: one ASCII character per phoneme.
+pub const SLP1: Scheme = Scheme {
+    name: "slp1",
+    label: "SLP1",
+    table: &[
+        // ── Svaras ──
+        ("a", 0x40),
+        ("A", 0x80),
+        ("i", 0x44),
+        ("I", 0x84),
+        ("u", 0x48),
+        ("U", 0x88),
+        ("f", 0x4C),
+        ("F", 0x8C),
+        ("x", 0x4F),
+        ("X", 0x8F),
+        ("e", 0x85),
+        ("E", 0x86),
+        ("o", 0x89),
+        ("O", 0x8A),
+        // ── Varga vyañjanas ──
+        ("k", 0x00),
+        ("K", 0x01),
+        ("g", 0x02),
+        ("G", 0x03),
+        ("N", 0x04),
+        ("c", 0x08),
+        ("C", 0x09),
+        ("j", 0x0A),
+        ("J", 0x0B),
+        ("Y", 0x0C),
+        ("w", 0x10),
+        ("W", 0x11),
+        ("q", 0x12),
+        ("Q", 0x13),
+        ("R", 0x14),
+        ("t", 0x18),
+        ("T", 0x19),
+        ("d", 0x1A),
+        ("D", 0x1B),
+        ("n", 0x1C),
+        ("p", 0x20),
+        ("P", 0x21),
+        ("b", 0x22),
+        ("B", 0x23),
+        ("m", 0x24),
+        // ── Sibilants ──
+        ("S", 0x29),
+        ("z", 0x2A),
+        ("s", 0x2B),
+        // ── Sonorants ──
+        ("y", 0x31),
+        ("v", 0x32),
+        ("r", 0x33),
+        ("l", 0x34),
+        // ── Glottal / special ──
+        ("h", 0x38),
+        ("H", 0x39),
+        ("M", 0x3A),
+        ("Z", 0x3B),
+        ("V", 0x3C),
+        // ── Lipi ──
+        (".", DANDA),
+        ("..", DOUBLE_DANDA),
+        ("|", DANDA),
+        ("||", DOUBLE_DANDA),
+        ("'", AVAGRAHA),
+        ("~", CANDRABINDU),
+    ],
+    extensions: &[("L", 0x04)],
+    vedic: &[],
+    clusters: &[],
+};
+
+/// Harvard-Kyoto. Tokenized longest-match, so "lRR" is ḹ, "lR" is ḷ, "RR"
+/// is ṝ, and "ai"/"au" are diphthongs.
+pub const HK: Scheme = Scheme {
+    name: "hk",
+    label: "Harvard-Kyoto",
+    table: &[
+        // ── Svaras ──
+        ("a", 0x40),
+        ("A", 0x80),
+        ("i", 0x44),
+        ("I", 0x84),
+        ("u", 0x48),
+        ("U", 0x88),
+        ("R", 0x4C),
+        ("RR", 0x8C),
+        ("lR", 0x4F),
+        ("lRR", 0x8F),
+        ("e", 0x85),
+        ("ai", 0x86),
+        ("o", 0x89),
+        ("au", 0x8A),
+        // ── Varga vyañjanas ──
+        ("k", 0x00),
+        ("kh", 0x01),
+        ("g", 0x02),
+        ("gh", 0x03),
+        ("G", 0x04),
+        ("c", 0x08),
+        ("ch", 0x09),
+        ("j", 0x0A),
+        ("jh", 0x0B),
+        ("J", 0x0C),
+        ("T", 0x10),
+        ("Th", 0x11),
+        ("D", 0x12),
+        ("Dh", 0x13),
+        ("N", 0x14),
+        ("t", 0x18),
+        ("th", 0x19),
+        ("d", 0x1A),
+        ("dh", 0x1B),
+        ("n", 0x1C),
+        ("p", 0x20),
+        ("ph", 0x21),
+        ("b", 0x22),
+        ("bh", 0x23),
+        ("m", 0x24),
+        // ── Sibilants ──
+        ("z", 0x29),
+        ("S", 0x2A),
+        ("s", 0x2B),
+        // ── Sonorants ──
+        ("y", 0x31),
+        ("v", 0x32),
+        ("r", 0x33),
+        ("l", 0x34),
+        // ── Glottal / special ──
+        ("h", 0x38),
+        ("H", 0x39),
+        ("M", 0x3A),
+        // ── Lipi ──
+        ("|", DANDA),
+        ("||", DOUBLE_DANDA),
+        ("'", AVAGRAHA),
+    ],
+    extensions: &[],
+    vedic: &[],
+    clusters: &[],
+};
+
+/// ITRANS, with its common variant spellings accepted on input: `aa`/`A`,
+/// `R^i`/`RRi`, `chh`/`Ch`, `.n`/`M`, `~n`/`JN`, `w`/`v`, and the clusters
+/// `x`/`kSh` (kṣ) and `GY`/`j~n` (jñ).
+pub const ITRANS: Scheme = Scheme {
+    name: "itrans",
+    label: "ITRANS",
+    table: &[
+        // ── Svaras ──
+        ("a", 0x40),
+        ("A", 0x80),
+        ("aa", 0x80),
+        ("i", 0x44),
+        ("I", 0x84),
+        ("ii", 0x84),
+        ("u", 0x48),
+        ("U", 0x88),
+        ("uu", 0x88),
+        ("RRi", 0x4C),
+        ("R^i", 0x4C),
+        ("RRI", 0x8C),
+        ("R^I", 0x8C),
+        ("LLi", 0x4F),
+        ("L^i", 0x4F),
+        ("LLI", 0x8F),
+        ("L^I", 0x8F),
+        ("e", 0x85),
+        ("ai", 0x86),
+        ("o", 0x89),
+        ("au", 0x8A),
+        // ── Varga vyañjanas ──
+        ("k", 0x00),
+        ("kh", 0x01),
+        ("g", 0x02),
+        ("gh", 0x03),
+        ("~N", 0x04),
+        ("N^", 0x04),
+        ("ch", 0x08),
+        ("c", 0x08),
+        ("Ch", 0x09),
+        ("chh", 0x09),
+        ("j", 0x0A),
+        ("jh", 0x0B),
+        ("~n", 0x0C),
+        ("JN", 0x0C),
+        ("T", 0x10),
+        ("Th", 0x11),
+        ("D", 0x12),
+        ("Dh", 0x13),
+        ("N", 0x14),
+        ("t", 0x18),
+        ("th", 0x19),
+        ("d", 0x1A),
+        ("dh", 0x1B),
+        ("n", 0x1C),
+        ("p", 0x20),
+        ("ph", 0x21),
+        ("b", 0x22),
+        ("bh", 0x23),
+        ("m", 0x24),
+        // ── Sibilants ──
+        ("sh", 0x29),
+        ("Sh", 0x2A),
+        ("shh", 0x2A),
+        ("s", 0x2B),
+        // ── Sonorants ──
+        ("y", 0x31),
+        ("v", 0x32),
+        ("w", 0x32),
+        ("r", 0x33),
+        ("l", 0x34),
+        // ── Glottal / special ──
+        ("h", 0x38),
+        ("H", 0x39),
+        ("M", 0x3A),
+        (".n", 0x3A),
+        (".m", 0x3A),
+        // ── Lipi ──
+        ("|", DANDA),
+        ("||", DOUBLE_DANDA),
+        (".a", AVAGRAHA),
+        (".N", CANDRABINDU),
+    ],
+    extensions: &[
+        ("q", 0x08),
+        ("K", 0x09),
+        ("G", 0x0A),
+        ("z", 0x0B),
+        ("f", 0x0C),
+    ],
+    vedic: &[],
+    clusters: &[("x", &[0x00, 0x2A]), ("GY", &[0x0A, 0x0C])],
+};
+
+/// Velthuis: IAST diacritics as ASCII prefixes (`.` for retroflexes and
+/// ṛ/ḷ/ḥ/ṃ, `"` for ṅ and ś, `~` for ñ).
+pub const VELTHUIS: Scheme = Scheme {
+    name: "velthuis",
+    label: "Velthuis",
+    table: &[
+        // ── Svaras ──
+        ("a", 0x40),
+        ("aa", 0x80),
+        ("A", 0x80),
+        ("i", 0x44),
+        ("ii", 0x84),
+        ("I", 0x84),
+        ("u", 0x48),
+        ("uu", 0x88),
+        ("U", 0x88),
+        (".r", 0x4C),
+        (".rr", 0x8C),
+        (".l", 0x4F),
+        (".ll", 0x8F),
+        ("e", 0x85),
+        ("ai", 0x86),
+        ("o", 0x89),
+        ("au", 0x8A),
+        // ── Varga vyañjanas ──
+        ("k", 0x00),
+        ("kh", 0x01),
+        ("g", 0x02),
+        ("gh", 0x03),
+        ("\"n", 0x04),
+        ("c", 0x08),
+        ("ch", 0x09),
+        ("j", 0x0A),
+        ("jh", 0x0B),
+        ("~n", 0x0C),
+        (".t", 0x10),
+        (".th", 0x11),
+        (".d", 0x12),
+        (".dh", 0x13),
+        (".n", 0x14),
+        ("t", 0x18),
+        ("th", 0x19),
+        ("d", 0x1A),
+        ("dh", 0x1B),
+        ("n", 0x1C),
+        ("p", 0x20),
+        ("ph", 0x21),
+        ("b", 0x22),
+        ("bh", 0x23),
+        ("m", 0x24),
+        // ── Sibilants ──
+        ("\"s", 0x29),
+        (".s", 0x2A),
+        ("s", 0x2B),
+        // ── Sonorants ──
+        ("y", 0x31),
+        ("v", 0x32),
+        ("r", 0x33),
+        ("l", 0x34),
+        // ── Glottal / special ──
+        ("h", 0x38),
+        (".h", 0x39),
+        (".m", 0x3A),
+        // ── Lipi ──
+        ("|", DANDA),
+        ("||", DOUBLE_DANDA),
+        (".a", AVAGRAHA),
+    ],
+    extensions: &[("q", 0x08), ("z", 0x0B), ("f", 0x0C)],
+    vedic: &[],
+    clusters: &[],
+};
+
+/// WX (IIIT Hyderabad), the notation of the Indian NLP toolkits: one
+/// letter per phoneme, case marking aspiration and length, `w`/`x` for
+/// dentals and `t`/`d` for retroflexes. WX has no letter for ḹ.
+pub const WX: Scheme = Scheme {
+    name: "wx",
+    label: "WX",
+    table: &[
+        // ── Svaras ──
+        ("a", 0x40),
+        ("A", 0x80),
+        ("i", 0x44),
+        ("I", 0x84),
+        ("u", 0x48),
+        ("U", 0x88),
+        ("q", 0x4C),
+        ("Q", 0x8C),
+        ("L", 0x4F),
+        ("e", 0x85),
+        ("E", 0x86),
+        ("o", 0x89),
+        ("O", 0x8A),
+        // ── Varga vyañjanas ──
+        ("k", 0x00),
+        ("K", 0x01),
+        ("g", 0x02),
+        ("G", 0x03),
+        ("f", 0x04),
+        ("c", 0x08),
+        ("C", 0x09),
+        ("j", 0x0A),
+        ("J", 0x0B),
+        ("F", 0x0C),
+        ("t", 0x10),
+        ("T", 0x11),
+        ("d", 0x12),
+        ("D", 0x13),
+        ("N", 0x14),
+        ("w", 0x18),
+        ("W", 0x19),
+        ("x", 0x1A),
+        ("X", 0x1B),
+        ("n", 0x1C),
+        ("p", 0x20),
+        ("P", 0x21),
+        ("b", 0x22),
+        ("B", 0x23),
+        ("m", 0x24),
+        // ── Sibilants ──
+        ("S", 0x29),
+        ("R", 0x2A),
+        ("s", 0x2B),
+        // ── Sonorants ──
+        ("y", 0x31),
+        ("v", 0x32),
+        ("r", 0x33),
+        ("l", 0x34),
+        // ── Glottal / special ──
+        ("h", 0x38),
+        ("H", 0x39),
+        ("M", 0x3A),
+        // ── Lipi ──
+        ("|", DANDA),
+        ("||", DOUBLE_DANDA),
+        ("Z", AVAGRAHA),
+    ],
+    extensions: &[],
+    vedic: &[],
+    clusters: &[],
+};
+
+/// ISO 15919, strict form: ring-below vocalic `r̥`/`l̥`, macron `ē`/`ō` (plain
+/// `e`/`o` are the short Dravidian vowels), and `ṁ` for anusvāra (`ṃ`
+/// accepted on input).
+pub const ISO15919: Scheme = Scheme {
+    name: "iso15919",
+    label: "ISO 15919",
+    table: &[
+        // ── Svaras ──
+        ("a", 0x40),
+        ("ā", 0x80),
+        ("i", 0x44),
+        ("ī", 0x84),
+        ("u", 0x48),
+        ("ū", 0x88),
+        ("r\u{0325}", 0x4C),
+        ("r\u{0325}\u{0304}", 0x8C),
+        ("l\u{0325}", 0x4F),
+        ("l\u{0325}\u{0304}", 0x8F),
+        ("ē", 0x85),
+        ("ai", 0x86),
+        ("ō", 0x89),
+        ("au", 0x8A),
+        // ── Varga vyañjanas ──
+        ("k", 0x00),
+        ("kh", 0x01),
+        ("g", 0x02),
+        ("gh", 0x03),
+        ("ṅ", 0x04),
+        ("c", 0x08),
+        ("ch", 0x09),
+        ("j", 0x0A),
+        ("jh", 0x0B),
+        ("ñ", 0x0C),
+        ("ṭ", 0x10),
+        ("ṭh", 0x11),
+        ("ḍ", 0x12),
+        ("ḍh", 0x13),
+        ("ṇ", 0x14),
+        ("t", 0x18),
+        ("th", 0x19),
+        ("d", 0x1A),
+        ("dh", 0x1B),
+        ("n", 0x1C),
+        ("p", 0x20),
+        ("ph", 0x21),
+        ("b", 0x22),
+        ("bh", 0x23),
+        ("m", 0x24),
+        // ── Sibilants ──
+        ("ś", 0x29),
+        ("ṣ", 0x2A),
+        ("s", 0x2B),
+        // ── Sonorants ──
+        ("y", 0x31),
+        ("v", 0x32),
+        ("r", 0x33),
+        ("l", 0x34),
+        // ── Glottal / special ──
+        ("h", 0x38),
+        ("ḥ", 0x39),
+        ("ṁ", 0x3A),
+        ("ṃ", 0x3A),
+        ("ẖ", 0x3B),
+        ("ḫ", 0x3C),
+        // ── Lipi ──
+        ("|", DANDA),
+        ("||", DOUBLE_DANDA),
+        ("।", DANDA),
+        ("।।", DOUBLE_DANDA),
+        ("॥", DOUBLE_DANDA),
+        ("'", AVAGRAHA),
+        ("m̐", CANDRABINDU),
+    ],
+    extensions: &[
+        ("ḻ", 0x01),
+        ("ṟ", 0x02),
+        ("ṉ", 0x03),
+        ("ḷ", 0x04),
+        ("ḷh", 0x05),
+        ("q", 0x08),
+        ("k\u{035F}h", 0x09),
+        ("ġ", 0x0A),
+        ("z", 0x0B),
+        ("f", 0x0C),
+        ("e", 0x10),
+        ("o", 0x11),
+    ],
+    vedic: &[],
+    clusters: &[],
+};
+
+/// All built-in schemes.
+pub const SCHEMES: &[&Scheme] = &[&IAST, &SLP1, &HK, &ITRANS, &VELTHUIS, &WX, &ISO15919];
+
+/// Look up a built-in scheme by name.
+pub fn scheme(name: &str) -> Option<&'static Scheme> {
+    SCHEMES.iter().copied().find(|s| s.name == name)
+}
+
+impl Scheme {
+    /// Canonical grapheme for a byte.
+    pub fn grapheme(&self, b: u8) -> Option<&'static str> {
+        self.table.iter().find(|(_, v)| *v == b).map(|(g, _)| *g)
+    }
+
+    /// Longest table entry that prefixes `input`: (byte, length in bytes).
+    pub fn longest_match(&self, input: &str) -> Option<(u8, usize)> {
+        self.table
+            .iter()
+            .filter(|(g, _)| input.starts_with(g))
+            .max_by_key(|(g, _)| g.len())
+            .map(|(g, b)| (*b, g.len()))
+    }
+
+    /// Canonical grapheme for an extension code.
+    pub fn ext_grapheme(&self, code: u8) -> Option<&'static str> {
+        self.extensions
+            .iter()
+            .chain(self.vedic)
+            .find(|(_, v)| *v == code)
+            .map(|(g, _)| *g)
+    }
+
+    /// Longest token (core byte or extension phoneme) prefixing `input`,
+    /// with its length in bytes.
+    pub fn match_token(&self, input: &str) -> Option<(Token, usize)> {
+        let core = self.longest_match(input);
+        let ext = self
+            .extensions
+            .iter()
+            .filter(|(g, _)| input.starts_with(g))
+            .max_by_key(|(g, _)| g.len());
+        match (core, ext) {
+            (Some((_, clen)), Some((g, code))) if g.len() > clen => {
+                Some((Token::Extension(*code), g.len()))
+            }
+            (None, Some((g, code))) => Some((Token::Extension(*code), g.len())),
+            (Some((b, len)), _) => Some((byte_token(b), len)),
+            (None, None) => None,
+        }
+    }
+
+    /// Longest grapheme of any kind prefixing `input`. An extension wins
+    /// only over a shorter core entry, a cluster only over a shorter token.
+    /// In Vedic text a `vedic` reading wins over an equal extension.
+    fn match_entry(&self, input: &str, vedic: bool) -> Option<(Entry, usize)> {
+        let core = self
+            .longest_match(input)
+            .map(|(b, len)| (Entry::Byte(b), len));
+        let vedic = if vedic { self.vedic } else { &[] };
+        // `max_by_key` keeps the last of equals, so Vedic readings go last
+        let ext = self
+            .extensions
+            .iter()
+            .chain(vedic)
+            .filter(|(g, _)| input.starts_with(g))
+            .max_by_key(|(g, _)| g.len())
+            .map(|(g, code)| (Entry::Ext(*code), g.len()));
+        let cluster = self
+            .clusters
+            .iter()
+            .filter(|(g, _)| input.starts_with(g))
+            .max_by_key(|(g, _)| g.len())
+            .map(|(g, bytes)| (Entry::Cluster(bytes), g.len()));
+        [core, ext, cluster]
+            .into_iter()
+            .flatten()
+            .fold(None, |best, m| match best {
+                Some((_, len)) if m.1 <= len => best,
+                _ => Some(m),
+            })
+    }
+
+    /// Tokenize text in this scheme.
+    pub fn tokenize(&self, input: &str) -> Result<Vec<Token>, SlbcError> {
+        tokenize_with(self.label, input, &EncodeOptions::default(), None, |rest| {
+            self.match_entry(rest, false)
+        })
+    }
+
+    /// Tokenize Vedic text in this scheme, reading `vedic` graphemes (IAST
+    /// ḻ, ḻh) as the Vedic laterals rather than as extension phonemes.
+    pub fn tokenize_vedic(&self, input: &str) -> Result<Vec<Token>, SlbcError> {
+        tokenize_with(self.label, input, &EncodeOptions::default(), None, |rest| {
+            self.match_entry(rest, true)
+        })
+    }
+
+    /// Tokenize text, skipping or substituting what cannot be read instead
+    /// of failing (see `encoder::recover`), with a warning for each.
+    pub fn tokenize_lossy(&self, input: &str) -> (Vec<Token>, Vec<EncodeWarning>) {
+        let mut warnings = Vec::new();
+        let tokens = tokenize_with(
+            self.label,
+            input,
+            &EncodeOptions::default(),
+            Some(&mut warnings),
+            |rest| self.match_entry(rest, false),
+        )
+        .expect("lenient tokenizing does not fail");
+        (tokens, warnings)
+    }
+
+    /// Tokenize text read as `opts` say: Vedic or not, and how whitespace,
+    /// punctuation, and capitals are read. Only IAST and ISO 15919 fold
+    /// case (see `CaseFolding`) or read full stops as daṇḍas; in the other
+    /// schemes case tells letters apart and a dot may be part of a letter,
+    /// so their text is read as it is.
+    pub fn tokenize_with_options(
+        &self,
+        input: &str,
+        opts: &EncodeOptions,
+    ) -> Result<Vec<Token>, SlbcError> {
+        let opts = match self.name {
+            "iast" | "iso15919" => *opts,
+            _ => EncodeOptions {
+                fold_case: CaseFolding::Off,
+                dot_dandas: false,
+                ..*opts
+            },
+        };
+        tokenize_with(self.label, input, &opts, None, |rest| {
+            self.match_entry(rest, opts.vedic)
+        })
+    }
+
+    /// Export the table as TSV: grapheme, byte, canonical flag.
+    pub fn to_tsv(&self) -> String {
+        let mut out = String::from("grapheme\tbyte\tcanonical\n");
+        for &(g, b) in self.table {
+            let canonical = self.grapheme(b) == Some(g);
+            out.push_str(&format!("{}\t0x{:02X}\t{}\n", g, b, canonical));
+        }
+        for &(g, code) in self.extensions {
+            out.push_str(&format!("{}\t0x{:02X} 0x{:02X}\ttrue\n", g, META_EXT, code));
+        }
+        out
+    }
+
+    /// Export the table as JSON.
+    pub fn to_json(&self) -> String {
+        let entries: Vec<String> = self
+            .table
+            .iter()
+            .map(|&(g, b)| {
+                format!(
+                    "{{\"grapheme\":{},\"byte\":{},\"canonical\":{}}}",
+                    json::quote(g),
+                    b,
+                    self.grapheme(b) == Some(g)
+                )
+            })
+            .collect();
+        let ext_entries = |entries: &[(&str, u8)]| -> Vec<String> {
+            entries
+                .iter()
+                .map(|&(g, code)| {
+                    format!("{{\"grapheme\":{},\"ext_code\":{}}}", json::quote(g), code)
+                })
+                .collect()
+        };
+        format!(
+            "{{\"name\":{},\"label\":{},\"table\":[{}],\"extensions\":[{}],\"vedic\":[{}]}}",
+            json::quote(self.name),
+            json::quote(self.label),
+            entries.join(","),
+            ext_entries(self.extensions).join(","),
+            ext_entries(self.vedic).join(",")
+        )
+    }
+}
+
+/// The token for a table byte.
+/// What a grapheme stands for.
+#[derive(Debug, Clone, Copy)]
+enum Entry {
+    Byte(u8),
+    Ext(u8),
+    Cluster(&'static [u8]),
+}
+
+/// The tokenizer loop shared by `Scheme` and `SchemeIndex`: whitespace,
+/// digits, accent and pluta marks, gap markers, and seams are handled here, everything else by
+/// `matcher`. With `warnings`, or when `opts` are lenient, what cannot be
+/// read is recovered from (and reported there) instead of failing.
+fn tokenize_with(
+    label: &str,
+    input: &str,
+    opts: &EncodeOptions,
+    warnings: Option<&mut Vec<EncodeWarning>>,
+    matcher: impl Fn(&str) -> Option<(Entry, usize)>,
+) -> Result<Vec<Token>, SlbcError> {
+    let fold = opts.fold_case;
+    let mut unreported = Vec::new();
+    let mut warnings = warnings.or(opts.lenient.then_some(&mut unreported));
+    let normalized = encoder::normalize_roman(input);
+    let folded = (fold != CaseFolding::Off).then(|| fold_capitals(&normalized));
+    let (input, capitals) = match &folded {
+        Some((lower, capitals)) => (lower.as_str(), capitals.as_slice()),
+        None => (&*normalized, &[][..]),
+    };
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+
+    while let Some(ch) = input[pos..].chars().next() {
+        // Skip carriage returns
+        if ch == '\r' {
+            pos += 1;
+            continue;
+        }
+
+        // Whitespace → SPACE token (collapsed)
+        if ch == ' ' || ch == '\t' || ch == '\n' {
+            encoder::push_whitespace(ch, &mut tokens, opts);
+            pos += 1;
+            continue;
+        }
+
+        // Pluta: "3" right after a vowel (ā3)
+        if ch == encoder::IAST_PLUTA && matches!(tokens.last(), Some(Token::Svara(_))) {
+            encoder::mark_last_svara(&mut tokens, ch);
+            pos += 1;
+            continue;
+        }
+
+        // Numerals: consecutive ASCII digits
+        if ch.is_ascii_digit() {
+            let len = input[pos..]
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(input.len() - pos);
+            tokens.push(Token::Numeral(input[pos..pos + len].to_string()));
+            pos += len;
+            continue;
+        }
+
+        // Full stops as daṇḍas
+        if ch == '.' && opts.dot_dandas {
+            let (token, len) = match input[pos + 1..].starts_with('.') {
+                true => (Token::DoubleDanda, 2),
+                false => (Token::Danda, 1),
+            };
+            tokens.push(token);
+            pos += len;
+            continue;
+        }
+
+        // Compound boundary
+        if Some(ch) == opts.samasa.mark() {
+            tokens.push(Token::Samasa);
+            pos += 1;
+            continue;
+        }
+
+        // Morpheme seam
+        if ch == '+' {
+            tokens.push(Token::Morph);
+            pos += 1;
+            continue;
+        }
+
+        // Recitation unit or quotation
+        if ch == encoder::PHON_OPEN || ch == encoder::PHON_CLOSE {
+            tokens.push(match ch {
+                encoder::PHON_OPEN => Token::PhonStart,
+                _ => Token::PhonEnd,
+            });
+            pos += ch.len_utf8();
+            continue;
+        }
+
+        // Vedic accent after a vowel
+        if matches!(
+            ch,
+            encoder::IAST_UDATTA | encoder::IAST_ANUDATTA | encoder::IAST_SVARITA
+        ) {
+            let position = input[..pos].chars().count();
+            match (
+                encoder::mark_last_svara(&mut tokens, ch),
+                warnings.as_deref_mut(),
+            ) {
+                (Some(()), _) => {}
+                (None, Some(warnings)) => warnings.push(EncodeWarning {
+                    position,
+                    found: ch,
+                    replacement: None,
+                }),
+                (None, None) => {
+                    let what = format!("{} accent mark without a vowel", label);
+                    return Err(SlbcError::encode(input, position, what));
+                }
+            }
+            pos += ch.len_utf8();
+            continue;
+        }
+
+        // Editorial gap markers
+        if let Some((token, len)) = encoder::gap_marker(&input[pos..]) {
+            tokens.push(token);
+            pos += len;
+            continue;
+        }
+
+        match matcher(&input[pos..]) {
+            Some((entry, len)) => {
+                if fold == CaseFolding::Preserve && starts_word(&tokens) {
+                    tokens.extend(word_case(input, pos, capitals));
+                }
+                match entry {
+                    Entry::Byte(b) => tokens.push(byte_token(b)),
+                    Entry::Ext(code) => tokens.push(Token::Extension(code)),
+                    Entry::Cluster(bytes) => tokens.extend(bytes.iter().map(|&b| byte_token(b))),
+                }
+                pos += len;
+            }
+            None if encoder::punctuate(ch, &mut tokens, opts.punctuation) => {
+                pos += ch.len_utf8();
+            }
+            None => {
+                let position = input[..pos].chars().count();
+                let Some(warnings) = warnings.as_deref_mut() else {
+                    let what = format!(
+                        "unrecognized {} character '{}' (U+{:04X})",
+                        label, ch, ch as u32
+                    );
+                    return Err(SlbcError::encode(input, position, what));
+                };
+                warnings.push(EncodeWarning {
+                    position,
+                    found: ch,
+                    replacement: encoder::recover(ch, &mut tokens),
+                });
+                pos += ch.len_utf8();
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// `input` in lower case, with a flag per byte set where a capital
+/// began.
+fn fold_capitals(input: &str) -> (String, Vec<bool>) {
+    let mut lower = String::with_capacity(input.len());
+    let mut capitals = Vec::with_capacity(input.len());
+    for ch in input.chars() {
+        let start = lower.len();
+        if ch.is_uppercase() {
+            lower.extend(ch.to_lowercase());
+        } else {
+            lower.push(ch);
+        }
+        capitals.resize(lower.len(), false);
+        capitals[start] = ch.is_uppercase();
+    }
+    (lower, capitals)
+}
+
+/// Whether the next phoneme begins a word.
+fn starts_word(tokens: &[Token]) -> bool {
+    !matches!(
+        tokens.last(),
+        Some(
+            Token::Svara(_)
+                | Token::Vyanjana(_)
+                | Token::Extension(_)
+                | Token::Avagraha
+                | Token::Morph
+                | Token::Samasa
+                | Token::Illegible
+                | Token::Lacuna
+                | Token::Capital
+                | Token::Uppercase
+        )
+    )
+}
+
+/// How the word at `pos` was capitalized: all capitals (two letters or
+/// more), or its first letter only. Other mixed case is not kept.
+fn word_case(input: &str, pos: usize, capitals: &[bool]) -> Option<Token> {
+    let (mut letters, mut caps, mut initial) = (0, 0, false);
+    for (i, ch) in input[pos..].char_indices() {
+        if ch.is_alphabetic() {
+            if capitals[pos + i] {
+                initial |= letters == 0;
+                caps += 1;
+            }
+            letters += 1;
+        } else if !matches!(ch, '\u{0300}'..='\u{036F}' | '+') {
+            break;
+        }
+    }
+    match (letters, caps) {
+        (2.., _) if caps == letters => Some(Token::Uppercase),
+        _ if initial => Some(Token::Capital),
+        _ => None,
+    }
+}
+
+/// A scheme's graphemes bucketed by first character, longest first, so
+/// tokenizing tries a handful of entries per position instead of the
+/// whole table. Built once and shared; it is `Send + Sync`.
+#[derive(Debug, Clone)]
+pub struct SchemeIndex {
+    scheme: &'static Scheme,
+    buckets: HashMap<char, Vec<(&'static str, Entry)>>,
+}
+
+impl SchemeIndex {
+    pub fn new(scheme: &'static Scheme) -> Self {
+        Self::build(scheme, false)
+    }
+
+    /// An index for Vedic text; same result as `Scheme::tokenize_vedic`.
+    pub fn vedic(scheme: &'static Scheme) -> Self {
+        Self::build(scheme, true)
+    }
+
+    fn build(scheme: &'static Scheme, vedic: bool) -> Self {
+        // Same precedence as `Scheme::match_entry` for equal lengths:
+        // core entries, then Vedic readings, extensions, and clusters
+        let mut entries: Vec<(&'static str, Entry, u8)> = Vec::new();
+        entries.extend(scheme.table.iter().map(|&(g, b)| (g, Entry::Byte(b), 0)));
+        if vedic {
+            entries.extend(scheme.vedic.iter().map(|&(g, c)| (g, Entry::Ext(c), 1)));
+        }
+        entries.extend(
+            scheme
+                .extensions
+                .iter()
+                .map(|&(g, c)| (g, Entry::Ext(c), 2)),
+        );
+        entries.extend(
+            scheme
+                .clusters
+                .iter()
+                .map(|&(g, b)| (g, Entry::Cluster(b), 3)),
+        );
+        entries.sort_by_key(|&(g, _, rank)| (std::cmp::Reverse(g.len()), rank));
+
+        let mut buckets: HashMap<char, Vec<(&'static str, Entry)>> = HashMap::new();
+        for (g, entry, _) in entries {
+            if let Some(first) = g.chars().next() {
+                buckets.entry(first).or_default().push((g, entry));
+            }
+        }
+        SchemeIndex { scheme, buckets }
+    }
+
+    pub fn scheme(&self) -> &'static Scheme {
+        self.scheme
+    }
+
+    /// Tokenize text; same result as `Scheme::tokenize`.
+    pub fn tokenize(&self, input: &str) -> Result<Vec<Token>, SlbcError> {
+        tokenize_with(
+            self.scheme.label,
+            input,
+            &EncodeOptions::default(),
+            None,
+            |rest| {
+                let first = rest.chars().next()?;
+                self.buckets
+                    .get(&first)?
+                    .iter()
+                    .find(|(g, _)| rest.starts_with(g))
+                    .map(|&(g, entry)| (entry, g.len()))
+            },
+        )
+    }
+}
+
+fn byte_token(b: u8) -> Token {
+    match b {
+        DANDA => Token::Danda,
+        DOUBLE_DANDA => Token::DoubleDanda,
+        AVAGRAHA => Token::Avagraha,
+        CANDRABINDU => Token::Candrabindu,
+        b if is_svara(b) => Token::Svara(b),
+        b => Token::Vyanjana(b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_longest_match() {
+        assert_eq!(IAST.longest_match("khala"), Some((0x01, 2)));
+        assert_eq!(IAST.longest_match("ka"), Some((0x00, 1)));
+        assert_eq!(IAST.longest_match("||"), Some((DOUBLE_DANDA, 2)));
+        assert_eq!(IAST.longest_match("ṛṣi"), Some((0x4C, "ṛ".len())));
+        assert_eq!(IAST.longest_match("x"), None);
+    }
+
+    #[test]
+    fn test_canonical_graphemes() {
+        assert_eq!(IAST.grapheme(AVAGRAHA), Some("'"));
+        assert_eq!(IAST.grapheme(0x86), Some("ai"));
+        assert_eq!(IAST.grapheme(0x05), None);
+        // Every byte round-trips through its canonical grapheme
+        for &(_, b) in IAST.table {
+            let g = IAST.grapheme(b).unwrap();
+            assert_eq!(IAST.longest_match(g), Some((b, g.len())));
+        }
+    }
+
+    #[test]
+    fn test_extensions() {
+        assert_eq!(IAST.match_token("qa"), Some((Token::Extension(0x08), 1)));
+        assert_eq!(
+            IAST.match_token("ḵha"),
+            Some((Token::Extension(0x09), "ḵh".len()))
+        );
+        assert_eq!(IAST.match_token("kha"), Some((Token::Vyanjana(0x01), 2)));
+        for &(g, code) in IAST.extensions {
+            assert!(crate::codepage::ext_phoneme(code).is_some(), "{}", g);
+        }
+    }
+
+    #[test]
+    fn test_slp1_is_one_char_per_phoneme() {
+        let phonemes = |s: &Scheme| {
+            let mut bytes: Vec<u8> = s
+                .table
+                .iter()
+                .map(|&(_, b)| b)
+                .filter(|&b| is_svara(b) || is_vyanjana(b))
+                .collect();
+            bytes.sort();
+            bytes
+        };
+        assert_eq!(phonemes(&SLP1), phonemes(&IAST));
+        for &(g, b) in SLP1.table {
+            if is_svara(b) || is_vyanjana(b) {
+                assert_eq!(g.len(), 1, "{}", g);
+            }
+        }
+        assert_eq!(SLP1.grapheme(DOUBLE_DANDA), Some(".."));
+    }
+
+    #[test]
+    fn test_hk_multi_letter_sequences() {
+        let bytes = |text: &str| -> Vec<u8> {
+            HK.tokenize(text)
+                .unwrap()
+                .into_iter()
+                .map(|t| match t {
+                    Token::Svara(b) | Token::Vyanjana(b) => b,
+                    other => panic!("{:?}", other),
+                })
+                .collect()
+        };
+        assert_eq!(bytes("klRpta"), [0x00, 0x4F, 0x20, 0x18, 0x40]);
+        assert_eq!(bytes("pitRRn"), [0x20, 0x44, 0x18, 0x8C, 0x1C]);
+        assert_eq!(bytes("lRR"), [0x8F]);
+        assert_eq!(
+            bytes("kaivalya"),
+            [0x00, 0x86, 0x32, 0x40, 0x34, 0x31, 0x40]
+        );
+        assert_eq!(bytes("Rju"), [0x4C, 0x0A, 0x48]);
+        assert_eq!(bytes("kRSNa"), [0x00, 0x4C, 0x2A, 0x14, 0x40]);
+    }
+
+    #[test]
+    fn test_itrans_variants() {
+        let same = |a: &str, b: &str| assert_eq!(ITRANS.tokenize(a), ITRANS.tokenize(b), "{}", a);
+        same("kaa", "kA");
+        same("R^iShi", "RRiShi");
+        same("chhandas", "Chandas");
+        same("sa.nskRRitam", "saMskRRitam");
+        same("xetra", "kShetra");
+        same("GYAna", "j~nAna");
+        same("w", "v");
+        assert_eq!(
+            ITRANS.tokenize("chha").unwrap(),
+            [Token::Vyanjana(0x09), Token::Svara(0x40)]
+        );
+        assert_eq!(ITRANS.tokenize("ca"), ITRANS.tokenize("cha"));
+        assert_eq!(ITRANS.tokenize("qa").unwrap()[0], Token::Extension(0x08));
+    }
+
+    #[test]
+    fn test_export() {
+        let tsv = IAST.to_tsv();
+        assert!(tsv.contains("ऽ\t0x27\tfalse\n"));
+        assert!(IAST.to_json().starts_with("{\"name\":\"iast\""));
+        assert!(scheme("iast").is_some());
+    }
+}
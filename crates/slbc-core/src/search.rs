@@ -0,0 +1,207 @@
+//! Pada search and concordance over PHON payloads.
+//!
+//! A query is one pada. Matching is exact on pada contents (ignoring any
+//! language tag) unless a `Lemmatizer` is plugged in, in which case a pada
+//! also matches when one of its lemmas equals the query stem. Either way,
+//! hits are reported at byte offsets into the payload.
+
+use std::collections::HashMap;
+
+use crate::encoder;
+use crate::error::SlbcError;
+use crate::lang;
+use crate::types::*;
+
+/// Maps an inflected pada to its candidate lemmas (stems).
+///
+/// Arguments and results are pada contents without PADA_START/PADA_END.
+/// Return an empty list for unknown forms.
+pub trait Lemmatizer {
+    fn lemmas(&self, pada: &[u8]) -> Vec<Vec<u8>>;
+}
+
+impl<F> Lemmatizer for F
+where
+    F: Fn(&[u8]) -> Vec<Vec<u8>>,
+{
+    fn lemmas(&self, pada: &[u8]) -> Vec<Vec<u8>> {
+        self(pada)
+    }
+}
+
+/// Exact matching only.
+pub struct NoLemmas;
+
+impl Lemmatizer for NoLemmas {
+    fn lemmas(&self, _: &[u8]) -> Vec<Vec<u8>> {
+        Vec::new()
+    }
+}
+
+/// A form → lemma table, e.g. exported from an external analyzer.
+#[derive(Debug, Default)]
+pub struct LemmaTable {
+    forms: HashMap<Vec<u8>, Vec<Vec<u8>>>,
+}
+
+impl LemmaTable {
+    /// Parse IAST TSV lines `form<TAB>lemma`. A form may appear on several
+    /// lines; blank lines and `#` comments are ignored.
+    pub fn from_tsv(tsv: &str) -> Result<Self, SlbcError> {
+        let mut table = LemmaTable::default();
+        for (n, line) in tsv.lines().enumerate() {
+            let line = line.trim_end_matches('\r');
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((form, lemma)) = line.split_once('\t') else {
+                return Err(SlbcError::invalid(format_args!(
+                    "line {}: expected form<TAB>lemma",
+                    n + 1
+                )));
+            };
+            let at_line = |e| SlbcError::invalid(format_args!("line {}: {}", n + 1, e));
+            let form = query_pada(form).map_err(at_line)?;
+            let lemma = query_pada(lemma).map_err(at_line)?;
+            table.insert(form, lemma);
+        }
+        Ok(table)
+    }
+
+    pub fn insert(&mut self, form: Vec<u8>, lemma: Vec<u8>) {
+        self.forms.entry(form).or_default().push(lemma);
+    }
+}
+
+impl Lemmatizer for LemmaTable {
+    fn lemmas(&self, pada: &[u8]) -> Vec<Vec<u8>> {
+        self.forms.get(pada).cloned().unwrap_or_default()
+    }
+}
+
+/// Encode an IAST query as the contents of a single pada.
+pub fn query_pada(iast: &str) -> Result<Vec<u8>, SlbcError> {
+    let bytes = encoder::encode_iast(iast.trim())?;
+    match bytes.as_slice() {
+        [PADA_START, body @ .., PADA_END] if !body.contains(&PADA_END) => Ok(body.to_vec()),
+        _ => Err(SlbcError::invalid(format_args!(
+            "query '{}' is not a single pada",
+            iast
+        ))),
+    }
+}
+
+/// One matching pada.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hit {
+    /// Index of the pada among the payload's padas.
+    pub pada: usize,
+    /// Offset of PADA_START.
+    pub offset: usize,
+    /// Length including PADA_START and PADA_END.
+    pub len: usize,
+    /// Matched through the lemmatizer rather than exactly.
+    pub via_lemma: bool,
+}
+
+/// Find every pada equal to `query`, or lemmatized to it.
+pub fn grep(data: &[u8], query: &[u8], lemmatizer: &dyn Lemmatizer) -> Result<Vec<Hit>, SlbcError> {
+    let mut hits = Vec::new();
+    for (n, (offset, pada)) in lang::padas(data)?.into_iter().enumerate() {
+        let body = &pada[lang::split_tag(pada)?.1..];
+        let via_lemma = if body == query {
+            false
+        } else if lemmatizer.lemmas(body).iter().any(|l| l == query) {
+            true
+        } else {
+            continue;
+        };
+        hits.push(Hit {
+            pada: n,
+            offset,
+            len: pada.len() + 2,
+            via_lemma,
+        });
+    }
+    Ok(hits)
+}
+
+/// A hit with up to `context` padas on either side.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConcordanceLine<'a> {
+    pub hit: Hit,
+    pub left: &'a [u8],
+    pub keyword: &'a [u8],
+    pub right: &'a [u8],
+}
+
+/// Key-word-in-context lines for a set of hits. The context slices are
+/// payload bytes (including separators), ready for decoding.
+pub fn concordance<'a>(
+    data: &'a [u8],
+    hits: &[Hit],
+    context: usize,
+) -> Result<Vec<ConcordanceLine<'a>>, SlbcError> {
+    let padas = lang::padas(data)?;
+    Ok(hits
+        .iter()
+        .map(|hit| {
+            let end = hit.offset + hit.len;
+            let first = hit.pada.saturating_sub(context);
+            let start = padas.get(first).map_or(hit.offset, |p| p.0);
+            let stop = match padas.get(hit.pada + context) {
+                Some(&(o, p)) if context > 0 => o + p.len() + 2,
+                _ if context > 0 => data.len(),
+                _ => end,
+            };
+            ConcordanceLine {
+                hit: hit.clone(),
+                left: &data[start..hit.offset],
+                keyword: &data[hit.offset..end],
+                right: &data[end..stop],
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoder::{self, Script};
+
+    #[test]
+    fn test_exact_and_lemmatized() {
+        let data = encoder::encode_iast("rāmo vanaṃ gacchati rāmeṇa saha sītā rāmaḥ").unwrap();
+        let query = query_pada("rāmaḥ").unwrap();
+        let exact = grep(&data, &query, &NoLemmas).unwrap();
+        assert_eq!(exact.len(), 1);
+        assert_eq!(exact[0].pada, 6);
+
+        let table = LemmaTable::from_tsv("rāmo\trāmaḥ\nrāmeṇa\trāmaḥ\n").unwrap();
+        let hits = grep(&data, &query, &table).unwrap();
+        assert_eq!(hits.iter().map(|h| h.pada).collect::<Vec<_>>(), [0, 3, 6]);
+        assert!(hits[0].via_lemma && !hits[2].via_lemma);
+        assert_eq!(data[hits[1].offset], PADA_START);
+
+        // Closures work as lemmatizers too
+        let strip = |p: &[u8]| vec![p[..p.len().saturating_sub(1)].to_vec()];
+        assert_eq!(
+            grep(&data, &query_pada("vana").unwrap(), &strip)
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_concordance() {
+        let data = encoder::encode_iast("a b c d e").unwrap();
+        let hits = grep(&data, &query_pada("c").unwrap(), &NoLemmas).unwrap();
+        let line = &concordance(&data, &hits, 1).unwrap()[0];
+        let text = |b: &[u8]| decoder::decode_phon(b, Script::Iast).unwrap();
+        assert_eq!(text(line.left), "b ");
+        assert_eq!(text(line.keyword), "c");
+        assert_eq!(text(line.right), " d");
+        assert!(query_pada("two words").is_err());
+    }
+}
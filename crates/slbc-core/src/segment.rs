@@ -0,0 +1,683 @@
+//! In-place replacement of a single verse or pada in a container.
+//!
+//! Corrections to a large file should not rewrite all of it. `plan` locates
+//! the segment (through the verse-checksum IDX chunk when present, otherwise
+//! by scanning the PHON payload), and returns the byte splices that swap in
+//! the re-encoded span and keep the chunk length and indexes consistent.
+//! `apply_to_file` writes same-length splices in place and rewrites only the
+//! file tail from the first splice that changes length.
+//!
+//! For editors, `plan_edit` takes the source text before and after an edit
+//! and re-encodes only the verses between the common prefix and suffix.
+//!
+//! Padas are counted as `lang::padas` counts them: top-level padas in the
+//! stored payload, so dictionary-referenced padas are not addressable.
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::ops::Range;
+
+use crate::akshara::AksharaIndex;
+use crate::checksum::{self, VerseSum, VerseSums};
+use crate::container::{self, ChunkEntry, SlbcHeader};
+use crate::decoder::Script;
+use crate::encoder;
+use crate::error::SlbcError;
+use crate::frame;
+use crate::index::PadaIndex;
+use crate::lang;
+use crate::types::*;
+
+/// A segment of a container's PHON payload, by 0-based index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Segment {
+    Verse(usize),
+    Pada(usize),
+}
+
+impl std::fmt::Display for Segment {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Segment::Verse(n) => write!(f, "verse {}", n + 1),
+            Segment::Pada(n) => write!(f, "pada {}", n + 1),
+        }
+    }
+}
+
+/// Replace `old_len` file bytes at `offset` with `bytes`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Splice {
+    pub offset: u64,
+    pub old_len: u64,
+    pub bytes: Vec<u8>,
+}
+
+impl Splice {
+    fn delta(&self) -> i64 {
+        self.bytes.len() as i64 - self.old_len as i64
+    }
+}
+
+fn read_at<R: Read + Seek>(r: &mut R, offset: u64, len: u64) -> Result<Vec<u8>, SlbcError> {
+    r.seek(SeekFrom::Start(offset)).map_err(|e| SlbcError::Io {
+        message: format!("seek error: {}", e),
+    })?;
+    let mut buf = vec![0; len as usize];
+    r.read_exact(&mut buf).map_err(|e| SlbcError::Io {
+        message: format!("read error: {}", e),
+    })?;
+    Ok(buf)
+}
+
+/// Check that `replacement` is exactly one segment of the given kind.
+fn check_replacement(segment: Segment, replacement: &[u8]) -> Result<(), SlbcError> {
+    let whole = match segment {
+        Segment::Verse(_) => match frame::verses(replacement)?[..] {
+            [ref v] => *v == (0..replacement.len()),
+            _ => false,
+        },
+        Segment::Pada(_) => match lang::padas(replacement)?[..] {
+            [(0, pada)] => pada.len() + 2 == replacement.len(),
+            _ => false,
+        },
+    };
+    if !whole {
+        return Err(SlbcError::invalid(format_args!(
+            "replacement is not a single {}",
+            segment
+        )));
+    }
+    Ok(())
+}
+
+/// Splice that rewrites a whole chunk with a new payload.
+fn rewrite_chunk(entry: &ChunkEntry, chunk_type: u8, payload: &[u8]) -> Splice {
+    let mut bytes = Vec::new();
+    container::write_chunk(&mut bytes, chunk_type, payload);
+    Splice {
+        offset: entry.offset,
+        old_len: entry.end() - entry.offset,
+        bytes,
+    }
+}
+
+/// Work out the splices that replace `segment` with `replacement` (encoded
+/// PHON bytes). Sorted by offset and non-overlapping.
+pub fn plan<R: Read + Seek>(
+    r: &mut R,
+    segment: Segment,
+    replacement: &[u8],
+) -> Result<Vec<Splice>, SlbcError> {
+    check_replacement(segment, replacement)?;
+    let Layout {
+        header,
+        phon,
+        sums,
+        akshara,
+        pada_index,
+        ..
+    } = layout(r)?;
+
+    // Locate the span; only a verse with checksums avoids reading the payload
+    let mut payload = None;
+    let span: Range<usize> = match (segment, &sums) {
+        (Segment::Verse(n), Some((_, s))) => {
+            let v = s.verses.get(n).ok_or_else(|| {
+                SlbcError::invalid(format_args!(
+                    "{} out of range ({} verses)",
+                    segment,
+                    s.verses.len()
+                ))
+            })?;
+            v.offset as usize..v.offset as usize + v.len as usize
+        }
+        (Segment::Verse(n), None) => {
+            let data = payload.insert(read_at(r, phon.payload_offset, phon.len)?);
+            let verses = frame::verses(data)?;
+            let count = verses.len();
+            verses.into_iter().nth(n).ok_or_else(|| {
+                SlbcError::invalid(format_args!("{} out of range ({} verses)", segment, count))
+            })?
+        }
+        (Segment::Pada(n), _) => {
+            let data = payload.insert(read_at(r, phon.payload_offset, phon.len)?);
+            let padas = lang::padas(data)?;
+            let &(offset, pada) = padas.get(n).ok_or_else(|| {
+                SlbcError::invalid(format_args!(
+                    "{} out of range ({} padas)",
+                    segment,
+                    padas.len()
+                ))
+            })?;
+            offset..offset + pada.len() + 2
+        }
+    };
+    if span.end as u64 > phon.len {
+        return Err(SlbcError::invalid(format_args!(
+            "{} extends beyond the PHON chunk",
+            segment
+        )));
+    }
+
+    let mut splices = phon_splices(&phon, &span, replacement);
+    let delta = replacement.len() as i64 - span.len() as i64;
+
+    if let Some((entry, mut s)) = sums {
+        let n = s
+            .verses
+            .iter()
+            .position(|v| {
+                v.offset as usize <= span.start && span.end <= v.offset as usize + v.len as usize
+            })
+            .ok_or_else(|| {
+                SlbcError::invalid(format_args!(
+                    "{} is not inside a checksummed verse",
+                    segment
+                ))
+            })?;
+        let v = s.verses[n];
+        let verse = match &payload {
+            Some(data) => data[v.offset as usize..v.offset as usize + v.len as usize].to_vec(),
+            None => read_at(r, phon.payload_offset + v.offset as u64, v.len as u64)?,
+        };
+        let at = span.start - v.offset as usize;
+        let edited = [&verse[..at], replacement, &verse[at + span.len()..]].concat();
+        s.verses[n].len = edited.len() as u32;
+        s.verses[n].crc = checksum::crc32(&edited);
+        for v in &mut s.verses[n + 1..] {
+            v.offset = (v.offset as i64 + delta) as u32;
+        }
+        splices.push(rewrite_chunk(&entry, CHUNK_IDX, &s.to_chunk().payload));
+    }
+    if let Some((entry, mut index)) = akshara {
+        if has_dict(&header)? {
+            return Err(SlbcError::invalid(
+                "cannot update the akṣara index of a container with a pada dictionary",
+            ));
+        }
+        index.splice(span.start as u32, span.len() as u32, replacement)?;
+        splices.push(rewrite_chunk(&entry, CHUNK_IDX, &index.to_chunk().payload));
+    }
+    if let Some((entry, mut index)) = pada_index {
+        index.splice(span.start as u32, span.len() as u32, replacement)?;
+        splices.push(rewrite_chunk(&entry, CHUNK_IDX, &index.to_chunk().payload));
+    }
+    splices.sort_by_key(|s| s.offset);
+    Ok(splices)
+}
+
+/// The chunks an edit touches.
+struct Layout {
+    header: SlbcHeader,
+    entries: Vec<ChunkEntry>,
+    /// The PHON chunk the indexes refer to (the first, without indexes).
+    phon: ChunkEntry,
+    sums: Option<(ChunkEntry, VerseSums)>,
+    akshara: Option<(ChunkEntry, AksharaIndex)>,
+    pada_index: Option<(ChunkEntry, PadaIndex)>,
+}
+
+fn layout<R: Read + Seek>(r: &mut R) -> Result<Layout, SlbcError> {
+    let (header, entries) = container::chunk_directory(r)?;
+
+    let mut sums = None;
+    let mut akshara = None;
+    let mut pada_index = None;
+    for &entry in entries.iter().filter(|e| e.chunk_type == CHUNK_IDX) {
+        let payload = read_at(r, entry.payload_offset, entry.len)?;
+        if let Some(s) = VerseSums::from_chunk_payload(&payload)? {
+            sums = Some((entry, s));
+        } else if let Some(index) = AksharaIndex::from_chunk_payload(&payload)? {
+            akshara = Some((entry, index));
+        } else if let Some(index) = PadaIndex::from_chunk_payload(&payload)? {
+            pada_index = Some((entry, index));
+        }
+    }
+    let ordinal = sums
+        .as_ref()
+        .map(|(_, s)| s.phon_chunk)
+        .or(akshara.as_ref().map(|(_, a)| a.phon_chunk))
+        .or(pada_index.as_ref().map(|(_, p)| p.phon_chunk))
+        .unwrap_or(0);
+    let phon = *entries
+        .iter()
+        .filter(|e| e.chunk_type == CHUNK_PHON)
+        .nth(ordinal as usize)
+        .ok_or_else(|| {
+            SlbcError::invalid(format_args!("container has no PHON chunk {}", ordinal))
+        })?;
+    Ok(Layout {
+        header,
+        entries,
+        phon,
+        sums,
+        akshara,
+        pada_index,
+    })
+}
+
+fn has_dict(header: &SlbcHeader) -> Result<bool, SlbcError> {
+    Ok(header
+        .ext_records()?
+        .iter()
+        .any(|rec| rec.tag == EXT_HDR_DICT))
+}
+
+/// Splices that replace `span` of the PHON payload and fix its length.
+fn phon_splices(phon: &ChunkEntry, span: &Range<usize>, replacement: &[u8]) -> Vec<Splice> {
+    let new_len = phon.len - span.len() as u64 + replacement.len() as u64;
+    let mut len_field = Vec::new();
+    container::write_uleb128(&mut len_field, new_len);
+    vec![
+        Splice {
+            offset: phon.offset + 1,
+            old_len: phon.payload_offset - phon.offset - 1,
+            bytes: len_field,
+        },
+        Splice {
+            offset: phon.payload_offset + span.start as u64,
+            old_len: span.len() as u64,
+            bytes: replacement.to_vec(),
+        },
+    ]
+}
+
+// ═══════════════════════════════════════════════
+//  Re-encoding an edited text
+// ═══════════════════════════════════════════════
+
+/// Verses of a source text, as `frame::verses` splits its encoding: spans
+/// ending just after each double daṇḍa ("||", "॥", "।।"), plus any trailing
+/// text. Leading whitespace is left out; all-whitespace spans are dropped.
+fn text_verses(text: &str) -> Vec<Range<usize>> {
+    let mut out = Vec::new();
+    let mut push = |start: usize, end: usize| {
+        if let Some(first) = text[start..end].find(|c: char| !c.is_whitespace()) {
+            out.push(start + first..end);
+        }
+    };
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let end = match (c, chars.peek()) {
+            ('॥', _) => i + c.len_utf8(),
+            ('|' | '।', Some(&(j, d @ ('|' | '।')))) => {
+                chars.next();
+                j + d.len_utf8()
+            }
+            _ => continue,
+        };
+        push(start, end);
+        start = end;
+    }
+    push(start, text.len());
+    out
+}
+
+/// Work out the splices that bring a container encoded from `original` (in
+/// `script`) up to date with `edited`. Only the verses the edit touches
+/// are re-encoded; the rest of the payload, and the verse checksums when
+/// present, are only located, not read. Texts are trimmed, as for a full
+/// encode. Containers with a pada dictionary or a packed payload are
+/// rejected, since their stored bytes are not the plain encoding.
+pub fn plan_edit<R: Read + Seek>(
+    r: &mut R,
+    original: &str,
+    edited: &str,
+    script: Script,
+) -> Result<Vec<Splice>, SlbcError> {
+    let (original, edited) = (original.trim(), edited.trim());
+    if original == edited {
+        return Ok(Vec::new());
+    }
+    let Layout {
+        header,
+        entries,
+        phon,
+        sums,
+        akshara,
+        pada_index,
+    } = layout(r)?;
+    if has_dict(&header)? || entries.iter().any(|e| e.chunk_type == CHUNK_DICT) {
+        return Err(SlbcError::invalid(
+            "cannot re-encode a container with a dictionary in place",
+        ));
+    }
+
+    // The changed region: what lies between the common prefix and suffix
+    let shorter = original.len().min(edited.len());
+    let prefix = original
+        .char_indices()
+        .zip(edited.chars())
+        .find(|&((_, a), b)| a != b)
+        .map_or(shorter, |((i, _), _)| i);
+    let suffix = original
+        .char_indices()
+        .rev()
+        .zip(edited.chars().rev())
+        .find(|&((i, a), b)| a != b || original.len() - i > shorter - prefix)
+        .map_or(shorter - prefix, |((i, a), _)| {
+            original.len() - i - a.len_utf8()
+        });
+
+    // Verses wholly before or after it are kept
+    let old = text_verses(original);
+    let new = text_verses(edited);
+    let lead = old
+        .iter()
+        .zip(&new)
+        .take_while(|(a, b)| a.end < prefix && a == b)
+        .count();
+    let from_end = |text: &str, v: &Range<usize>| (text.len() - v.start, text.len() - v.end);
+    let trail = old
+        .iter()
+        .rev()
+        .zip(new.iter().rev())
+        .take_while(|(a, b)| {
+            a.start > original.len() - suffix && from_end(original, a) == from_end(edited, b)
+        })
+        .count()
+        .min(old.len() - lead)
+        .min(new.len() - lead);
+    let region = |verses: &[Range<usize>], len: usize| {
+        let start = if lead == 0 { 0 } else { verses[lead - 1].end };
+        let end = if trail == 0 {
+            len
+        } else {
+            verses[verses.len() - trail].start
+        };
+        start..end
+    };
+    let replacement = encoder::encode_script(&edited[region(&new, edited.len())], script)?;
+
+    let stored = match &sums {
+        Some((_, s)) => s
+            .verses
+            .iter()
+            .map(|v| v.offset as usize..v.offset as usize + v.len as usize)
+            .collect(),
+        None => frame::verses(&read_at(r, phon.payload_offset, phon.len)?)?,
+    };
+    if stored.len() != old.len() {
+        return Err(SlbcError::invalid(format_args!(
+            "container has {} verses, the original text {}",
+            stored.len(),
+            old.len()
+        )));
+    }
+    let span = region(&stored, phon.len as usize);
+
+    let mut splices = phon_splices(&phon, &span, &replacement);
+    let delta = replacement.len() as i64 - span.len() as i64;
+    if let Some((entry, mut s)) = sums {
+        let fresh = VerseSums::build(&replacement, s.phon_chunk)?
+            .verses
+            .into_iter()
+            .map(|v| VerseSum {
+                offset: v.offset + span.start as u32,
+                ..v
+            });
+        let kept = s.verses.len() - trail;
+        s.verses.splice(lead..kept, fresh);
+        let n = s.verses.len();
+        for v in &mut s.verses[n - trail..] {
+            v.offset = (v.offset as i64 + delta) as u32;
+        }
+        splices.push(rewrite_chunk(&entry, CHUNK_IDX, &s.to_chunk().payload));
+    }
+    if let Some((entry, mut index)) = akshara {
+        index.splice(span.start as u32, span.len() as u32, &replacement)?;
+        splices.push(rewrite_chunk(&entry, CHUNK_IDX, &index.to_chunk().payload));
+    }
+    if let Some((entry, mut index)) = pada_index {
+        index.splice(span.start as u32, span.len() as u32, &replacement)?;
+        splices.push(rewrite_chunk(&entry, CHUNK_IDX, &index.to_chunk().payload));
+    }
+    splices.sort_by_key(|s| s.offset);
+    Ok(splices)
+}
+
+/// Apply splices to an in-memory container.
+pub fn apply_to_vec(data: &mut Vec<u8>, splices: &[Splice]) {
+    for s in splices.iter().rev() {
+        let start = s.offset as usize;
+        data.splice(start..start + s.old_len as usize, s.bytes.iter().copied());
+    }
+}
+
+/// Apply splices to a file. Same-length splices are written in place; from
+/// the first splice that changes length, the rest of the file is rewritten.
+/// Returns the number of bytes written.
+pub fn apply_to_file(file: &mut File, splices: &[Splice]) -> Result<u64, SlbcError> {
+    let io = |e: std::io::Error| SlbcError::Io {
+        message: format!("I/O error: {}", e),
+    };
+    let first_change = splices.iter().position(|s| s.delta() != 0);
+    let in_place = &splices[..first_change.unwrap_or(splices.len())];
+    let mut written = 0;
+    for s in in_place {
+        file.seek(SeekFrom::Start(s.offset)).map_err(io)?;
+        file.write_all(&s.bytes).map_err(io)?;
+        written += s.bytes.len() as u64;
+    }
+    if let Some(i) = first_change {
+        let from = splices[i].offset;
+        let end = file.seek(SeekFrom::End(0)).map_err(io)?;
+        let mut tail = read_at(file, from, end - from)?;
+        let shifted: Vec<Splice> = splices[i..]
+            .iter()
+            .map(|s| Splice {
+                offset: s.offset - from,
+                ..s.clone()
+            })
+            .collect();
+        apply_to_vec(&mut tail, &shifted);
+        file.seek(SeekFrom::Start(from)).map_err(io)?;
+        file.write_all(&tail).map_err(io)?;
+        file.set_len(from + tail.len() as u64).map_err(io)?;
+        written += tail.len() as u64;
+    }
+    Ok(written)
+}
+
+/// Replace one segment of a container file. Returns the bytes written.
+pub fn replace(file: &mut File, segment: Segment, replacement: &[u8]) -> Result<u64, SlbcError> {
+    let splices = plan(file, segment, replacement)?;
+    apply_to_file(file, &splices)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoder::{self, Script};
+    use crate::encoder;
+    use std::io::Cursor;
+
+    const TEXT: &str =
+        "tat savitur vareṇyaṃ || bhargo devasya dhīmahi || dhiyo yo naḥ pracodayāt ||";
+
+    fn container(text: &str) -> Vec<u8> {
+        let data = encoder::encode_iast(text).unwrap();
+        let extra = [
+            VerseSums::build(&data, 0).unwrap().to_chunk(),
+            AksharaIndex::build(&data, 0).unwrap().to_chunk(),
+            PadaIndex::build(&data, 0).unwrap().to_chunk(),
+        ];
+        container::build_slbc_with(&data, &extra)
+    }
+
+    fn summed(text: &str) -> Vec<u8> {
+        let data = encoder::encode_iast(text).unwrap();
+        container::build_slbc_with(&data, &[VerseSums::build(&data, 0).unwrap().to_chunk()])
+    }
+
+    fn replaced(file: &[u8], segment: Segment, iast: &str) -> Vec<u8> {
+        let replacement = encoder::encode_iast(iast).unwrap();
+        let splices = plan(&mut Cursor::new(file), segment, &replacement).unwrap();
+        let mut out = file.to_vec();
+        apply_to_vec(&mut out, &splices);
+        out
+    }
+
+    #[test]
+    fn test_replace_verse_updates_indexes() {
+        let file = container(TEXT);
+        let out = replaced(&file, Segment::Verse(1), "bhargo devasya dhīmahī ||");
+        let edited = TEXT.replace("dhīmahi", "dhīmahī");
+        assert_eq!(out, container(&edited));
+
+        // Length-changing edit of a verse, then of a pada after it
+        let out = replaced(&file, Segment::Verse(0), "tat savitur vareṇyam ||");
+        let out = replaced(&out, Segment::Pada(9), "pracodayātu");
+        let edited = TEXT
+            .replace("vareṇyaṃ", "vareṇyam")
+            .replace("pracodayāt", "pracodayātu");
+        assert_eq!(out, container(&edited));
+        let report = checksum::verify_fast(Cursor::new(&out), None).unwrap();
+        assert!(report.checked.iter().all(|&(_, ok)| ok));
+        let (_, chunks) = container::parse_slbc(&out).unwrap();
+        let phon = chunks.iter().find(|c| c.chunk_type == CHUNK_PHON).unwrap();
+        assert_eq!(
+            decoder::decode_phon(&phon.payload, Script::Iast).unwrap(),
+            edited
+        );
+
+        // Without indexes the payload is scanned
+        let plain = container::build_slbc(&encoder::encode_iast(TEXT).unwrap());
+        let out = replaced(&plain, Segment::Verse(2), "dhiyo yo naḥ ||");
+        let expected = encoder::encode_iast(&TEXT.replace(" pracodayāt", "")).unwrap();
+        assert_eq!(out, container::build_slbc(&expected));
+    }
+
+    fn edited(file: &[u8], original: &str, edited: &str) -> Vec<u8> {
+        let splices = plan_edit(&mut Cursor::new(file), original, edited, Script::Iast).unwrap();
+        let mut out = file.to_vec();
+        apply_to_vec(&mut out, &splices);
+        out
+    }
+
+    #[test]
+    fn test_plan_edit_matches_full_encode() {
+        let edits = [
+            TEXT.replace("dhīmahi", "dhīmahī"),
+            TEXT.replace("vareṇyaṃ", "vareṇyam"),
+            TEXT.replace("pracodayāt", "pracodayātu"),
+            TEXT.replace(" || bhargo", " bhargo"),
+            TEXT.replace("devasya ||", "devasya || dhīmahi ||"),
+            TEXT.replace("||  dhiyo", "||\ndhiyo"),
+            format!("oṃ || {}", TEXT),
+            format!("{} iti", TEXT),
+            TEXT.replace("tat savitur vareṇyaṃ || ", ""),
+            TEXT.to_string(),
+        ];
+        for text in &edits {
+            for build in [container, summed, |t: &str| {
+                container::build_slbc(&encoder::encode_iast(t).unwrap())
+            }] {
+                assert_eq!(edited(&build(TEXT), TEXT, text), build(text), "{}", text);
+            }
+        }
+
+        // Only the edited verse is re-encoded
+        let splices = plan_edit(
+            &mut Cursor::new(container(TEXT)),
+            TEXT,
+            &edits[0],
+            Script::Iast,
+        )
+        .unwrap();
+        let verse = encoder::encode_iast(" bhargo devasya dhīmahī || ").unwrap();
+        assert!(splices.iter().any(|s| s.bytes == verse));
+
+        // Devanāgarī daṇḍas
+        let deva = "तत् सवितुः ॥ भर्गो देवस्य ।।";
+        let data = encoder::encode_devanagari(deva).unwrap();
+        let file = container::build_slbc(&data);
+        let splices = plan_edit(
+            &mut Cursor::new(&file),
+            deva,
+            &deva.replace("देवस्य", "देवस्या"),
+            Script::Devanagari,
+        )
+        .unwrap();
+        let verse = encoder::encode_devanagari(" भर्गो देवस्या ॥").unwrap();
+        assert!(splices.iter().any(|s| s.bytes == verse));
+    }
+
+    #[test]
+    fn test_seek_after_edit() {
+        let text = "rāmaḥ vanam gacchati | sītā tiṣṭhati || lakṣmaṇaḥ api ||";
+        let data = encoder::encode_iast(text).unwrap();
+        let file =
+            container::build_slbc_with(&data, &[PadaIndex::build(&data, 0).unwrap().to_chunk()]);
+        let longer = text.replace("tiṣṭhati ||", "tiṣṭhati ca gṛhe ciram ||");
+        let seek = |file: &[u8], verse: usize, pada: usize| {
+            let verse = container::seek_verse(&mut Cursor::new(file), verse).unwrap();
+            let pada = container::seek_pada(&mut Cursor::new(file), pada).unwrap();
+            (
+                decoder::decode_phon(&verse, Script::Iast).unwrap(),
+                decoder::decode_phon(&pada, Script::Iast).unwrap(),
+            )
+        };
+
+        let out = edited(&file, text, &longer);
+        let (_, chunks) = container::parse_slbc(&out).unwrap();
+        assert_eq!(PadaIndex::find(&chunks).unwrap().unwrap().padas.len(), 10);
+        assert_eq!(
+            seek(&out, 1, 8),
+            ("lakṣmaṇaḥ api ||".to_string(), "lakṣmaṇaḥ".to_string())
+        );
+        assert!(container::seek_pada(&mut Cursor::new(&out), 10).is_err());
+
+        // A pada edit grows its verse
+        let out = replaced(&out, Segment::Pada(7), "cirāt");
+        assert_eq!(
+            seek(&out, 0, 7),
+            (
+                "rāmaḥ vanam gacchati | sītā tiṣṭhati ca gṛhe cirāt ||".to_string(),
+                "cirāt".to_string()
+            )
+        );
+        assert_eq!(seek(&out, 1, 9).1, "api");
+    }
+
+    #[test]
+    fn test_plan_edit_rejects_mismatch() {
+        let file = container(TEXT);
+        let other = "a || b";
+        assert!(plan_edit(&mut Cursor::new(&file), other, "a || c", Script::Iast).is_err());
+    }
+
+    #[test]
+    fn test_rejects_bad_replacement() {
+        let file = container(TEXT);
+        let mut r = Cursor::new(&file);
+        let two = encoder::encode_iast("a || b ||").unwrap();
+        assert!(plan(&mut r, Segment::Verse(0), &two).is_err());
+        let two = encoder::encode_iast("a b").unwrap();
+        assert!(plan(&mut r, Segment::Pada(0), &two).is_err());
+        let one = encoder::encode_iast("a ||").unwrap();
+        assert!(plan(&mut r, Segment::Verse(3), &one).is_err());
+    }
+
+    #[test]
+    fn test_apply_to_file() {
+        let path = std::env::temp_dir().join(format!("slbc-segment-{}.slbc", std::process::id()));
+        let file = summed(TEXT);
+        std::fs::write(&path, &file).unwrap();
+        let mut f = File::options().read(true).write(true).open(&path).unwrap();
+
+        // Same length: only the pada, the PHON length, and the IDX chunk are written
+        let replacement = encoder::encode_iast("savitar").unwrap();
+        let written = replace(&mut f, Segment::Pada(1), &replacement).unwrap();
+        assert!(written < file.len() as u64 / 2);
+        let shorter = encoder::encode_iast("yaḥ").unwrap();
+        replace(&mut f, Segment::Pada(8), &shorter).unwrap();
+        drop(f);
+
+        let out = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let edited = TEXT.replace("savitur", "savitar").replace("naḥ", "yaḥ");
+        assert_eq!(out, summed(&edited));
+    }
+}
@@ -4,6 +4,10 @@
 //! Vyañjana algebra: jaśtva, voice toggle, aspiration toggle, nasal.
 //! Saṃprasāraṇa: sonorant ↔ svara.
 
+use crate::codepage;
+use crate::container;
+use crate::error::SlbcError;
+use crate::numeral;
 use crate::types::*;
 
 /// The result of an algebraic transformation.
@@ -11,87 +15,275 @@ use crate::types::*;
 pub struct TransformResult {
     pub input_byte: u8,
     pub output_byte: u8,
+    /// The r or l written after `output_byte` when guṇa/vṛddhi replaces ṛ or
+    /// ḷ (1.1.51 uraṇ raparaḥ: ar, ār, al, āl).
+    pub rapara: Option<u8>,
     pub operation: &'static str,
     pub input_iast: String,
     pub output_iast: String,
 }
 
+impl TransformResult {
+    /// The replacement bytes: `output_byte`, then `rapara` if any.
+    pub fn output_bytes(&self) -> Vec<u8> {
+        std::iter::once(self.output_byte)
+            .chain(self.rapara)
+            .collect()
+    }
+}
+
 // ═══════════════════════════════════════════════
 //  Svara Algebra (§5.1)
 // ═══════════════════════════════════════════════
 
-/// Guṇa: set G := 01, Q := 10 (dīrgha). Accent preserved.
-pub fn guna(b: u8) -> Result<TransformResult, String> {
-    if !is_svara(b) {
-        return Err(format!("0x{:02X} is not a svara", b));
-    }
-    let s = svara_s(b);
-    if s == 0b00 {
-        return Err("a-series has no guṇa transformation".into());
+/// Guṇa (1.1.2 adeṅ guṇaḥ) of an ik vowel: i/u → e/o, with Q := 10 and
+/// accent preserved; ṛ/ḷ → ar/al (a with `rapara`). Guṇa of e/o returns them
+/// unchanged. The a-series and the vṛddhi vowels ai/au have no guṇa, and
+/// bytes outside the phoneme table are rejected.
+pub fn guna(b: u8) -> Result<TransformResult, SlbcError> {
+    let s = require_grade_operand(b)?;
+    let accent = svara_a(b);
+    match (s, svara_g(b)) {
+        (0b00, _) => Err(SlbcError::invalid("a-series has no guṇa transformation")),
+        (0b11, _) => Ok(rapara_result(b, (0b01 << 6) | (accent << 4), "guṇa")),
+        (_, 0b10) => Err(SlbcError::invalid(format_args!(
+            "{} is already vṛddhi — guṇa does not replace it",
+            crate::decoder::byte_to_iast(b)
+        ))),
+        _ => {
+            let result = (0b10 << 6) | (accent << 4) | (s << 2) | 0b01;
+            Ok(make_svara_result(b, result, "guṇa"))
+        }
     }
-    // Preserve accent (bits 5:4), set Q=10, G=01
+}
+
+/// Vṛddhi (1.1.1 vṛddhir ādaic): a → ā, i/u and e/o → ai/au, with Q := 10
+/// and accent preserved; ṛ/ḷ → ār/āl (ā with `rapara`). Vṛddhi of ai/au
+/// returns them unchanged. Bytes outside the phoneme table are rejected.
+pub fn vrddhi(b: u8) -> Result<TransformResult, SlbcError> {
+    let s = require_grade_operand(b)?;
     let accent = svara_a(b);
-    let result = (0b10 << 6) | (accent << 4) | (s << 2) | 0b01;
-    Ok(make_svara_result(b, result, "guṇa"))
+    match s {
+        0b00 => Ok(make_svara_result(b, (0b10 << 6) | (accent << 4), "vṛddhi")),
+        0b11 => Ok(rapara_result(b, (0b10 << 6) | (accent << 4), "vṛddhi")),
+        _ => {
+            let result = (0b10 << 6) | (accent << 4) | (s << 2) | 0b10;
+            Ok(make_svara_result(b, result, "vṛddhi"))
+        }
+    }
 }
 
-/// Vṛddhi: set G := 10, Q := 10 (dīrgha). Accent preserved.
-pub fn vrddhi(b: u8) -> Result<TransformResult, String> {
+/// Guṇa and vṛddhi take only the §4.1 vowels (any accent, or pluta); returns
+/// the S field.
+fn require_grade_operand(b: u8) -> Result<u8, SlbcError> {
     if !is_svara(b) {
-        return Err(format!("0x{:02X} is not a svara", b));
+        return Err(SlbcError::invalid(format_args!(
+            "0x{:02X} is not a svara",
+            b
+        )));
     }
-    let s = svara_s(b);
-    if s == 0b00 {
-        // a → ā (vṛddhi of a is ā in the a-series)
-        let accent = svara_a(b);
-        let result = (0b10 << 6) | (accent << 4) | 0b10; // Q=10, A=accent, S=00(a), G=10(vṛddhi)
-        return Ok(make_svara_result(b, result, "vṛddhi"));
+    if !crate::codespace::is_phonemic_svara(b) {
+        return Err(SlbcError::invalid(format_args!(
+            "0x{:02X} is not a phonemic svara",
+            b
+        )));
     }
-    let accent = svara_a(b);
-    let result = (0b10 << 6) | (accent << 4) | (s << 2) | 0b10;
-    Ok(make_svara_result(b, result, "vṛddhi"))
+    Ok(svara_s(b))
+}
+
+/// Guṇa/vṛddhi of ṛ or ḷ: the a-series vowel followed by r or l.
+fn rapara_result(input: u8, vowel: u8, op: &'static str) -> TransformResult {
+    let liquid = if svara_g(input) == 0b11 { 0x34 } else { 0x33 };
+    let mut r = make_svara_result(input, vowel, op);
+    r.rapara = Some(liquid);
+    r.output_iast.push_str(crate::decoder::byte_to_iast(liquid));
+    r
 }
 
 /// Dīrgha: set Q := 10. Everything else preserved.
-pub fn dirgha(b: u8) -> Result<TransformResult, String> {
+pub fn dirgha(b: u8) -> Result<TransformResult, SlbcError> {
     if !is_svara(b) {
-        return Err(format!("0x{:02X} is not a svara", b));
+        return Err(SlbcError::invalid(format_args!(
+            "0x{:02X} is not a svara",
+            b
+        )));
     }
     let result = (b & 0b00_11_11_11) | (0b10 << 6);
     Ok(make_svara_result(b, result, "dīrgha"))
 }
 
 /// Hrasva: set Q := 01. Everything else preserved.
-pub fn hrasva(b: u8) -> Result<TransformResult, String> {
+pub fn hrasva(b: u8) -> Result<TransformResult, SlbcError> {
     if !is_svara(b) {
-        return Err(format!("0x{:02X} is not a svara", b));
+        return Err(SlbcError::invalid(format_args!(
+            "0x{:02X} is not a svara",
+            b
+        )));
     }
     let result = (b & 0b00_11_11_11) | (0b01 << 6);
     Ok(make_svara_result(b, result, "hrasva"))
 }
 
+/// Pluta: set Q := 11. Everything else preserved.
+pub fn pluta(b: u8) -> Result<TransformResult, SlbcError> {
+    if !is_svara(b) {
+        return Err(SlbcError::invalid(format_args!(
+            "0x{:02X} is not a svara",
+            b
+        )));
+    }
+    let result = (b & 0b00_11_11_11) | (0b11 << 6);
+    Ok(make_svara_result(b, result, "pluta"))
+}
+
+/// How a sandhi operation resolves the accents of its two operands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AccentPolicy {
+    /// Keep the accent of the first operand.
+    #[default]
+    KeepFirst,
+    /// Keep the accent of the second operand.
+    KeepSecond,
+    /// 8.2.5 ekādeśa udāttenodāttaḥ — a single substitute for an udātta is
+    /// udātta. Otherwise svarita outranks anudātta, which outranks neutral.
+    UdattaDominant,
+    /// Reject operands whose accents differ.
+    RequireEqual,
+}
+
+impl AccentPolicy {
+    /// Short name, as accepted by the CLI `--accent` flag.
+    pub fn name(&self) -> &'static str {
+        match self {
+            AccentPolicy::KeepFirst => "first",
+            AccentPolicy::KeepSecond => "second",
+            AccentPolicy::UdattaDominant => "udatta",
+            AccentPolicy::RequireEqual => "equal",
+        }
+    }
+
+    /// Parse a policy from its short name.
+    pub fn from_name(name: &str) -> Option<AccentPolicy> {
+        match name {
+            "first" => Some(AccentPolicy::KeepFirst),
+            "second" => Some(AccentPolicy::KeepSecond),
+            "udatta" => Some(AccentPolicy::UdattaDominant),
+            "equal" => Some(AccentPolicy::RequireEqual),
+            _ => None,
+        }
+    }
+
+    /// Resolve two A-field values to one.
+    fn resolve(&self, a: u8, b: u8) -> Result<u8, SlbcError> {
+        match self {
+            AccentPolicy::KeepFirst => Ok(a),
+            AccentPolicy::KeepSecond => Ok(b),
+            AccentPolicy::UdattaDominant => {
+                // udātta (01) > svarita (11) > anudātta (10) > neutral (00)
+                let rank = |x: u8| match x {
+                    0b01 => 3,
+                    0b11 => 2,
+                    0b10 => 1,
+                    _ => 0,
+                };
+                Ok(if rank(a) >= rank(b) { a } else { b })
+            }
+            AccentPolicy::RequireEqual => {
+                if a == b {
+                    Ok(a)
+                } else {
+                    Err(SlbcError::invalid(format_args!(
+                        "operand accents differ (A={:02b} vs A={:02b})",
+                        a, b
+                    )))
+                }
+            }
+        }
+    }
+}
+
+/// Options shared by the two-operand sandhi operations.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SandhiOptions {
+    /// Accent resolution for the merged vowel.
+    pub accent: AccentPolicy,
+    /// Enforce the Pāṇinian operand domain (quantity and grade), not just
+    /// series equality.
+    pub strict: bool,
+}
+
+/// The result of a two-operand sandhi operation, recording how it was applied.
+#[derive(Debug)]
+pub struct SandhiResult {
+    pub operands: [u8; 2],
+    pub transform: TransformResult,
+    /// Governing sūtra.
+    pub rule: &'static str,
+    /// The accent policy used to produce the output.
+    pub accent_policy: AccentPolicy,
+}
+
 /// Savarṇa-dīrgha: if two svaras share the same series, produce dīrgha.
-pub fn savarna_dirgha(a: u8, b: u8) -> Result<TransformResult, String> {
+///
+/// Lenient legacy behavior: only series equality is checked, and the first
+/// operand's accent is kept. See [`savarna_dirgha_with`].
+pub fn savarna_dirgha(a: u8, b: u8) -> Result<TransformResult, SlbcError> {
+    savarna_dirgha_with(a, b, &SandhiOptions::default()).map(|r| r.transform)
+}
+
+/// Savarṇa-dīrgha (6.1.101 akaḥ savarṇe dīrghaḥ) with configurable validation
+/// and accent resolution.
+///
+/// In strict mode both operands must be ak vowels — śuddha a/i/u/ṛ or the
+/// special-grade ḷ — and neither may be pluta (pluta is exempt from sandhi,
+/// 6.1.125 plutapragṛhyā aci nityam).
+pub fn savarna_dirgha_with(a: u8, b: u8, opts: &SandhiOptions) -> Result<SandhiResult, SlbcError> {
     if !is_svara(a) || !is_svara(b) {
-        return Err("both inputs must be svaras".into());
+        return Err(SlbcError::invalid("both inputs must be svaras"));
     }
     if svara_s(a) != svara_s(b) {
-        return Err("svaras are not savarṇa (different series)".into());
+        return Err(SlbcError::invalid(
+            "svaras are not savarṇa (different series)",
+        ));
     }
-    // Result: dīrgha of the series, preserving accent of first
-    let accent = svara_a(a);
+    if opts.strict {
+        for x in [a, b] {
+            if svara_q(x) == 0b11 {
+                return Err(SlbcError::invalid(format_args!(
+                    "0x{:02X} is pluta — pluta vowels do not undergo sandhi",
+                    x
+                )));
+            }
+            let g = svara_g(x);
+            let is_ak = g == 0b00 || (g == 0b11 && svara_s(x) == 0b11);
+            if !is_ak {
+                return Err(SlbcError::invalid(format_args!(
+                    "0x{:02X} is not an ak vowel — savarṇa-dīrgha needs śuddha operands",
+                    x
+                )));
+            }
+        }
+    }
+    // Result: dīrgha of the series, accent per policy
+    let accent = opts.accent.resolve(svara_a(a), svara_a(b))?;
     let s = svara_s(a);
     let result = (0b10 << 6) | (accent << 4) | (s << 2); // Q=10(dīrgha), A=accent, S=series, G=00(śuddha)
-    Ok(TransformResult {
-        input_byte: a,
-        output_byte: result,
-        operation: "savarṇa-dīrgha",
-        input_iast: format!(
-            "{} + {}",
-            crate::decoder::byte_to_iast(a),
-            crate::decoder::byte_to_iast(b)
-        ),
-        output_iast: crate::decoder::byte_to_iast(result).to_string(),
+    Ok(SandhiResult {
+        operands: [a, b],
+        transform: TransformResult {
+            input_byte: a,
+            output_byte: result,
+            rapara: None,
+            operation: "savarṇa-dīrgha",
+            input_iast: format!(
+                "{} + {}",
+                crate::decoder::byte_to_iast(a),
+                crate::decoder::byte_to_iast(b)
+            ),
+            output_iast: crate::decoder::byte_to_iast(result).to_string(),
+        },
+        rule: "6.1.101 akaḥ savarṇe dīrghaḥ",
+        accent_policy: opts.accent,
     })
 }
 
@@ -99,9 +291,10 @@ fn make_svara_result(input: u8, output: u8, op: &'static str) -> TransformResult
     TransformResult {
         input_byte: input,
         output_byte: output,
+        rapara: None,
         operation: op,
-        input_iast: crate::decoder::byte_to_iast(input).to_string(),
-        output_iast: crate::decoder::byte_to_iast(output).to_string(),
+        input_iast: crate::decoder::phoneme_to_iast(input),
+        output_iast: crate::decoder::phoneme_to_iast(output),
     }
 }
 
@@ -109,55 +302,90 @@ fn make_svara_result(input: u8, output: u8, op: &'static str) -> TransformResult
 //  Vyañjana Algebra (§5.2) — PLACE ∈ {0–4} only
 // ═══════════════════════════════════════════════
 
-fn require_varga(b: u8, op: &str) -> Result<(), String> {
+fn require_varga(b: u8, op: &str) -> Result<(), SlbcError> {
     if !is_varga(b) {
-        return Err(format!(
+        return Err(SlbcError::invalid(format_args!(
             "0x{:02X} is not a varga consonant — {} is defined only for PLACE ∈ {{0–4}}",
             b, op
-        ));
+        )));
     }
     Ok(())
 }
 
 /// Jaśtva: COL := 010 (voiced unaspirated).
-pub fn jastva(b: u8) -> Result<TransformResult, String> {
+pub fn jastva(b: u8) -> Result<TransformResult, SlbcError> {
     require_varga(b, "jaśtva")?;
     let result = (b & 0b11_111_000) | 0b010;
     Ok(make_vyanjana_result(b, result, "jaśtva"))
 }
 
 /// Toggle voice: COL ^= 010.
-pub fn toggle_voice(b: u8) -> Result<TransformResult, String> {
+pub fn toggle_voice(b: u8) -> Result<TransformResult, SlbcError> {
     require_varga(b, "toggle voice")?;
     let result = b ^ 0b010;
     Ok(make_vyanjana_result(b, result, "toggle voice"))
 }
 
 /// Toggle aspiration: COL ^= 001.
-pub fn toggle_aspiration(b: u8) -> Result<TransformResult, String> {
+pub fn toggle_aspiration(b: u8) -> Result<TransformResult, SlbcError> {
     require_varga(b, "toggle aspiration")?;
     let result = b ^ 0b001;
     Ok(make_vyanjana_result(b, result, "toggle aspiration"))
 }
 
 /// Make nasal: COL := 100.
-pub fn make_nasal(b: u8) -> Result<TransformResult, String> {
+pub fn make_nasal(b: u8) -> Result<TransformResult, SlbcError> {
     require_varga(b, "make nasal")?;
     let result = (b & 0b11_111_000) | 0b100;
     Ok(make_vyanjana_result(b, result, "make nasal"))
 }
 
 /// Homorganic nasal: copy PLACE from target, COL := 100.
-pub fn homorganic_nasal(target: u8) -> Result<TransformResult, String> {
+pub fn homorganic_nasal(target: u8) -> Result<TransformResult, SlbcError> {
     require_varga(target, "homorganic nasal")?;
     let result = (target & 0b11_111_000) | 0b100;
     Ok(make_vyanjana_result(target, result, "homorganic nasal"))
 }
 
+/// Nasal assimilation: the form a nasal or anusvāra takes before the
+/// following varga consonant (8.4.58 anusvārasya yayi parasavarṇaḥ).
+///
+/// Anusvāra becomes the nasal of the following consonant's varga. A varga
+/// nasal does so before a stop and is kept before another nasal (n + m in
+/// sanmati). Consonants outside the vargas are rejected.
+pub fn assimilate_nasal(nasal: u8, following: u8) -> Result<TransformResult, SlbcError> {
+    let is_nasal = (is_varga(nasal) && column(nasal) == 4) || nasal == 0x3A;
+    if !is_nasal {
+        return Err(SlbcError::invalid(format_args!(
+            "0x{:02X} is not a nasal or anusvāra",
+            nasal
+        )));
+    }
+    require_varga(following, "nasal assimilation")?;
+    let result = if nasal != 0x3A && column(following) == 4 {
+        nasal
+    } else {
+        (following & 0b11_111_000) | 0b100
+    };
+    Ok(TransformResult {
+        input_byte: nasal,
+        output_byte: result,
+        rapara: None,
+        operation: "nasal assimilation",
+        input_iast: format!(
+            "{} + {}",
+            crate::decoder::byte_to_iast(nasal),
+            crate::decoder::byte_to_iast(following)
+        ),
+        output_iast: crate::decoder::byte_to_iast(result).to_string(),
+    })
+}
+
 fn make_vyanjana_result(input: u8, output: u8, op: &'static str) -> TransformResult {
     TransformResult {
         input_byte: input,
         output_byte: output,
+        rapara: None,
         operation: op,
         input_iast: crate::decoder::byte_to_iast(input).to_string(),
         output_iast: crate::decoder::byte_to_iast(output).to_string(),
@@ -169,17 +397,23 @@ fn make_vyanjana_result(input: u8, output: u8, op: &'static str) -> TransformRes
 // ═══════════════════════════════════════════════
 
 /// Sonorant → svara (saṃprasāraṇa direction).
-pub fn samprasarana_to_svara(b: u8) -> Result<TransformResult, String> {
+pub fn samprasarana_to_svara(b: u8) -> Result<TransformResult, SlbcError> {
     let result = match b {
         0x31 => 0x44, // ya → i
         0x32 => 0x48, // va → u
         0x33 => 0x4C, // ra → ṛ
         0x34 => 0x4F, // la → ḷ  (special case)
-        _ => return Err(format!("0x{:02X} is not a sonorant (ya/va/ra/la)", b)),
+        _ => {
+            return Err(SlbcError::invalid(format_args!(
+                "0x{:02X} is not a sonorant (ya/va/ra/la)",
+                b
+            )))
+        }
     };
     Ok(TransformResult {
         input_byte: b,
         output_byte: result,
+        rapara: None,
         operation: "saṃprasāraṇa (→svara)",
         input_iast: crate::decoder::byte_to_iast(b).to_string(),
         output_iast: crate::decoder::byte_to_iast(result).to_string(),
@@ -187,23 +421,297 @@ pub fn samprasarana_to_svara(b: u8) -> Result<TransformResult, String> {
 }
 
 /// Svara → sonorant (reverse saṃprasāraṇa).
-pub fn samprasarana_to_sonorant(b: u8) -> Result<TransformResult, String> {
+pub fn samprasarana_to_sonorant(b: u8) -> Result<TransformResult, SlbcError> {
     let result = match b {
         0x44 => 0x31, // i → ya
         0x48 => 0x32, // u → va
         0x4C => 0x33, // ṛ → ra
         0x4F => 0x34, // ḷ → la  (special case)
-        _ => return Err(format!("0x{:02X} is not a saṃprasāraṇa-eligible svara", b)),
+        _ => {
+            return Err(SlbcError::invalid(format_args!(
+                "0x{:02X} is not a saṃprasāraṇa-eligible svara",
+                b
+            )))
+        }
     };
     Ok(TransformResult {
         input_byte: b,
         output_byte: result,
+        rapara: None,
         operation: "saṃprasāraṇa (→sonorant)",
         input_iast: crate::decoder::byte_to_iast(b).to_string(),
         output_iast: crate::decoder::byte_to_iast(result).to_string(),
     })
 }
 
+// ═══════════════════════════════════════════════
+//  Payload passes
+// ═══════════════════════════════════════════════
+
+/// Apply `assimilate_nasal` to every nasal or anusvāra directly followed by
+/// a varga consonant inside a pada (saṅkalpa for saṃkalpa, aṅka for anka).
+/// Pada boundaries are left alone: final m before a stop in the next word is
+/// optional sandhi (8.4.59). Returns the payload and the number of bytes
+/// changed.
+pub fn assimilate_nasals(data: &[u8]) -> Result<(Vec<u8>, usize), SlbcError> {
+    let mut out = data.to_vec();
+    let mut changed = 0;
+    for (start, pada) in crate::lang::padas(data)? {
+        let mut j = 0;
+        while j + 1 < pada.len() {
+            match pada[j] {
+                // The code byte, or a long extension's payload, is not a
+                // phoneme
+                META_EXT => j += codepage::ext_len(pada, j)?,
+                META_START => {
+                    j += pada[j..]
+                        .iter()
+                        .position(|&b| b == META_END)
+                        .map_or(pada.len() - j, |p| p + 1)
+                }
+                // Dictionary indices may encode as any byte
+                DICT_REF => break,
+                b => {
+                    if let Ok(r) = assimilate_nasal(b, pada[j + 1]) {
+                        if r.output_byte != b {
+                            out[start + 1 + j] = r.output_byte;
+                            changed += 1;
+                        }
+                    }
+                    j += 1;
+                }
+            }
+        }
+    }
+    Ok((out, changed))
+}
+
+/// Write each avagraha that stands for an a elided after e or o (6.1.109
+/// eṅaḥ padāntād ati: te 'pi for te api) as that a, so the text reads
+/// without sandhi. Only the avagraha byte changes: padas, spacing, and the
+/// rest of the lipi layer stay as they were, so `apply_elision` undoes it.
+/// An avagraha after anything else (a prolonged vowel) is left alone.
+/// Returns the payload and the number of bytes changed.
+pub fn restore_elision(data: &[u8]) -> Result<(Vec<u8>, usize), SlbcError> {
+    rewrite_elision(data, AVAGRAHA, 0x40)
+}
+
+/// The inverse of `restore_elision`: elide an unaccented a after e or o,
+/// at the start of a pada or inside one, as an avagraha.
+pub fn apply_elision(data: &[u8]) -> Result<(Vec<u8>, usize), SlbcError> {
+    rewrite_elision(data, 0x40, AVAGRAHA)
+}
+
+/// Replace `from` with `to` wherever it directly follows an e or o, past
+/// nothing but pada boundaries, spacing, and annotations.
+fn rewrite_elision(data: &[u8], from: u8, to: u8) -> Result<(Vec<u8>, usize), SlbcError> {
+    let mut out = data.to_vec();
+    let mut changed = 0;
+    let mut after_eng = false;
+    let mut i = 0;
+    while i < data.len() {
+        let b = data[i];
+        let len = unit_len(data, i)?;
+        if b == from && after_eng {
+            out[i] = to;
+            changed += 1;
+        }
+        after_eng = match b {
+            _ if b == from => false,
+            // e and o, any accent
+            b if is_svara(b) => matches!(b & 0b11_00_11_11, 0x85 | 0x89),
+            // Boundaries, spacing, annotations and case marks
+            PADA_START | PADA_END | SPACE | LINE_BREAK | META_START | CAPITAL | UPPERCASE
+            | MORPH => after_eng,
+            META_EXT => after_eng && codepage::is_samasa(data, i),
+            _ => false,
+        };
+        i += len;
+    }
+    Ok((out, changed))
+}
+
+/// Write each visarga before k or kh as jihvāmūlīya (ẖ) and before p or ph
+/// as upadhmānīya (ḫ), as the śikṣās prescribe (8.3.37 kupvoḥ ≍ka≍pau ca):
+/// rāmaẖ karoti, tataḫ param. The next phoneme decides, across a word
+/// boundary too, but not across a daṇḍa or a numeral. Returns the payload
+/// and the number of bytes changed.
+pub fn visarga_allophones(data: &[u8]) -> Result<(Vec<u8>, usize), SlbcError> {
+    let mut out = data.to_vec();
+    let mut changed = 0;
+    // A visarga awaiting the phoneme after it
+    let mut visarga = None;
+    let mut i = 0;
+    while i < data.len() {
+        let b = data[i];
+        match b {
+            // Boundaries, spacing, annotations and case marks
+            PADA_START | PADA_END | SPACE | LINE_BREAK | META_START | CAPITAL | UPPERCASE
+            | MORPH => {}
+            META_EXT if codepage::is_samasa(data, i) => {}
+            _ => {
+                let allophone = match b {
+                    0x00 | 0x01 => Some(0x3B),
+                    0x20 | 0x21 => Some(0x3C),
+                    _ => None,
+                };
+                if let (Some(at), Some(allophone)) = (visarga.take(), allophone) {
+                    out[at] = allophone;
+                    changed += 1;
+                }
+                if b == 0x39 {
+                    visarga = Some(i);
+                }
+            }
+        }
+        i += unit_len(data, i)?;
+    }
+    Ok((out, changed))
+}
+
+/// Length of the unit at `i`: a numeral span, dictionary reference,
+/// extension, or META block, or else one byte.
+fn unit_len(data: &[u8], i: usize) -> Result<usize, SlbcError> {
+    Ok(match data[i] {
+        SANKHYA_START => numeral::decode_sankhya(data, i)?.1,
+        NUM => numeral::decode_num(data, i)?.1,
+        // The index that follows may encode as any byte
+        DICT_REF => {
+            let (_, consumed) = container::read_uleb128(&data[i + 1..])
+                .map_err(|e| e.context(i + 1, "DICT_REF index error"))?;
+            1 + consumed
+        }
+        META_EXT => codepage::ext_len(data, i)?,
+        META_START => data[i..]
+            .iter()
+            .position(|&b| b == META_END)
+            .map_or(data.len() - i, |p| p + 1),
+        _ => 1,
+    })
+}
+
+// ═══════════════════════════════════════════════
+//  Coverage Matrix
+// ═══════════════════════════════════════════════
+
+/// A single-byte transform function.
+pub type UnaryOp = fn(u8) -> Result<TransformResult, SlbcError>;
+
+/// Every single-byte transform, keyed by its CLI operation name.
+pub const UNARY_OPS: [(&str, UnaryOp); 12] = [
+    ("guna", guna),
+    ("vrddhi", vrddhi),
+    ("dirgha", dirgha),
+    ("hrasva", hrasva),
+    ("pluta", pluta),
+    ("jastva", jastva),
+    ("toggle-voice", toggle_voice),
+    ("toggle-aspiration", toggle_aspiration),
+    ("nasal", make_nasal),
+    ("homorganic-nasal", homorganic_nasal),
+    ("samprasarana-svara", samprasarana_to_svara),
+    ("samprasarana-sonorant", samprasarana_to_sonorant),
+];
+
+/// Look up a single-byte transform by its CLI operation name.
+pub fn unary_op(name: &str) -> Option<UnaryOp> {
+    UNARY_OPS
+        .iter()
+        .find(|(op_name, _)| *op_name == name)
+        .map(|(_, op)| *op)
+}
+
+/// One row of the coverage matrix: the operand bytes and the result.
+#[derive(Debug)]
+pub struct MatrixEntry {
+    pub inputs: Vec<u8>,
+    pub result: TransformResult,
+}
+
+/// Apply every defined transform to every phoneme byte it accepts.
+///
+/// Unary operations are tried on every byte that names a phoneme (any accent);
+/// rejected inputs are omitted. Savarṇa-dīrgha is applied to every ordered pair
+/// of neutral-accent svaras (A=00) — accent handling is already covered by the
+/// unary rows — and nasal assimilation to every ordered pair of consonants.
+/// An output IAST of "?" marks a result outside the phoneme table.
+pub fn coverage_matrix() -> Vec<MatrixEntry> {
+    let phonemes: Vec<u8> = (0..=255u8)
+        .filter(|&b| crate::decoder::byte_to_iast(b) != "?")
+        .collect();
+    let mut rows = Vec::new();
+
+    for (_, op) in UNARY_OPS.iter() {
+        for &b in &phonemes {
+            if let Ok(result) = op(b) {
+                rows.push(MatrixEntry {
+                    inputs: vec![b],
+                    result,
+                });
+            }
+        }
+    }
+
+    let neutral: Vec<u8> = phonemes
+        .iter()
+        .copied()
+        .filter(|&b| is_svara(b) && svara_a(b) == 0)
+        .collect();
+    for &a in &neutral {
+        for &b in &neutral {
+            if let Ok(result) = savarna_dirgha(a, b) {
+                rows.push(MatrixEntry {
+                    inputs: vec![a, b],
+                    result,
+                });
+            }
+        }
+    }
+
+    let consonants: Vec<u8> = phonemes
+        .iter()
+        .copied()
+        .filter(|&b| is_vyanjana(b))
+        .collect();
+    for &a in &consonants {
+        for &b in &consonants {
+            if let Ok(result) = assimilate_nasal(a, b) {
+                rows.push(MatrixEntry {
+                    inputs: vec![a, b],
+                    result,
+                });
+            }
+        }
+    }
+
+    rows
+}
+
+/// Format the coverage matrix as a tab-separated table with a header row.
+///
+/// The output is stable and is checked in as a golden file, so any change to
+/// the bit-level algebra shows up as a diff.
+pub fn format_matrix(rows: &[MatrixEntry]) -> String {
+    let mut out = String::from("operation\tinput\tinput_iast\toutput\toutput_iast\n");
+    for row in rows {
+        let inputs: Vec<String> = row.inputs.iter().map(|b| format!("0x{:02X}", b)).collect();
+        out.push_str(&format!(
+            "{}\t{}\t{}\t{}\t{}\n",
+            row.result.operation,
+            inputs.join("+"),
+            row.result.input_iast,
+            row.result
+                .output_bytes()
+                .iter()
+                .map(|b| format!("0x{:02X}", b))
+                .collect::<Vec<_>>()
+                .join("+"),
+            row.result.output_iast
+        ));
+    }
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -220,6 +728,31 @@ mod tests {
         assert_eq!(r.output_byte, 0x86);
     }
 
+    #[test]
+    fn test_guna_vrddhi_edges() {
+        assert_eq!(guna(0x4C).unwrap().output_bytes(), vec![0x40, 0x33]); // ṛ → ar
+        assert_eq!(vrddhi(0x8F).unwrap().output_bytes(), vec![0x80, 0x34]); // ḹ → āl
+        assert_eq!(guna(0x5F).unwrap().output_iast, "al");
+        assert_eq!(vrddhi(0x50).unwrap().output_byte, 0x90); // á → ā́
+        assert_eq!(guna(0x85).unwrap().output_byte, 0x85); // e stays e
+        assert_eq!(vrddhi(0x8A).unwrap().output_byte, 0x8A); // au stays au
+        assert!(guna(0x86).is_err()); // ai has no guṇa
+        assert!(guna(0x8D).is_err()); // not a phoneme
+
+        // Exhaustively: every result is a phoneme with the operand's accent,
+        // and only phonemic svaras are accepted
+        for b in 0x40..=0xFFu8 {
+            for op in [guna, vrddhi] {
+                let Ok(r) = op(b) else { continue };
+                assert!(crate::codespace::is_phonemic_svara(b), "0x{:02X}", b);
+                assert!(crate::codespace::is_phonemic_svara(r.output_byte));
+                assert_eq!(svara_a(r.output_byte), svara_a(b));
+                assert_eq!(r.rapara.is_some(), svara_s(b) == 0b11);
+                assert!(!r.output_iast.contains('?'));
+            }
+        }
+    }
+
     #[test]
     fn test_jastva_ka_to_ga() {
         let r = jastva(0x00).unwrap(); // ka → ga
@@ -243,6 +776,87 @@ mod tests {
         assert_eq!(r.output_byte, 0x4F); // la → ḷ (special case)
     }
 
+    #[test]
+    fn test_assimilate_nasal() {
+        assert_eq!(assimilate_nasal(0x3A, 0x00).unwrap().output_byte, 0x04); // ṃ + k → ṅ
+        assert_eq!(assimilate_nasal(0x1C, 0x10).unwrap().output_byte, 0x14); // n + ṭ → ṇ
+        assert_eq!(assimilate_nasal(0x3A, 0x24).unwrap().output_byte, 0x24); // ṃ + m → m
+        assert_eq!(assimilate_nasal(0x1C, 0x24).unwrap().output_byte, 0x1C); // n + m kept
+        assert!(assimilate_nasal(0x1C, 0x31).is_err()); // y is not varga
+        assert!(assimilate_nasal(0x18, 0x00).is_err()); // t is not a nasal
+
+        let data = crate::encoder::encode_iast("saṃkalpa anka saṃyama tam ca").unwrap();
+        let (fixed, n) = assimilate_nasals(&data).unwrap();
+        assert_eq!(n, 2);
+        assert_eq!(
+            crate::decoder::decode_phon(&fixed, crate::decoder::Script::Iast).unwrap(),
+            "saṅkalpa aṅka saṃyama tam ca"
+        );
+        // Extension code bytes are not nasals
+        let ext = [PADA_START, META_EXT, 0x0C, 0x00, 0x40, PADA_END];
+        assert_eq!(assimilate_nasals(&ext).unwrap(), (ext.to_vec(), 0));
+    }
+
+    #[test]
+    fn test_elision() {
+        use crate::decoder::{decode_phon, Script};
+        use crate::encoder::encode_iast;
+
+        let elided = encode_iast("te 'pi so'haṃ rāmo 'sti").unwrap();
+        let (restored, n) = restore_elision(&elided).unwrap();
+        assert_eq!(n, 3);
+        assert_eq!(restored, encode_iast("te api soahaṃ rāmo asti").unwrap());
+        assert_eq!(
+            decode_phon(&restored, Script::Devanagari).unwrap(),
+            "ते अपि सोअहं रामो अस्ति"
+        );
+        let opts = crate::decoder::DecodeOptions {
+            restore_elision: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            crate::decoder::decode_phon_with(&elided, Script::Iast, &opts).unwrap(),
+            "te api soahaṃ rāmo asti"
+        );
+        assert_eq!(apply_elision(&restored).unwrap(), (elided, 3));
+
+        // Only after e or o; a case mark is passed over
+        let kept = encode_iast("rāma api").unwrap();
+        assert_eq!(restore_elision(&kept).unwrap().1, 0);
+        assert_eq!(apply_elision(&kept).unwrap().1, 0);
+        let preserve = crate::encoder::EncodeOptions {
+            fold_case: crate::encoder::CaseFolding::Preserve,
+            ..Default::default()
+        };
+        let capital = crate::encoder::encode_iast_with("te Api", &preserve).unwrap();
+        assert_eq!(apply_elision(&capital).unwrap().1, 1);
+    }
+
+    #[test]
+    fn test_visarga_allophones() {
+        use crate::decoder::{decode_phon, decode_phon_with, DecodeOptions, Script};
+        use crate::encoder::encode_iast;
+
+        let data = encode_iast("rāmaḥ karoti tataḥ phalam | naraḥ+khanati vanaḥ 12 puraḥ").unwrap();
+        let (marked, n) = visarga_allophones(&data).unwrap();
+        assert_eq!(n, 3);
+        assert_eq!(
+            decode_phon(&marked, Script::Iast).unwrap(),
+            "rāmaẖ karoti tataḫ phalam | naraẖkhanati vanaḥ 12 puraḥ"
+        );
+        let opts = DecodeOptions {
+            visarga_allophones: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            decode_phon_with(&data, Script::Devanagari, &opts).unwrap(),
+            "रामᳵ करोति ततᳶ फलम् । नरᳵखनति वनः १२ पुरः"
+        );
+        // Not across a daṇḍa, nor before anything but k, kh, p, ph
+        let kept = encode_iast("rāmaḥ | karoti naraḥ gacchati").unwrap();
+        assert_eq!(visarga_allophones(&kept).unwrap(), (kept.clone(), 0));
+    }
+
     #[test]
     fn test_toggle_voice() {
         let r = toggle_voice(0x00).unwrap(); // ka ↔ ga
@@ -260,4 +874,63 @@ mod tests {
         assert_eq!(r.output_byte, 0x95);
         assert_eq!(svara_a(r.output_byte), 0b01); // accent preserved
     }
+
+    #[test]
+    fn test_savarna_dirgha_keeps_first_accent() {
+        // udātta a (0x50) + anudātta a (0x60) → udātta ā
+        let r = savarna_dirgha(0x50, 0x60).unwrap();
+        assert_eq!(r.output_byte, 0x90);
+    }
+
+    #[test]
+    fn test_savarna_dirgha_accent_policies() {
+        let with = |accent| SandhiOptions {
+            accent,
+            strict: false,
+        };
+        // anudātta i (0x64) + udātta i (0x54)
+        let r = savarna_dirgha_with(0x64, 0x54, &with(AccentPolicy::KeepSecond)).unwrap();
+        assert_eq!(r.transform.output_byte, 0x94);
+        assert_eq!(r.accent_policy, AccentPolicy::KeepSecond);
+
+        let r = savarna_dirgha_with(0x64, 0x54, &with(AccentPolicy::UdattaDominant)).unwrap();
+        assert_eq!(svara_a(r.transform.output_byte), 0b01);
+
+        assert!(savarna_dirgha_with(0x64, 0x54, &with(AccentPolicy::RequireEqual)).is_err());
+    }
+
+    #[test]
+    fn test_savarna_dirgha_strict_rejects_guna_and_pluta() {
+        let strict = SandhiOptions {
+            accent: AccentPolicy::KeepFirst,
+            strict: true,
+        };
+        assert!(savarna_dirgha_with(0x85, 0x85, &strict).is_err()); // e + e
+        assert!(savarna_dirgha_with(0xC0, 0x40, &strict).is_err()); // a3 + a
+        assert!(savarna_dirgha(0x85, 0x85).is_ok()); // lenient legacy path
+        let r = savarna_dirgha_with(0x4F, 0x4C, &strict).unwrap(); // ḷ + ṛ
+        assert_eq!(r.transform.output_byte, 0x8C);
+    }
+
+    #[test]
+    fn test_pluta() {
+        let r = pluta(0x50).unwrap(); // á
+        assert_eq!(r.output_byte, 0xD0);
+        assert_eq!(r.output_iast, "ā3");
+        assert_eq!(hrasva(0xD0).unwrap().output_byte, 0x50);
+        assert!(pluta(0x00).is_err());
+    }
+
+    #[test]
+    fn test_unary_op_lookup() {
+        let op = unary_op("jastva").unwrap();
+        assert_eq!(op(0x00).unwrap().output_byte, 0x02);
+        assert!(unary_op("bogus").is_none());
+    }
+
+    #[test]
+    fn test_coverage_matrix_golden() {
+        let table = format_matrix(&coverage_matrix());
+        assert_eq!(table, include_str!("../testdata/transform_matrix.tsv"));
+    }
 }
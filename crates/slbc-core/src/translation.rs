@@ -0,0 +1,331 @@
+//! Verse-aligned translations (ANUV chunk, 0x08).
+//!
+//! An ANUV chunk pairs verses of one PHON chunk (see `frame::verses`) with
+//! their translation into one language:
+//! ```text
+//! PHON chunk ordinal (ULEB128) | language tag (ULEB128 length + UTF-8)
+//! pair count (ULEB128)
+//! per pair: verse index (ULEB128, delta from the previous pair's)
+//!           text (ULEB128 length + UTF-8)
+//! ```
+//! Verses without a translation are left out. A container MAY carry one
+//! ANUV chunk per language.
+//!
+//! `pair` builds the pairs from a translation text file: segments are
+//! separated by blank lines (one per line if the file has none). When every
+//! segment starts with a number ("11.", "2.11"), segments are matched to
+//! verses by the number before each verse's closing double daṇḍa;
+//! otherwise they are paired in order. A verse holding only a number
+//! ("… || 11 ||") labels the verse before it and is not paired itself.
+
+use crate::container::{self, Chunk};
+use crate::decoder::{self, Script};
+use crate::error::SlbcError;
+use crate::frame;
+use crate::types::*;
+
+/// One language's translation of a PHON chunk's verses.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Translation {
+    /// Which PHON chunk (0-based, among PHON chunks) the verses belong to.
+    pub phon_chunk: u32,
+    /// Language tag (e.g. "en").
+    pub language: String,
+    /// (verse index, text), ascending by verse.
+    pub verses: Vec<(u32, String)>,
+}
+
+fn read_field(data: &[u8], pos: &mut usize, what: &str) -> Result<u64, SlbcError> {
+    let (v, consumed) = container::read_uleb128(&data[(*pos).min(data.len())..])
+        .map_err(|e| e.context(*pos, format_args!("translation {} error", what)))?;
+    *pos += consumed;
+    Ok(v)
+}
+
+fn read_text(data: &[u8], pos: &mut usize, what: &str) -> Result<String, SlbcError> {
+    let len = read_field(data, pos, what)? as usize;
+    let bytes = data.get(*pos..*pos + len).ok_or_else(|| {
+        SlbcError::truncated(*pos, format_args!("truncated translation {}", what))
+    })?;
+    *pos += len;
+    String::from_utf8(bytes.to_vec()).map_err(|_| {
+        SlbcError::malformed(
+            *pos - len,
+            format_args!("translation {} is not UTF-8", what),
+        )
+    })
+}
+
+fn write_text(out: &mut Vec<u8>, text: &str) {
+    container::write_uleb128(out, text.len() as u64);
+    out.extend_from_slice(text.as_bytes());
+}
+
+impl Translation {
+    /// Build the ANUV chunk.
+    pub fn to_chunk(&self) -> Chunk {
+        let mut payload = Vec::new();
+        container::write_uleb128(&mut payload, self.phon_chunk as u64);
+        write_text(&mut payload, &self.language);
+        container::write_uleb128(&mut payload, self.verses.len() as u64);
+        let mut prev = 0;
+        for (verse, text) in &self.verses {
+            container::write_uleb128(&mut payload, (verse - prev) as u64);
+            write_text(&mut payload, text);
+            prev = *verse;
+        }
+        Chunk {
+            chunk_type: CHUNK_ANUV,
+            payload,
+        }
+    }
+
+    /// Parse an ANUV chunk payload.
+    pub fn from_chunk_payload(payload: &[u8]) -> Result<Self, SlbcError> {
+        let mut pos = 0;
+        let phon_chunk = read_field(payload, &mut pos, "chunk ordinal")? as u32;
+        let language = read_text(payload, &mut pos, "language tag")?;
+        let count = read_field(payload, &mut pos, "pair count")?;
+        let mut verses = Vec::with_capacity(count.min(1 << 16) as usize);
+        let mut prev = 0u32;
+        for _ in 0..count {
+            let field = pos;
+            let gap = read_field(payload, &mut pos, "verse index")?;
+            let verse = u32::try_from(gap)
+                .ok()
+                .and_then(|gap| prev.checked_add(gap))
+                .ok_or_else(|| {
+                    SlbcError::malformed(field, "translation verse index out of range")
+                })?;
+            verses.push((verse, read_text(payload, &mut pos, "text")?));
+            prev = verse;
+        }
+        if pos != payload.len() {
+            return Err(SlbcError::malformed(
+                pos,
+                "trailing bytes in translation chunk",
+            ));
+        }
+        Ok(Translation {
+            phon_chunk,
+            language,
+            verses,
+        })
+    }
+
+    /// Every translation among a container's chunks.
+    pub fn all(chunks: &[Chunk]) -> Result<Vec<Self>, SlbcError> {
+        chunks
+            .iter()
+            .filter(|c| c.chunk_type == CHUNK_ANUV)
+            .map(|c| Translation::from_chunk_payload(&c.payload))
+            .collect()
+    }
+}
+
+/// Translation segments: blank-line separated, or one per line if the
+/// text has no blank lines.
+pub fn segments(text: &str) -> Vec<String> {
+    let lines: Vec<&str> = text.lines().map(str::trim).collect();
+    if !lines.iter().any(|l| l.is_empty()) {
+        return lines.into_iter().map(String::from).collect();
+    }
+    lines
+        .split(|l| l.is_empty())
+        .filter(|para| !para.is_empty())
+        .map(|para| para.join(" "))
+        .collect()
+}
+
+/// The number a segment starts with: the last part of "2.11", "11." or
+/// "11)".
+fn segment_number(segment: &str) -> Option<u64> {
+    let label = segment.split_whitespace().next()?;
+    let label = label.trim_end_matches(['.', ')', ':']);
+    let last = label.rsplit('.').next()?;
+    if label.is_empty() || !label.chars().all(|c| c.is_ascii_digit() || c == '.') {
+        return None;
+    }
+    last.parse().ok()
+}
+
+/// The number just before a verse's closing double daṇḍa ("… 11 ||"), and
+/// whether that number is all the verse holds.
+fn verse_number(verse: &[u8]) -> Result<(Option<u64>, bool), SlbcError> {
+    let text = decoder::decode_phon(verse, Script::Iast)?;
+    let body = text.trim_end().trim_end_matches('|').trim_end();
+    let rest = body.trim_end_matches(|c: char| c.is_ascii_digit());
+    let number = body[rest.len()..].parse().ok();
+    Ok((number, number.is_some() && rest.trim().is_empty()))
+}
+
+/// Verses paired with a translation, and what was left over.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pairing {
+    pub translation: Translation,
+    /// Verse indices with no segment. Verses holding only a verse number
+    /// are not counted.
+    pub unmatched_verses: Vec<usize>,
+    /// Segment indices with no verse.
+    pub unmatched_segments: Vec<usize>,
+}
+
+/// Pair the verses of a PHON payload with the segments of a translation.
+pub fn pair(
+    payload: &[u8],
+    phon_chunk: u32,
+    text: &str,
+    language: &str,
+) -> Result<Pairing, SlbcError> {
+    let segments = segments(text);
+    let numbers: Option<Vec<u64>> = segments.iter().map(|s| segment_number(s)).collect();
+
+    // A number standing alone ("… || 11 ||") labels the verse before it
+    let mut verses: Vec<(usize, Option<u64>)> = Vec::new();
+    for (v, range) in frame::verses(payload)?.into_iter().enumerate() {
+        match verse_number(&payload[range])? {
+            (n, true) => match verses.last_mut() {
+                Some((_, label @ None)) => *label = n,
+                _ => verses.push((v, n)),
+            },
+            (n, false) => verses.push((v, n)),
+        }
+    }
+
+    let mut matched: Vec<Option<usize>> = vec![None; verses.len()];
+    match numbers {
+        Some(numbers) if !numbers.is_empty() => {
+            let mut used = vec![false; segments.len()];
+            for (slot, &(_, n)) in matched.iter_mut().zip(&verses) {
+                let Some(n) = n else {
+                    continue;
+                };
+                if let Some(s) = (0..segments.len()).find(|&s| !used[s] && numbers[s] == n) {
+                    used[s] = true;
+                    *slot = Some(s);
+                }
+            }
+        }
+        _ => {
+            for (s, slot) in matched.iter_mut().enumerate().take(segments.len()) {
+                *slot = Some(s);
+            }
+        }
+    }
+
+    let mut used = vec![false; segments.len()];
+    let mut pairs = Vec::new();
+    let mut unmatched_verses = Vec::new();
+    for (&(v, _), m) in verses.iter().zip(matched) {
+        match m {
+            Some(s) => {
+                used[s] = true;
+                pairs.push((v as u32, segments[s].clone()));
+            }
+            None => unmatched_verses.push(v),
+        }
+    }
+    Ok(Pairing {
+        translation: Translation {
+            phon_chunk,
+            language: language.to_string(),
+            verses: pairs,
+        },
+        unmatched_verses,
+        unmatched_segments: (0..segments.len()).filter(|&s| !used[s]).collect(),
+    })
+}
+
+/// A container with `translation` added before EOF, replacing any ANUV
+/// chunk for the same PHON chunk and language.
+pub fn attach(data: &[u8], translation: &Translation) -> Result<Vec<u8>, SlbcError> {
+    let (header, chunks) = container::parse_slbc(data)?;
+    let mut out = header.to_bytes_with(&header.ext_records()?)?;
+    for chunk in &chunks {
+        if chunk.chunk_type == CHUNK_ANUV {
+            let existing = Translation::from_chunk_payload(&chunk.payload)?;
+            if existing.phon_chunk == translation.phon_chunk
+                && existing.language == translation.language
+            {
+                continue;
+            }
+        }
+        if chunk.chunk_type == CHUNK_EOF {
+            let new = translation.to_chunk();
+            container::write_chunk(&mut out, new.chunk_type, &new.payload);
+        }
+        container::write_chunk(&mut out, chunk.chunk_type, &chunk.payload);
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoder;
+
+    const GAYATRI: &str =
+        "tat savitur vareṇyaṃ || 1 || bhargo devasya dhīmahi || 2 || dhiyo yo naḥ pracodayāt || 3 ||";
+
+    #[test]
+    fn test_chunk_roundtrip() {
+        let t = Translation {
+            phon_chunk: 0,
+            language: "en".into(),
+            verses: vec![(0, "That excellent".into()), (2, "who may inspire".into())],
+        };
+        let chunk = t.to_chunk();
+        assert_eq!(Translation::from_chunk_payload(&chunk.payload).unwrap(), t);
+        assert!(
+            Translation::from_chunk_payload(&chunk.payload[..chunk.payload.len() - 1]).is_err()
+        );
+
+        // Verse gaps that add up past u32::MAX
+        let mut payload = vec![0, 2, b'e', b'n', 2];
+        for gap in [u32::MAX as u64, 1] {
+            container::write_uleb128(&mut payload, gap);
+            write_text(&mut payload, "");
+        }
+        let e = Translation::from_chunk_payload(&payload).unwrap_err();
+        assert!(matches!(
+            e,
+            SlbcError::Malformed {
+                offset: Some(11),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_pair_by_number() {
+        let data = encoder::encode_iast(GAYATRI).unwrap();
+        let text = "3. who may inspire our thoughts\n\n1. That excellent\nof Savitṛ\n\n7. stray";
+        let p = pair(&data, 0, text, "en").unwrap();
+        assert_eq!(
+            p.translation.verses,
+            [
+                (0, "1. That excellent of Savitṛ".to_string()),
+                (4, "3. who may inspire our thoughts".to_string())
+            ]
+        );
+        assert_eq!(p.unmatched_verses, [2]);
+        assert_eq!(p.unmatched_segments, [2]);
+    }
+
+    #[test]
+    fn test_pair_in_order_and_attach() {
+        let data =
+            encoder::encode_iast("tat savitur vareṇyaṃ || bhargo devasya dhīmahi ||").unwrap();
+        let p = pair(&data, 0, "That excellent\nthe splendour\nextra", "en").unwrap();
+        assert_eq!(p.translation.verses.len(), 2);
+        assert_eq!(p.unmatched_segments, [2]);
+
+        let file = container::build_slbc(&data);
+        let once = attach(&file, &p.translation).unwrap();
+        let twice = attach(&once, &p.translation).unwrap();
+        assert_eq!(once, twice);
+        let (_, chunks) = container::parse_slbc(&once).unwrap();
+        assert_eq!(Translation::all(&chunks).unwrap(), [p.translation]);
+        assert_eq!(chunks.last().unwrap().chunk_type, CHUNK_EOF);
+    }
+}
@@ -12,19 +12,30 @@ pub const PHON_START: u8 = 0x16;
 pub const PHON_END: u8 = 0x1E;
 pub const PADA_START: u8 = 0x26;
 pub const PADA_END: u8 = 0x2E;
-// 0x36 reserved
+pub const DICT_REF: u8 = 0x36; // pada-dictionary reference (inside PADA, + ULEB128 index)
 pub const SANKHYA_START: u8 = 0x3E;
 
 // ── Lipi Control Bytes (COLUMN = 111) ──
 
-// 0x07 reserved
+pub const ILLEGIBLE: u8 = 0x07; // one illegible akṣara (editorial gap)
 pub const DANDA: u8 = 0x0F;
 pub const DOUBLE_DANDA: u8 = 0x17;
 pub const SPACE: u8 = 0x1F;
 pub const AVAGRAHA: u8 = 0x27;
 pub const NUM: u8 = 0x2F;
 pub const META_EXT: u8 = 0x37;
-// 0x3F reserved
+pub const LACUNA: u8 = 0x3F; // lacuna of unknown length (editorial gap)
+
+// ── Annotation Bytes (COLUMN = 101) ──
+
+pub const MORPH: u8 = 0x05; // morpheme seam inside a pada (§6.5)
+pub const CANDRABINDU: u8 = 0x0D; // preceding anusvāra written as candrabindu (§6.6)
+pub const PRANAVA: u8 = 0x15; // preceding oṃ written as the sacred symbol ॐ (§6.7)
+pub const YAMA: u8 = 0x1D; // nasal twin of the preceding stop, before a nasal (§6.8)
+pub const NASIKYA: u8 = 0x25; // nasal glide after h, before a nasal (§6.8)
+pub const CAPITAL: u8 = 0x2D; // pada written with an initial capital (§6.9)
+pub const UPPERCASE: u8 = 0x35; // pada written in capitals (§6.9)
+pub const LINE_BREAK: u8 = 0x3D; // line break of the source, between padas (§6.10)
 
 // ── Chunk Types (§7.3) ──
 
@@ -35,14 +46,166 @@ pub const CHUNK_META: u8 = 0x04;
 pub const CHUNK_DICT: u8 = 0x05;
 pub const CHUNK_IDX: u8 = 0x06;
 pub const CHUNK_ANVY: u8 = 0x07;
+pub const CHUNK_ANUV: u8 = 0x08;
 pub const CHUNK_EXT: u8 = 0x10;
 pub const CHUNK_EOF: u8 = 0xFF;
 
+/// A chunk type byte with its name. Readers keep types they don't know as
+/// `Unknown` rather than rejecting them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChunkType {
+    Phon,
+    Bha,
+    Lipi,
+    Meta,
+    Dict,
+    Idx,
+    Anvy,
+    Anuv,
+    Ext,
+    Eof,
+    Unknown(u8),
+}
+
+impl ChunkType {
+    /// Every registered type, in byte order.
+    pub const KNOWN: [ChunkType; 10] = [
+        ChunkType::Phon,
+        ChunkType::Bha,
+        ChunkType::Lipi,
+        ChunkType::Meta,
+        ChunkType::Dict,
+        ChunkType::Idx,
+        ChunkType::Anvy,
+        ChunkType::Anuv,
+        ChunkType::Ext,
+        ChunkType::Eof,
+    ];
+
+    pub fn byte(self) -> u8 {
+        match self {
+            ChunkType::Phon => CHUNK_PHON,
+            ChunkType::Bha => CHUNK_BHA,
+            ChunkType::Lipi => CHUNK_LIPI,
+            ChunkType::Meta => CHUNK_META,
+            ChunkType::Dict => CHUNK_DICT,
+            ChunkType::Idx => CHUNK_IDX,
+            ChunkType::Anvy => CHUNK_ANVY,
+            ChunkType::Anuv => CHUNK_ANUV,
+            ChunkType::Ext => CHUNK_EXT,
+            ChunkType::Eof => CHUNK_EOF,
+            ChunkType::Unknown(b) => b,
+        }
+    }
+
+    /// Name as written in the spec (e.g. "PHON"); `None` for unknown types.
+    pub fn name(self) -> Option<&'static str> {
+        Some(match self {
+            ChunkType::Phon => "PHON",
+            ChunkType::Bha => "BHA",
+            ChunkType::Lipi => "LIPI",
+            ChunkType::Meta => "META",
+            ChunkType::Dict => "DICT",
+            ChunkType::Idx => "IDX",
+            ChunkType::Anvy => "ANVY",
+            ChunkType::Anuv => "ANUV",
+            ChunkType::Ext => "EXT",
+            ChunkType::Eof => "EOF",
+            ChunkType::Unknown(_) => return None,
+        })
+    }
+
+    pub fn is_known(self) -> bool {
+        !matches!(self, ChunkType::Unknown(_))
+    }
+}
+
+impl From<u8> for ChunkType {
+    fn from(b: u8) -> Self {
+        ChunkType::KNOWN
+            .into_iter()
+            .find(|t| t.byte() == b)
+            .unwrap_or(ChunkType::Unknown(b))
+    }
+}
+
+impl From<ChunkType> for u8 {
+    fn from(t: ChunkType) -> Self {
+        t.byte()
+    }
+}
+
+impl std::fmt::Display for ChunkType {
+    /// The name, or the type byte in hex for unknown types.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.name() {
+            Some(name) => f.write_str(name),
+            None => write!(f, "0x{:02X}", self.byte()),
+        }
+    }
+}
+
+impl std::str::FromStr for ChunkType {
+    type Err = crate::error::SlbcError;
+
+    /// Parses a name (any case) or a hex type byte such as "0x10".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(t) = ChunkType::KNOWN
+            .into_iter()
+            .find(|t| t.name().is_some_and(|n| n.eq_ignore_ascii_case(s)))
+        {
+            return Ok(t);
+        }
+        s.strip_prefix("0x")
+            .or_else(|| s.strip_prefix("0X"))
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            .map(ChunkType::from)
+            .ok_or_else(|| {
+                crate::error::SlbcError::invalid(format_args!("unknown chunk type '{}'", s))
+            })
+    }
+}
+
+// ── META sub-tag markers (§8.3) ──
+
+pub const SUBTAG_NOTE: u8 = 0xFA; // inline annotation (key, value)
+pub const SUBTAG_EMEND: u8 = 0xFB; // editorial emendation span
+pub const SUBTAG_LANG: u8 = 0xFC; // language/dialect tag
+pub const SUBTAG_KARAKA: u8 = 0xFD;
+pub const SUBTAG_SANDHI: u8 = 0xFE;
+
+// Emendation block kinds, after SUBTAG_EMEND
+pub const EMEND_OPEN: u8 = 0x01;
+pub const EMEND_CLOSE: u8 = 0x02;
+
+// ── IDX chunk index kinds (§7.3) ──
+
+pub const IDX_PADA: u8 = 0x01;
+pub const IDX_AKSHARA: u8 = 0x02;
+pub const IDX_VERSE_SUM: u8 = 0x03;
+
+// ── DICT chunk registry types and modes (§9.6) ──
+
+pub const REGISTRY_DHATU: u8 = 0x01;
+pub const REGISTRY_PRATIPADIKA: u8 = 0x02;
+pub const REGISTRY_SANDHI: u8 = 0x03;
+pub const REGISTRY_PADA: u8 = 0x04;
+pub const REGISTRY_CLUSTER: u8 = 0x05; // pack table (packed cluster mode)
+
+pub const DICT_MODE_EMBEDDED: u8 = 0x00;
+pub const DICT_MODE_EXTERNAL: u8 = 0x01;
+pub const DICT_MODE_HYBRID: u8 = 0x02;
+
 // ── Container ──
 
 pub const MAGIC: &[u8; 4] = b"SLBC";
 pub const VERSION: [u8; 4] = [0x00, 0x00, 0x00, 0x0A]; // v0.10
 
+// ── Extended header record tags (§7.1) ──
+
+pub const EXT_HDR_DICT: u8 = 0x01; // external pada-dictionary reference
+pub const EXT_HDR_PROVENANCE: u8 = 0x02; // transform provenance log
+
 // ── Flag bits (byte 11) ──
 
 pub const FLAG_HAS_LIPI: u8 = 0b1000_0000;
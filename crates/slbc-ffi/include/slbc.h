@@ -0,0 +1,65 @@
+/*
+ * SLBC push-parser — C declarations for the slbc-ffi crate.
+ *
+ * The caller owns the parser storage. Feed bytes in any slice size; the
+ * callback receives one event per phoneme, control byte, and chunk boundary
+ * before slbc_parser_feed returns. No allocation takes place.
+ */
+#ifndef SLBC_H
+#define SLBC_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Event kinds */
+#define SLBC_EV_HEADER        1  /* value: flags, arg: version (big-endian word) */
+#define SLBC_EV_CHUNK_START   2  /* value: chunk type, arg: payload length */
+#define SLBC_EV_PHONEME       3  /* value: svara or vyañjana byte */
+#define SLBC_EV_EXT           4  /* value: extension code (META_EXT <code>) */
+#define SLBC_EV_CONTROL       5  /* value: bhāṣā or lipi control byte */
+#define SLBC_EV_META          6  /* value: byte inside a META block */
+#define SLBC_EV_DIGIT         7  /* value: digit glyph 0–9 in a NUM span */
+#define SLBC_EV_SANKHYA_COUNT 8  /* arg: number of digit-word padas */
+#define SLBC_EV_DICT_REF      9  /* arg: pada-dictionary index */
+#define SLBC_EV_CHUNK_END     10 /* value: chunk type */
+#define SLBC_EV_END           11 /* EOF chunk read */
+#define SLBC_EV_LONG_EXT      12 /* arg: long-extension payload length */
+#define SLBC_EV_EXT_DATA      13 /* value: long-extension payload byte */
+
+/* Return codes */
+#define SLBC_OK                  0
+#define SLBC_ERR_NULL           -1
+#define SLBC_ERR_MAGIC          -2
+#define SLBC_ERR_LENGTH         -3
+#define SLBC_ERR_TRUNCATED      -4
+#define SLBC_ERR_TRAILING       -5
+#define SLBC_ERR_UNEXPECTED_END -6
+
+typedef struct slbc_event {
+    uint8_t kind;
+    uint8_t value;
+    uint32_t arg;
+} slbc_event;
+
+/* Opaque parser state; allocate statically or on the stack. */
+typedef struct slbc_parser {
+    uint64_t opaque[8];
+} slbc_parser;
+
+typedef void (*slbc_callback)(const slbc_event *event, void *user);
+
+int32_t slbc_parser_init(slbc_parser *parser);
+int32_t slbc_parser_feed(slbc_parser *parser, const uint8_t *data, size_t len,
+                         slbc_callback callback, void *user);
+int32_t slbc_parser_finish(slbc_parser *parser);
+uint64_t slbc_parser_offset(slbc_parser *parser);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* SLBC_H */
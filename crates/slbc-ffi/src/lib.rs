@@ -0,0 +1,219 @@
+//! SLBC C ABI.
+//!
+//! A push-parser for devices that decode .slbc straight from flash: the
+//! caller owns the parser storage (`slbc_parser`, 64 bytes), feeds bytes in
+//! whatever slices it reads, and receives one callback per event. Nothing is
+//! allocated. See `include/slbc.h` for the C declarations.
+
+use std::ffi::c_void;
+
+use slbc::push::{Event, PushError, PushParser};
+
+// ── Event kinds (slbc_event.kind) ──
+
+pub const SLBC_EV_HEADER: u8 = 1;
+pub const SLBC_EV_CHUNK_START: u8 = 2;
+pub const SLBC_EV_PHONEME: u8 = 3;
+pub const SLBC_EV_EXT: u8 = 4;
+pub const SLBC_EV_CONTROL: u8 = 5;
+pub const SLBC_EV_META: u8 = 6;
+pub const SLBC_EV_DIGIT: u8 = 7;
+pub const SLBC_EV_SANKHYA_COUNT: u8 = 8;
+pub const SLBC_EV_DICT_REF: u8 = 9;
+pub const SLBC_EV_CHUNK_END: u8 = 10;
+pub const SLBC_EV_END: u8 = 11;
+pub const SLBC_EV_LONG_EXT: u8 = 12;
+pub const SLBC_EV_EXT_DATA: u8 = 13;
+
+// ── Return codes ──
+
+pub const SLBC_OK: i32 = 0;
+pub const SLBC_ERR_NULL: i32 = -1;
+pub const SLBC_ERR_MAGIC: i32 = -2;
+pub const SLBC_ERR_LENGTH: i32 = -3;
+pub const SLBC_ERR_TRUNCATED: i32 = -4;
+pub const SLBC_ERR_TRAILING: i32 = -5;
+pub const SLBC_ERR_UNEXPECTED_END: i32 = -6;
+
+/// One event, as passed to the callback.
+///
+/// `value` holds the byte (phoneme, control, ext code, META byte, digit,
+/// long-extension payload byte, chunk type, or header flags); `arg` holds
+/// the chunk length, long-extension length, SAṄKHYĀ count, or DICT_REF
+/// index, and the version word for the header event.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlbcEvent {
+    pub kind: u8,
+    pub value: u8,
+    pub arg: u32,
+}
+
+pub type SlbcCallback = Option<unsafe extern "C" fn(event: *const SlbcEvent, user: *mut c_void)>;
+
+/// Caller-owned parser storage.
+#[repr(C, align(8))]
+pub struct SlbcParser {
+    opaque: [u64; 8],
+}
+
+const _: () = assert!(std::mem::size_of::<PushParser>() <= std::mem::size_of::<SlbcParser>());
+const _: () = assert!(std::mem::align_of::<PushParser>() <= std::mem::align_of::<SlbcParser>());
+
+impl From<Event> for SlbcEvent {
+    fn from(event: Event) -> Self {
+        let (kind, value, arg) = match event {
+            Event::Header { version, flags } => {
+                (SLBC_EV_HEADER, flags, u32::from_be_bytes(version))
+            }
+            Event::ChunkStart { chunk_type, len } => (SLBC_EV_CHUNK_START, chunk_type, len),
+            Event::Phoneme(b) => (SLBC_EV_PHONEME, b, 0),
+            Event::Ext(code) => (SLBC_EV_EXT, code, 0),
+            Event::Control(b) => (SLBC_EV_CONTROL, b, 0),
+            Event::Meta(b) => (SLBC_EV_META, b, 0),
+            Event::Digit(d) => (SLBC_EV_DIGIT, d, 0),
+            Event::SankhyaCount(n) => (SLBC_EV_SANKHYA_COUNT, 0, n),
+            Event::DictRef(index) => (SLBC_EV_DICT_REF, 0, index),
+            Event::ChunkEnd { chunk_type } => (SLBC_EV_CHUNK_END, chunk_type, 0),
+            Event::End => (SLBC_EV_END, 0, 0),
+            Event::LongExt(len) => (SLBC_EV_LONG_EXT, 0, len),
+            Event::ExtData(b) => (SLBC_EV_EXT_DATA, b, 0),
+        };
+        SlbcEvent { kind, value, arg }
+    }
+}
+
+fn error_code(e: PushError) -> i32 {
+    match e {
+        PushError::BadMagic => SLBC_ERR_MAGIC,
+        PushError::BadLength { .. } => SLBC_ERR_LENGTH,
+        PushError::TruncatedSpan { .. } => SLBC_ERR_TRUNCATED,
+        PushError::TrailingData { .. } => SLBC_ERR_TRAILING,
+        PushError::UnexpectedEnd { .. } => SLBC_ERR_UNEXPECTED_END,
+    }
+}
+
+/// View caller storage as the parser.
+///
+/// # Safety
+/// `parser` must be null or point to storage initialized by `slbc_parser_init`.
+unsafe fn parser_mut<'a>(parser: *mut SlbcParser) -> Option<&'a mut PushParser> {
+    (parser as *mut PushParser).as_mut()
+}
+
+/// Initialize (or reset) a parser in caller-owned storage.
+///
+/// # Safety
+/// `parser` must be null or valid for writes of `sizeof(slbc_parser)` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn slbc_parser_init(parser: *mut SlbcParser) -> i32 {
+    if parser.is_null() {
+        return SLBC_ERR_NULL;
+    }
+    (parser as *mut PushParser).write(PushParser::new());
+    SLBC_OK
+}
+
+/// Feed `len` bytes; `callback` runs once per event before this returns.
+///
+/// # Safety
+/// `parser` must be initialized, `data` valid for `len` bytes, and
+/// `callback` (if non-null) safe to call with `user`.
+#[no_mangle]
+pub unsafe extern "C" fn slbc_parser_feed(
+    parser: *mut SlbcParser,
+    data: *const u8,
+    len: usize,
+    callback: SlbcCallback,
+    user: *mut c_void,
+) -> i32 {
+    let Some(parser) = parser_mut(parser) else {
+        return SLBC_ERR_NULL;
+    };
+    if data.is_null() && len > 0 {
+        return SLBC_ERR_NULL;
+    }
+    let data = match len {
+        0 => &[][..],
+        _ => std::slice::from_raw_parts(data, len),
+    };
+    let result = parser.feed(data, |event| {
+        if let Some(callback) = callback {
+            let event = SlbcEvent::from(event);
+            callback(&event, user);
+        }
+    });
+    match result {
+        Ok(()) => SLBC_OK,
+        Err(e) => error_code(e),
+    }
+}
+
+/// Check that the stream ended with the EOF chunk.
+///
+/// # Safety
+/// `parser` must be null or initialized.
+#[no_mangle]
+pub unsafe extern "C" fn slbc_parser_finish(parser: *mut SlbcParser) -> i32 {
+    match parser_mut(parser) {
+        None => SLBC_ERR_NULL,
+        Some(parser) => parser.finish().map_or_else(error_code, |()| SLBC_OK),
+    }
+}
+
+/// Bytes consumed so far (the error offset after a failed feed).
+///
+/// # Safety
+/// `parser` must be null or initialized.
+#[no_mangle]
+pub unsafe extern "C" fn slbc_parser_offset(parser: *mut SlbcParser) -> u64 {
+    parser_mut(parser).map_or(0, |p| p.offset())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use slbc::{container, encoder};
+
+    unsafe extern "C" fn collect(event: *const SlbcEvent, user: *mut c_void) {
+        (*(user as *mut Vec<SlbcEvent>)).push(*event);
+    }
+
+    #[test]
+    fn test_feed_through_c_abi() {
+        let file = container::build_slbc(&encoder::encode_iast("oṃ").unwrap());
+        let mut storage = SlbcParser { opaque: [0; 8] };
+        let mut events: Vec<SlbcEvent> = Vec::new();
+        unsafe {
+            assert_eq!(slbc_parser_init(&mut storage), SLBC_OK);
+            for b in &file {
+                let user = &mut events as *mut Vec<SlbcEvent> as *mut c_void;
+                assert_eq!(
+                    slbc_parser_feed(&mut storage, b, 1, Some(collect), user),
+                    SLBC_OK
+                );
+            }
+            assert_eq!(slbc_parser_finish(&mut storage), SLBC_OK);
+            assert_eq!(slbc_parser_offset(&mut storage), file.len() as u64);
+        }
+        let phonemes: Vec<u8> = events
+            .iter()
+            .filter(|e| e.kind == SLBC_EV_PHONEME)
+            .map(|e| e.value)
+            .collect();
+        assert_eq!(phonemes, [0x89, 0x3A]);
+        assert_eq!(events.last().unwrap().kind, SLBC_EV_END);
+    }
+
+    #[test]
+    fn test_null_and_errors() {
+        let mut storage = SlbcParser { opaque: [0; 8] };
+        unsafe {
+            assert_eq!(slbc_parser_init(std::ptr::null_mut()), SLBC_ERR_NULL);
+            slbc_parser_init(&mut storage);
+            let bad = b"XLBC";
+            let code = slbc_parser_feed(&mut storage, bad.as_ptr(), 4, None, std::ptr::null_mut());
+            assert_eq!(code, SLBC_ERR_MAGIC);
+        }
+    }
+}
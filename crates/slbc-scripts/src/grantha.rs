@@ -0,0 +1,120 @@
+//! Grantha (U+11300 block).
+
+use crate::{no_mark, Abugida, ExtLetter};
+
+/// Grantha (U+11300 block) as used for South Indian Vedic texts: Tamil
+/// digits, Devanāgarī daṇḍas, and the Devanāgarī stress signs for accent —
+/// udātta ॑, anudātta ॒, and the double svarita ᳚.
+pub const GRANTHA: Abugida = Abugida {
+    name: "Grantha",
+    consonant: grantha_consonant,
+    independent: grantha_independent,
+    matra: grantha_matra,
+    extension: grantha_extension,
+    virama: "𑍍",
+    anusvara: "𑌂",
+    candrabindu: "𑌁",
+    visarga: "𑌃",
+    jihvamuliya: "",
+    upadhmaniya: "",
+    // Grantha has no daṇḍa of its own
+    danda: "।",
+    double_danda: "॥",
+    avagraha: "𑌽",
+    om: "𑍐",
+    digits: ['௦', '௧', '௨', '௩', '௪', '௫', '௬', '௭', '௮', '௯'],
+    accents: ["", "\u{0951}", "\u{0952}", "\u{1CDA}"],
+    consonant_mark: no_mark,
+};
+
+fn grantha_consonant(b: u8) -> &'static str {
+    match b {
+        0x00 => "𑌕",
+        0x01 => "𑌖",
+        0x02 => "𑌗",
+        0x03 => "𑌘",
+        0x04 => "𑌙",
+        0x08 => "𑌚",
+        0x09 => "𑌛",
+        0x0A => "𑌜",
+        0x0B => "𑌝",
+        0x0C => "𑌞",
+        0x10 => "𑌟",
+        0x11 => "𑌠",
+        0x12 => "𑌡",
+        0x13 => "𑌢",
+        0x14 => "𑌣",
+        0x18 => "𑌤",
+        0x19 => "𑌥",
+        0x1A => "𑌦",
+        0x1B => "𑌧",
+        0x1C => "𑌨",
+        0x20 => "𑌪",
+        0x21 => "𑌫",
+        0x22 => "𑌬",
+        0x23 => "𑌭",
+        0x24 => "𑌮",
+        0x29 => "𑌶",
+        0x2A => "𑌷",
+        0x2B => "𑌸",
+        0x31 => "𑌯",
+        0x32 => "𑌵",
+        0x33 => "𑌰",
+        0x34 => "𑌲",
+        0x38 => "𑌹",
+        _ => "?",
+    }
+}
+
+fn grantha_independent(b: u8) -> &'static str {
+    match b & 0b11_00_11_11 {
+        0x40 => "𑌅",
+        0x80 => "𑌆",
+        0x44 => "𑌇",
+        0x84 => "𑌈",
+        0x48 => "𑌉",
+        0x88 => "𑌊",
+        0x4C => "𑌋",
+        0x8C => "𑍠",
+        0x4F => "𑌌",
+        0x8F => "𑍡",
+        0x85 => "𑌏",
+        0x86 => "𑌐",
+        0x89 => "𑌓",
+        0x8A => "𑌔",
+        _ => "?",
+    }
+}
+
+fn grantha_matra(b: u8) -> Option<&'static str> {
+    match b & 0b11_00_11_11 {
+        0x80 => Some("𑌾"),
+        0x44 => Some("𑌿"),
+        0x84 => Some("𑍀"),
+        0x48 => Some("𑍁"),
+        0x88 => Some("𑍂"),
+        0x4C => Some("𑍃"),
+        0x8C => Some("𑍄"),
+        0x4F => Some("𑍢"),
+        0x8F => Some("𑍣"),
+        0x85 => Some("𑍇"),
+        0x86 => Some("𑍈"),
+        0x89 => Some("𑍋"),
+        0x8A => Some("𑍌"),
+        _ => None,
+    }
+}
+
+fn grantha_extension(code: u8) -> Option<ExtLetter> {
+    // Nukta letters are base + U+1133C; Grantha has no ḻ, ṟ, ṉ or short e/o
+    Some(match code {
+        0x04 => ("𑌳", None),
+        0x05 => ("𑌳𑍍𑌹", None),
+        0x08 => ("𑌕𑌼", None),
+        0x09 => ("𑌖𑌼", None),
+        0x0A => ("𑌗𑌼", None),
+        0x0B => ("𑌜𑌼", None),
+        0x0C => ("𑌫𑌼", None),
+        _ => return None,
+    })
+}
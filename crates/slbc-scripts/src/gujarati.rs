@@ -0,0 +1,119 @@
+//! Gujarati.
+
+use crate::{no_mark, Abugida, ExtLetter, NO_ACCENTS};
+
+/// Gujarati has its own letter for every Sanskrit sound;
+/// it lacks the Dravidian ḻ/ṟ/ṉ and short e/o.
+pub const GUJARATI: Abugida = Abugida {
+    name: "Gujarati",
+    consonant: gujarati_consonant,
+    independent: gujarati_independent,
+    matra: gujarati_matra,
+    extension: gujarati_extension,
+    virama: "્",
+    anusvara: "ં",
+    candrabindu: "ઁ",
+    visarga: "ઃ",
+    jihvamuliya: "",
+    upadhmaniya: "",
+    // Gujarati has no daṇḍa of its own
+    danda: "।",
+    double_danda: "॥",
+    avagraha: "ઽ",
+    om: "ૐ",
+    digits: ['૦', '૧', '૨', '૩', '૪', '૫', '૬', '૭', '૮', '૯'],
+    accents: NO_ACCENTS,
+    consonant_mark: no_mark,
+};
+
+fn gujarati_consonant(b: u8) -> &'static str {
+    match b {
+        0x00 => "ક",
+        0x01 => "ખ",
+        0x02 => "ગ",
+        0x03 => "ઘ",
+        0x04 => "ઙ",
+        0x08 => "ચ",
+        0x09 => "છ",
+        0x0A => "જ",
+        0x0B => "ઝ",
+        0x0C => "ઞ",
+        0x10 => "ટ",
+        0x11 => "ઠ",
+        0x12 => "ડ",
+        0x13 => "ઢ",
+        0x14 => "ણ",
+        0x18 => "ત",
+        0x19 => "થ",
+        0x1A => "દ",
+        0x1B => "ધ",
+        0x1C => "ન",
+        0x20 => "પ",
+        0x21 => "ફ",
+        0x22 => "બ",
+        0x23 => "ભ",
+        0x24 => "મ",
+        0x29 => "શ",
+        0x2A => "ષ",
+        0x2B => "સ",
+        0x31 => "ય",
+        0x32 => "વ",
+        0x33 => "ર",
+        0x34 => "લ",
+        0x38 => "હ",
+        _ => "?",
+    }
+}
+
+fn gujarati_independent(b: u8) -> &'static str {
+    match b & 0b11_00_11_11 {
+        0x40 => "અ",
+        0x80 => "આ",
+        0x44 => "ઇ",
+        0x84 => "ઈ",
+        0x48 => "ઉ",
+        0x88 => "ઊ",
+        0x4C => "ઋ",
+        0x8C => "ૠ",
+        0x4F => "ઌ",
+        0x8F => "ૡ",
+        0x85 => "એ",
+        0x86 => "ઐ",
+        0x89 => "ઓ",
+        0x8A => "ઔ",
+        _ => "?",
+    }
+}
+
+fn gujarati_matra(b: u8) -> Option<&'static str> {
+    match b & 0b11_00_11_11 {
+        0x80 => Some("ા"),
+        0x44 => Some("િ"),
+        0x84 => Some("ી"),
+        0x48 => Some("ુ"),
+        0x88 => Some("ૂ"),
+        0x4C => Some("ૃ"),
+        0x8C => Some("ૄ"),
+        0x4F => Some("ૢ"),
+        0x8F => Some("ૣ"),
+        0x85 => Some("ે"),
+        0x86 => Some("ૈ"),
+        0x89 => Some("ો"),
+        0x8A => Some("ૌ"),
+        _ => None,
+    }
+}
+
+fn gujarati_extension(code: u8) -> Option<ExtLetter> {
+    // Nukta letters are base + U+0ABC
+    Some(match code {
+        0x04 => ("ળ", None),
+        0x05 => ("ળ્હ", None),
+        0x08 => ("ક઼", None),
+        0x09 => ("ખ઼", None),
+        0x0A => ("ગ઼", None),
+        0x0B => ("જ઼", None),
+        0x0C => ("ફ઼", None),
+        _ => return None,
+    })
+}
@@ -0,0 +1,120 @@
+//! Gurmukhī.
+
+use crate::{no_mark, Abugida, ExtLetter, NO_ACCENTS};
+
+/// Gurmukhī has no vocalic liquids or ṣa. Following the usual way of
+/// writing Sanskrit in it, ṛ is ri (ਕ੍ਰਿ for kṛ), ḷ is li, and ṣ is the
+/// ਖ of its North Indian pronunciation, so kha and ṣa share a letter.
+pub const GURMUKHI: Abugida = Abugida {
+    name: "Gurmukhī",
+    consonant: gurmukhi_consonant,
+    independent: gurmukhi_independent,
+    matra: gurmukhi_matra,
+    extension: gurmukhi_extension,
+    virama: "੍",
+    anusvara: "ਂ",
+    candrabindu: "ਁ",
+    visarga: "ਃ",
+    jihvamuliya: "",
+    upadhmaniya: "",
+    // Gurmukhī has no daṇḍa or avagraha of its own
+    danda: "।",
+    double_danda: "॥",
+    avagraha: "ऽ",
+    om: "",
+    digits: ['੦', '੧', '੨', '੩', '੪', '੫', '੬', '੭', '੮', '੯'],
+    accents: NO_ACCENTS,
+    consonant_mark: no_mark,
+};
+
+fn gurmukhi_consonant(b: u8) -> &'static str {
+    match b {
+        0x00 => "ਕ",
+        0x01 => "ਖ",
+        0x02 => "ਗ",
+        0x03 => "ਘ",
+        0x04 => "ਙ",
+        0x08 => "ਚ",
+        0x09 => "ਛ",
+        0x0A => "ਜ",
+        0x0B => "ਝ",
+        0x0C => "ਞ",
+        0x10 => "ਟ",
+        0x11 => "ਠ",
+        0x12 => "ਡ",
+        0x13 => "ਢ",
+        0x14 => "ਣ",
+        0x18 => "ਤ",
+        0x19 => "ਥ",
+        0x1A => "ਦ",
+        0x1B => "ਧ",
+        0x1C => "ਨ",
+        0x20 => "ਪ",
+        0x21 => "ਫ",
+        0x22 => "ਬ",
+        0x23 => "ਭ",
+        0x24 => "ਮ",
+        0x29 => "ਸ਼",
+        0x2A => "ਖ", // ṣa, as kha
+        0x2B => "ਸ",
+        0x31 => "ਯ",
+        0x32 => "ਵ",
+        0x33 => "ਰ",
+        0x34 => "ਲ",
+        0x38 => "ਹ",
+        _ => "?",
+    }
+}
+
+fn gurmukhi_independent(b: u8) -> &'static str {
+    match b & 0b11_00_11_11 {
+        0x40 => "ਅ",
+        0x80 => "ਆ",
+        0x44 => "ਇ",
+        0x84 => "ਈ",
+        0x48 => "ਉ",
+        0x88 => "ਊ",
+        0x4C => "ਰਿ",
+        0x8C => "ਰੀ",
+        0x4F => "ਲਿ",
+        0x8F => "ਲੀ",
+        0x85 => "ਏ",
+        0x86 => "ਐ",
+        0x89 => "ਓ",
+        0x8A => "ਔ",
+        _ => "?",
+    }
+}
+
+fn gurmukhi_matra(b: u8) -> Option<&'static str> {
+    match b & 0b11_00_11_11 {
+        0x80 => Some("ਾ"),
+        0x44 => Some("ਿ"),
+        0x84 => Some("ੀ"),
+        0x48 => Some("ੁ"),
+        0x88 => Some("ੂ"),
+        0x4C => Some("੍ਰਿ"),
+        0x8C => Some("੍ਰੀ"),
+        0x4F => Some("੍ਲਿ"),
+        0x8F => Some("੍ਲੀ"),
+        0x85 => Some("ੇ"),
+        0x86 => Some("ੈ"),
+        0x89 => Some("ੋ"),
+        0x8A => Some("ੌ"),
+        _ => None,
+    }
+}
+
+fn gurmukhi_extension(code: u8) -> Option<ExtLetter> {
+    // Nukta letters are base + U+0A3C; Gurmukhī has no ḻ, ṟ, ṉ or short e/o
+    Some(match code {
+        0x04 => ("ਲ਼", None),
+        0x05 => ("ਲ਼੍ਹ", None),
+        0x08 => ("ਕ਼", None),
+        0x09 => ("ਖ਼", None),
+        0x0A => ("ਗ਼", None),
+        0x0B => ("ਜ਼", None),
+        0x0C => ("ਫ਼", None),
+        _ => return None,
+    })
+}
@@ -0,0 +1,124 @@
+//! Kannada.
+
+use crate::{no_mark, Abugida, ExtLetter, NO_ACCENTS};
+
+/// Kannada keeps Sanskrit e/o as its long ೇ/ೋ; the short vowels are the
+/// extension codes.
+pub const KANNADA: Abugida = Abugida {
+    name: "Kannada",
+    consonant: kannada_consonant,
+    independent: kannada_independent,
+    matra: kannada_matra,
+    extension: kannada_extension,
+    virama: "್",
+    anusvara: "ಂ",
+    candrabindu: "ಁ",
+    visarga: "ಃ",
+    jihvamuliya: "ೱ",
+    upadhmaniya: "ೲ",
+    // Kannada has no daṇḍa of its own
+    danda: "।",
+    double_danda: "॥",
+    avagraha: "ಽ",
+    om: "",
+    digits: ['೦', '೧', '೨', '೩', '೪', '೫', '೬', '೭', '೮', '೯'],
+    accents: NO_ACCENTS,
+    consonant_mark: no_mark,
+};
+
+fn kannada_consonant(b: u8) -> &'static str {
+    match b {
+        0x00 => "ಕ",
+        0x01 => "ಖ",
+        0x02 => "ಗ",
+        0x03 => "ಘ",
+        0x04 => "ಙ",
+        0x08 => "ಚ",
+        0x09 => "ಛ",
+        0x0A => "ಜ",
+        0x0B => "ಝ",
+        0x0C => "ಞ",
+        0x10 => "ಟ",
+        0x11 => "ಠ",
+        0x12 => "ಡ",
+        0x13 => "ಢ",
+        0x14 => "ಣ",
+        0x18 => "ತ",
+        0x19 => "ಥ",
+        0x1A => "ದ",
+        0x1B => "ಧ",
+        0x1C => "ನ",
+        0x20 => "ಪ",
+        0x21 => "ಫ",
+        0x22 => "ಬ",
+        0x23 => "ಭ",
+        0x24 => "ಮ",
+        0x29 => "ಶ",
+        0x2A => "ಷ",
+        0x2B => "ಸ",
+        0x31 => "ಯ",
+        0x32 => "ವ",
+        0x33 => "ರ",
+        0x34 => "ಲ",
+        0x38 => "ಹ",
+        _ => "?",
+    }
+}
+
+fn kannada_independent(b: u8) -> &'static str {
+    match b & 0b11_00_11_11 {
+        0x40 => "ಅ",
+        0x80 => "ಆ",
+        0x44 => "ಇ",
+        0x84 => "ಈ",
+        0x48 => "ಉ",
+        0x88 => "ಊ",
+        0x4C => "ಋ",
+        0x8C => "ೠ",
+        0x4F => "ಌ",
+        0x8F => "ೡ",
+        0x85 => "ಏ",
+        0x86 => "ಐ",
+        0x89 => "ಓ",
+        0x8A => "ಔ",
+        _ => "?",
+    }
+}
+
+fn kannada_matra(b: u8) -> Option<&'static str> {
+    match b & 0b11_00_11_11 {
+        0x80 => Some("ಾ"),
+        0x44 => Some("ಿ"),
+        0x84 => Some("ೀ"),
+        0x48 => Some("ು"),
+        0x88 => Some("ೂ"),
+        0x4C => Some("ೃ"),
+        0x8C => Some("ೄ"),
+        0x4F => Some("ೢ"),
+        0x8F => Some("ೣ"),
+        0x85 => Some("ೇ"),
+        0x86 => Some("ೈ"),
+        0x89 => Some("ೋ"),
+        0x8A => Some("ೌ"),
+        _ => None,
+    }
+}
+
+fn kannada_extension(code: u8) -> Option<ExtLetter> {
+    // Nukta letters are base + U+0CBC; ḻ is the historic ೞ
+    Some(match code {
+        0x01 => ("ೞ", None),
+        0x02 => ("ಱ", None),
+        0x03 => ("ನ಼", None),
+        0x04 => ("ಳ", None),
+        0x05 => ("ಳ್ಹ", None),
+        0x08 => ("ಕ಼", None),
+        0x09 => ("ಖ಼", None),
+        0x0A => ("ಗ಼", None),
+        0x0B => ("ಜ಼", None),
+        0x0C => ("ಫ಼", None),
+        0x10 => ("ಎ", Some("ೆ")),
+        0x11 => ("ಒ", Some("ೊ")),
+        _ => return None,
+    })
+}
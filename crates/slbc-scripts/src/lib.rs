@@ -0,0 +1,93 @@
+//! Renderer tables for the Brahmic scripts SLBC decodes to.
+//!
+//! Devanāgarī and the regional scripts write Sanskrit the same way (§4.2): a
+//! consonant letter carries an inherent a, other vowels follow it as signs,
+//! and a virāma marks a consonant with no vowel. Each script only supplies
+//! its letters here; `slbc-core`'s decoder runs one state machine over any
+//! `Abugida`. Devanāgarī itself lives in `slbc-core`, which also reads it;
+//! the regional tables sit behind the `regional` feature.
+
+/// An extension sound's letter (consonant or independent vowel) and, for
+/// vowels, its sign.
+pub type ExtLetter = (&'static str, Option<&'static str>);
+
+/// One script's graphemes.
+#[derive(Debug)]
+pub struct Abugida {
+    pub name: &'static str,
+    /// Consonant letter (with inherent a) per vyañjana byte.
+    pub consonant: fn(u8) -> &'static str,
+    /// Independent vowel per svara byte.
+    pub independent: fn(u8) -> &'static str,
+    /// Vowel sign per svara byte; `None` for a.
+    pub matra: fn(u8) -> Option<&'static str>,
+    /// Letter per extension code.
+    pub extension: fn(u8) -> Option<ExtLetter>,
+    pub virama: &'static str,
+    pub anusvara: &'static str,
+    /// Empty where the script has none; the anusvāra is written instead.
+    pub candrabindu: &'static str,
+    pub visarga: &'static str,
+    /// Visarga before k/kh and p/ph; empty where the script has no sign of
+    /// its own, and the visarga is written instead.
+    pub jihvamuliya: &'static str,
+    pub upadhmaniya: &'static str,
+    pub danda: &'static str,
+    pub double_danda: &'static str,
+    pub avagraha: &'static str,
+    /// The sacred syllable oṃ as one sign; empty where the script has
+    /// none, and it is spelled out.
+    pub om: &'static str,
+    pub digits: [char; 10],
+    /// Mark after a vowel per A field; empty where the script writes none.
+    pub accents: [&'static str; 4],
+    /// Mark written after a consonant's vowel sign or virāma, for scripts
+    /// that distinguish consonants by superscript (Tamil).
+    pub consonant_mark: fn(u8) -> &'static str,
+}
+
+/// For scripts whose Sanskrit is written without accents.
+pub const NO_ACCENTS: [&str; 4] = [""; 4];
+
+/// For scripts with a letter per consonant.
+pub fn no_mark(_: u8) -> &'static str {
+    ""
+}
+
+#[cfg(feature = "regional")]
+mod grantha;
+#[cfg(feature = "regional")]
+mod gujarati;
+#[cfg(feature = "regional")]
+mod gurmukhi;
+#[cfg(feature = "regional")]
+mod kannada;
+#[cfg(feature = "regional")]
+mod odia;
+#[cfg(feature = "regional")]
+mod sharada;
+#[cfg(feature = "regional")]
+mod siddham;
+#[cfg(feature = "regional")]
+mod tamil;
+#[cfg(feature = "regional")]
+mod telugu;
+
+#[cfg(feature = "regional")]
+pub use grantha::GRANTHA;
+#[cfg(feature = "regional")]
+pub use gujarati::GUJARATI;
+#[cfg(feature = "regional")]
+pub use gurmukhi::GURMUKHI;
+#[cfg(feature = "regional")]
+pub use kannada::KANNADA;
+#[cfg(feature = "regional")]
+pub use odia::ODIA;
+#[cfg(feature = "regional")]
+pub use sharada::SHARADA;
+#[cfg(feature = "regional")]
+pub use siddham::SIDDHAM;
+#[cfg(feature = "regional")]
+pub use tamil::TAMIL;
+#[cfg(feature = "regional")]
+pub use telugu::TELUGU;
@@ -0,0 +1,120 @@
+//! Odia.
+
+use crate::{no_mark, Abugida, ExtLetter, NO_ACCENTS};
+
+/// Odia writes ba and va alike as ବ in everyday text; va is rendered with
+/// ଵ (U+0B35), encoded for Sanskrit, so the two stay apart. Odia lacks the
+/// Dravidian ḻ/ṟ/ṉ and short e/o.
+pub const ODIA: Abugida = Abugida {
+    name: "Odia",
+    consonant: odia_consonant,
+    independent: odia_independent,
+    matra: odia_matra,
+    extension: odia_extension,
+    virama: "୍",
+    anusvara: "ଂ",
+    candrabindu: "ଁ",
+    visarga: "ଃ",
+    jihvamuliya: "",
+    upadhmaniya: "",
+    // Odia has no daṇḍa of its own
+    danda: "।",
+    double_danda: "॥",
+    avagraha: "ଽ",
+    om: "",
+    digits: ['୦', '୧', '୨', '୩', '୪', '୫', '୬', '୭', '୮', '୯'],
+    accents: NO_ACCENTS,
+    consonant_mark: no_mark,
+};
+
+fn odia_consonant(b: u8) -> &'static str {
+    match b {
+        0x00 => "କ",
+        0x01 => "ଖ",
+        0x02 => "ଗ",
+        0x03 => "ଘ",
+        0x04 => "ଙ",
+        0x08 => "ଚ",
+        0x09 => "ଛ",
+        0x0A => "ଜ",
+        0x0B => "ଝ",
+        0x0C => "ଞ",
+        0x10 => "ଟ",
+        0x11 => "ଠ",
+        0x12 => "ଡ",
+        0x13 => "ଢ",
+        0x14 => "ଣ",
+        0x18 => "ତ",
+        0x19 => "ଥ",
+        0x1A => "ଦ",
+        0x1B => "ଧ",
+        0x1C => "ନ",
+        0x20 => "ପ",
+        0x21 => "ଫ",
+        0x22 => "ବ", // ba
+        0x23 => "ଭ",
+        0x24 => "ମ",
+        0x29 => "ଶ",
+        0x2A => "ଷ",
+        0x2B => "ସ",
+        0x31 => "ଯ",
+        0x32 => "ଵ", // va, not ବ
+        0x33 => "ର",
+        0x34 => "ଲ",
+        0x38 => "ହ",
+        _ => "?",
+    }
+}
+
+fn odia_independent(b: u8) -> &'static str {
+    match b & 0b11_00_11_11 {
+        0x40 => "ଅ",
+        0x80 => "ଆ",
+        0x44 => "ଇ",
+        0x84 => "ଈ",
+        0x48 => "ଉ",
+        0x88 => "ଊ",
+        0x4C => "ଋ",
+        0x8C => "ୠ",
+        0x4F => "ଌ",
+        0x8F => "ୡ",
+        0x85 => "ଏ",
+        0x86 => "ଐ",
+        0x89 => "ଓ",
+        0x8A => "ଔ",
+        _ => "?",
+    }
+}
+
+fn odia_matra(b: u8) -> Option<&'static str> {
+    match b & 0b11_00_11_11 {
+        0x80 => Some("ା"),
+        0x44 => Some("ି"),
+        0x84 => Some("ୀ"),
+        0x48 => Some("ୁ"),
+        0x88 => Some("ୂ"),
+        0x4C => Some("ୃ"),
+        0x8C => Some("ୄ"),
+        0x4F => Some("ୢ"),
+        0x8F => Some("ୣ"),
+        0x85 => Some("େ"),
+        0x86 => Some("ୈ"),
+        0x89 => Some("ୋ"),
+        0x8A => Some("ୌ"),
+        _ => None,
+    }
+}
+
+fn odia_extension(code: u8) -> Option<ExtLetter> {
+    // Nukta letters are base + U+0B3C
+    Some(match code {
+        0x04 => ("ଳ", None),
+        0x05 => ("ଳ୍ହ", None),
+        0x08 => ("କ଼", None),
+        0x09 => ("ଖ଼", None),
+        0x0A => ("ଗ଼", None),
+        0x0B => ("ଜ଼", None),
+        0x0C => ("ଫ଼", None),
+        _ => return None,
+    })
+}
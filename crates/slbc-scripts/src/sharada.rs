@@ -0,0 +1,118 @@
+//! Śāradā (U+11180 block).
+
+use crate::{no_mark, Abugida, ExtLetter, NO_ACCENTS};
+
+/// Śāradā (U+11180 block), the script of Kashmiri manuscripts, with its own
+/// daṇḍas and digits.
+pub const SHARADA: Abugida = Abugida {
+    name: "Śāradā",
+    consonant: sharada_consonant,
+    independent: sharada_independent,
+    matra: sharada_matra,
+    extension: sharada_extension,
+    virama: "𑇀",
+    anusvara: "𑆁",
+    candrabindu: "𑆀",
+    visarga: "𑆂",
+    jihvamuliya: "𑇂",
+    upadhmaniya: "𑇃",
+    danda: "𑇅",
+    double_danda: "𑇆",
+    avagraha: "𑇁",
+    om: "𑇄",
+    digits: ['𑇐', '𑇑', '𑇒', '𑇓', '𑇔', '𑇕', '𑇖', '𑇗', '𑇘', '𑇙'],
+    accents: NO_ACCENTS,
+    consonant_mark: no_mark,
+};
+
+fn sharada_consonant(b: u8) -> &'static str {
+    match b {
+        0x00 => "𑆑",
+        0x01 => "𑆒",
+        0x02 => "𑆓",
+        0x03 => "𑆔",
+        0x04 => "𑆕",
+        0x08 => "𑆖",
+        0x09 => "𑆗",
+        0x0A => "𑆘",
+        0x0B => "𑆙",
+        0x0C => "𑆚",
+        0x10 => "𑆛",
+        0x11 => "𑆜",
+        0x12 => "𑆝",
+        0x13 => "𑆞",
+        0x14 => "𑆟",
+        0x18 => "𑆠",
+        0x19 => "𑆡",
+        0x1A => "𑆢",
+        0x1B => "𑆣",
+        0x1C => "𑆤",
+        0x20 => "𑆥",
+        0x21 => "𑆦",
+        0x22 => "𑆧",
+        0x23 => "𑆨",
+        0x24 => "𑆩",
+        0x29 => "𑆯",
+        0x2A => "𑆰",
+        0x2B => "𑆱",
+        0x31 => "𑆪",
+        0x32 => "𑆮",
+        0x33 => "𑆫",
+        0x34 => "𑆬",
+        0x38 => "𑆲",
+        _ => "?",
+    }
+}
+
+fn sharada_independent(b: u8) -> &'static str {
+    match b & 0b11_00_11_11 {
+        0x40 => "𑆃",
+        0x80 => "𑆄",
+        0x44 => "𑆅",
+        0x84 => "𑆆",
+        0x48 => "𑆇",
+        0x88 => "𑆈",
+        0x4C => "𑆉",
+        0x8C => "𑆊",
+        0x4F => "𑆋",
+        0x8F => "𑆌",
+        0x85 => "𑆍",
+        0x86 => "𑆎",
+        0x89 => "𑆏",
+        0x8A => "𑆐",
+        _ => "?",
+    }
+}
+
+fn sharada_matra(b: u8) -> Option<&'static str> {
+    match b & 0b11_00_11_11 {
+        0x80 => Some("𑆳"),
+        0x44 => Some("𑆴"),
+        0x84 => Some("𑆵"),
+        0x48 => Some("𑆶"),
+        0x88 => Some("𑆷"),
+        0x4C => Some("𑆸"),
+        0x8C => Some("𑆹"),
+        0x4F => Some("𑆺"),
+        0x8F => Some("𑆻"),
+        0x85 => Some("𑆼"),
+        0x86 => Some("𑆽"),
+        0x89 => Some("𑆾"),
+        0x8A => Some("𑆿"),
+        _ => None,
+    }
+}
+
+fn sharada_extension(code: u8) -> Option<ExtLetter> {
+    // Nukta letters are base + U+111CA; Śāradā has no ḻ, ṟ, ṉ or short e/o
+    Some(match code {
+        0x04 => ("𑆭", None),
+        0x05 => ("𑆭𑇀𑆲", None),
+        0x08 => ("𑆑𑇊", None),
+        0x09 => ("𑆒𑇊", None),
+        0x0A => ("𑆓𑇊", None),
+        0x0B => ("𑆘𑇊", None),
+        0x0C => ("𑆦𑇊", None),
+        _ => return None,
+    })
+}
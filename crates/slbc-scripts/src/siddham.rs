@@ -0,0 +1,119 @@
+//! Siddhaṃ (U+11580 block).
+
+use crate::{no_mark, Abugida, ExtLetter, NO_ACCENTS};
+
+/// Siddhaṃ (U+11580 block), the script of East Asian Buddhist manuscripts,
+/// with its own daṇḍas. Unicode encodes no Siddhaṃ digits or avagraha, so
+/// those are Devanāgarī.
+pub const SIDDHAM: Abugida = Abugida {
+    name: "Siddhaṃ",
+    consonant: siddham_consonant,
+    independent: siddham_independent,
+    matra: siddham_matra,
+    extension: siddham_extension,
+    virama: "𑖿",
+    anusvara: "𑖽",
+    candrabindu: "𑖼",
+    visarga: "𑖾",
+    jihvamuliya: "",
+    upadhmaniya: "",
+    danda: "𑗂",
+    double_danda: "𑗃",
+    avagraha: "ऽ",
+    om: "",
+    digits: ['०', '१', '२', '३', '४', '५', '६', '७', '८', '९'],
+    accents: NO_ACCENTS,
+    consonant_mark: no_mark,
+};
+
+fn siddham_consonant(b: u8) -> &'static str {
+    match b {
+        0x00 => "𑖎",
+        0x01 => "𑖏",
+        0x02 => "𑖐",
+        0x03 => "𑖑",
+        0x04 => "𑖒",
+        0x08 => "𑖓",
+        0x09 => "𑖔",
+        0x0A => "𑖕",
+        0x0B => "𑖖",
+        0x0C => "𑖗",
+        0x10 => "𑖘",
+        0x11 => "𑖙",
+        0x12 => "𑖚",
+        0x13 => "𑖛",
+        0x14 => "𑖜",
+        0x18 => "𑖝",
+        0x19 => "𑖞",
+        0x1A => "𑖟",
+        0x1B => "𑖠",
+        0x1C => "𑖡",
+        0x20 => "𑖢",
+        0x21 => "𑖣",
+        0x22 => "𑖤",
+        0x23 => "𑖥",
+        0x24 => "𑖦",
+        0x29 => "𑖫",
+        0x2A => "𑖬",
+        0x2B => "𑖭",
+        0x31 => "𑖧",
+        0x32 => "𑖪",
+        0x33 => "𑖨",
+        0x34 => "𑖩",
+        0x38 => "𑖮",
+        _ => "?",
+    }
+}
+
+fn siddham_independent(b: u8) -> &'static str {
+    match b & 0b11_00_11_11 {
+        0x40 => "𑖀",
+        0x80 => "𑖁",
+        0x44 => "𑖂",
+        0x84 => "𑖃",
+        0x48 => "𑖄",
+        0x88 => "𑖅",
+        0x4C => "𑖆",
+        0x8C => "𑖇",
+        0x4F => "𑖈",
+        0x8F => "𑖉",
+        0x85 => "𑖊",
+        0x86 => "𑖋",
+        0x89 => "𑖌",
+        0x8A => "𑖍",
+        _ => "?",
+    }
+}
+
+fn siddham_matra(b: u8) -> Option<&'static str> {
+    // Siddhaṃ has no vocalic l signs; ḷ/ḹ after a consonant are written
+    // virāma + vowel letter
+    match b & 0b11_00_11_11 {
+        0x80 => Some("𑖯"),
+        0x44 => Some("𑖰"),
+        0x84 => Some("𑖱"),
+        0x48 => Some("𑖲"),
+        0x88 => Some("𑖳"),
+        0x4C => Some("𑖴"),
+        0x8C => Some("𑖵"),
+        0x4F => Some("𑖿𑖈"),
+        0x8F => Some("𑖿𑖉"),
+        0x85 => Some("𑖸"),
+        0x86 => Some("𑖹"),
+        0x89 => Some("𑖺"),
+        0x8A => Some("𑖻"),
+        _ => None,
+    }
+}
+
+fn siddham_extension(code: u8) -> Option<ExtLetter> {
+    // Nukta letters are base + U+115C0; Siddhaṃ has no ḻ, ṟ, ṉ or short e/o
+    Some(match code {
+        0x08 => ("𑖎𑗀", None),
+        0x09 => ("𑖏𑗀", None),
+        0x0A => ("𑖐𑗀", None),
+        0x0B => ("𑖕𑗀", None),
+        0x0C => ("𑖣𑗀", None),
+        _ => return None,
+    })
+}
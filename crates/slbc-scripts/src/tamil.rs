@@ -0,0 +1,126 @@
+//! Tamil.
+
+use crate::{Abugida, ExtLetter, NO_ACCENTS};
+
+/// Tamil writes one letter per varga and tells the columns apart with
+/// superscript digits: க க² க³ க⁴. Its own ஜ ஶ ஷ ஸ ஹ need none. Sanskrit e/o
+/// are the long ஏ/ஓ; the short vowels are the extension codes.
+pub const TAMIL: Abugida = Abugida {
+    name: "Tamil",
+    consonant: tamil_consonant,
+    independent: tamil_independent,
+    matra: tamil_matra,
+    extension: tamil_extension,
+    virama: "்",
+    anusvara: "ஂ",
+    candrabindu: "",
+    visarga: "ஃ",
+    jihvamuliya: "",
+    upadhmaniya: "",
+    // Tamil has no daṇḍa or avagraha of its own
+    danda: "।",
+    double_danda: "॥",
+    avagraha: "ऽ",
+    om: "ௐ",
+    digits: ['௦', '௧', '௨', '௩', '௪', '௫', '௬', '௭', '௮', '௯'],
+    accents: NO_ACCENTS,
+    consonant_mark: tamil_mark,
+};
+
+fn tamil_consonant(b: u8) -> &'static str {
+    match b {
+        0x00..=0x03 => "க",
+        0x04 => "ங",
+        0x08 | 0x09 => "ச",
+        0x0A | 0x0B => "ஜ",
+        0x0C => "ஞ",
+        0x10..=0x13 => "ட",
+        0x14 => "ண",
+        0x18..=0x1B => "த",
+        0x1C => "ந",
+        0x20..=0x23 => "ப",
+        0x24 => "ம",
+        0x29 => "ஶ",
+        0x2A => "ஷ",
+        0x2B => "ஸ",
+        0x31 => "ய",
+        0x32 => "வ",
+        0x33 => "ர",
+        0x34 => "ல",
+        0x38 => "ஹ",
+        _ => "?",
+    }
+}
+
+/// The superscript for a varga stop's column; ja is ஜ and jha ஜ⁴.
+fn tamil_mark(b: u8) -> &'static str {
+    if b > 0x23 || b & 0b111 > 3 {
+        return "";
+    }
+    match (b >> 3, b & 0b111) {
+        (1, 2) => "",
+        (_, 1) => "²",
+        (_, 2) => "³",
+        (_, 3) => "⁴",
+        _ => "",
+    }
+}
+
+fn tamil_independent(b: u8) -> &'static str {
+    match b & 0b11_00_11_11 {
+        0x40 => "அ",
+        0x80 => "ஆ",
+        0x44 => "இ",
+        0x84 => "ஈ",
+        0x48 => "உ",
+        0x88 => "ஊ",
+        // Tamil has no vocalic liquids: ṛ is written ru, ḷ lu
+        0x4C => "ரு",
+        0x8C => "ரூ",
+        0x4F => "லு",
+        0x8F => "லூ",
+        0x85 => "ஏ",
+        0x86 => "ஐ",
+        0x89 => "ஓ",
+        0x8A => "ஔ",
+        _ => "?",
+    }
+}
+
+fn tamil_matra(b: u8) -> Option<&'static str> {
+    match b & 0b11_00_11_11 {
+        0x80 => Some("ா"),
+        0x44 => Some("ி"),
+        0x84 => Some("ீ"),
+        0x48 => Some("ு"),
+        0x88 => Some("ூ"),
+        0x4C => Some("்ரு"),
+        0x8C => Some("்ரூ"),
+        0x4F => Some("்லு"),
+        0x8F => Some("்லூ"),
+        0x85 => Some("ே"),
+        0x86 => Some("ை"),
+        0x89 => Some("ோ"),
+        0x8A => Some("ௌ"),
+        _ => None,
+    }
+}
+
+fn tamil_extension(code: u8) -> Option<ExtLetter> {
+    // Foreign sounds are written with a leading āytam: ஃப for f
+    Some(match code {
+        0x01 => ("ழ", None),
+        0x02 => ("ற", None),
+        0x03 => ("ன", None),
+        0x04 => ("ள", None),
+        0x05 => ("ள்ஹ", None),
+        0x08 => ("ஃக", None),
+        0x09 => ("ஃக²", None),
+        0x0A => ("ஃக³", None),
+        0x0B => ("ஃஜ", None),
+        0x0C => ("ஃப", None),
+        0x10 => ("எ", Some("ெ")),
+        0x11 => ("ஒ", Some("ொ")),
+        _ => return None,
+    })
+}
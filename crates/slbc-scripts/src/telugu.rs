@@ -0,0 +1,124 @@
+//! Telugu.
+
+use crate::{no_mark, Abugida, ExtLetter, NO_ACCENTS};
+
+/// Telugu keeps Sanskrit e/o as its long ే/ో; the short vowels are the
+/// extension codes.
+pub const TELUGU: Abugida = Abugida {
+    name: "Telugu",
+    consonant: telugu_consonant,
+    independent: telugu_independent,
+    matra: telugu_matra,
+    extension: telugu_extension,
+    virama: "్",
+    anusvara: "ం",
+    candrabindu: "ఁ",
+    visarga: "ః",
+    jihvamuliya: "",
+    upadhmaniya: "",
+    // Telugu has no daṇḍa of its own
+    danda: "।",
+    double_danda: "॥",
+    avagraha: "ఽ",
+    om: "",
+    digits: ['౦', '౧', '౨', '౩', '౪', '౫', '౬', '౭', '౮', '౯'],
+    accents: NO_ACCENTS,
+    consonant_mark: no_mark,
+};
+
+fn telugu_consonant(b: u8) -> &'static str {
+    match b {
+        0x00 => "క",
+        0x01 => "ఖ",
+        0x02 => "గ",
+        0x03 => "ఘ",
+        0x04 => "ఙ",
+        0x08 => "చ",
+        0x09 => "ఛ",
+        0x0A => "జ",
+        0x0B => "ఝ",
+        0x0C => "ఞ",
+        0x10 => "ట",
+        0x11 => "ఠ",
+        0x12 => "డ",
+        0x13 => "ఢ",
+        0x14 => "ణ",
+        0x18 => "త",
+        0x19 => "థ",
+        0x1A => "ద",
+        0x1B => "ధ",
+        0x1C => "న",
+        0x20 => "ప",
+        0x21 => "ఫ",
+        0x22 => "బ",
+        0x23 => "భ",
+        0x24 => "మ",
+        0x29 => "శ",
+        0x2A => "ష",
+        0x2B => "స",
+        0x31 => "య",
+        0x32 => "వ",
+        0x33 => "ర",
+        0x34 => "ల",
+        0x38 => "హ",
+        _ => "?",
+    }
+}
+
+fn telugu_independent(b: u8) -> &'static str {
+    match b & 0b11_00_11_11 {
+        0x40 => "అ",
+        0x80 => "ఆ",
+        0x44 => "ఇ",
+        0x84 => "ఈ",
+        0x48 => "ఉ",
+        0x88 => "ఊ",
+        0x4C => "ఋ",
+        0x8C => "ౠ",
+        0x4F => "ఌ",
+        0x8F => "ౡ",
+        0x85 => "ఏ",
+        0x86 => "ఐ",
+        0x89 => "ఓ",
+        0x8A => "ఔ",
+        _ => "?",
+    }
+}
+
+fn telugu_matra(b: u8) -> Option<&'static str> {
+    match b & 0b11_00_11_11 {
+        0x80 => Some("ా"),
+        0x44 => Some("ి"),
+        0x84 => Some("ీ"),
+        0x48 => Some("ు"),
+        0x88 => Some("ూ"),
+        0x4C => Some("ృ"),
+        0x8C => Some("ౄ"),
+        0x4F => Some("ౢ"),
+        0x8F => Some("ౣ"),
+        0x85 => Some("ే"),
+        0x86 => Some("ై"),
+        0x89 => Some("ో"),
+        0x8A => Some("ౌ"),
+        _ => None,
+    }
+}
+
+fn telugu_extension(code: u8) -> Option<ExtLetter> {
+    // Nukta letters are base + U+0C3C
+    Some(match code {
+        0x01 => ("ఴ", None),
+        0x02 => ("ఱ", None),
+        0x03 => ("న఼", None),
+        0x04 => ("ళ", None),
+        0x05 => ("ళ్హ", None),
+        0x08 => ("క఼", None),
+        0x09 => ("ఖ఼", None),
+        0x0A => ("గ఼", None),
+        0x0B => ("జ఼", None),
+        0x0C => ("ఫ఼", None),
+        0x10 => ("ఎ", Some("ె")),
+        0x11 => ("ఒ", Some("ొ")),
+        _ => return None,
+    })
+}
@@ -0,0 +1,11 @@
+//! Sanskrit Linguistic Binary Codec (SLBC)
+//!
+//! Umbrella crate: everything in `slbc-core`, plus the feature crates —
+//! `scripts` (regional abugida output, from `slbc-scripts`) and `analysis`
+//! (`slbc-analysis`, as `slbc::analysis`). Embedded and WASM builds can
+//! depend on `slbc-core` with `default-features = false` instead.
+
+pub use slbc_core::*;
+
+#[cfg(feature = "analysis")]
+pub use slbc_analysis as analysis;